@@ -0,0 +1,45 @@
+//! Benchmark of bitmap iteration
+//!
+//! Iterates over the set and unset indices of sparse and dense bitmaps of 256
+//! and 4096 bits.
+//!
+//! Run with `cargo bench --bench bitmap_iteration`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hwlocality::bitmap::Bitmap;
+use std::hint::black_box;
+
+fn bitmap_iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bitmap_iteration");
+    for num_bits in [256, 4096] {
+        let dense = Bitmap::from_range(0..num_bits);
+        let mut sparse = Bitmap::new();
+        for idx in (0..num_bits).step_by(17) {
+            sparse.set(idx);
+        }
+        for (name, bitmap) in [("dense", &dense), ("sparse", &sparse)] {
+            let parameter = format!("{num_bits} bits, {name}");
+            group.bench_with_input(
+                BenchmarkId::new("iter_set", &parameter),
+                bitmap,
+                |b, bitmap| b.iter(|| black_box(bitmap).iter_set().count()),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("iter_unset", &parameter),
+                bitmap,
+                |b, bitmap| {
+                    b.iter(|| {
+                        black_box(bitmap)
+                            .iter_unset()
+                            .take_while(|&idx| usize::from(idx) < num_bits)
+                            .count()
+                    });
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bitmap_iteration);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+//! Benchmark of type-to-depth queries
+//!
+//! The first query for a given object type crosses the FFI boundary (and may
+//! scan all depths of the topology for `depth_or_(above|below)_for_type`),
+//! while later queries are served from the topology's depth cache. This
+//! benchmark compares both situations.
+//!
+//! Run with `cargo bench --bench depth_queries`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use hwlocality::{object::types::ObjectType, Topology};
+use std::hint::black_box;
+
+/// Type-to-depth query, returning whether it succeeded
+type Query = fn(&Topology) -> bool;
+
+fn depth_queries(c: &mut Criterion) {
+    let topology = Topology::new().expect("Failed to build the topology");
+    let queries: [(&str, Query); 3] = [
+        ("depth_for_type", |topology| {
+            topology.depth_for_type(ObjectType::L3Cache).is_ok()
+        }),
+        ("depth_or_below_for_type", |topology| {
+            topology
+                .depth_or_below_for_type(ObjectType::L2ICache)
+                .is_ok()
+        }),
+        ("depth_or_above_for_type", |topology| {
+            topology
+                .depth_or_above_for_type(ObjectType::L2ICache)
+                .is_ok()
+        }),
+    ];
+    let mut group = c.benchmark_group("depth_queries");
+    for (name, query) in queries {
+        // Uncached queries are made on fresh topology clones, whose cache is
+        // empty
+        group.bench_function(format!("{name}/uncached"), |b| {
+            b.iter_batched_ref(
+                || topology.clone(),
+                |clone| query(black_box(clone)),
+                BatchSize::SmallInput,
+            );
+        });
+
+        // Cached queries are repeatedly made on the same topology
+        query(&topology);
+        group.bench_function(format!("{name}/cached"), |b| {
+            b.iter(|| query(black_box(&topology)));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, depth_queries);
+criterion_main!(benches);
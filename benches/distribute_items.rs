@@ -0,0 +1,77 @@
+//! Benchmark of work item distribution on large symmetric topologies
+//!
+//! [`Topology::distribute_items()`] computes the distribution of items within
+//! the first child of a symmetric subtree once, then replicates it to the
+//! other children. This benchmark compares it with
+//! [`Topology::distribute_items_by_cpu_kind()`] using uniform weights, which
+//! produces the same output without this fast path, on synthetic topologies
+//! with 1024 PUs.
+//!
+//! Run with `cargo bench --bench distribute_items --features hwloc-2_4_0`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hwlocality::{
+    object::depth::NormalDepth,
+    topology::{synthetic::SyntheticBuilder, DistributeFlags, Topology},
+};
+use std::hint::black_box;
+
+/// Work item distribution method
+type Distribute = fn(&Topology, usize) -> usize;
+
+fn distribute_items(c: &mut Criterion) {
+    let topologies = [
+        (
+            "4 packages x 128 cores x 2 PUs",
+            SyntheticBuilder::new().packages(4).cores(128).pus(2),
+        ),
+        (
+            "2 packages x 8 L3 x 8 cores x 8 PUs",
+            SyntheticBuilder::new().packages(2).l3(8).cores(8).pus(8),
+        ),
+    ];
+    let methods: [(&str, Distribute); 2] = [
+        ("symmetric", |topology, num_items| {
+            topology
+                .distribute_items(
+                    &[topology.root_object()],
+                    num_items,
+                    NormalDepth::MAX,
+                    DistributeFlags::empty(),
+                )
+                .unwrap()
+                .len()
+        }),
+        ("naive", |topology, num_items| {
+            topology
+                .distribute_items_by_cpu_kind(
+                    &[topology.root_object()],
+                    num_items,
+                    |_kind_index, _efficiency, _infos| 1,
+                    NormalDepth::MAX,
+                    DistributeFlags::empty(),
+                )
+                .unwrap()
+                .len()
+        }),
+    ];
+    let mut group = c.benchmark_group("distribute_items");
+    for (topology_name, builder) in topologies {
+        let topology = builder
+            .build()
+            .expect("Failed to build the synthetic topology");
+        for num_items in [64, 1024] {
+            for (method_name, distribute) in methods {
+                group.bench_with_input(
+                    BenchmarkId::new(method_name, format!("{topology_name}, {num_items} items")),
+                    &num_items,
+                    |b, &num_items| b.iter(|| distribute(black_box(&topology), num_items)),
+                );
+            }
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, distribute_items);
+criterion_main!(benches);
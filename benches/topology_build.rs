@@ -0,0 +1,33 @@
+//! Benchmark of topology building
+//!
+//! Compares the time it takes to build a default topology with that of the
+//! reduced-discovery [`TopologyBuilder::minimal_cpu()`] preset, which is meant
+//! for programs that only need PUs, cores and NUMA nodes for pinning.
+//!
+//! Run with `cargo bench --bench topology_build`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hwlocality::topology::{builder::TopologyBuilder, Topology};
+
+/// Topology building method
+type Build = fn() -> Topology;
+
+fn topology_build(c: &mut Criterion) {
+    let builds: [(&str, Build); 2] = [
+        ("default", || Topology::new().unwrap()),
+        ("minimal_cpu", || {
+            TopologyBuilder::minimal_cpu().build().unwrap()
+        }),
+    ];
+    let mut group = c.benchmark_group("topology_build");
+    // Topology building is slow, so fewer samples are taken than by default
+    group.sample_size(20);
+    for (name, build) in builds {
+        println!("{name} topology has {} objects", build().objects().count());
+        group.bench_function(name, |b| b.iter(build));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, topology_build);
+criterion_main!(benches);
@@ -6,8 +6,9 @@ use std::{
 };
 
 fn main() {
-    // We don't need hwloc on docs.rs since it only builds the docs
-    if std::env::var("DOCS_RS").is_err() {
+    // We don't need hwloc on docs.rs since it only builds the docs, and we
+    // don't need it at build time when it is loaded at runtime
+    if std::env::var("DOCS_RS").is_err() && !cfg!(feature = "dlopen") {
         setup_hwloc();
     }
 }
@@ -15,7 +16,13 @@ fn main() {
 /// Configure the hwloc dependency
 fn setup_hwloc() {
     // Determine the minimal supported hwloc version with current featurees
-    let required_version = if cfg!(feature = "hwloc-2_8_0") {
+    let required_version = if cfg!(feature = "hwloc-3_0_0") {
+        "3.0.0"
+    } else if cfg!(feature = "hwloc-2_11_0") {
+        "2.11.0"
+    } else if cfg!(feature = "hwloc-2_10_0") {
+        "2.10.0"
+    } else if cfg!(feature = "hwloc-2_8_0") {
         "2.8.0"
     } else if cfg!(feature = "hwloc-2_5_0") {
         "2.5.0"
@@ -56,6 +63,7 @@ fn find_hwloc(required_version: Option<&str>) -> pkg_config::Library {
             .expect("No major version in required_version")
         {
             "2" => "3.0.0",
+            "3" => "4.0.0",
             other => panic!("Please add support for hwloc v{other}.x"),
         };
         config.range_version(required_version..first_unsupported_version);
@@ -94,6 +102,8 @@ fn setup_vendored_hwloc(required_version: &str) {
         .expect("No major version in required_version")
     {
         "2" => "v2.x",
+        // hwloc v3.0 is not released yet, so there is no release branch
+        "3" => "master",
         other => panic!("Please add support for bundling hwloc v{other}.x"),
     };
     let out_path = env::var("OUT_DIR").expect("No output directory given");
@@ -134,7 +144,7 @@ fn fetch_hwloc(parent_path: impl AsRef<Path>, version: &str) -> PathBuf {
             .expect("git clone for hwloc failed")
     } else {
         Command::new("git")
-            .args(["pull", "--ff-only", "origin", "v2.x"])
+            .args(["pull", "--ff-only", "origin", version])
             .current_dir(&repo_path)
             .output()
             .expect("git pull for hwloc failed")
@@ -181,6 +191,18 @@ fn install_hwloc_cmake(source_path: impl AsRef<Path>) {
     config.define("HWLOC_SKIP_LSTOPO", "1");
     config.define("HWLOC_SKIP_TOOLS", "1");
 
+    // Disable optional dependencies in minimal builds
+    if minimal_build() {
+        for option in [
+            "HWLOC_WITH_LIBXML2",
+            "HWLOC_WITH_OPENCL",
+            "HWLOC_WITH_CUDA",
+            "HWLOC_WITH_NVML",
+        ] {
+            config.define(option, "OFF");
+        }
+    }
+
     // Build hwloc
     let install_path = config.always_configure(false).build();
 
@@ -197,14 +219,36 @@ fn install_hwloc_cmake(source_path: impl AsRef<Path>) {
 fn install_hwloc_autotools(source_path: impl AsRef<Path>) {
     // Build using autotools
     let mut config = autotools::Config::new(source_path);
+    let minimal = minimal_build();
     if cfg!(target_os = "macos") {
         // macOS need some extra stuff to be linked for all symbols to be found
         config.ldflag("-F/System/Library/Frameworks -framework CoreFoundation");
         // And libxml2 needs to be linked in explicitly for some inexplicable reason
-        println!("cargo:rustc-link-lib=xml2");
+        if !minimal {
+            println!("cargo:rustc-link-lib=xml2");
+        }
     }
     config.enable_static();
     config.disable_shared();
+
+    // Disable optional dependencies in minimal builds
+    if minimal {
+        for component in [
+            "cairo",
+            "libxml2",
+            "libudev",
+            "pciaccess",
+            "opencl",
+            "cuda",
+            "nvml",
+            "rsmi",
+            "levelzero",
+            "gl",
+            "plugin-dlopen",
+        ] {
+            config.disable(component, None);
+        }
+    }
     let install_path = config.fast_build(true).reconf("-ivf").build();
 
     // Compute the associated PKG_CONFIG_PATH
@@ -227,3 +271,15 @@ fn install_hwloc_autotools(source_path: impl AsRef<Path>) {
     // Configure this build to use hwloc via pkg-config
     find_hwloc(None);
 }
+
+/// Truth that the user asked for a minimal vendored hwloc build
+///
+/// Minimal builds disable all optional hwloc dependencies (libxml2, GPU
+/// libraries, libudev...), which is useful when producing self-contained
+/// binaries for containers and embedded environments. hwloc then uses its
+/// built-in minimalistic XML support, and GPU devices are not discovered.
+#[cfg(feature = "vendored")]
+fn minimal_build() -> bool {
+    println!("cargo:rerun-if-env-changed=HWLOC_VENDORED_MINIMAL");
+    env::var("HWLOC_VENDORED_MINIMAL").is_ok_and(|value| !value.is_empty() && value != "0")
+}
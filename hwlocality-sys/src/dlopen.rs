@@ -0,0 +1,267 @@
+//! Runtime loading of the hwloc library
+//!
+//! When the `dlopen` feature is enabled, `libhwloc` is not linked into the
+//! final binary. Instead, it is loaded the first time an hwloc entry point is
+//! called, or when [`load_hwloc()`] is called, whichever comes first. This
+//! lets binaries run on machines where hwloc is not installed, as long as they
+//! check the output of [`load_hwloc()`] before calling any hwloc entry point.
+
+#[cfg(not(unix))]
+compile_error!("The dlopen feature is currently only supported on Unix systems");
+
+use std::{
+    error::Error,
+    ffi::{c_void, CStr},
+    fmt::{self, Display},
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        OnceLock,
+    },
+};
+
+/// Names under which the hwloc library is looked up, in order of preference
+///
+/// hwloc v2.x releases all use ABI version 15, which is what the versioned
+/// names refer to. The unversioned names are only installed alongside hwloc's
+/// development files, but they are still tried as a fallback.
+#[cfg(all(target_os = "macos", not(feature = "hwloc-3_0_0")))]
+const LIBRARY_NAMES: &[&[u8]] = &[b"libhwloc.15.dylib\0", b"libhwloc.dylib\0"];
+//
+/// Names under which the hwloc library is looked up, in order of preference
+///
+/// hwloc v2.x releases all use ABI version 15, which is what the versioned
+/// names refer to. The unversioned names are only installed alongside hwloc's
+/// development files, but they are still tried as a fallback.
+#[cfg(all(not(target_os = "macos"), not(feature = "hwloc-3_0_0")))]
+const LIBRARY_NAMES: &[&[u8]] = &[b"libhwloc.so.15\0", b"libhwloc.so\0"];
+//
+/// Names under which the hwloc library is looked up, in order of preference
+///
+/// hwloc v3.0 breaks the v2.x ABI, so the ABI version 15 libraries must not be
+/// loaded. As hwloc v3.0 is not released yet, its final ABI version is not
+/// known, and only the unversioned name of the development files is tried.
+/// The major version of the library is checked after loading it.
+#[cfg(all(target_os = "macos", feature = "hwloc-3_0_0"))]
+const LIBRARY_NAMES: &[&[u8]] = &[b"libhwloc.dylib\0"];
+//
+/// Names under which the hwloc library is looked up, in order of preference
+///
+/// hwloc v3.0 breaks the v2.x ABI, so the ABI version 15 libraries must not be
+/// loaded. As hwloc v3.0 is not released yet, its final ABI version is not
+/// known, and only the unversioned name of the development files is tried.
+/// The major version of the library is checked after loading it.
+#[cfg(all(not(target_os = "macos"), feature = "hwloc-3_0_0"))]
+const LIBRARY_NAMES: &[&[u8]] = &[b"libhwloc.so\0"];
+
+/// Load the hwloc library, if this was not done already
+///
+/// It is not necessary to call this function before using hwloc entry points,
+/// which load hwloc on first use. But entry points cannot report failure to
+/// load hwloc, so they abort the process instead. Programs that want to handle
+/// a missing hwloc library gracefully should therefore call this function
+/// first, and refrain from calling any hwloc entry point if it fails.
+///
+/// # Errors
+///
+/// [`LoadError`] if no suitable hwloc library could be loaded. The outcome of
+/// the first loading attempt is cached, so later calls fail in the same way.
+pub fn load_hwloc() -> Result<(), LoadError> {
+    library().map(|_| ())
+}
+
+/// Failed to load the hwloc library at runtime
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LoadError {
+    /// Library names that were tried
+    tried: Vec<String>,
+
+    /// Error message from the dynamic loader, for the last name that was tried
+    message: String,
+}
+//
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to load the hwloc library (tried {}): {}",
+            self.tried.join(", "),
+            self.message
+        )
+    }
+}
+//
+impl Error for LoadError {}
+
+/// Resolve the hwloc symbol called `name`, caching the address in `cache`
+///
+/// `name` must be NUL-terminated. The process is aborted if the hwloc library
+/// cannot be loaded or does not contain the requested symbol, since entry
+/// points have no other way to report this error.
+pub(crate) fn resolve(cache: &AtomicPtr<c_void>, name: &'static str) -> *mut c_void {
+    let cached = cache.load(Ordering::Relaxed);
+    if !cached.is_null() {
+        return cached;
+    }
+    let library = match library() {
+        Ok(library) => library,
+        Err(e) => abort(&e.to_string()),
+    };
+    let name_cstr =
+        CStr::from_bytes_with_nul(name.as_bytes()).expect("Symbol names should be NUL-terminated");
+    // SAFETY: library is a valid handle that is never closed, and name_cstr
+    //         is a valid C string
+    let symbol = unsafe { libc::dlsym(library.0.as_ptr(), name_cstr.as_ptr()) };
+    if symbol.is_null() {
+        abort(&format!(
+            "hwloc entry point {} is not available: {}",
+            &name[..name.len() - 1],
+            loader_error()
+        ));
+    }
+    cache.store(symbol, Ordering::Relaxed);
+    symbol
+}
+
+/// Handle to the hwloc library, loaded on first use
+fn library() -> Result<&'static Library, LoadError> {
+    /// Loaded hwloc library
+    static LIBRARY: OnceLock<Result<Library, LoadError>> = OnceLock::new();
+    LIBRARY
+        .get_or_init(|| {
+            let mut message = String::new();
+            for name in LIBRARY_NAMES {
+                let name = CStr::from_bytes_with_nul(name)
+                    .expect("Library names should be NUL-terminated");
+                // hwloc plugins are shared libraries that hwloc loads itself,
+                // and which resolve hwloc core symbols from the global scope,
+                // so hwloc must be loaded with RTLD_GLOBAL for them to work.
+                //
+                // SAFETY: name is a valid C string, and loading hwloc does not
+                //         run any initialization code with preconditions
+                let handle =
+                    unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_GLOBAL) };
+                let Some(handle) = NonNull::new(handle) else {
+                    message = loader_error();
+                    continue;
+                };
+                match check_api_version(handle) {
+                    Ok(()) => return Ok(Library(handle)),
+                    Err(mismatch) => {
+                        // SAFETY: handle is valid, and no symbol that was
+                        //         resolved from it is used after this point
+                        unsafe { libc::dlclose(handle.as_ptr()) };
+                        message = mismatch;
+                    }
+                }
+            }
+            Err(LoadError {
+                tried: LIBRARY_NAMES
+                    .iter()
+                    .map(|name| String::from_utf8_lossy(&name[..name.len() - 1]).into_owned())
+                    .collect(),
+                message,
+            })
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Check that a freshly loaded hwloc library has the expected major version
+///
+/// Unversioned library names may resolve to any hwloc release, including one
+/// whose ABI does not match the `hwloc_obj` layout that this crate was built
+/// for, so this must be checked before any other entry point is called.
+fn check_api_version(handle: NonNull<c_void>) -> Result<(), String> {
+    let expected_major = if cfg!(feature = "hwloc-3_0_0") { 3 } else { 2 };
+    // SAFETY: handle was just returned by a successful dlopen() call, and the
+    //         symbol name is a valid C string
+    let symbol =
+        unsafe { libc::dlsym(handle.as_ptr(), b"hwloc_get_api_version\0".as_ptr().cast()) };
+    if symbol.is_null() {
+        return Err(loader_error());
+    }
+    // SAFETY: - hwloc_get_api_version has this signature in all hwloc releases
+    //         - hwloc_get_api_version has no safety preconditions
+    let version = unsafe {
+        let get_api_version: unsafe extern "C" fn() -> std::ffi::c_uint =
+            std::mem::transmute(symbol);
+        get_api_version()
+    };
+    let actual_major = version >> 16;
+    if actual_major == expected_major {
+        Ok(())
+    } else {
+        Err(format!(
+            "expected hwloc v{expected_major}.x, but found hwloc v{actual_major}.x"
+        ))
+    }
+}
+
+/// Handle to a library that was loaded with `dlopen()`, and is never closed
+#[derive(Debug)]
+struct Library(NonNull<c_void>);
+//
+// SAFETY: dlopen() handles are not tied to the thread that created them, and
+//         the functions that use them are thread-safe
+unsafe impl Send for Library {}
+//
+// SAFETY: See above
+unsafe impl Sync for Library {}
+
+/// Last error message from the dynamic loader
+fn loader_error() -> String {
+    // SAFETY: dlerror() has no safety preconditions
+    let message = unsafe { libc::dlerror() };
+    if message.is_null() {
+        "unknown error".to_owned()
+    } else {
+        // SAFETY: dlerror() returns a valid C string when it returns non-null
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Report a fatal hwloc loading error and abort the process
+///
+/// Panicking is not an option here, since unwinding out of the `extern "C"`
+/// entry points is not allowed.
+#[allow(clippy::print_stderr)]
+fn abort(message: &str) -> ! {
+    eprintln!("{message}");
+    std::process::abort()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash};
+
+    assert_impl_all!(LoadError: Clone, Debug, Error, Eq, Hash, Send, Sync);
+
+    #[test]
+    fn load() {
+        // hwloc must be installed to run the tests, so loading should succeed
+        assert_eq!(load_hwloc(), Ok(()));
+        assert_eq!(load_hwloc(), Ok(()));
+
+        // Entry points should then forward to hwloc
+        // SAFETY: This hwloc entry point has no safety preconditions
+        let version = unsafe { crate::hwloc_get_api_version() };
+        assert!(version >= 0x20000);
+    }
+
+    #[test]
+    fn load_error() {
+        let error = LoadError {
+            tried: vec!["libhwloc.so.15".to_owned(), "libhwloc.so".to_owned()],
+            message: "file not found".to_owned(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "failed to load the hwloc library (tried libhwloc.so.15, libhwloc.so): file not found"
+        );
+    }
+}
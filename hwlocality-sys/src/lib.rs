@@ -205,6 +205,8 @@
 use libc::pid_t;
 #[cfg(doc)]
 use std::panic::UnwindSafe;
+#[cfg(feature = "dlopen")]
+use std::sync::atomic::AtomicPtr;
 use std::{
     cell::UnsafeCell,
     ffi::{c_char, c_float, c_int, c_uchar, c_uint, c_ulong, c_ushort, c_void},
@@ -766,13 +768,25 @@ pub struct hwloc_obj {
     ///
     /// Beware that hwloc allows multiple informations with the same key to
     /// exist, although no sane programs should leverage this possibility.
+    #[cfg(not(feature = "hwloc-3_0_0"))]
     pub infos: *mut hwloc_info_s,
 
     /// Number of (key, value) pairs in [`infos`]
     ///
     /// [`infos`]: Self::infos
+    #[cfg(not(feature = "hwloc-3_0_0"))]
     pub infos_count: c_uint,
 
+    /// Complete list of (key, value) textual info pairs
+    ///
+    /// hwloc defines [a number of standard object info attribute names with
+    /// associated semantics](https://hwloc.readthedocs.io/en/v2.9/attributes.html#attributes_info).
+    ///
+    /// Beware that hwloc allows multiple informations with the same key to
+    /// exist, although no sane programs should leverage this possibility.
+    #[cfg(feature = "hwloc-3_0_0")]
+    pub infos: hwloc_infos_s,
+
     /// Application-given private data pointer, initialized to NULL, use it as
     /// you wish
     //
@@ -1095,6 +1109,35 @@ pub struct hwloc_info_s {
     pub value: *mut c_char,
 }
 
+/// Array of key-value string attributes
+///
+/// hwloc v3.0 uses this struct wherever hwloc v2.x used a pointer to
+/// [`hwloc_info_s`] followed by an element count.
+///
+/// This type does not implement [`Default`] because hwloc all but guarantees
+/// that the inner pointers of this struct will not be null.
+#[cfg(feature = "hwloc-3_0_0")]
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct hwloc_infos_s {
+    /// Array of [`count`] (key, value) pairs
+    ///
+    /// [`count`]: Self::count
+    pub array: *mut hwloc_info_s,
+
+    /// Number of (key, value) pairs in [`array`]
+    ///
+    /// [`array`]: Self::array
+    pub count: c_uint,
+
+    /// Number of (key, value) pairs that [`array`] has room for
+    ///
+    /// This is an hwloc implementation detail that should not be relied upon.
+    ///
+    /// [`array`]: Self::array
+    pub allocated: c_uint,
+}
+
 // === Topology Creation and Destruction: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__creation.html
 
 /// Opaque topology struct
@@ -1374,6 +1417,21 @@ pub const HWLOC_MEMBIND_INTERLEAVE: hwloc_membind_policy_t = 3;
 /// set.
 pub const HWLOC_MEMBIND_NEXTTOUCH: hwloc_membind_policy_t = 4;
 
+/// Allocate memory on the given nodes in an interleaved weighted manner
+///
+/// The precise layout of the memory across multiple NUMA nodes is OS/system
+/// specific.
+///
+/// Weighted interleaving can be useful when threads distributed across the
+/// specified NUMA nodes with different bandwidth capabilities will all be
+/// accessing the whole memory range concurrently, since the interleave will
+/// then balance the memory references.
+///
+/// Only available if
+/// [`hwloc_topology_membind_support::weighted_interleave_membind`] is set.
+#[cfg(feature = "hwloc-2_11_0")]
+pub const HWLOC_MEMBIND_WEIGHTED_INTERLEAVE: hwloc_membind_policy_t = 5;
+
 /// Mixture of memory binding policies
 ///
 /// Returned by `get_membind()` functions when multiple threads or parts of a
@@ -1728,6 +1786,10 @@ pub struct hwloc_topology_membind_support {
 
     /// Getting the last NUMA nodes where a memory area was allocated is supported
     pub get_area_memlocation: c_uchar,
+
+    /// Weighted interleave policy is supported
+    #[cfg(feature = "hwloc-2_11_0")]
+    pub weighted_interleave_membind: c_uchar,
 }
 
 /// Miscellaneous support information
@@ -2330,11 +2392,16 @@ pub use memory_attributes::*;
 
 // === Entry points
 
-/// Implement all the entry points with the right link name
-macro_rules! extern_c_block {
-    ($link_name:literal) => {
-        #[link(name = $link_name)]
-        extern "C" {
+/// Declare all the entry points using the `$declare` macro
+///
+/// `$declare` is either `link_entry_points`, which lets the linker resolve
+/// them from the library called `$link_name`, or `load_entry_points`, which
+/// resolves them from a library that is loaded at runtime.
+macro_rules! hwloc_entry_points {
+    ($declare:ident $(, $link_name:literal)?) => {
+        $declare! {
+            $($link_name;)?
+
             // === API versioning: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__api__version.html
 
             /// Indicate at runtime which hwloc API version was used at build time
@@ -2524,6 +2591,8 @@ macro_rules! extern_c_block {
 
             // === Converting between object types, attributes and strings: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__object__strings.html
 
+            #[must_use]
+            pub fn hwloc_obj_type_string(ty: hwloc_obj_type_t) -> *const c_char;
             #[must_use]
             pub fn hwloc_obj_type_snprintf(
                 into: *mut c_char,
@@ -2910,8 +2979,10 @@ macro_rules! extern_c_block {
                 end: c_int,
             ) -> c_int;
             pub fn hwloc_bitmap_singlify(bitmap: hwloc_bitmap_t) -> c_int;
-            // NOTE: Not exposing ulong-based APIs for now, so no to_ulong, to_ith_ulong, to_ulongs and nr_ulongs
+            // NOTE: Only exposing to_ith_ulong (used for fast iteration), so no to_ulong, to_ulongs and nr_ulongs
             //       If I decide to add them, gate nr_ulongs and to_ulongs with #[cfg(feature = "hwloc-2_1_0")]
+            #[must_use]
+            pub fn hwloc_bitmap_to_ith_ulong(bitmap: hwloc_const_bitmap_t, i: c_uint) -> c_ulong;
 
             #[must_use]
             pub fn hwloc_bitmap_isset(bitmap: hwloc_const_bitmap_t, id: c_uint) -> c_int;
@@ -3137,6 +3208,13 @@ macro_rules! extern_c_block {
                 nodes: *mut *const hwloc_obj,
                 flags: hwloc_local_numanode_flag_e,
             ) -> c_int;
+            #[cfg(feature = "hwloc-2_10_0")]
+            #[must_use]
+            pub fn hwloc_topology_get_default_nodeset(
+                topology: hwloc_const_topology_t,
+                nodeset: hwloc_nodeset_t,
+                flags: c_ulong,
+            ) -> c_int;
             #[cfg(feature = "hwloc-2_3_0")]
             #[must_use]
             pub fn hwloc_memattr_get_value(
@@ -3323,14 +3401,69 @@ macro_rules! extern_c_block {
     };
 }
 
-#[cfg(all(not(feature = "vendored"), target_os = "windows"))]
-extern_c_block!("libhwloc");
+/// Declare entry points that are resolved by the linker
+#[cfg(not(feature = "dlopen"))]
+macro_rules! link_entry_points {
+    ($link_name:literal; $($entry_points:tt)*) => {
+        #[link(name = $link_name)]
+        extern "C" {
+            $($entry_points)*
+        }
+    };
+}
 
-#[cfg(all(feature = "vendored", target_os = "windows"))]
-extern_c_block!("hwloc");
+#[cfg(all(
+    not(feature = "dlopen"),
+    not(feature = "vendored"),
+    target_os = "windows"
+))]
+hwloc_entry_points!(link_entry_points, "libhwloc");
+
+#[cfg(all(not(feature = "dlopen"), feature = "vendored", target_os = "windows"))]
+hwloc_entry_points!(link_entry_points, "hwloc");
+
+#[cfg(all(not(feature = "dlopen"), not(target_os = "windows")))]
+hwloc_entry_points!(link_entry_points, "hwloc");
+
+/// Declare entry points that are resolved from a library loaded at runtime
+///
+/// Each entry point becomes an `extern "C"` function with the same signature,
+/// which resolves the matching hwloc symbol on first use and forwards to it.
+#[cfg(feature = "dlopen")]
+macro_rules! load_entry_points {
+    ($(
+        $(#[$attr:meta])*
+        pub fn $name:ident($($arg:ident: $arg_ty:ty),* $(,)?) $(-> $ret:ty)?;
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe extern "C" fn $name($($arg: $arg_ty),*) $(-> $ret)? {
+                /// Address of the hwloc symbol, once resolved
+                static SYMBOL: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+                let symbol = dlopen::resolve(&SYMBOL, concat!(stringify!($name), "\0"));
+                // SAFETY: The symbol was resolved from the hwloc library, and
+                //         this crate declares hwloc entry points with their
+                //         C signature.
+                let entry_point = unsafe {
+                    std::mem::transmute::<*mut c_void, unsafe extern "C" fn($($arg_ty),*) $(-> $ret)?>(
+                        symbol,
+                    )
+                };
+                // SAFETY: Per function precondition
+                unsafe { entry_point($($arg),*) }
+            }
+        )*
+    };
+}
 
-#[cfg(not(target_os = "windows"))]
-extern_c_block!("hwloc");
+#[cfg(feature = "dlopen")]
+hwloc_entry_points!(load_entry_points);
+
+#[cfg(feature = "dlopen")]
+mod dlopen;
+#[cfg(feature = "dlopen")]
+pub use dlopen::{load_hwloc, LoadError};
 
 #[cfg(test)]
 mod tests {
@@ -3568,6 +3701,118 @@ mod tests {
         assert_eq!(format!("{attr:?}"), "hwloc_obj_attr_u { .. }");
     }
 
+    #[test]
+    fn hwloc_obj_layout() {
+        // Exhaustive destructuring, so that adding or removing a field without
+        // updating this test is a compilation error
+        #[allow(unused)]
+        fn fields(obj: super::hwloc_obj) {
+            let hwloc_obj {
+                ty,
+                subtype,
+                os_index,
+                name,
+                total_memory,
+                attr,
+                depth,
+                logical_index,
+                next_cousin,
+                prev_cousin,
+                parent,
+                sibling_rank,
+                next_sibling,
+                prev_sibling,
+                arity,
+                children,
+                first_child,
+                last_child,
+                symmetric_subtree,
+                memory_arity,
+                memory_first_child,
+                io_arity,
+                io_first_child,
+                misc_arity,
+                misc_first_child,
+                cpuset,
+                complete_cpuset,
+                nodeset,
+                complete_nodeset,
+                infos,
+                #[cfg(not(feature = "hwloc-3_0_0"))]
+                infos_count,
+                userdata,
+                gp_index,
+            } = obj;
+        }
+
+        // Field offsets should match the C struct layout on LP64 platforms
+        #[cfg(target_pointer_width = "64")]
+        {
+            let obj = std::mem::MaybeUninit::<hwloc_obj>::uninit();
+            let base = obj.as_ptr();
+            macro_rules! offset {
+                ($field:ident) => {
+                    // SAFETY: Only computes the address of a field, which is
+                    //         in bounds of the allocation
+                    unsafe {
+                        ptr::addr_of!((*base).$field)
+                            .cast::<u8>()
+                            .offset_from(base.cast::<u8>())
+                    }
+                };
+            }
+            let expected_offsets = [
+                ("ty", offset!(ty), 0),
+                ("subtype", offset!(subtype), 8),
+                ("os_index", offset!(os_index), 16),
+                ("name", offset!(name), 24),
+                ("total_memory", offset!(total_memory), 32),
+                ("attr", offset!(attr), 40),
+                ("depth", offset!(depth), 48),
+                ("logical_index", offset!(logical_index), 52),
+                ("next_cousin", offset!(next_cousin), 56),
+                ("prev_cousin", offset!(prev_cousin), 64),
+                ("parent", offset!(parent), 72),
+                ("sibling_rank", offset!(sibling_rank), 80),
+                ("next_sibling", offset!(next_sibling), 88),
+                ("prev_sibling", offset!(prev_sibling), 96),
+                ("arity", offset!(arity), 104),
+                ("children", offset!(children), 112),
+                ("first_child", offset!(first_child), 120),
+                ("last_child", offset!(last_child), 128),
+                ("symmetric_subtree", offset!(symmetric_subtree), 136),
+                ("memory_arity", offset!(memory_arity), 140),
+                ("memory_first_child", offset!(memory_first_child), 144),
+                ("io_arity", offset!(io_arity), 152),
+                ("io_first_child", offset!(io_first_child), 160),
+                ("misc_arity", offset!(misc_arity), 168),
+                ("misc_first_child", offset!(misc_first_child), 176),
+                ("cpuset", offset!(cpuset), 184),
+                ("complete_cpuset", offset!(complete_cpuset), 192),
+                ("nodeset", offset!(nodeset), 200),
+                ("complete_nodeset", offset!(complete_nodeset), 208),
+                ("infos", offset!(infos), 216),
+                ("userdata", offset!(userdata), 232),
+                ("gp_index", offset!(gp_index), 240),
+            ];
+            for (field, actual, expected) in expected_offsets {
+                assert_eq!(actual, expected, "unexpected offset for field {field}");
+            }
+
+            // hwloc v3.0 moved the info count into struct hwloc_infos_s,
+            // which has the same size as the v2.x (pointer, count) pair
+            #[cfg(not(feature = "hwloc-3_0_0"))]
+            assert_eq!(offset!(infos_count), 224);
+            #[cfg(feature = "hwloc-3_0_0")]
+            {
+                assert_eq!(std::mem::size_of::<hwloc_infos_s>(), 16);
+                assert_eq!(std::mem::align_of::<hwloc_infos_s>(), 8);
+            }
+            assert_eq!(std::mem::size_of::<hwloc_obj>(), 248);
+            assert_eq!(std::mem::align_of::<hwloc_obj>(), 8);
+        }
+    }
+
     #[test]
     fn hwloc_numanode_attr_s() {
         let hwloc_numanode_attr_s {
@@ -72,7 +72,7 @@ use std::{
     borrow::Borrow,
     cmp::Ordering,
     convert::TryFrom,
-    ffi::{c_int, c_uint},
+    ffi::{c_int, c_uint, c_ulong},
     fmt::{self, Debug, Display, Formatter, Pointer},
     hash::{self, Hash},
     iter::{FromIterator, FusedIterator},
@@ -829,7 +829,7 @@ impl Bitmap {
     #[doc(alias = "hwloc_bitmap_foreach_end")]
     #[doc(alias = "hwloc_bitmap_next")]
     pub fn iter_set(&self) -> Iter<&Self> {
-        Iter::new(self, Self::next_set)
+        Iter::over_set(self)
     }
 
     /// Check the last set index, if any
@@ -917,7 +917,7 @@ impl Bitmap {
     /// ```
     #[doc(alias = "hwloc_bitmap_next_unset")]
     pub fn iter_unset(&self) -> Iter<&Self> {
-        Iter::new(self, Self::next_unset)
+        Iter::over_unset(self)
     }
 
     /// Check the last unset index, if any
@@ -1112,54 +1112,16 @@ impl Bitmap {
         BitmapIndex::try_from_c_int(result).ok()
     }
 
-    /// Iterator building block
+    /// Iterator building block: `i`-th word of the bitmap
     ///
-    /// # Safety
-    ///
-    /// `next_fn` must be an hwloc entry point that takes a bitmap and start
-    /// index as a parameter, and returns a next index as an output, such that...
-    ///
-    /// - The bitmap `*const` parameter is not modified by the operation
-    /// - Start index can be -1 to find the first index matching a certain
-    ///   criterion, or in `0..=c_int::MAX` to find the next index matching this
-    ///   criterion after the specified index
-    /// - Return value is the next index matching the selected criterion, or -1
-    ///   to indicate absence of such index (and thus end of iteration)
-    unsafe fn next(
-        &self,
-        api: &'static str,
-        next_fn: impl FnOnce(*const hwloc_bitmap_s, c_int) -> c_int,
-        index: Option<BitmapIndex>,
-    ) -> Option<BitmapIndex> {
-        self.query_index(api, || {
-            // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
-            //         - hwloc ops are trusted not to modify *const parameters
-            next_fn(self.as_ptr(), index.map_or(-1, BitmapIndex::to_c_int))
-        })
-    }
-
-    /// Set index iterator building block
-    fn next_set(&self, index: Option<BitmapIndex>) -> Option<BitmapIndex> {
-        // SAFETY: This function is an hwloc bitmap iteration function
-        unsafe {
-            self.next(
-                "hwloc_bitmap_next",
-                |bitmap, prev| hwlocality_sys::hwloc_bitmap_next(bitmap, prev),
-                index,
-            )
-        }
-    }
-
-    /// Unset index iterator building block
-    fn next_unset(&self, index: Option<BitmapIndex>) -> Option<BitmapIndex> {
-        // SAFETY: This function is an hwloc bitmap iteration function
-        unsafe {
-            self.next(
-                "hwloc_bitmap_next_unset",
-                |bitmap, prev| hwlocality_sys::hwloc_bitmap_next_unset(bitmap, prev),
-                index,
-            )
-        }
+    /// Bit `j` of the `i`-th word is bit `i * c_ulong::BITS + j` of the bitmap.
+    /// Words beyond the end of the bitmap's internal storage are all-zeroes or
+    /// all-ones depending on whether the bitmap is infinitely set.
+    fn ith_word(&self, i: c_uint) -> c_ulong {
+        // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - Any word index is accepted by this function
+        unsafe { hwlocality_sys::hwloc_bitmap_to_ith_ulong(self.as_ptr(), i) }
     }
 }
 
@@ -1460,25 +1422,64 @@ impl Hash for Bitmap {
 }
 
 /// Iterator over set or unset [`Bitmap`] indices
+//
+// --- Implementation details ---
+//
+// Instead of making one hwloc_bitmap_next() call per index, this iterator
+// pulls whole words out of the bitmap and scans them with trailing_zeros(),
+// which is much faster for dense bitmaps.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Iter<B> {
     /// Bitmap over which we're iterating
     bitmap: B,
 
-    /// Last explored index
-    prev: Option<BitmapIndex>,
+    /// Truth that unset indices are being iterated over
+    unset: bool,
+
+    /// Indices of the current word that remain to be yielded, as set bits
+    word: c_ulong,
+
+    /// Index of the next word to be fetched
+    next_word: c_uint,
 
-    /// Mapping from last index to next index
-    next: fn(&Bitmap, Option<BitmapIndex>) -> Option<BitmapIndex>,
+    /// Index of the last word that may contain yielded indices, if any
+    last_word: Option<c_uint>,
 }
 //
-impl<B> Iter<B> {
-    /// Set up a bitmap iterator
-    fn new(bitmap: B, next: fn(&Bitmap, Option<BitmapIndex>) -> Option<BitmapIndex>) -> Self {
+impl<B: Borrow<Bitmap>> Iter<B> {
+    /// Number of bitmap indices per word
+    const WORD_BITS: c_uint = c_ulong::BITS;
+
+    /// Set up an iterator over set indices
+    fn over_set(bitmap: B) -> Self {
+        let last_index = {
+            let bitmap = bitmap.borrow();
+            bitmap
+                .last_set()
+                .or_else(|| (!bitmap.is_empty()).then_some(BitmapIndex::MAX))
+        };
+        Self::new(bitmap, false, last_index)
+    }
+
+    /// Set up an iterator over unset indices
+    fn over_unset(bitmap: B) -> Self {
+        let last_index = {
+            let bitmap = bitmap.borrow();
+            bitmap
+                .last_unset()
+                .or_else(|| (!bitmap.is_full()).then_some(BitmapIndex::MAX))
+        };
+        Self::new(bitmap, true, last_index)
+    }
+
+    /// Set up a bitmap iterator that stops after `last_index`
+    fn new(bitmap: B, unset: bool, last_index: Option<BitmapIndex>) -> Self {
         Self {
             bitmap,
-            prev: None,
-            next,
+            unset,
+            word: 0,
+            next_word: 0,
+            last_word: last_index.map(|idx| idx.to_c_uint() / Self::WORD_BITS),
         }
     }
 }
@@ -1487,8 +1488,18 @@ impl<B: Borrow<Bitmap>> Iterator for Iter<B> {
     type Item = BitmapIndex;
 
     fn next(&mut self) -> Option<BitmapIndex> {
-        self.prev = (self.next)(self.bitmap.borrow(), self.prev);
-        self.prev
+        while self.word == 0 {
+            if self.next_word > self.last_word? {
+                return None;
+            }
+            let word = self.bitmap.borrow().ith_word(self.next_word);
+            self.word = if self.unset { !word } else { word };
+            self.next_word += 1;
+        }
+        let bit = self.word.trailing_zeros();
+        self.word &= self.word - 1;
+        let index = (self.next_word - 1) * Self::WORD_BITS + bit;
+        BitmapIndex::try_from_c_int(c_int::try_from(index).ok()?).ok()
     }
 }
 //
@@ -1499,7 +1510,7 @@ impl<'bitmap> IntoIterator for &'bitmap Bitmap {
     type IntoIter = Iter<Self>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self, Bitmap::next_set)
+        Iter::over_set(self)
     }
 }
 //
@@ -1508,7 +1519,7 @@ impl IntoIterator for Bitmap {
     type IntoIter = Iter<Self>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter::new(self, Self::next_set)
+        Iter::over_set(self)
     }
 }
 
@@ -2793,4 +2804,73 @@ pub(crate) mod tests {
             test_bitmap_ref_binops(&bitmap, &other)?;
         }
     }
+
+    /// Indices yielded by repeated `hwloc_bitmap_next(_unset)` calls, which
+    /// the word-wise [`Iter`] should agree with
+    fn hwloc_next_indices(bitmap: &Bitmap, unset: bool) -> impl Iterator<Item = BitmapIndex> + '_ {
+        let mut prev = -1;
+        std::iter::from_fn(move || {
+            // SAFETY: - Bitmaps are trusted to contain a valid ptr (type invariant)
+            //         - hwloc ops are trusted not to modify *const parameters
+            //         - Any previous index >= -1 is accepted
+            let next = unsafe {
+                if unset {
+                    hwlocality_sys::hwloc_bitmap_next_unset(bitmap.as_ptr(), prev)
+                } else {
+                    hwlocality_sys::hwloc_bitmap_next(bitmap.as_ptr(), prev)
+                }
+            };
+            prev = next;
+            BitmapIndex::try_from_c_int(next).ok()
+        })
+    }
+
+    /// Check [`Bitmap::iter_set()`] and [`Bitmap::iter_unset()`] against
+    /// `hwloc_bitmap_next(_unset)`, including in the infinite tail of the
+    /// bitmap if there is one
+    fn check_iter_against_hwloc(bitmap: &Bitmap) -> Result<(), TestCaseError> {
+        let finite_len = [bitmap.last_set(), bitmap.last_unset()]
+            .into_iter()
+            .flatten()
+            .map(usize::from)
+            .max()
+            .map_or(0, |last| last + 1);
+        let num_checked = finite_len + 2 * INFINITE_EXPLORE_ITERS;
+        for unset in [false, true] {
+            let iter = if unset {
+                bitmap.iter_unset()
+            } else {
+                bitmap.iter_set()
+            };
+            prop_assert!(iter
+                .take(num_checked)
+                .eq(hwloc_next_indices(bitmap, unset).take(num_checked)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn iter_vs_hwloc_next() -> Result<(), TestCaseError> {
+        let word_bits = usize::try_from(c_ulong::BITS).unwrap();
+        let bitmaps = [
+            Bitmap::new(),
+            Bitmap::full(),
+            Bitmap::from_range(0..word_bits),
+            Bitmap::from_range(word_bits - 1..=word_bits),
+            Bitmap::from_range(3..),
+            Bitmap::from_range(word_bits..),
+            !Bitmap::from_range(1..2 * word_bits),
+        ];
+        for bitmap in &bitmaps {
+            check_iter_against_hwloc(bitmap)?;
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_iter_vs_hwloc_next(bitmap: Bitmap) {
+            check_iter_against_hwloc(&bitmap)?;
+        }
+    }
 }
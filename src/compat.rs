@@ -0,0 +1,93 @@
+//! Compatibility layer between hwloc major versions
+//!
+//! hwloc v3.0 changes the meaning of some hwloc v2.x APIs. For example, memory
+//! devices such as Linux DAX files were reported as [`OSDeviceType::Storage`]
+//! devices by hwloc v2.x, but are reported as [`OSDeviceType::Memory`] devices
+//! by hwloc v3.0, which hwlocality targets when the experimental `hwloc-3_0_0`
+//! Cargo feature is enabled.
+//!
+//! This module provides helpers that give the same answer no matter which hwloc
+//! major version hwlocality is built against. Code that must support both hwloc
+//! v2.x and v3.0 should use them instead of matching on the affected types
+//! directly, so that it does not need `cfg(feature = "hwloc-3_0_0")` guards
+//! and does not silently change behavior when that feature is toggled.
+//!
+//! Changes that can be expressed through the Rust type system are handled
+//! without the help of this module. For instance, [`PCIDomain`] is an alias to
+//! the PCI domain integer type of the hwloc version in use.
+//!
+//! [`OSDeviceType::Memory`]: crate::object::types::OSDeviceType#variant.Memory
+//! [`PCIDomain`]: crate::object::attributes::PCIDomain
+
+#[cfg(doc)]
+use crate::object::types::ObjectType;
+use crate::object::{attributes::ObjectAttributes, types::OSDeviceType, TopologyObject};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Truth that `obj` is an [`OSDevice`] that provides access to memory, such as
+/// a Linux DAX device
+///
+/// With hwloc v3.0, these are the [`OSDeviceType::Memory`] devices. Older
+/// hwloc releases report them as [`OSDeviceType::Storage`] devices, which
+/// are recognized by their name.
+///
+/// [`OSDevice`]: ObjectType::OSDevice
+/// [`OSDeviceType::Memory`]: crate::object::types::OSDeviceType#variant.Memory
+pub fn is_memory_device(obj: &TopologyObject) -> bool {
+    match os_device_type(obj) {
+        #[cfg(feature = "hwloc-3_0_0")]
+        Some(OSDeviceType::Memory) => true,
+        Some(OSDeviceType::Storage) => cfg!(not(feature = "hwloc-3_0_0")) && is_dax_name(obj),
+        _ => false,
+    }
+}
+
+/// Truth that `obj` is an [`OSDevice`] that provides access to storage, such
+/// as a disk, excluding the memory devices of [`is_memory_device()`]
+///
+/// [`OSDevice`]: ObjectType::OSDevice
+pub fn is_storage_device(obj: &TopologyObject) -> bool {
+    os_device_type(obj) == Some(OSDeviceType::Storage) && !is_memory_device(obj)
+}
+
+/// Type of OS device, if `obj` is an OS device
+fn os_device_type(obj: &TopologyObject) -> Option<OSDeviceType> {
+    if let Some(ObjectAttributes::OSDevice(osdev)) = obj.attributes() {
+        Some(osdev.device_type())
+    } else {
+        None
+    }
+}
+
+/// Truth that `obj` is named like a Linux DAX device (e.g. "dax2.0")
+fn is_dax_name(obj: &TopologyObject) -> bool {
+    obj.name()
+        .and_then(|name| name.to_str().ok())
+        .is_some_and(|name| name.starts_with("dax"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{object::types::ObjectType, topology::Topology};
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn os_device_kinds() {
+        for obj in Topology::test_objects() {
+            let is_memory = is_memory_device(obj);
+            let is_storage = is_storage_device(obj);
+            assert!(!(is_memory && is_storage));
+            if obj.object_type() != ObjectType::OSDevice {
+                assert!(!is_memory && !is_storage);
+                continue;
+            }
+            if is_dax_name(obj) {
+                assert!(is_memory);
+            }
+        }
+    }
+}
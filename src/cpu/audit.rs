@@ -0,0 +1,277 @@
+//! Thread pinning audits
+//!
+//! Runtimes that pin their threads to CPUs (thread pools, thread-per-core
+//! services...) are easy to get subtly wrong: two workers may end up sharing a
+//! hardware thread while a whole core stays idle, or threads that were meant
+//! to cooperate may end up on different NUMA nodes. This module analyzes a set
+//! of thread CPU bindings and reports such problems in a [`PinningAudit`],
+//! which test suites can use to assert that pinning logic behaves as intended.
+
+use crate::{
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+    },
+    errors::{BindingError, HybridError},
+    memory::nodeset::NodeSet,
+    object::{types::ObjectType, TopologyObject},
+    topology::Topology,
+    ThreadId,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::collections::BTreeMap;
+
+/// # Thread pinning audits
+impl Topology {
+    /// Audit the CPU bindings of a set of threads
+    ///
+    /// `bindings` lists the CPU set that each thread is bound to. The i-th
+    /// binding is considered to be the binding of thread i in the resulting
+    /// [`PinningAudit`]. CPUs which are not part of this topology are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::pinning::AffinityPolicy, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let num_cores = topology.objects_with_type(hwlocality::object::types::ObjectType::Core).len();
+    /// let bindings = topology
+    ///     .affinity_assignments(num_cores, AffinityPolicy::PerCore)?
+    ///     .collect::<Vec<_>>();
+    /// let audit = topology.audit_pinning(&bindings);
+    /// assert!(audit.oversubscribed_pus().is_empty());
+    /// assert!(audit.idle_cores().is_empty());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn audit_pinning<'set>(
+        &self,
+        bindings: impl IntoIterator<Item = &'set CpuSet>,
+    ) -> PinningAudit {
+        let bindings = bindings
+            .into_iter()
+            .map(|binding| binding & self.cpuset())
+            .collect::<Vec<_>>();
+
+        // Spread each thread's load evenly across the PUs it is bound to
+        let mut pu_loads = BTreeMap::<usize, f64>::new();
+        for binding in &bindings {
+            let Some(weight) = binding.weight().filter(|&weight| weight > 0) else {
+                continue;
+            };
+            #[allow(clippy::cast_precision_loss)]
+            let share = 1.0 / weight as f64;
+            for pu in binding {
+                *pu_loads.entry(usize::from(pu)).or_default() += share;
+            }
+        }
+        let mut oversubscribed_pus = CpuSet::new();
+        for (&pu, &load) in &pu_loads {
+            if load > 1.0 + OVERSUBSCRIPTION_TOLERANCE {
+                oversubscribed_pus.set(pu);
+            }
+        }
+
+        // Find cores that no thread may run on
+        let used_pus = bindings
+            .iter()
+            .fold(CpuSet::new(), |acc, binding| acc | binding);
+        let idle_cores = self
+            .objects_with_type(ObjectType::Core)
+            .filter(|core| {
+                core.cpuset().is_some_and(|cpuset| {
+                    let allowed = cpuset & self.allowed_cpuset();
+                    !allowed.is_empty() && !allowed.intersects(&used_pus)
+                })
+            })
+            .map(TopologyObject::logical_index)
+            .collect();
+
+        // Find threads that do not share any NUMA node
+        let nodesets = bindings
+            .iter()
+            .map(|binding| NodeSet::from_cpuset(self, binding))
+            .collect::<Vec<_>>();
+        let mut cross_numa_pairs = Vec::new();
+        for (first, first_nodes) in nodesets.iter().enumerate() {
+            for (second, second_nodes) in nodesets.iter().enumerate().skip(first + 1) {
+                if !first_nodes.is_empty()
+                    && !second_nodes.is_empty()
+                    && !first_nodes.intersects(second_nodes)
+                {
+                    cross_numa_pairs.push((first, second));
+                }
+            }
+        }
+
+        PinningAudit {
+            pu_loads,
+            oversubscribed_pus,
+            idle_cores,
+            nodesets,
+            cross_numa_pairs,
+        }
+    }
+
+    /// Query the CPU bindings of a set of live threads, then audit them
+    ///
+    /// Bindings are queried using [`Topology::thread_cpu_binding()`], then
+    /// analyzed using [`Topology::audit_pinning()`]. The i-th thread of
+    /// `threads` is thread i in the resulting [`PinningAudit`].
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Topology::thread_cpu_binding()`].
+    pub fn audit_thread_pinning(
+        &self,
+        threads: impl IntoIterator<Item = ThreadId>,
+    ) -> Result<PinningAudit, BindingError<HybridError<CpuBindingError>>> {
+        let bindings = threads
+            .into_iter()
+            .map(|tid| self.thread_cpu_binding(tid, CpuBindingFlags::empty()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.audit_pinning(&bindings))
+    }
+}
+
+/// Tolerance for floating-point rounding errors in PU load computations
+const OVERSUBSCRIPTION_TOLERANCE: f64 = 1e-9;
+
+/// Report on the CPU bindings of a set of threads, as produced by
+/// [`Topology::audit_pinning()`]
+///
+/// Threads are identified by their index in the input of the audit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PinningAudit {
+    /// Expected load of each PU (by OS index) that some thread is bound to
+    pu_loads: BTreeMap<usize, f64>,
+
+    /// PUs whose expected load is greater than one thread
+    oversubscribed_pus: CpuSet,
+
+    /// Logical indices of cores that no thread is bound to
+    idle_cores: Vec<usize>,
+
+    /// NUMA nodes that are local to each thread's binding
+    nodesets: Vec<NodeSet>,
+
+    /// Pairs of threads that are not local to any common NUMA node
+    cross_numa_pairs: Vec<(usize, usize)>,
+}
+//
+impl PinningAudit {
+    /// Number of threads that were audited
+    pub fn num_threads(&self) -> usize {
+        self.nodesets.len()
+    }
+
+    /// Expected number of threads running on PU `pu` (by OS index)
+    ///
+    /// Each thread is assumed to spread its time evenly across the PUs that
+    /// it is bound to. For example, a thread that is bound to two PUs
+    /// contributes a load of 0.5 to each of these PUs.
+    pub fn pu_load(&self, pu: usize) -> f64 {
+        self.pu_loads.get(&pu).copied().unwrap_or(0.0)
+    }
+
+    /// PUs whose expected load is greater than one thread
+    ///
+    /// Threads that share these PUs will compete for them even though some
+    /// other PUs may be available.
+    pub fn oversubscribed_pus(&self) -> &CpuSet {
+        &self.oversubscribed_pus
+    }
+
+    /// Logical indices of the CPU cores that no thread is bound to
+    ///
+    /// Only cores with at least one allowed PU are considered.
+    pub fn idle_cores(&self) -> &[usize] {
+        &self.idle_cores[..]
+    }
+
+    /// NUMA nodes that are local to the CPU binding of thread `thread`
+    ///
+    /// Returns `None` if `thread` is not a valid thread index.
+    pub fn thread_nodeset(&self, thread: usize) -> Option<&NodeSet> {
+        self.nodesets.get(thread)
+    }
+
+    /// Threads whose CPU binding spans more than one NUMA node
+    ///
+    /// The memory accesses of such threads may be local or remote depending
+    /// on where the operating system schedules them.
+    pub fn numa_spanning_threads(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodesets
+            .iter()
+            .enumerate()
+            .filter(|(_thread, nodeset)| nodeset.weight().map_or(true, |weight| weight > 1))
+            .map(|(thread, _nodeset)| thread)
+    }
+
+    /// Pairs of threads `(i, j)` with `i < j` whose CPU bindings are not local
+    /// to any common NUMA node
+    ///
+    /// Data that is shared by such threads cannot be local to both of them.
+    /// Threads without a CPU binding inside of the topology are ignored.
+    pub fn cross_numa_pairs(&self) -> &[(usize, usize)] {
+        &self.cross_numa_pairs[..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::fmt::Debug;
+
+    assert_impl_all!(PinningAudit: Clone, Debug, PartialEq, Send, Sync);
+
+    #[test]
+    fn one_thread_per_pu() {
+        let topology = Topology::test_instance();
+        let pus = topology
+            .objects_with_type(ObjectType::PU)
+            .filter_map(TopologyObject::cpuset)
+            .map(|cpuset| cpuset & topology.allowed_cpuset())
+            .filter(|cpuset| !cpuset.is_empty())
+            .collect::<Vec<_>>();
+        let audit = topology.audit_pinning(&pus);
+        assert_eq!(audit.num_threads(), pus.len());
+        assert!(audit.oversubscribed_pus().is_empty());
+        assert!(audit.idle_cores().is_empty());
+        assert_eq!(audit.numa_spanning_threads().count(), 0);
+        for pu in &pus {
+            let os_index = usize::from(pu.first_set().unwrap());
+            assert!((audit.pu_load(os_index) - 1.0).abs() < 1e-9);
+        }
+        for &(first, second) in audit.cross_numa_pairs() {
+            assert!(first < second);
+            assert!(!audit
+                .thread_nodeset(first)
+                .unwrap()
+                .intersects(audit.thread_nodeset(second).unwrap()));
+        }
+    }
+
+    #[test]
+    fn all_threads_on_first_pu() {
+        let topology = Topology::test_instance();
+        let first_pu = topology.allowed_cpuset().first_set().unwrap();
+        let cpuset = CpuSet::from_range(first_pu..=first_pu);
+        let audit = topology.audit_pinning([&cpuset, &cpuset]);
+        assert!((audit.pu_load(usize::from(first_pu)) - 2.0).abs() < 1e-9);
+        assert_eq!(audit.oversubscribed_pus(), &cpuset);
+        assert!(audit.cross_numa_pairs().is_empty());
+        let num_cores = topology
+            .objects_with_type(ObjectType::Core)
+            .filter(|core| {
+                core.cpuset()
+                    .is_some_and(|cores| cores.intersects(topology.allowed_cpuset()))
+            })
+            .count();
+        assert_eq!(audit.idle_cores().len(), num_cores.saturating_sub(1));
+    }
+}
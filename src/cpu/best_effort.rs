@@ -0,0 +1,194 @@
+//! Best-effort CPU binding
+//!
+//! Some operating systems do not support binding threads to specific CPUs.
+//! Most notably, macOS only lets applications provide scheduling hints in the
+//! form of thread affinity tags: threads that share a tag are preferably
+//! scheduled on CPUs that share an L2 cache, but are not restricted to any
+//! particular set of CPUs.
+//!
+//! [`Topology::bind_cpu()`] fails on such operating systems. This module
+//! provides [`Topology::bind_cpu_best_effort()`], which falls back to such
+//! weaker scheduling hints when possible, and reports what was actually done
+//! in a [`BindingOutcome`] so that callers know when they got degraded
+//! semantics.
+
+use crate::{
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+    },
+    errors::BindingError,
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{ffi::c_int, fmt, ops::Deref};
+
+/// # Best-effort CPU binding
+impl Topology {
+    /// Bind the current process or thread on CPUs given in `set`, or
+    /// approximate this binding if the operating system cannot do it
+    ///
+    /// This first tries [`Topology::bind_cpu()`] with the specified `set` and
+    /// `flags`. If that succeeds, [`BindingOutcome::Bound`] is returned.
+    ///
+    /// On macOS, where CPU binding is not supported, this function then falls
+    /// back to setting a Mach thread affinity tag that is derived from the
+    /// contents of `set`, so that all threads of the current process which
+    /// request the same `set` share an affinity tag. The macOS scheduler then
+    /// tries to run these threads on CPUs which share an L2 cache, but
+    /// nothing prevents them from running on CPUs outside of `set`. This
+    /// degraded outcome is reported as [`BindingOutcome::AffinityTag`].
+    ///
+    /// The fallback is only attempted when binding the current thread (i.e.
+    /// with flag [`THREAD`] or [`ASSUME_SINGLE_THREAD`]), and only if flag
+    /// [`STRICT`] is not set. Apple Silicon Macs ignore affinity tags, so the
+    /// fallback will fail and the original binding error is reported there.
+    ///
+    /// On all other operating systems, this behaves exactly like
+    /// [`Topology::bind_cpu()`].
+    ///
+    /// # Errors
+    ///
+    /// The error from [`Topology::bind_cpu()`] is reported if binding failed
+    /// and no fallback could be applied.
+    ///
+    /// [`ASSUME_SINGLE_THREAD`]: CpuBindingFlags::ASSUME_SINGLE_THREAD
+    /// [`STRICT`]: CpuBindingFlags::STRICT
+    /// [`THREAD`]: CpuBindingFlags::THREAD
+    #[doc(alias = "thread_policy_set")]
+    #[doc(alias = "THREAD_AFFINITY_POLICY")]
+    pub fn bind_cpu_best_effort(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        flags: CpuBindingFlags,
+    ) -> Result<BindingOutcome, BindingError<CpuBindingError>> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(
+            self_: &Topology,
+            set: &CpuSet,
+            flags: CpuBindingFlags,
+        ) -> Result<BindingOutcome, BindingError<CpuBindingError>> {
+            match self_.bind_cpu(set, flags) {
+                Ok(()) => Ok(BindingOutcome::Bound),
+                #[cfg(target_os = "macos")]
+                Err(e)
+                    if matches!(e.cause(), CpuBindingError::BadObject(_))
+                        && flags.intersects(
+                            CpuBindingFlags::THREAD | CpuBindingFlags::ASSUME_SINGLE_THREAD,
+                        )
+                        && !flags.contains(CpuBindingFlags::STRICT) =>
+                {
+                    let tag = macos::affinity_tag(set);
+                    if macos::set_current_thread_affinity_tag(tag) {
+                        Ok(BindingOutcome::AffinityTag(tag))
+                    } else {
+                        Err(e)
+                    }
+                }
+                Err(e) => Err(e),
+            }
+        }
+        polymorphized(self, &set, flags)
+    }
+}
+
+/// What [`Topology::bind_cpu_best_effort()`] actually did
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum BindingOutcome {
+    /// The target is bound to the requested CPU set
+    Bound,
+
+    /// The current thread was given this macOS thread affinity tag
+    ///
+    /// This is only a scheduling hint: the thread may still run on CPUs
+    /// outside of the requested CPU set.
+    AffinityTag(c_int),
+}
+//
+impl BindingOutcome {
+    /// Truth that the requested binding could only be approximated
+    pub fn is_degraded(self) -> bool {
+        self != Self::Bound
+    }
+}
+//
+impl fmt::Display for BindingOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bound => f.pad("bound to requested CPUs"),
+            Self::AffinityTag(tag) => {
+                let s = format!("scheduling hint only (affinity tag {tag})");
+                f.pad(&s)
+            }
+        }
+    }
+}
+
+/// macOS-specific implementation details
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::*;
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+        ptr,
+    };
+
+    /// Nonzero affinity tag that is shared by all identical CPU sets
+    pub(super) fn affinity_tag(set: &CpuSet) -> c_int {
+        // DefaultHasher::new() is deterministic within a given process, which
+        // is all we need since affinity tags are scoped to a single task.
+        let mut hasher = DefaultHasher::new();
+        set.hash(&mut hasher);
+        let max_tag = u64::try_from(c_int::MAX).expect("c_int::MAX is positive");
+        // Tag 0 is THREAD_AFFINITY_TAG_NULL, which means "no affinity"
+        c_int::try_from(hasher.finish() % max_tag + 1).expect("Tag is in c_int range")
+    }
+
+    /// Set the Mach thread affinity tag of the current thread
+    pub(super) fn set_current_thread_affinity_tag(tag: c_int) -> bool {
+        let mut policy = libc::thread_affinity_policy_data_t { affinity_tag: tag };
+        let flavor = libc::thread_policy_flavor_t::try_from(libc::THREAD_AFFINITY_POLICY)
+            .expect("THREAD_AFFINITY_POLICY is positive");
+        // SAFETY: - pthread_self() always returns a valid handle to the
+        //           current thread, which pthread_mach_thread_np() translates
+        //           into a Mach thread port without taking a port reference
+        //         - policy is a valid thread_affinity_policy_data_t, and the
+        //           flavor and count match this policy type
+        let result = unsafe {
+            libc::thread_policy_set(
+                libc::pthread_mach_thread_np(libc::pthread_self()),
+                flavor,
+                ptr::addr_of_mut!(policy).cast(),
+                libc::THREAD_AFFINITY_POLICY_COUNT,
+            )
+        };
+        result == libc::KERN_SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash};
+
+    assert_impl_all!(BindingOutcome:
+        Copy, Debug, fmt::Display, Eq, Hash, Send, Sync
+    );
+
+    #[test]
+    fn outcome() {
+        assert!(!BindingOutcome::Bound.is_degraded());
+        assert!(BindingOutcome::AffinityTag(42).is_degraded());
+        assert_eq!(
+            BindingOutcome::AffinityTag(42).to_string(),
+            "scheduling hint only (affinity tag 42)"
+        );
+    }
+}
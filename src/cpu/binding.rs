@@ -11,7 +11,7 @@
 use crate::{bitmap::Bitmap, object::types::ObjectType, topology::support::CpuBindingSupport};
 use crate::{
     cpu::cpuset::CpuSet,
-    errors::{self, FlagsError, HybridError, RawHwlocError},
+    errors::{self, BindingContext, BindingError, FlagsError, HybridError, RawHwlocError},
     topology::Topology,
     ProcessId, ThreadId,
 };
@@ -64,8 +64,7 @@ impl Topology {
     ///
     /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
     ///
-    /// To unbind, just call the binding method with either a full cpuset or a
-    /// cpuset equal to the system cpuset.
+    /// To unbind, use [`Topology::unbind_cpu()`].
     ///
     /// You must specify exactly one of the [`ASSUME_SINGLE_THREAD`],
     /// [`THREAD`] and [`PROCESS`] binding target flags (listed in order of
@@ -108,13 +107,13 @@ impl Topology {
         &self,
         set: impl Deref<Target = CpuSet>,
         flags: CpuBindingFlags,
-    ) -> Result<(), CpuBindingError> {
+    ) -> Result<(), BindingError<CpuBindingError>> {
         /// Polymorphized version of this function (avoids generics code bloat)
         fn polymorphized(
             self_: &Topology,
             set: &CpuSet,
             flags: CpuBindingFlags,
-        ) -> Result<(), CpuBindingError> {
+        ) -> Result<(), BindingError<CpuBindingError>> {
             // SAFETY: - ThisProgram is the correct target for this operation
             //         - hwloc_set_cpubind is accepted by definition
             //         - FFI is guaranteed to be passed valid (topology, cpuset, flags)
@@ -129,11 +128,12 @@ impl Topology {
                     },
                 )
             };
-            match res {
-                Ok(()) => Ok(()),
-                Err(HybridError::Rust(e)) => Err(e),
-                Err(HybridError::Hwloc(e)) => unreachable!("Unexpected hwloc error: {e}"),
-            }
+            res.map_err(|e| {
+                e.map_cause(|cause| match cause {
+                    HybridError::Rust(e) => e,
+                    HybridError::Hwloc(e) => unreachable!("Unexpected hwloc error: {e}"),
+                })
+            })
         }
         polymorphized(self, &set, flags)
     }
@@ -169,12 +169,33 @@ impl Topology {
     pub fn cpu_binding(
         &self,
         flags: CpuBindingFlags,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        let mut set = CpuSet::new();
+        self.cpu_binding_into(&mut set, flags).map(|()| set)
+    }
+
+    /// Variant of [`Topology::cpu_binding()`] that writes into an existing [`CpuSet`]
+    ///
+    /// This avoids allocating a new [`CpuSet`] on every query, which is useful
+    /// when sampling the CPU binding of the current process or thread at a high
+    /// rate, e.g. in monitoring loops. On success, the previous contents of
+    /// `set` are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::cpu_binding()`].
+    #[doc(alias = "hwloc_get_cpubind")]
+    pub fn cpu_binding_into(
+        &self,
+        set: &mut CpuSet,
+        flags: CpuBindingFlags,
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - ThisProgram is the correct target for this operation
         //         - hwloc_get_cpubind is accepted by definition
         //         - FFI is guaranteed to be passed valid (topology, cpuset, flags)
         unsafe {
             self.cpu_binding_impl(
+                set,
                 flags,
                 CpuBoundObject::ThisProgram,
                 "hwloc_get_cpubind",
@@ -185,6 +206,88 @@ impl Topology {
         }
     }
 
+    /// Binds the current process or thread on given CPUs, then query which
+    /// CPUs it actually ended up bound to
+    ///
+    /// When [`STRICT`] is not set, the operating system is allowed to bind to
+    /// a different set of CPUs than the requested one, for example a larger
+    /// set if it cannot bind to individual [`PU`]s. This variant of
+    /// [`Topology::bind_cpu()`] lets you detect such degraded placements by
+    /// querying the binding with [`Topology::cpu_binding()`] right after
+    /// setting it, so that you can log them or react accordingly.
+    ///
+    /// Flag [`NO_MEMORY_BINDING`] is only used when setting the binding.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Topology::bind_cpu()`] or [`Topology::cpu_binding()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::binding::CpuBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let mut requested = topology.cpuset().clone_target();
+    /// requested.singlify();
+    /// if let Ok(achieved) = topology.bind_cpu_verified(&requested, CpuBindingFlags::THREAD) {
+    ///     if achieved != requested {
+    ///         eprintln!("Requested binding to {requested}, but got {achieved}");
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`NO_MEMORY_BINDING`]: CpuBindingFlags::NO_MEMORY_BINDING
+    /// [`PU`]: ObjectType::PU
+    /// [`STRICT`]: CpuBindingFlags::STRICT
+    pub fn bind_cpu_verified(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+        flags: CpuBindingFlags,
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        self.bind_cpu(set, flags)
+            .map_err(|e| e.map_cause(HybridError::Rust))?;
+        self.cpu_binding(flags - CpuBindingFlags::NO_MEMORY_BINDING)
+    }
+
+    /// Undo the CPU binding of the current process or thread
+    ///
+    /// This binds the current process or thread to all CPUs that it is allowed
+    /// to run on, as given by [`Topology::allowed_cpuset()`]. Binding to a
+    /// full CPU set or to [`Topology::complete_cpuset()`] would not be
+    /// appropriate, as these sets may contain CPUs which the process is not
+    /// allowed to use (e.g. due to Linux cgroups), which makes binding fail on
+    /// some operating systems.
+    ///
+    /// Note that if this topology was restricted to a subset of the machine,
+    /// [`Topology::allowed_cpuset()`] is restricted accordingly, so the
+    /// process or thread will still be confined to that subset.
+    ///
+    /// Binding target flags are handled as in [`Topology::bind_cpu()`]. See
+    /// also [`Topology::unbind_memory()`] for the memory binding counterpart.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Topology::bind_cpu()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::binding::CpuBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let mut core = topology.cpuset().clone_target();
+    /// core.singlify();
+    /// if topology.bind_cpu(&core, CpuBindingFlags::THREAD).is_ok() {
+    ///     // ...do some work on that core...
+    ///     topology.unbind_cpu(CpuBindingFlags::THREAD)?;
+    ///     let binding = topology.cpu_binding(CpuBindingFlags::THREAD)?;
+    ///     assert!(binding.includes(topology.allowed_cpuset()));
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn unbind_cpu(&self, flags: CpuBindingFlags) -> Result<(), BindingError<CpuBindingError>> {
+        self.bind_cpu(self.allowed_cpuset(), flags)
+    }
+
     /// Binds a process (identified by its `pid`) on given CPUs
     ///
     /// As a special case on Linux, if a tid (thread ID) is supplied instead of
@@ -222,7 +325,7 @@ impl Topology {
         pid: ProcessId,
         set: impl Deref<Target = CpuSet>,
         flags: CpuBindingFlags,
-    ) -> Result<(), HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - ProcessOrThread is the correct target for this operation
         //         - hwloc_set_proc_cpubind with pid argument curried away
         //           behaves like hwloc_set_cpubind
@@ -282,7 +385,29 @@ impl Topology {
         &self,
         pid: ProcessId,
         flags: CpuBindingFlags,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        let mut set = CpuSet::new();
+        self.process_cpu_binding_into(&mut set, pid, flags)
+            .map(|()| set)
+    }
+
+    /// Variant of [`Topology::process_cpu_binding()`] that writes into an existing [`CpuSet`]
+    ///
+    /// This avoids allocating a new [`CpuSet`] on every query, which is useful
+    /// when sampling the CPU binding of a process at a high rate, e.g. in
+    /// monitoring loops. On success, the previous contents of `set` are
+    /// overwritten.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::process_cpu_binding()`].
+    #[doc(alias = "hwloc_get_proc_cpubind")]
+    pub fn process_cpu_binding_into(
+        &self,
+        set: &mut CpuSet,
+        pid: ProcessId,
+        flags: CpuBindingFlags,
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - ProcessOrThread is the correct target for this operation
         //         - hwloc_get_proc_cpubind with pid argument curried away
         //           behaves like hwloc_get_cpubind
@@ -291,6 +416,7 @@ impl Topology {
         //           able to handle an invalid PID
         unsafe {
             self.cpu_binding_impl(
+                set,
                 flags,
                 CpuBoundObject::ProcessOrThread(pid),
                 "hwloc_get_proc_cpubind",
@@ -329,7 +455,7 @@ impl Topology {
         tid: ThreadId,
         set: impl Deref<Target = CpuSet>,
         flags: CpuBindingFlags,
-    ) -> Result<(), HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - Thread is the correct target for this operation
         //         - hwloc_set_thread_cpubind with tid argument curried away
         //           behaves like hwloc_set_cpubind
@@ -377,7 +503,29 @@ impl Topology {
         &self,
         tid: ThreadId,
         flags: CpuBindingFlags,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        let mut set = CpuSet::new();
+        self.thread_cpu_binding_into(&mut set, tid, flags)
+            .map(|()| set)
+    }
+
+    /// Variant of [`Topology::thread_cpu_binding()`] that writes into an existing [`CpuSet`]
+    ///
+    /// This avoids allocating a new [`CpuSet`] on every query, which is useful
+    /// when sampling the CPU binding of a thread at a high rate, e.g. in
+    /// monitoring loops. On success, the previous contents of `set` are
+    /// overwritten.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::thread_cpu_binding()`].
+    #[doc(alias = "hwloc_get_thread_cpubind")]
+    pub fn thread_cpu_binding_into(
+        &self,
+        set: &mut CpuSet,
+        tid: ThreadId,
+        flags: CpuBindingFlags,
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - Thread is the correct target for this operation
         //         - hwloc_get_thread_cpubind with tid argument curried away
         //           behaves like hwloc_get_cpubind
@@ -386,6 +534,7 @@ impl Topology {
         //           able to handle an invalid TID
         unsafe {
             self.cpu_binding_impl(
+                set,
                 flags,
                 CpuBoundObject::Thread(tid),
                 "hwloc_get_thread_cpubind",
@@ -436,12 +585,33 @@ impl Topology {
     pub fn last_cpu_location(
         &self,
         flags: CpuBindingFlags,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        let mut set = CpuSet::new();
+        self.last_cpu_location_into(&mut set, flags).map(|()| set)
+    }
+
+    /// Variant of [`Topology::last_cpu_location()`] that writes into an existing [`CpuSet`]
+    ///
+    /// This avoids allocating a new [`CpuSet`] on every query, which is useful
+    /// when sampling the last CPU location of the current process or thread at
+    /// a high rate, e.g. in monitoring loops. On success, the previous contents
+    /// of `set` are overwritten.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::last_cpu_location()`].
+    #[doc(alias = "hwloc_get_last_cpu_location")]
+    pub fn last_cpu_location_into(
+        &self,
+        set: &mut CpuSet,
+        flags: CpuBindingFlags,
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - ThisProgram is the correct target for this operation
         //         - hwloc_get_last_cpu_location is accepted by definition
         //         - FFI is guaranteed to be passed valid (topology, cpuset, flags)
         unsafe {
             self.last_cpu_location_impl(
+                set,
                 flags,
                 CpuBoundObject::ThisProgram,
                 "hwloc_get_last_cpu_location",
@@ -488,7 +658,29 @@ impl Topology {
         &self,
         pid: ProcessId,
         flags: CpuBindingFlags,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>> {
+        let mut set = CpuSet::new();
+        self.last_process_cpu_location_into(&mut set, pid, flags)
+            .map(|()| set)
+    }
+
+    /// Variant of [`Topology::last_process_cpu_location()`] that writes into an existing [`CpuSet`]
+    ///
+    /// This avoids allocating a new [`CpuSet`] on every query, which is useful
+    /// when sampling the last CPU location of a process at a high rate, e.g. in
+    /// monitoring loops. On success, the previous contents of `set` are
+    /// overwritten.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::last_process_cpu_location()`].
+    #[doc(alias = "hwloc_get_proc_last_cpu_location")]
+    pub fn last_process_cpu_location_into(
+        &self,
+        set: &mut CpuSet,
+        pid: ProcessId,
+        flags: CpuBindingFlags,
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - ProcessOrThread is the correct target for this operation
         //         - hwloc_get_proc_last_cpu_location with pid argument curried
         //           away behaves like hwloc_get_last_cpu_location
@@ -497,6 +689,7 @@ impl Topology {
         //           able to handle an invalid PID
         unsafe {
             self.last_cpu_location_impl(
+                set,
                 flags,
                 CpuBoundObject::ProcessOrThread(pid),
                 "hwloc_get_proc_last_cpu_location",
@@ -528,9 +721,19 @@ impl Topology {
         target: CpuBoundObject,
         api: &'static str,
         ffi: impl FnOnce(hwloc_const_topology_t, hwloc_const_cpuset_t, hwloc_cpubind_flags_t) -> c_int,
-    ) -> Result<(), HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "bind_cpu",
+            api,
+            %target,
+            ?flags,
+            set_weight = ?set.weight(),
+        )
+        .entered();
+        let context = || BindingContext::new(api, target, flags, Some(set));
         let Some(flags) = flags.validate(target, CpuBindingOperation::SetBinding) else {
-            return Err(CpuBindingError::from(flags).into());
+            return Err(BindingError::new(context(), CpuBindingError::from(flags)));
         };
         call_hwloc(api, target, Some(set), || {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
@@ -539,6 +742,7 @@ impl Topology {
             //         - flags should be valid if target is valid
             ffi(self.as_ptr(), set.as_ptr(), flags.bits())
         })
+        .map_err(|e| BindingError::new(context(), e))
     }
 
     /// Binding for `hwloc_get_cpubind`-like functions
@@ -552,14 +756,24 @@ impl Topology {
     ///   with a valid (topology, out bitmap, flags) tuple
     unsafe fn cpu_binding_impl(
         &self,
+        set: &mut CpuSet,
         flags: CpuBindingFlags,
         target: CpuBoundObject,
         api: &'static str,
         ffi: impl FnOnce(hwloc_const_topology_t, hwloc_cpuset_t, hwloc_cpubind_flags_t) -> c_int,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - GetBinding is the valid operation tag for this FFI
         //         - Rest is per function precondition
-        unsafe { self.get_cpuset(flags, target, CpuBindingOperation::GetBinding, api, ffi) }
+        unsafe {
+            self.get_cpuset(
+                set,
+                flags,
+                target,
+                CpuBindingOperation::GetBinding,
+                api,
+                ffi,
+            )
+        }
     }
 
     /// Binding for `hwloc_get_last_cpu_location`-like functions
@@ -573,15 +787,17 @@ impl Topology {
     ///   with a valid (topology, out bitmap, flags) tuple
     unsafe fn last_cpu_location_impl(
         &self,
+        set: &mut CpuSet,
         flags: CpuBindingFlags,
         target: CpuBoundObject,
         api: &'static str,
         ffi: impl FnOnce(hwloc_const_topology_t, hwloc_cpuset_t, hwloc_cpubind_flags_t) -> c_int,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
         // SAFETY: - GetLastLocation is the valid operation tag for this FFI
         //         - Rest is per function precondition
         unsafe {
             self.get_cpuset(
+                set,
                 flags,
                 target,
                 CpuBindingOperation::GetLastLocation,
@@ -605,16 +821,20 @@ impl Topology {
     ///   with a valid (topology, out bitmap, flags) tuple
     unsafe fn get_cpuset(
         &self,
+        cpuset: &mut CpuSet,
         flags: CpuBindingFlags,
         target: CpuBoundObject,
         operation: CpuBindingOperation,
         api: &'static str,
         ffi: impl FnOnce(hwloc_const_topology_t, hwloc_cpuset_t, hwloc_cpubind_flags_t) -> c_int,
-    ) -> Result<CpuSet, HybridError<CpuBindingError>> {
+    ) -> Result<(), BindingError<HybridError<CpuBindingError>>> {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("get_cpu_binding", api, %target, %operation, ?flags).entered();
+        let context = || BindingContext::new(api, target, flags, None);
         let Some(flags) = flags.validate(target, operation) else {
-            return Err(CpuBindingError::from(flags).into());
+            return Err(BindingError::new(context(), CpuBindingError::from(flags)));
         };
-        let mut cpuset = CpuSet::new();
         call_hwloc(api, target, None, || {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - Bitmap is trusted to contain a valid ptr (type invariant)
@@ -624,7 +844,7 @@ impl Topology {
             //         - flags should be valid if target & operation are valid
             ffi(self.as_ptr(), cpuset.as_mut_ptr(), flags.bits())
         })
-        .map(|()| cpuset)
+        .map_err(|e| BindingError::new(context(), e))
     }
 }
 
@@ -911,3 +1131,273 @@ pub(crate) fn call_hwloc(
     }
     translate_result(object, cpuset, errors::call_hwloc_int_normal(api, ffi))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        current_thread_id,
+        topology::support::{CpuBindingSupport, FeatureSupport},
+    };
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Truth that a CPU binding feature is supported
+    fn supports(topology: &Topology, feature: fn(&CpuBindingSupport) -> bool) -> bool {
+        topology.supports(FeatureSupport::cpu_binding, feature)
+    }
+
+    /// CPU set that is not part of the topology, used to check that `_into`
+    /// variants overwrite their output
+    fn garbage(topology: &Topology) -> CpuSet {
+        let after_last = usize::from(topology.complete_cpuset().last_set().unwrap()) + 1;
+        CpuSet::from_range(after_last..=after_last)
+    }
+
+    /// Check a `_into` query against its allocating variant
+    fn check_into(
+        topology: &Topology,
+        allocating: impl FnOnce() -> Result<CpuSet, BindingError<HybridError<CpuBindingError>>>,
+        into: impl FnOnce(&mut CpuSet) -> Result<(), BindingError<HybridError<CpuBindingError>>>,
+    ) {
+        let expected = allocating();
+        let mut set = garbage(topology);
+        let result = into(&mut set);
+        assert_eq!(result.map(|()| set), expected);
+    }
+
+    /// Bind the current thread to its first allowed PU, if supported, and
+    /// return its previous binding
+    fn bind_thread_to_first_pu(topology: &Topology) -> Option<CpuSet> {
+        if !(supports(topology, CpuBindingSupport::set_current_thread)
+            && supports(topology, CpuBindingSupport::get_current_thread))
+        {
+            return None;
+        }
+        let previous = topology.cpu_binding(CpuBindingFlags::THREAD).unwrap();
+        let first = topology.allowed_cpuset().first_set().unwrap();
+        topology
+            .bind_cpu(&CpuSet::from_range(first..=first), CpuBindingFlags::THREAD)
+            .unwrap();
+        Some(previous)
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing() {
+        use std::sync::{Arc, Mutex, PoisonError};
+        use tracing::{
+            field::{Field, Visit},
+            span::{Attributes, Id, Record},
+            subscriber::with_default,
+            Event, Metadata, Subscriber,
+        };
+
+        /// Minimal subscriber that records span names and event fields
+        #[derive(Clone, Default)]
+        struct Recorder(Arc<Mutex<Vec<String>>>);
+        //
+        impl Recorder {
+            fn push(&self, entry: String) {
+                self.0
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push(entry);
+            }
+        }
+        //
+        impl Visit for Recorder {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.push(format!("{}={value:?}", field.name()));
+            }
+        }
+        //
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                self.push(format!("span {}", span.metadata().name()));
+                span.record(&mut self.clone());
+                Id::from_u64(1)
+            }
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                event.record(&mut self.clone());
+            }
+            fn enter(&self, _span: &Id) {}
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let topology = Topology::test_instance();
+        let invalid = CpuBindingFlags::THREAD | CpuBindingFlags::PROCESS;
+        let recorder = Recorder::default();
+        with_default(recorder.clone(), || {
+            // Invalid flags are reported in the span, without any hwloc call
+            topology.bind_cpu(topology.cpuset(), invalid).unwrap_err();
+
+            // Valid queries are reported along with the hwloc call
+            let _binding = topology.cpu_binding(CpuBindingFlags::THREAD);
+        });
+        let entries = recorder.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let weight = topology.cpuset().weight();
+        let expected_prefix = [
+            "span bind_cpu".to_owned(),
+            "api=\"hwloc_set_cpubind\"".to_owned(),
+            "target=the current process/thread".to_owned(),
+            format!("flags={invalid:?}"),
+            format!("set_weight={weight:?}"),
+            "span get_cpu_binding".to_owned(),
+            "api=\"hwloc_get_cpubind\"".to_owned(),
+        ];
+        assert_eq!(entries[..expected_prefix.len()], expected_prefix[..]);
+        assert!(entries[expected_prefix.len()..]
+            .iter()
+            .any(|entry| entry == "api=\"hwloc_get_cpubind\""));
+    }
+
+    #[test]
+    fn error_context() {
+        let topology = Topology::test_instance();
+        let invalid = CpuBindingFlags::THREAD | CpuBindingFlags::PROCESS;
+
+        let error = topology.bind_cpu(topology.cpuset(), invalid).unwrap_err();
+        assert_eq!(error.cause(), &CpuBindingError::from(invalid));
+        let context = error.context();
+        assert_eq!(context.api(), "hwloc_set_cpubind");
+        assert_eq!(context.target(), CpuBoundObject::ThisProgram.to_string());
+        assert_eq!(context.flags(), format!("{invalid:?}"));
+        assert_eq!(context.set(), Some(topology.cpuset().to_string().as_str()));
+
+        let error = topology.cpu_binding(invalid).unwrap_err();
+        assert_eq!(
+            error.cause(),
+            &HybridError::Rust(CpuBindingError::from(invalid))
+        );
+        assert_eq!(error.context().api(), "hwloc_get_cpubind");
+        assert_eq!(error.context().set(), None);
+    }
+
+    #[test]
+    fn bind_cpu_verified() {
+        let topology = Topology::test_instance();
+        let Some(previous) = bind_thread_to_first_pu(topology) else {
+            return;
+        };
+        let flags = CpuBindingFlags::THREAD;
+        let mut target = topology.allowed_cpuset().clone_target();
+        target.singlify();
+        let achieved = topology.bind_cpu_verified(&target, flags).unwrap();
+        assert_eq!(achieved, topology.cpu_binding(flags).unwrap());
+        assert!(achieved.includes(&target));
+        topology.bind_cpu(&previous, flags).unwrap();
+    }
+
+    #[test]
+    fn unbind_cpu() {
+        let topology = Topology::test_instance();
+        let Some(previous) = bind_thread_to_first_pu(topology) else {
+            return;
+        };
+        let flags = CpuBindingFlags::THREAD;
+        topology.unbind_cpu(flags).unwrap();
+        assert_eq!(
+            topology.cpu_binding(flags).unwrap(),
+            *topology.allowed_cpuset()
+        );
+        topology.bind_cpu(&previous, flags).unwrap();
+    }
+
+    #[test]
+    fn cpu_binding_into() {
+        let topology = Topology::test_instance();
+        if !supports(topology, CpuBindingSupport::get_current_thread) {
+            return;
+        }
+        let flags = CpuBindingFlags::THREAD;
+        check_into(
+            topology,
+            || topology.cpu_binding(flags),
+            |set| topology.cpu_binding_into(set, flags),
+        );
+    }
+
+    #[test]
+    fn process_cpu_binding_into() {
+        let topology = Topology::test_instance();
+        if !supports(topology, CpuBindingSupport::get_process) {
+            return;
+        }
+        let pid = std::process::id();
+        let flags = CpuBindingFlags::empty();
+        check_into(
+            topology,
+            || topology.process_cpu_binding(pid, flags),
+            |set| topology.process_cpu_binding_into(set, pid, flags),
+        );
+    }
+
+    #[test]
+    fn thread_cpu_binding_into() {
+        let topology = Topology::test_instance();
+        if !supports(topology, CpuBindingSupport::get_thread) {
+            return;
+        }
+        let tid = current_thread_id();
+        let flags = CpuBindingFlags::empty();
+        check_into(
+            topology,
+            || topology.thread_cpu_binding(tid, flags),
+            |set| topology.thread_cpu_binding_into(set, tid, flags),
+        );
+    }
+
+    #[test]
+    fn last_cpu_location_into() {
+        let topology = Topology::test_instance();
+        if !supports(
+            topology,
+            CpuBindingSupport::get_current_thread_last_cpu_location,
+        ) {
+            return;
+        }
+        let flags = CpuBindingFlags::THREAD;
+
+        // The thread can only be pinned down if binding is supported,
+        // otherwise it may migrate between the two queries
+        let Some(previous) = bind_thread_to_first_pu(topology) else {
+            let mut set = garbage(topology);
+            topology.last_cpu_location_into(&mut set, flags).unwrap();
+            assert!(topology.complete_cpuset().includes(&set));
+            return;
+        };
+        check_into(
+            topology,
+            || topology.last_cpu_location(flags),
+            |set| topology.last_cpu_location_into(set, flags),
+        );
+        topology.bind_cpu(&previous, flags).unwrap();
+    }
+
+    #[test]
+    fn last_process_cpu_location_into() {
+        let topology = Topology::test_instance();
+        if !supports(topology, CpuBindingSupport::get_process_last_cpu_location) {
+            return;
+        }
+
+        // Other threads of the test process may migrate at any time, so
+        // only the validity of the output can be checked
+        let pid = std::process::id();
+        let flags = CpuBindingFlags::empty();
+        let expected = topology.last_process_cpu_location(pid, flags);
+        let mut set = garbage(topology);
+        let result = topology.last_process_cpu_location_into(&mut set, pid, flags);
+        assert_eq!(result.is_ok(), expected.is_ok());
+        if result.is_ok() {
+            assert!(!set.is_empty());
+            assert!(topology.complete_cpuset().includes(&set));
+        }
+    }
+}
@@ -8,13 +8,18 @@
 //! This functionality is an hwlocality-specific extension to the hwloc API.
 
 use crate::{
-    object::{attributes::ObjectAttributes, types::ObjectType},
+    object::{
+        attributes::{CacheAssociativity, ObjectAttributes},
+        types::ObjectType,
+        TopologyObject,
+    },
     topology::Topology,
 };
 use arrayvec::ArrayVec;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
+use std::num::{NonZeroU64, NonZeroUsize};
 
 /// # CPU cache statistics
 impl Topology {
@@ -196,3 +201,280 @@ impl CpuCacheStats {
         &self.total_data_cache_sizes[..]
     }
 }
+
+/// CPU cache types, from closest to farthest from the PUs
+const CACHE_TYPES: &[ObjectType] = &[
+    ObjectType::L1Cache,
+    ObjectType::L1ICache,
+    ObjectType::L2Cache,
+    ObjectType::L2ICache,
+    ObjectType::L3Cache,
+    ObjectType::L3ICache,
+    ObjectType::L4Cache,
+    ObjectType::L5Cache,
+];
+
+/// # CPU cache hierarchy
+impl Topology {
+    /// Summarize the properties of each CPU cache level
+    ///
+    /// One [`CacheLevelSummary`] is returned for each CPU cache type that is
+    /// present in the topology, from the closest to the farthest from the PUs.
+    /// Instruction caches are only reported if they were not filtered out when
+    /// building the topology.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// for level in topology.cache_summary() {
+    ///     println!(
+    ///         "{}: {} cache(s) of {:?} bytes, shared by up to {} PU(s)",
+    ///         level.cache_type(),
+    ///         level.count(),
+    ///         level.size(),
+    ///         level.sharing_degree()
+    ///     );
+    ///     assert!(level.count() > 0);
+    /// }
+    /// ```
+    pub fn cache_summary(&self) -> Vec<CacheLevelSummary> {
+        CACHE_TYPES
+            .iter()
+            .filter_map(|&cache_type| CacheLevelSummary::new(self, cache_type))
+            .collect()
+    }
+
+    /// CPU caches that the PU with OS index `pu` has access to, from the
+    /// closest to the farthest
+    ///
+    /// Returns `None` if there is no PU with this OS index in the topology.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let first_pu = topology.cpuset().first_set().unwrap();
+    /// for cache in topology.caches_of(first_pu.into()).unwrap() {
+    ///     assert!(cache.object_type().is_cpu_cache());
+    ///     println!("{cache}");
+    /// }
+    /// ```
+    pub fn caches_of(&self, pu: usize) -> Option<impl Iterator<Item = &TopologyObject> + Clone> {
+        let pu = self.pu_with_os_index(pu)?;
+        Some(
+            pu.ancestors()
+                .filter(|ancestor| ancestor.object_type().is_cpu_cache()),
+        )
+    }
+}
+
+/// Properties of one CPU cache level
+///
+/// Caches of a given level may not be identical, for example on hybrid CPUs.
+/// In this case, the properties are chosen to be conservative for the purpose
+/// of sizing cache blocking parameters, as detailed in the documentation of
+/// each accessor.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CacheLevelSummary {
+    /// Type of cache
+    cache_type: ObjectType,
+
+    /// Number of caches of this type
+    count: usize,
+
+    /// Smallest known cache size
+    size: Option<NonZeroU64>,
+
+    /// Largest known cache line size
+    line_size: Option<NonZeroUsize>,
+
+    /// Associativity, if common to all caches
+    associativity: CacheAssociativity,
+
+    /// Largest number of PUs sharing one cache
+    sharing_degree: usize,
+}
+
+impl CacheLevelSummary {
+    /// Summarize the caches of type `cache_type`, if there is at least one
+    fn new(topology: &Topology, cache_type: ObjectType) -> Option<Self> {
+        let mut caches = topology.objects_with_type(cache_type).peekable();
+        caches.peek()?;
+        let mut summary = Self {
+            cache_type,
+            count: 0,
+            size: None,
+            line_size: None,
+            associativity: CacheAssociativity::Unknown,
+            sharing_degree: 0,
+        };
+        let mut associativity = None;
+        for cache in caches {
+            let Some(ObjectAttributes::Cache(attributes)) = cache.attributes() else {
+                unreachable!("Caches should have cache attributes")
+            };
+            summary.count += 1;
+            if let Some(size) = attributes.size() {
+                summary.size = Some(summary.size.map_or(size, |min| min.min(size)));
+            }
+            if let Some(line_size) = attributes.line_size() {
+                summary.line_size = Some(
+                    summary
+                        .line_size
+                        .map_or(line_size, |max| max.max(line_size)),
+                );
+            }
+            associativity = match associativity {
+                None => Some(attributes.associativity()),
+                Some(common) if common == attributes.associativity() => Some(common),
+                Some(_) => Some(CacheAssociativity::Unknown),
+            };
+            let num_pus = cache
+                .cpuset()
+                .and_then(|set| set.weight())
+                .expect("Caches should have finite cpusets");
+            summary.sharing_degree = summary.sharing_degree.max(num_pus);
+        }
+        summary.associativity = associativity.unwrap_or_default();
+        Some(summary)
+    }
+
+    /// Type of cache
+    ///
+    /// This tells apart data (or unified) caches from instruction caches.
+    pub fn cache_type(&self) -> ObjectType {
+        self.cache_type
+    }
+
+    /// Number of caches of this type in the topology
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Smallest cache size in bytes, if known
+    ///
+    /// Caches whose size is unknown are ignored.
+    pub fn size(&self) -> Option<NonZeroU64> {
+        self.size
+    }
+
+    /// Largest cache line size in bytes, if known
+    ///
+    /// This is the granularity that should be used to avoid false sharing.
+    /// Caches whose line size is unknown are ignored.
+    pub fn line_size(&self) -> Option<NonZeroUsize> {
+        self.line_size
+    }
+
+    /// Cache associativity
+    ///
+    /// If caches of this type do not all have the same associativity, this is
+    /// reported as [`CacheAssociativity::Unknown`].
+    pub fn associativity(&self) -> CacheAssociativity {
+        self.associativity
+    }
+
+    /// Largest number of PUs sharing one cache of this type
+    pub fn sharing_degree(&self) -> usize {
+        self.sharing_degree
+    }
+
+    /// Smallest cache capacity per PU in bytes, if known
+    ///
+    /// This is the [`size()`](Self::size()) divided by the
+    /// [`sharing_degree()`](Self::sharing_degree()), which is what each thread
+    /// can expect to get when all PUs are busy.
+    pub fn size_per_pu(&self) -> Option<u64> {
+        let sharing_degree =
+            u64::try_from(self.sharing_degree).expect("Number of PUs should fit in u64");
+        Some(self.size?.get() / sharing_degree.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::synthetic::SyntheticBuilder;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// 2 packages with one L3 cache each, 2 L2 and L1 caches per L3 cache,
+    /// one core per L1 cache and 2 PUs per core
+    fn two_packages() -> Topology {
+        SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(1)
+            .l3(1)
+            .l2(2)
+            .l1(1)
+            .cores(1)
+            .pus(2)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn cache_summary() {
+        let topology = two_packages();
+        let summary = topology.cache_summary();
+        let levels = summary
+            .iter()
+            .map(|level| (level.cache_type(), level.count(), level.sharing_degree()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            levels,
+            [
+                (ObjectType::L1Cache, 4, 2),
+                (ObjectType::L2Cache, 4, 2),
+                (ObjectType::L3Cache, 2, 4),
+            ]
+        );
+        for level in &summary {
+            let first = topology
+                .objects_with_type(level.cache_type())
+                .next()
+                .unwrap();
+            let Some(ObjectAttributes::Cache(attributes)) = first.attributes() else {
+                unreachable!("Caches should have cache attributes")
+            };
+            assert_eq!(level.size(), attributes.size());
+            assert_eq!(level.line_size(), attributes.line_size());
+            assert_eq!(level.associativity(), attributes.associativity());
+            let sharing_degree = u64::try_from(level.sharing_degree()).unwrap();
+            assert_eq!(
+                level.size_per_pu(),
+                level.size().map(|size| size.get() / sharing_degree)
+            );
+        }
+    }
+
+    #[test]
+    fn caches_of() {
+        let topology = two_packages();
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            let os_index = pu.os_index().unwrap();
+            let caches = topology.caches_of(os_index).unwrap().collect::<Vec<_>>();
+            assert_eq!(
+                caches
+                    .iter()
+                    .map(|cache| cache.object_type())
+                    .collect::<Vec<_>>(),
+                [
+                    ObjectType::L1Cache,
+                    ObjectType::L2Cache,
+                    ObjectType::L3Cache
+                ]
+            );
+            for cache in caches {
+                assert!(cache.cpuset().unwrap().is_set(os_index));
+            }
+        }
+        let after_last = topology.objects_with_type(ObjectType::PU).count();
+        assert!(topology.caches_of(after_last).is_none());
+    }
+}
@@ -0,0 +1,356 @@
+//! Location expressions
+//!
+//! The `hwloc-calc` command-line tool lets users designate sets of CPUs using
+//! location expressions like `numa:0.core:2-3.pu:0` or `core:all ~pu:1`. This
+//! module lets you evaluate such expressions against a [`Topology`], which
+//! makes it possible for configuration files to specify CPU bindings
+//! symbolically. Most of its functionality is exposed via
+//! [`Topology::cpuset_from_expression()`].
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    object::{
+        attributes::ObjectAttributes,
+        types::{CacheType, ObjectType},
+        TopologyObject,
+    },
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use thiserror::Error;
+
+/// # Location expressions
+impl Topology {
+    /// Evaluate an `hwloc-calc` style location expression into a [`CpuSet`]
+    ///
+    /// An expression is a whitespace-separated list of locations. Each
+    /// location designates a set of CPUs, and is combined with the CPUs
+    /// designated by previous locations according to its prefix:
+    ///
+    /// - No prefix or `+`: add CPUs to the set
+    /// - `~`: remove CPUs from the set
+    /// - `x`: only keep CPUs that are also in this location
+    /// - `^`: toggle CPUs (symmetric difference)
+    ///
+    /// A location is either `all`, which designates all CPUs of the topology,
+    /// a hexadecimal CPU mask like `0xff`, or a `.`-separated chain of
+    /// `type:indices` components like `numa:0.core:2-3.pu:0`. In such a chain,
+    /// each component selects objects of a given type by logical index within
+    /// the objects selected by the previous component, starting from the root
+    /// of the topology.
+    ///
+    /// Object types are designated by their name (`machine`, `package` or
+    /// `socket`, `die`, `core`, `pu`, `numa` or `node`, `group`), or by a CPU
+    /// cache name like `l3`, `l2d` or `l1icache`. Type names are not case
+    /// sensitive.
+    ///
+    /// Indices can be `all`, `odd`, `even`, a single index `N`, a range `N-M`,
+    /// an open range `N-`, a count `N:C` designating `C` consecutive objects
+    /// starting at `N`, or a comma-separated list of the above. Indices that
+    /// are out of range are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadLocation`] if a location is not syntactically valid
+    /// - [`BadIndices`] if the indices of a component are not valid
+    /// - [`UnknownType`] if an object type name is not recognized
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let first_cores = topology.cpuset_from_expression("core:0-1")?;
+    /// let all_but_first_pu = topology.cpuset_from_expression("all ~pu:0")?;
+    /// println!("{first_cores} / {all_but_first_pu}");
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`BadIndices`]: LocationError::BadIndices
+    /// [`BadLocation`]: LocationError::BadLocation
+    /// [`UnknownType`]: LocationError::UnknownType
+    pub fn cpuset_from_expression(&self, expression: &str) -> Result<CpuSet, LocationError> {
+        let mut result = CpuSet::new();
+        for location in expression.split_whitespace() {
+            if let Some(location) = location.strip_prefix('~') {
+                result -= self.location_cpuset(location)?;
+            } else if let Some(location) = location.strip_prefix('x') {
+                result &= self.location_cpuset(location)?;
+            } else if let Some(location) = location.strip_prefix('^') {
+                result ^= self.location_cpuset(location)?;
+            } else {
+                let location = location.strip_prefix('+').unwrap_or(location);
+                result |= self.location_cpuset(location)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Evaluate a single location, without prefix, into a [`CpuSet`]
+    fn location_cpuset(&self, location: &str) -> Result<CpuSet, LocationError> {
+        // Handle special locations
+        let bad_location = || LocationError::BadLocation(location.to_owned());
+        if location.eq_ignore_ascii_case("all") {
+            return Ok(self.cpuset().clone_target());
+        }
+        if let Some(hex) = location
+            .strip_prefix("0x")
+            .or_else(|| location.strip_prefix("0X"))
+        {
+            return parse_hex_mask(hex).ok_or_else(bad_location);
+        }
+
+        // Walk down the chain of type:indices components
+        let mut objects = vec![self.root_object()];
+        for component in location.split('.') {
+            let (ty, indices) = component.split_once(':').ok_or_else(bad_location)?;
+            let filter = ObjectFilter::parse(ty)?;
+            let mut selected = Vec::new();
+            for parent in objects {
+                let candidates = self
+                    .objects()
+                    .filter(|obj| filter.matches(obj) && is_inside(obj, parent))
+                    .collect::<Vec<_>>();
+                for idx in select_indices(indices, candidates.len())? {
+                    selected.push(candidates[idx]);
+                }
+            }
+            objects = selected;
+        }
+
+        // Merge the CPU sets of the selected objects
+        let mut result = CpuSet::new();
+        for obj in objects {
+            if let Some(cpuset) = obj.cpuset() {
+                result |= cpuset;
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Error while evaluating a location expression
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+pub enum LocationError {
+    /// A location is not syntactically valid
+    #[error("invalid location {0:?}")]
+    BadLocation(String),
+
+    /// The indices of a location component are not valid
+    #[error("invalid object indices {0:?}")]
+    BadIndices(String),
+
+    /// An object type name was not recognized
+    #[error("unknown object type {0:?}")]
+    UnknownType(String),
+}
+
+/// Criterion used to select objects in a location component
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ObjectFilter {
+    /// Objects of a certain type
+    Type(ObjectType),
+
+    /// CPU caches of a certain level, optionally of a certain type
+    Cache(usize, Option<CacheType>),
+}
+//
+impl ObjectFilter {
+    /// Parse an object type name
+    fn parse(name: &str) -> Result<Self, LocationError> {
+        let unknown = || LocationError::UnknownType(name.to_owned());
+        let lower = name.to_ascii_lowercase();
+        let ty = match lower.as_str() {
+            "machine" => ObjectType::Machine,
+            "package" | "socket" => ObjectType::Package,
+            #[cfg(feature = "hwloc-2_1_0")]
+            "die" => ObjectType::Die,
+            "core" => ObjectType::Core,
+            "pu" => ObjectType::PU,
+            "numa" | "node" | "numanode" => ObjectType::NUMANode,
+            "group" => ObjectType::Group,
+            _ => {
+                // Cache names look like l3, l2d, l1icache...
+                let cache = lower.strip_prefix('l').ok_or_else(unknown)?;
+                let cache = cache.strip_suffix("cache").unwrap_or(cache);
+                let suffixes = [
+                    ('d', CacheType::Data),
+                    ('i', CacheType::Instruction),
+                    ('u', CacheType::Unified),
+                ];
+                let (level, cache_type) = suffixes
+                    .into_iter()
+                    .find_map(|(suffix, ty)| Some((cache.strip_suffix(suffix)?, Some(ty))))
+                    .unwrap_or((cache, None));
+                let level = level.parse::<usize>().map_err(|_| unknown())?;
+                return Ok(Self::Cache(level, cache_type));
+            }
+        };
+        Ok(Self::Type(ty))
+    }
+
+    /// Truth that an object matches this filter
+    fn matches(self, obj: &TopologyObject) -> bool {
+        match self {
+            Self::Type(ty) => obj.object_type() == ty,
+            Self::Cache(level, cache_type) => {
+                let Some(ObjectAttributes::Cache(cache)) = obj.attributes() else {
+                    return false;
+                };
+                obj.object_type().is_cpu_cache()
+                    && cache.depth().get() == level
+                    && cache_type.map_or(true, |ty| {
+                        cache.cache_type() == ty || cache.cache_type() == CacheType::Unified
+                    })
+            }
+        }
+    }
+}
+
+/// Truth that `obj` lies inside of `parent`, according to CPU and NUMA node
+/// sets
+fn is_inside(obj: &TopologyObject, parent: &TopologyObject) -> bool {
+    let cpus_inside = match (obj.cpuset(), parent.cpuset()) {
+        (Some(cpuset), Some(parent_cpuset)) => parent_cpuset.includes(cpuset),
+        _ => false,
+    };
+    let nodes_inside = match (obj.nodeset(), parent.nodeset()) {
+        (Some(nodeset), Some(parent_nodeset)) => parent_nodeset.includes(nodeset),
+        _ => false,
+    };
+    cpus_inside && nodes_inside
+}
+
+/// Select indices from `0..len` according to an index specification
+fn select_indices(spec: &str, len: usize) -> Result<Vec<usize>, LocationError> {
+    let bad_indices = || LocationError::BadIndices(spec.to_owned());
+    let parse = |s: &str| s.parse::<usize>().map_err(|_| bad_indices());
+    let mut selected = Vec::new();
+    for part in spec.split(',') {
+        match part {
+            "all" => selected.extend(0..len),
+            "odd" => selected.extend((1..len).step_by(2)),
+            "even" => selected.extend((0..len).step_by(2)),
+            _ => {
+                let range = if let Some((start, end)) = part.split_once('-') {
+                    let end = if end.is_empty() {
+                        len
+                    } else {
+                        parse(end)?.saturating_add(1)
+                    };
+                    parse(start)?..end
+                } else if let Some((start, count)) = part.split_once(':') {
+                    let start = parse(start)?;
+                    start..start.saturating_add(parse(count)?)
+                } else {
+                    let idx = parse(part)?;
+                    idx..idx.saturating_add(1)
+                };
+                selected.extend(range.start..range.end.min(len));
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// Parse a hexadecimal CPU mask (without the `0x` prefix)
+fn parse_hex_mask(hex: &str) -> Option<CpuSet> {
+    let mut result = CpuSet::new();
+    let digits = hex.chars().filter(|&c| c != ',');
+    for (digit_idx, digit) in digits.rev().enumerate() {
+        let value = digit.to_digit(16)?;
+        for bit in 0..4 {
+            if value & (1 << bit) != 0 {
+                result.set(digit_idx * 4 + bit);
+            }
+        }
+    }
+    (!hex.is_empty()).then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn indices() {
+        assert_eq!(select_indices("all", 3).unwrap(), vec![0, 1, 2]);
+        assert_eq!(select_indices("odd", 4).unwrap(), vec![1, 3]);
+        assert_eq!(select_indices("even", 4).unwrap(), vec![0, 2]);
+        assert_eq!(select_indices("2", 4).unwrap(), vec![2]);
+        assert_eq!(select_indices("5", 4).unwrap(), Vec::<usize>::new());
+        assert_eq!(select_indices("1-2", 4).unwrap(), vec![1, 2]);
+        assert_eq!(select_indices("2-", 4).unwrap(), vec![2, 3]);
+        assert_eq!(select_indices("1:2", 4).unwrap(), vec![1, 2]);
+        assert_eq!(select_indices("0,3", 4).unwrap(), vec![0, 3]);
+        assert_eq!(
+            select_indices("x", 4),
+            Err(LocationError::BadIndices("x".to_owned()))
+        );
+    }
+
+    #[test]
+    fn hex_mask() {
+        assert_eq!(
+            parse_hex_mask("5"),
+            Some(CpuSet::from_range(0..1) | CpuSet::from_range(2..3))
+        );
+        assert_eq!(parse_hex_mask("1,0"), Some(CpuSet::from_range(4..5)));
+        assert_eq!(parse_hex_mask(""), None);
+        assert_eq!(parse_hex_mask("g"), None);
+    }
+
+    #[test]
+    fn object_filter() {
+        assert_eq!(
+            ObjectFilter::parse("Core"),
+            Ok(ObjectFilter::Type(ObjectType::Core))
+        );
+        assert_eq!(
+            ObjectFilter::parse("socket"),
+            Ok(ObjectFilter::Type(ObjectType::Package))
+        );
+        assert_eq!(
+            ObjectFilter::parse("l2dcache"),
+            Ok(ObjectFilter::Cache(2, Some(CacheType::Data)))
+        );
+        assert_eq!(ObjectFilter::parse("l3"), Ok(ObjectFilter::Cache(3, None)));
+        assert_eq!(
+            ObjectFilter::parse("lx"),
+            Err(LocationError::UnknownType("lx".to_owned()))
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        let topology = Topology::test_instance();
+        let all = topology.cpuset_from_expression("all").unwrap();
+        assert_eq!(all, *topology.cpuset());
+        assert_eq!(topology.cpuset_from_expression("pu:all").unwrap(), all);
+        assert_eq!(
+            topology.cpuset_from_expression("all ~pu:all").unwrap(),
+            CpuSet::new()
+        );
+
+        // A chain selects objects by logical index within their parent
+        let first_core = topology.objects_with_type(ObjectType::Core).next().unwrap();
+        let first_pu = first_core.cpuset().unwrap().first_set().unwrap();
+        assert_eq!(
+            topology.cpuset_from_expression("core:0.pu:0").unwrap(),
+            CpuSet::from(first_pu)
+        );
+        assert_eq!(
+            topology.cpuset_from_expression("pu:0 ^core:0").unwrap(),
+            first_core.cpuset().unwrap().clone_target() - CpuSet::from(first_pu)
+        );
+
+        assert_eq!(
+            topology.cpuset_from_expression("core"),
+            Err(LocationError::BadLocation("core".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! Chiplet boundaries
+//!
+//! Modern AMD and Intel processors are built from several chiplets, and
+//! communication across chiplet boundaries is noticeably slower than
+//! communication within a chiplet. hwloc exposes these boundaries at two
+//! levels:
+//!
+//! - Dies, also known as Core Complex Dies (CCDs) on AMD processors, are
+//!   exposed as `Die` objects, which require the `hwloc-2_1_0` feature.
+//! - Core complexes (CCXs), whose cores share a last-level cache, are exposed
+//!   as the data caches of the last level that is present in the topology.
+//!
+//! This module provides helpers to enumerate these levels, which are exposed as
+//! methods of [`Topology`] and [`TopologyObject`].
+
+#[cfg(feature = "hwloc-2_1_0")]
+use crate::cpu::cpuset::CpuSet;
+use crate::{
+    object::{types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::iter::FusedIterator;
+
+/// Data (or unified) cache levels, from the farthest to the closest to PUs
+const DATA_CACHES_FROM_LAST_LEVEL: &[ObjectType] = &[
+    ObjectType::L5Cache,
+    ObjectType::L4Cache,
+    ObjectType::L3Cache,
+    ObjectType::L2Cache,
+    ObjectType::L1Cache,
+];
+
+/// # Chiplet boundaries
+impl Topology {
+    /// Dies of this topology
+    ///
+    /// This is a shorthand for `objects_with_type(ObjectType::Die)`. No die
+    /// will be reported if hwloc could not detect them on this system.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// for die in topology.dies() {
+    ///     println!("{die} has {} PU(s)", die.cpuset().unwrap().weight().unwrap());
+    /// }
+    /// ```
+    #[cfg(feature = "hwloc-2_1_0")]
+    pub fn dies(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + ExactSizeIterator + FusedIterator
+    {
+        self.objects_with_type(ObjectType::Die)
+    }
+
+    /// CPU set of each die of this topology, in logical order
+    ///
+    /// This is empty if no die was detected.
+    #[cfg(feature = "hwloc-2_1_0")]
+    pub fn cpuset_per_die(&self) -> Vec<CpuSet> {
+        self.dies()
+            .map(|die| {
+                die.cpuset()
+                    .expect("Dies should have a cpuset")
+                    .clone_target()
+            })
+            .collect()
+    }
+
+    /// Groups of cores that share a last-level CPU cache
+    ///
+    /// On AMD processors, these are the Core Complexes (CCXs). This returns the
+    /// data caches of the farthest level from PUs that is present in the
+    /// topology, or nothing if no CPU cache was detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// for complex in topology.core_complexes() {
+    ///     assert!(complex.object_type().is_cpu_data_cache());
+    /// }
+    /// ```
+    pub fn core_complexes(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
+        DATA_CACHES_FROM_LAST_LEVEL
+            .iter()
+            .map(|&cache_type| self.objects_with_type(cache_type))
+            .find(|caches| caches.len() > 0)
+            .into_iter()
+            .flatten()
+    }
+}
+
+/// # Chiplet boundaries
+impl TopologyObject {
+    /// Die that this object belongs to, if any
+    ///
+    /// For a [`Die`](ObjectType::Die), this is the object itself. `None` is
+    /// returned for objects that are not below a die, including objects like
+    /// Packages that contain dies and all objects of topologies where no die
+    /// was detected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for core in topology.objects_with_type(ObjectType::Core) {
+    ///     if let Some(die) = core.die() {
+    ///         println!("{core} is on {die}");
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "hwloc-2_1_0")]
+    pub fn die(&self) -> Option<&Self> {
+        if self.object_type() == ObjectType::Die {
+            Some(self)
+        } else {
+            self.first_ancestor_with_type(ObjectType::Die)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::synthetic::SyntheticBuilder;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn core_complexes() {
+        // The last data cache level is picked when several are present
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .l3(2)
+            .l2(2)
+            .cores(1)
+            .pus(1)
+            .build()
+            .unwrap();
+        let complexes = topology.core_complexes().collect::<Vec<_>>();
+        assert_eq!(complexes.len(), 4);
+        for (idx, complex) in complexes.iter().enumerate() {
+            assert_eq!(complex.object_type(), ObjectType::L3Cache);
+            assert_eq!(complex.logical_index(), idx);
+        }
+
+        // Lower cache levels are used when there is no L3 cache
+        let topology = SyntheticBuilder::new()
+            .packages(1)
+            .l2(2)
+            .cores(2)
+            .pus(1)
+            .build()
+            .unwrap();
+        assert_eq!(topology.core_complexes().count(), 2);
+        assert!(topology
+            .core_complexes()
+            .all(|complex| complex.object_type() == ObjectType::L2Cache));
+
+        // There is no core complex without CPU caches
+        let topology = SyntheticBuilder::new()
+            .packages(1)
+            .cores(2)
+            .pus(1)
+            .build()
+            .unwrap();
+        assert_eq!(topology.core_complexes().count(), 0);
+    }
+
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[test]
+    fn dies() {
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .dies(2)
+            .l3(1)
+            .cores(2)
+            .pus(1)
+            .build()
+            .unwrap();
+        assert_eq!(topology.dies().len(), 4);
+        assert_eq!(
+            topology.cpuset_per_die(),
+            topology
+                .dies()
+                .map(|die| die.cpuset().unwrap().clone_target())
+                .collect::<Vec<_>>()
+        );
+        for (idx, die_cpuset) in topology.cpuset_per_die().iter().enumerate() {
+            assert_eq!(die_cpuset, &CpuSet::from_range(2 * idx..=2 * idx + 1));
+        }
+
+        // Objects below a die belong to it, others do not
+        for die in topology.dies() {
+            assert!(std::ptr::eq(die.die().unwrap(), die));
+        }
+        for ty in [ObjectType::L3Cache, ObjectType::Core, ObjectType::PU] {
+            for obj in topology.objects_with_type(ty) {
+                let die = obj.die().unwrap();
+                assert_eq!(die.object_type(), ObjectType::Die);
+                assert!(die.cpuset().unwrap().includes(obj.cpuset().unwrap()));
+            }
+        }
+        for ty in [
+            ObjectType::Machine,
+            ObjectType::Package,
+            ObjectType::NUMANode,
+        ] {
+            for obj in topology.objects_with_type(ty) {
+                assert!(obj.die().is_none());
+            }
+        }
+    }
+
+    #[cfg(feature = "hwloc-2_1_0")]
+    #[test]
+    fn no_dies() {
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .cores(2)
+            .pus(1)
+            .build()
+            .unwrap();
+        assert_eq!(topology.dies().len(), 0);
+        assert!(topology.cpuset_per_die().is_empty());
+        assert!(topology
+            .objects_with_type(ObjectType::PU)
+            .all(|pu| pu.die().is_none()));
+    }
+}
@@ -25,7 +25,13 @@ use crate::{
 use similar_asserts::assert_eq;
 #[cfg(feature = "hwloc-2_2_0")]
 use std::ffi::c_uint;
-use std::{debug_assert, fmt::Debug, iter::FusedIterator, ops::Deref, ptr};
+use std::{
+    debug_assert,
+    fmt::{self, Debug, Display},
+    iter::FusedIterator,
+    ops::Deref,
+    ptr,
+};
 use thiserror::Error;
 
 /// # Finding objects inside a CPU set
@@ -507,6 +513,192 @@ impl CpuSet {
         }
         polymorphized(topology, &nodeset)
     }
+
+    /// Partition this CPU set into `n` subsets of balanced size
+    ///
+    /// CPUs are assigned to subsets by increasing OS index, so each subset is
+    /// made of consecutive CPUs of this set, and the sizes of the subsets
+    /// differ by at most one. If there are fewer CPUs than subsets, the last
+    /// subsets are empty. If `n` is 0, no subset is returned.
+    ///
+    /// This partitioning does not take the topology into account, use
+    /// [`CpuSet::split_by()`] if subsets should follow object boundaries.
+    ///
+    /// # Panics
+    ///
+    /// If this CPU set is infinite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::cpu::cpuset::CpuSet;
+    /// let set = CpuSet::from_range(0..10);
+    /// let subsets = set.split_evenly(3);
+    /// assert_eq!(subsets, [
+    ///     CpuSet::from_range(0..4),
+    ///     CpuSet::from_range(4..7),
+    ///     CpuSet::from_range(7..10),
+    /// ]);
+    /// ```
+    pub fn split_evenly(&self, n: usize) -> Vec<Self> {
+        let weight = self
+            .weight()
+            .expect("Can't split an infinite CPU set evenly");
+        let mut subsets = Vec::with_capacity(n);
+        let mut cpus = self.iter_set();
+        for subset_idx in 0..n {
+            let subset_len = weight / n + usize::from(subset_idx < weight % n);
+            let mut subset = Self::new();
+            for cpu in cpus.by_ref().take(subset_len) {
+                subset.set(cpu);
+            }
+            subsets.push(subset);
+        }
+        subsets
+    }
+
+    /// Partition this CPU set along the boundaries of objects of type `ty`
+    ///
+    /// One subset is returned for each object of type `ty` in `topology`
+    /// whose CPU set intersects with this CPU set, in logical order. This
+    /// subset is the intersection of this CPU set with the object's CPU set.
+    ///
+    /// CPUs that are not below any object of type `ty`, and all CPUs if `ty`
+    /// is an object type without CPU sets like I/O objects, are left out.
+    /// Subsets may overlap if objects of type `ty` can be nested, which is
+    /// the case of [`Group`] objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let per_core = topology.cpuset().split_by(topology, ObjectType::Core);
+    /// assert_eq!(per_core.len(), topology.objects_with_type(ObjectType::Core).count());
+    /// ```
+    ///
+    /// [`Group`]: ObjectType::Group
+    pub fn split_by(&self, topology: &Topology, ty: ObjectType) -> Vec<Self> {
+        topology
+            .objects_with_type(ty)
+            .filter_map(|obj| {
+                let subset = obj.cpuset()? & self;
+                (!subset.is_empty()).then_some(subset)
+            })
+            .collect()
+    }
+
+    /// Display this CPU set in terms of topology objects
+    ///
+    /// Instead of listing raw PU OS indices, the returned adapter renders the
+    /// set as a `+`-separated list of the largest topology objects that it
+    /// covers, e.g. `Package#0 + Core#2-5 + PU#33`. Objects are designated by
+    /// type and logical index, and runs of consecutive objects of the same
+    /// type are collapsed into ranges. This is meant for logs and error
+    /// messages that humans must interpret.
+    ///
+    /// CPU caches and [`Group`] objects are not used for display, as they are
+    /// less familiar to most readers: they are replaced by their children.
+    /// CPUs that are not part of `topology` are listed by OS index at the end,
+    /// with a `P#` prefix. The empty set is displayed as `(empty)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let set = topology.cpuset();
+    /// assert_eq!(set.display_with(topology).to_string(), "Machine#0");
+    /// ```
+    ///
+    /// [`Group`]: ObjectType::Group
+    pub fn display_with<'self_>(&'self_ self, topology: &'self_ Topology) -> CpuSetDisplay<'self_> {
+        CpuSetDisplay {
+            set: self,
+            topology,
+        }
+    }
+}
+
+/// Helper for displaying a [`CpuSet`] in terms of topology objects
+///
+/// This is returned by [`CpuSet::display_with()`].
+#[derive(Copy, Clone, Debug)]
+pub struct CpuSetDisplay<'set> {
+    /// CPU set to be displayed
+    set: &'set CpuSet,
+
+    /// Topology whose objects are used for display
+    topology: &'set Topology,
+}
+//
+impl<'set> CpuSetDisplay<'set> {
+    /// Topology objects used to display the CPU set, and leftover CPUs
+    ///
+    /// Objects are listed in the order of their first CPU.
+    fn objects(&self) -> (Vec<&'set TopologyObject>, CpuSet) {
+        /// Expand CPU caches and Groups into their normal children
+        fn push_displayable<'topology>(
+            obj: &'topology TopologyObject,
+            out: &mut Vec<&'topology TopologyObject>,
+        ) {
+            let ty = obj.object_type();
+            if (ty.is_cpu_cache() || ty == ObjectType::Group) && obj.normal_arity() > 0 {
+                for child in obj.normal_children() {
+                    push_displayable(child, out);
+                }
+            } else {
+                out.push(obj);
+            }
+        }
+
+        let known = self.set & self.topology.cpuset();
+        let leftover = self.set - &known;
+        let mut objects = Vec::new();
+        for obj in self.topology.largest_objects_inside_cpuset(known) {
+            push_displayable(obj, &mut objects);
+        }
+        objects.sort_by_key(|obj| obj.cpuset().and_then(|set| set.first_set()));
+        (objects, leftover)
+    }
+}
+//
+impl Display for CpuSetDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (objects, leftover) = self.objects();
+
+        // Collapse runs of consecutive objects of identical type
+        let mut terms = Vec::<(ObjectType, usize, usize)>::new();
+        for obj in objects {
+            let (ty, idx) = (obj.object_type(), obj.logical_index());
+            match terms.last_mut() {
+                Some((last_ty, _, last_idx)) if *last_ty == ty && *last_idx + 1 == idx => {
+                    *last_idx = idx;
+                }
+                _ => terms.push((ty, idx, idx)),
+            }
+        }
+
+        // Render the terms, followed by CPUs that are not in the topology
+        let mut out = terms
+            .into_iter()
+            .map(|(ty, first, last)| {
+                if first == last {
+                    format!("{ty}#{first}")
+                } else {
+                    format!("{ty}#{first}-{last}")
+                }
+            })
+            .collect::<Vec<_>>();
+        if !leftover.is_empty() {
+            out.push(format!("P#{leftover}"));
+        }
+        if out.is_empty() {
+            f.pad("(empty)")
+        } else {
+            f.pad(&out.join(" + "))
+        }
+    }
 }
 
 impl_bitmap_newtype!(
@@ -796,6 +988,92 @@ mod tests {
         )
     }
 
+    proptest! {
+        /// Test for [`CpuSet::split_evenly()`]
+        #[test]
+        fn split_evenly(set in topology_related_set(Topology::cpuset), n in 0usize..10) {
+            if set.weight().is_none() {
+                crate::tests::assert_panics(|| set.split_evenly(n))?;
+                return Ok(());
+            }
+            let subsets = set.split_evenly(n);
+            prop_assert_eq!(subsets.len(), n);
+            let mut union = CpuSet::new();
+            for subset in &subsets {
+                prop_assert!(!union.intersects(subset));
+                union |= subset;
+            }
+            if n > 0 {
+                prop_assert_eq!(union, set);
+            }
+            let weights = subsets.iter().map(|subset| subset.weight().unwrap());
+            if let (Some(min), Some(max)) = (weights.clone().min(), weights.max()) {
+                prop_assert!(max - min <= 1);
+            }
+        }
+
+        /// Test for [`CpuSet::split_by()`]
+        #[test]
+        fn split_by(set in topology_related_set(Topology::cpuset), ty: ObjectType) {
+            let topology = Topology::test_instance();
+            let subsets = set.split_by(topology, ty);
+
+            // Subsets are non-empty parts of the input set, which only
+            // overlap for object types that can be nested
+            let mut union = CpuSet::new();
+            for subset in &subsets {
+                prop_assert!(!subset.is_empty());
+                prop_assert!(set.includes(subset));
+                if ty.is_normal() && ty != ObjectType::Group {
+                    prop_assert!(!union.intersects(subset));
+                }
+                union |= subset;
+            }
+
+            // Together, they cover every CPU of the input set that is below
+            // an object of type ty, with one subset per such object
+            let objects = topology
+                .objects_with_type(ty)
+                .filter_map(TopologyObject::cpuset)
+                .filter(|obj_set| obj_set.intersects(&set))
+                .collect::<Vec<_>>();
+            let covered = objects.iter().fold(CpuSet::new(), |mut covered, obj_set| {
+                covered |= obj_set;
+                covered
+            });
+            prop_assert_eq!(subsets.len(), objects.len());
+            prop_assert_eq!(union, covered & &set);
+        }
+
+        /// Test for [`CpuSet::display_with()`]
+        #[test]
+        fn display_with(set in topology_related_set(Topology::cpuset)) {
+            let topology = Topology::test_instance();
+            let display = set.display_with(topology);
+            let (objects, leftover) = display.objects();
+
+            // Objects and leftover CPUs should exactly cover the input set
+            let mut union = leftover.clone();
+            for obj in &objects {
+                let obj_set = obj.cpuset().unwrap();
+                prop_assert!(!union.intersects(obj_set));
+                prop_assert!(!obj.object_type().is_cpu_cache());
+                union |= obj_set;
+            }
+            prop_assert_eq!(&union, &set);
+            prop_assert!(!topology.cpuset().intersects(&leftover));
+
+            // Display should have at most one term per object, plus leftovers
+            let string = display.to_string();
+            if set.is_empty() {
+                prop_assert_eq!(string, "(empty)");
+            } else {
+                let num_terms = string.split(" + ").count();
+                prop_assert!(num_terms <= objects.len() + usize::from(!leftover.is_empty()));
+            }
+        }
+    }
+
     proptest! {
         // Test all of the above for all depth types
         #[test]
@@ -213,39 +213,92 @@ impl Topology {
         &self,
         set: impl Deref<Target = CpuSet>,
     ) -> Result<(CpuSet, Option<CpuEfficiency>, &[TextualInfo]), FromSetError> {
-        /// Polymorphized version of this function (avoids generics code bloat)
-        fn polymorphized<'self_>(
-            self_: &'self_ Topology,
-            set: &CpuSet,
-        ) -> Result<(CpuSet, Option<CpuEfficiency>, &'self_ [TextualInfo]), FromSetError> {
-            // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
-            //         - Bitmap is trusted to contain a valid ptr (type invariant)
-            //         - hwloc ops are trusted not to modify *const parameters
-            //         - Per documentation, flags should be zero
-            let result = errors::call_hwloc_int_normal("hwloc_cpukinds_get_by_cpuset", || unsafe {
-                hwlocality_sys::hwloc_cpukinds_get_by_cpuset(self_.as_ptr(), set.as_ptr(), 0)
-            });
-            let kind_index = match result {
-                Ok(idx) => int::expect_usize(idx),
-                Err(
-                    raw_error @ RawHwlocError {
-                        errno: Some(errno), ..
-                    },
-                ) => match errno.0 {
-                    EXDEV => {
-                        return Err(FromSetError(set.clone(), FromSetProblem::PartiallyIncluded))
-                    }
-                    ENOENT => return Err(FromSetError(set.clone(), FromSetProblem::NotIncluded)),
-                    EINVAL => return Err(FromSetError(set.clone(), FromSetProblem::InvalidSet)),
-                    _ => unreachable!("Unexpected hwloc error: {raw_error}"),
+        let kind_index = self.cpu_kind_index(&set)?;
+        // SAFETY: In absence of errors, we trust hwloc_cpukinds_get_by_cpuset
+        //         to produce correct CPU kind indices
+        Ok(unsafe { self.cpu_kind(kind_index) })
+    }
+
+    /// Index and efficiency of the CPU kind that contains CPUs listed in `set`
+    ///
+    /// `set` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// This is a lighter-weight alternative to [`cpu_kind_from_set()`] for
+    /// situations where you only need to know which CPU kind a set of CPUs
+    /// belongs to, for example in order to route latency-sensitive work to
+    /// the most efficient CPU cores of a hybrid CPU. The returned index is the
+    /// position of the CPU kind within the output of [`cpu_kinds()`], so
+    /// higher indices designate more efficient kinds of CPU cores.
+    ///
+    /// # Errors
+    ///
+    /// - [`PartiallyIncluded`] if `set` spans multiple CPU kinds
+    ///   (i.e. some CPUs in the set belong to a kind, others to other kind(s))
+    /// - [`NotIncluded`] if `set` is not included in any kind, even partially
+    ///   (i.e. CPU kind info isn't known or CPU set does not cover real CPUs)
+    /// - [`InvalidSet`] if the CPU set is considered invalid for another reason
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let pu = topology
+    ///     .objects_with_type(ObjectType::PU)
+    ///     .next()
+    ///     .expect("There should be at least one PU");
+    /// let cpuset = pu.cpuset().expect("PUs should have a cpuset");
+    /// match topology.cpu_kind_of(cpuset) {
+    ///     Ok((kind_index, efficiency)) => {
+    ///         println!("{pu} has CPU kind #{kind_index} (efficiency {efficiency:?})")
+    ///     }
+    ///     Err(e) => println!("Failed to query the CPU kind of {pu}: {e}"),
+    /// }
+    /// ```
+    ///
+    /// [`cpu_kind_from_set()`]: Topology::cpu_kind_from_set()
+    /// [`cpu_kinds()`]: Topology::cpu_kinds()
+    /// [`InvalidSet`]: FromSetProblem::InvalidSet
+    /// [`NotIncluded`]: FromSetProblem::NotIncluded
+    /// [`PartiallyIncluded`]: FromSetProblem::PartiallyIncluded
+    #[doc(alias = "hwloc_cpukinds_get_by_cpuset")]
+    pub fn cpu_kind_of(
+        &self,
+        set: impl Deref<Target = CpuSet>,
+    ) -> Result<(usize, Option<CpuEfficiency>), FromSetError> {
+        let kind_index = self.cpu_kind_index(&set)?;
+        // SAFETY: In absence of errors, we trust hwloc_cpukinds_get_by_cpuset
+        //         to produce correct CPU kind indices
+        let (_cpuset, efficiency, _infos) = unsafe { self.cpu_kind(kind_index) };
+        Ok((kind_index, efficiency))
+    }
+
+    /// Index of the CPU kind that contains CPUs listed in `set`
+    ///
+    /// See [`Topology::cpu_kind_from_set()`] for a description of the possible
+    /// errors.
+    fn cpu_kind_index(&self, set: &CpuSet) -> Result<usize, FromSetError> {
+        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+        //         - Bitmap is trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - Per documentation, flags should be zero
+        let result = errors::call_hwloc_int_normal("hwloc_cpukinds_get_by_cpuset", || unsafe {
+            hwlocality_sys::hwloc_cpukinds_get_by_cpuset(self.as_ptr(), set.as_ptr(), 0)
+        });
+        match result {
+            Ok(idx) => Ok(int::expect_usize(idx)),
+            Err(
+                raw_error @ RawHwlocError {
+                    errno: Some(errno), ..
                 },
-                Err(raw_error) => unreachable!("Unexpected hwloc error: {raw_error}"),
-            };
-            // SAFETY: In absence of errors, we trust hwloc_cpukinds_get_by_cpuset
-            //         to produce correct CPU kind indices
-            Ok(unsafe { self_.cpu_kind(kind_index) })
+            ) => match errno.0 {
+                EXDEV => Err(FromSetError(set.clone(), FromSetProblem::PartiallyIncluded)),
+                ENOENT => Err(FromSetError(set.clone(), FromSetProblem::NotIncluded)),
+                EINVAL => Err(FromSetError(set.clone(), FromSetProblem::InvalidSet)),
+                _ => unreachable!("Unexpected hwloc error: {raw_error}"),
+            },
+            Err(raw_error) => unreachable!("Unexpected hwloc error: {raw_error}"),
         }
-        polymorphized(self, &set)
     }
 }
 
@@ -443,3 +496,91 @@ impl From<CpuEfficiency> for RegisterError {
         Self::ExcessiveEfficiency(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        object::types::ObjectType,
+        strategies::{set_with_reference, test_object},
+    };
+    use proptest::prelude::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Predict the outcome of [`Topology::cpu_kind_of()`] from the output of
+    /// [`Topology::cpu_kinds()`]
+    fn expected_kind_of(
+        topology: &Topology,
+        set: &CpuSet,
+    ) -> Result<(usize, Option<CpuEfficiency>), FromSetProblem> {
+        if set.is_empty() {
+            return Err(FromSetProblem::InvalidSet);
+        }
+        let Ok(kinds) = topology.cpu_kinds() else {
+            return Err(FromSetProblem::NotIncluded);
+        };
+        for (kind_index, (kind_set, efficiency, _infos)) in kinds.enumerate() {
+            if kind_set.includes(set) {
+                return Ok((kind_index, efficiency));
+            }
+            if kind_set.intersects(set) {
+                return Err(FromSetProblem::PartiallyIncluded);
+            }
+        }
+        Err(FromSetProblem::NotIncluded)
+    }
+
+    /// Check [`Topology::cpu_kind_of()`] against [`expected_kind_of()`]
+    fn check_kind_of(topology: &Topology, set: &CpuSet) {
+        let result = topology.cpu_kind_of(set);
+        assert_eq!(
+            result,
+            expected_kind_of(topology, set).map_err(|problem| FromSetError(set.clone(), problem))
+        );
+        if let Ok((kind_index, efficiency)) = result {
+            let (kind_set, kind_efficiency, _infos) = topology
+                .cpu_kinds()
+                .unwrap()
+                .nth(kind_index)
+                .expect("cpu_kind_of() should return a valid CPU kind index");
+            assert!(kind_set.includes(set));
+            assert_eq!(efficiency, kind_efficiency);
+        }
+    }
+
+    #[test]
+    fn kind_of_pus() {
+        let topology = Topology::test_instance();
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            let cpuset = pu.cpuset().unwrap();
+            check_kind_of(topology, &cpuset);
+            if topology.cpu_kinds().is_ok() {
+                // Every PU belongs to exactly one CPU kind when kinds are known
+                topology.cpu_kind_of(cpuset).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn kind_of_empty() {
+        let topology = Topology::test_instance();
+        let empty = CpuSet::new();
+        let result = topology.cpu_kind_of(&empty);
+        assert_eq!(result, Err(FromSetError(empty, FromSetProblem::InvalidSet)));
+    }
+
+    proptest! {
+        #[test]
+        fn kind_of_object(obj in test_object()) {
+            if let Some(cpuset) = obj.cpuset() {
+                check_kind_of(Topology::test_instance(), &cpuset);
+            }
+        }
+
+        #[test]
+        fn kind_of_set(set in set_with_reference(&*Topology::test_instance().cpuset())) {
+            check_kind_of(Topology::test_instance(), &set);
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Locality between CPU sets
+//!
+//! Message-passing frameworks need to know how close the producers and
+//! consumers of a channel are in order to pick sharding and batch sizes: a
+//! channel between two threads that share a cache can afford small batches,
+//! whereas a channel that crosses NUMA nodes benefits from larger ones.
+//!
+//! [`Topology::locality_between()`] summarizes this information as a
+//! [`Locality`] report.
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    memory::nodeset::NodeSet,
+    object::{distance::DistancesKind, types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::ops::Deref;
+
+/// # Locality between CPU sets
+impl Topology {
+    /// Locality relationship between a `producer` and a `consumer` CPU set
+    ///
+    /// `producer` and `consumer` can be `&'_ CpuSet`s or
+    /// `BitmapRef<'_, CpuSet>`s.
+    ///
+    /// Returns `None` if one of the CPU sets is empty or is not included in
+    /// the topology's CPU set.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let mut pus = topology.objects_with_type(ObjectType::PU);
+    /// let first = pus.next().unwrap().cpuset().unwrap();
+    /// let last = pus.last().unwrap_or(topology.root_object()).cpuset().unwrap();
+    /// let locality = topology.locality_between(first, last).unwrap();
+    /// if locality.shares_l3() {
+    ///     println!("Small batches will do");
+    /// } else {
+    ///     println!("Use larger batches, {} hops apart", locality.hops());
+    /// }
+    /// ```
+    pub fn locality_between(
+        &self,
+        producer: impl Deref<Target = CpuSet>,
+        consumer: impl Deref<Target = CpuSet>,
+    ) -> Option<Locality<'_>> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized<'self_>(
+            self_: &'self_ Topology,
+            producer: &CpuSet,
+            consumer: &CpuSet,
+        ) -> Option<Locality<'self_>> {
+            let producer_obj = self_.smallest_object_covering_cpuset(producer)?;
+            let consumer_obj = self_.smallest_object_covering_cpuset(consumer)?;
+            let common_ancestor = self_.smallest_object_covering_cpuset(&(producer | consumer))?;
+            let normal_depth = |obj: &TopologyObject| {
+                usize::try_from(obj.depth()).expect("Should be a normal object")
+            };
+            let common_depth = normal_depth(common_ancestor);
+            let hops = (normal_depth(producer_obj) - common_depth)
+                + (normal_depth(consumer_obj) - common_depth);
+            let producer_nodes = NodeSet::from_cpuset(self_, producer);
+            let consumer_nodes = NodeSet::from_cpuset(self_, consumer);
+            let shares_numa_node = !producer_nodes.is_empty() && producer_nodes == consumer_nodes;
+            let numa_latency = if shares_numa_node {
+                None
+            } else {
+                numa_latency(self_, &producer_nodes, &consumer_nodes)
+            };
+            Some(Locality {
+                common_ancestor,
+                hops,
+                shares_numa_node,
+                numa_latency,
+            })
+        }
+        polymorphized(self, &producer, &consumer)
+    }
+}
+
+/// NUMA latency between the first NUMA nodes of two local node sets, if known
+fn numa_latency(
+    topology: &Topology,
+    producer_nodes: &NodeSet,
+    consumer_nodes: &NodeSet,
+) -> Option<u64> {
+    let first_node = |nodeset: &NodeSet| {
+        let first_index = nodeset.first_set()?;
+        topology.node_with_os_index(first_index.into())
+    };
+    let producer_node = first_node(producer_nodes)?;
+    let consumer_node = first_node(consumer_nodes)?;
+    topology
+        .distances_with_type(DistancesKind::MEANS_LATENCY, ObjectType::NUMANode)
+        .ok()?
+        .iter()
+        .find_map(|distances| distances.object_pair_distance((producer_node, consumer_node)))
+        .map(|(there, _back)| there)
+}
+
+/// Locality relationship between two CPU sets
+///
+/// This is returned by [`Topology::locality_between()`].
+#[derive(Copy, Clone, Debug)]
+pub struct Locality<'topology> {
+    /// Smallest object covering both CPU sets
+    common_ancestor: &'topology TopologyObject,
+
+    /// Number of tree edges between the objects covering each CPU set
+    hops: usize,
+
+    /// Truth that both CPU sets are local to the same NUMA nodes
+    shares_numa_node: bool,
+
+    /// NUMA latency between the CPU sets, if known and relevant
+    numa_latency: Option<u64>,
+}
+//
+impl<'topology> Locality<'topology> {
+    /// Smallest topology object that covers both CPU sets
+    pub fn common_ancestor(&self) -> &'topology TopologyObject {
+        self.common_ancestor
+    }
+
+    /// Truth that both CPU sets are below a common object of type `ty`
+    pub fn shares(&self, ty: ObjectType) -> bool {
+        self.common_ancestor.object_type() == ty
+            || self.common_ancestor.first_ancestor_with_type(ty).is_some()
+    }
+
+    /// Truth that both CPU sets are below a common CPU core
+    pub fn shares_core(&self) -> bool {
+        self.shares(ObjectType::Core)
+    }
+
+    /// Truth that both CPU sets are below a common L3 cache
+    pub fn shares_l3(&self) -> bool {
+        self.shares(ObjectType::L3Cache)
+    }
+
+    /// Truth that both CPU sets are local to the same NUMA node(s)
+    ///
+    /// This compares the sets of NUMA nodes that are local to each CPU set,
+    /// as computed by [`NodeSet::from_cpuset()`]. It is `false` if the CPU
+    /// sets have different local NUMA nodes, even if some are shared.
+    pub fn shares_numa_node(&self) -> bool {
+        self.shares_numa_node
+    }
+
+    /// Distance between the two CPU sets in the topology tree
+    ///
+    /// This is the number of parent/child edges from the smallest object
+    /// covering the producer CPU set, up to the [common
+    /// ancestor](Self::common_ancestor()), and back down to the smallest
+    /// object covering the consumer CPU set. It is 0 if both CPU sets are
+    /// covered by the same object.
+    pub fn hops(&self) -> usize {
+        self.hops
+    }
+
+    /// Relative latency between the NUMA nodes of the two CPU sets, if known
+    ///
+    /// This is taken from the NUMA latency matrix of the topology, which is
+    /// not available on all systems. It is `None` when both CPU sets
+    /// [share a NUMA node](Self::shares_numa_node()).
+    pub fn numa_latency(&self) -> Option<u64> {
+        self.numa_latency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::synthetic::SyntheticBuilder;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::fmt::Debug;
+
+    assert_impl_all!(Locality<'static>: Copy, Debug, Send, Sync);
+
+    /// 2 packages with one NUMA node and L3 cache each, 2 cores per package
+    /// and 2 PUs per core
+    fn two_packages() -> Topology {
+        SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(1)
+            .l3(1)
+            .cores(2)
+            .pus(2)
+            .build()
+            .unwrap()
+    }
+
+    /// Locality between the PUs with OS indices `producer` and `consumer`
+    fn pu_locality(topology: &Topology, producer: usize, consumer: usize) -> Locality<'_> {
+        topology
+            .locality_between(
+                &CpuSet::from_range(producer..=producer),
+                &CpuSet::from_range(consumer..=consumer),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn invalid_cpusets() {
+        let topology = two_packages();
+        let pu = CpuSet::from_range(0..=0);
+        assert!(topology.locality_between(&CpuSet::new(), &pu).is_none());
+        assert!(topology.locality_between(&pu, &CpuSet::new()).is_none());
+        let outside = CpuSet::from_range(8..=8);
+        assert!(topology.locality_between(&outside, &pu).is_none());
+        assert!(topology.locality_between(&pu, &outside).is_none());
+    }
+
+    #[test]
+    fn same_pu() {
+        let topology = two_packages();
+        let locality = pu_locality(&topology, 0, 0);
+        assert_eq!(locality.common_ancestor().object_type(), ObjectType::PU);
+        assert_eq!(locality.hops(), 0);
+        assert!(locality.shares(ObjectType::PU));
+        assert!(locality.shares_core());
+        assert!(locality.shares_l3());
+        assert!(locality.shares_numa_node());
+        assert_eq!(locality.numa_latency(), None);
+    }
+
+    #[test]
+    fn same_core() {
+        let topology = two_packages();
+        let locality = pu_locality(&topology, 0, 1);
+        assert_eq!(locality.common_ancestor().object_type(), ObjectType::Core);
+        assert_eq!(locality.hops(), 2);
+        assert!(!locality.shares(ObjectType::PU));
+        assert!(locality.shares_core());
+        assert!(locality.shares_l3());
+        assert!(locality.shares(ObjectType::Package));
+        assert!(locality.shares_numa_node());
+        assert_eq!(locality.numa_latency(), None);
+    }
+
+    #[test]
+    fn same_package() {
+        let topology = two_packages();
+        let locality = pu_locality(&topology, 0, 2);
+        assert_eq!(
+            locality.common_ancestor().object_type(),
+            ObjectType::L3Cache
+        );
+        assert_eq!(locality.hops(), 4);
+        assert!(!locality.shares_core());
+        assert!(locality.shares_l3());
+        assert!(locality.shares(ObjectType::Package));
+        assert!(locality.shares_numa_node());
+        assert_eq!(locality.numa_latency(), None);
+    }
+
+    #[test]
+    fn different_packages() {
+        let topology = two_packages();
+        let locality = pu_locality(&topology, 1, 6);
+        assert_eq!(
+            locality.common_ancestor().object_type(),
+            ObjectType::Machine
+        );
+        assert_eq!(locality.hops(), 8);
+        assert!(!locality.shares_core());
+        assert!(!locality.shares_l3());
+        assert!(!locality.shares(ObjectType::Package));
+        assert!(locality.shares(ObjectType::Machine));
+        assert!(!locality.shares_numa_node());
+
+        // Locality is symmetrical
+        let reverse = pu_locality(&topology, 6, 1);
+        assert_eq!(
+            reverse.common_ancestor().global_persistent_index(),
+            locality.common_ancestor().global_persistent_index()
+        );
+        assert_eq!(reverse.hops(), locality.hops());
+        assert_eq!(reverse.shares_numa_node(), locality.shares_numa_node());
+    }
+
+    #[test]
+    fn multiple_local_nodes() {
+        // Both NUMA nodes of a package are local to all of its PUs, so PUs
+        // from the same package share their NUMA nodes even though no single
+        // NUMA node covers their common ancestor's memory
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(2)
+            .cores(2)
+            .pus(1)
+            .build()
+            .unwrap();
+        let same_package = pu_locality(&topology, 0, 1);
+        assert_eq!(
+            same_package.common_ancestor().object_type(),
+            ObjectType::Package
+        );
+        assert!(same_package.shares_numa_node());
+        assert_eq!(same_package.numa_latency(), None);
+        assert!(!pu_locality(&topology, 0, 2).shares_numa_node());
+
+        // CPU sets that span different sets of NUMA nodes do not share them
+        let locality = topology
+            .locality_between(&CpuSet::from_range(0..=0), &CpuSet::from_range(0..=2))
+            .unwrap();
+        assert!(!locality.shares_numa_node());
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn numa_latency() {
+        use crate::object::distance::AddDistancesFlags;
+        let mut topology = two_packages();
+        topology
+            .edit(|editor| {
+                editor.add_distances(
+                    Some("TestLatency"),
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        let nodes = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .map(Some)
+                            .collect();
+                        (nodes, vec![10, 21, 32, 10])
+                    },
+                )
+            })
+            .unwrap();
+        assert_eq!(pu_locality(&topology, 0, 2).numa_latency(), None);
+        assert_eq!(pu_locality(&topology, 0, 4).numa_latency(), Some(21));
+        assert_eq!(pu_locality(&topology, 4, 0).numa_latency(), Some(32));
+    }
+}
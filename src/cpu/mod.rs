@@ -4,11 +4,19 @@
 //! [`Topology`] struct. The module itself only hosts type definitions that are
 //! related to this functionality.
 
+pub mod audit;
+#[cfg(feature = "macos-binding-fallback")]
+pub mod best_effort;
 pub mod binding;
 pub mod cache;
+pub mod calc;
+pub mod chiplet;
 pub mod cpuset;
 #[cfg(feature = "hwloc-2_4_0")]
 pub mod kind;
+pub mod locality;
+pub mod pinning;
+pub mod state;
 
 #[cfg(doc)]
 use crate::topology::Topology;
@@ -0,0 +1,1115 @@
+//! Thread pool pinning
+//!
+//! Thread pools, like those of `rayon` and `tokio`, usually let you run a
+//! callback when each worker thread starts. This module provides such
+//! callbacks, which bind each worker thread to a distinct part of the topology,
+//! so that you can get NUMA-aware thread pools without computing CPU bindings
+//! yourself.
+//!
+//! Most of this module's functionality is exposed via [`WorkerPinning`], and
+//! [`Topology::affinity_assignments()`] computes the CPU sets of a thread pool
+//! according to the usual [`AffinityPolicy`]s. For
+//! thread-per-core services that manage their own threads, [`PinnedThreads`]
+//! spawns one bound thread per topology object.
+
+use crate::{
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+    },
+    errors::BindingError,
+    memory::nodeset::NodeSet,
+    object::{depth::Depth, types::ObjectType, TopologyObject},
+    topology::{DistributeError, DistributeFlags, SharedTopology, Topology},
+};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    io,
+    iter::FusedIterator,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle, Thread},
+};
+
+/// # Thread pool pinning
+impl Topology {
+    /// CPU sets to which the workers of a thread pool should be bound
+    ///
+    /// Workers are spread over the topology using
+    /// [`Topology::distribute_items()`], then each worker is restricted to a
+    /// single CPU core. If there are more workers than CPU cores, some
+    /// workers will share a core.
+    ///
+    /// If `avoid_smt` is true, each worker is further restricted to a single
+    /// hardware thread of its CPU core, so that workers which do not share a
+    /// core do not compete with each other for the core's resources through
+    /// simultaneous multithreading.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let cpusets = topology.worker_cpusets(4, true)?;
+    /// assert_eq!(cpusets.len(), 4);
+    /// for cpuset in cpusets {
+    ///     assert_eq!(cpuset.weight(), Some(1));
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn worker_cpusets(
+        &self,
+        num_workers: usize,
+        avoid_smt: bool,
+    ) -> Result<Vec<CpuSet>, DistributeError> {
+        let max_depth = match self.depth_or_above_for_type(ObjectType::Core) {
+            Ok(Depth::Normal(depth)) => depth,
+            _ => self.depth() - 1,
+        };
+        let mut cpusets = self.distribute_items(
+            &[self.root_object()],
+            num_workers,
+            max_depth,
+            DistributeFlags::default(),
+        )?;
+        for cpuset in &mut cpusets {
+            if let Some(core_set) = self
+                .objects_with_type(ObjectType::Core)
+                .filter_map(TopologyObject::cpuset)
+                .find(|core_set| cpuset.includes(&**core_set) && !core_set.is_empty())
+            {
+                *cpuset = core_set.clone_target();
+            }
+            if avoid_smt {
+                cpuset.singlify();
+            }
+        }
+        Ok(cpusets)
+    }
+
+    /// CPU sets to which `num_threads` threads should be bound according to
+    /// an [`AffinityPolicy`]
+    ///
+    /// The resulting iterator yields exactly `num_threads` CPU sets, the i-th
+    /// of which is meant for the i-th thread. Only CPUs from
+    /// [`Topology::allowed_cpuset()`] are used.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU, or if no
+    ///   object of the kind targeted by `policy` contains accessible CPUs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::pinning::AffinityPolicy, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let assignments = topology.affinity_assignments(8, AffinityPolicy::PerCore)?;
+    /// assert_eq!(assignments.len(), 8);
+    /// for (thread, cpuset) in assignments.enumerate() {
+    ///     println!("Thread {thread} should be bound to {cpuset}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn affinity_assignments(
+        &self,
+        num_threads: usize,
+        policy: AffinityPolicy,
+    ) -> Result<
+        impl DoubleEndedIterator<Item = CpuSet> + Clone + ExactSizeIterator + FusedIterator,
+        DistributeError,
+    > {
+        let domains = match policy {
+            AffinityPolicy::Compact => self.allowed_cpusets_of(ObjectType::PU),
+            AffinityPolicy::Scatter => self.distribute_items(
+                &[self.root_object()],
+                num_threads,
+                self.depth() - 1,
+                DistributeFlags::default(),
+            )?,
+            AffinityPolicy::PerCore => self.allowed_cpusets_of(ObjectType::Core),
+            AffinityPolicy::PerL3 => self.allowed_l3_cpusets(),
+            AffinityPolicy::PackWithinL3 => {
+                let cores = self.allowed_cpusets_of(ObjectType::Core);
+                self.allowed_l3_cpusets()
+                    .into_iter()
+                    .flat_map(|l3_cpuset| {
+                        let num_cores = cores
+                            .iter()
+                            .filter(|core_cpuset| l3_cpuset.includes(*core_cpuset))
+                            .count();
+                        std::iter::repeat(l3_cpuset).take(num_cores.max(1))
+                    })
+                    .collect()
+            }
+            AffinityPolicy::PerNUMANode => self.allowed_cpusets_of(ObjectType::NUMANode),
+        };
+        if domains.is_empty() && num_threads > 0 {
+            return Err(DistributeError::EmptyRoots);
+        }
+        Ok((0..num_threads).map(move |thread| domains[thread % domains.len()].clone()))
+    }
+
+    /// Allowed CPUs of each L3 cache that has some, in logical order, or of
+    /// each core complex if the topology has no L3 cache
+    fn allowed_l3_cpusets(&self) -> Vec<CpuSet> {
+        let l3_cpusets = self.allowed_cpusets_of(ObjectType::L3Cache);
+        if l3_cpusets.is_empty() {
+            self.core_complexes()
+                .filter_map(|complex| self.allowed_cpuset_of(complex))
+                .collect()
+        } else {
+            l3_cpusets
+        }
+    }
+
+    /// Allowed CPUs of each object of type `ty` that has some, in logical
+    /// order
+    fn allowed_cpusets_of(&self, ty: ObjectType) -> Vec<CpuSet> {
+        self.objects_with_type(ty)
+            .filter_map(|obj| self.allowed_cpuset_of(obj))
+            .collect()
+    }
+
+    /// Allowed CPUs of `obj`, if it has some
+    fn allowed_cpuset_of(&self, obj: &TopologyObject) -> Option<CpuSet> {
+        let cpuset = obj.cpuset()? & self.allowed_cpuset();
+        (!cpuset.is_empty()).then_some(cpuset)
+    }
+}
+
+/// Policy used to assign CPUs to threads
+///
+/// These presets cover the most common thread pinning strategies. They are
+/// used by [`Topology::affinity_assignments()`], and by the higher-level
+/// [`WorkerPinning::with_policy()`] and [`PlacementPlan::distribute_with_policy()`]
+/// helpers.
+///
+/// Except for [`Scatter`](Self::Scatter) and
+/// [`PackWithinL3`](Self::PackWithinL3), all policies assign the objects of a
+/// certain type to threads in round-robin fashion, by increasing logical
+/// index. If there are more threads than objects, some threads share an
+/// object.
+///
+/// [`PlacementPlan::distribute_with_policy()`]: crate::topology::placement::PlacementPlan::distribute_with_policy()
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum AffinityPolicy {
+    /// Bind each thread to a single PU, filling PUs in logical order
+    ///
+    /// This packs threads close to each other, so that they share as many
+    /// caches as possible, at the expense of competing for the resources of
+    /// their CPU cores through simultaneous multithreading.
+    #[display(fmt = "compact")]
+    #[doc(alias = "OnePerPu")]
+    Compact,
+
+    /// Spread threads as far apart from each other as possible
+    ///
+    /// This uses [`Topology::distribute_items()`] over the whole topology, so
+    /// that threads are balanced across packages, caches and cores. Each
+    /// thread may be bound to several PUs if there are fewer threads than PUs.
+    #[display(fmt = "scatter")]
+    Scatter,
+
+    /// Bind each thread to all PUs of a CPU core
+    #[display(fmt = "per-core")]
+    #[doc(alias = "OnePerCore")]
+    PerCore,
+
+    /// Bind each thread to all PUs sharing an L3 cache
+    ///
+    /// If the topology has no L3 cache, the last-level cache is used instead,
+    /// as in [`Topology::core_complexes()`].
+    #[display(fmt = "per-L3")]
+    PerL3,
+
+    /// Bind threads to all PUs sharing an L3 cache, filling each L3 cache
+    /// with one thread per CPU core before moving on to the next one
+    ///
+    /// This keeps threads that have neighboring indices on the same L3 cache,
+    /// where they can share data cheaply, while letting the operating system
+    /// balance them across the cores of that cache. As with
+    /// [`PerL3`](Self::PerL3), the last-level cache is used if the topology
+    /// has no L3 cache.
+    #[display(fmt = "pack-L3")]
+    PackWithinL3,
+
+    /// Bind each thread to all PUs that are local to a NUMA node
+    ///
+    /// NUMA nodes without CPUs are skipped. Threads with consecutive indices
+    /// land on different NUMA nodes, so this spreads threads evenly across
+    /// NUMA nodes.
+    #[display(fmt = "per-NUMA-node")]
+    #[doc(alias = "SpreadAcrossNuma")]
+    PerNUMANode,
+}
+
+/// Binding of the workers of a thread pool to distinct CPUs
+///
+/// This type can be used with any thread pool that lets you run a callback
+/// when each worker thread starts. Use [`WorkerPinning::start_handler()`] if
+/// the callback receives the index of the worker, as in `rayon`, and
+/// [`WorkerPinning::thread_binder()`] if it does not, as in `tokio`.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{cpu::pinning::WorkerPinning, Topology};
+/// # use std::sync::Arc;
+/// let topology = Arc::new(Topology::new()?);
+/// let pinning = WorkerPinning::new(topology, 2, false)?;
+/// let start_handler = pinning.start_handler(|index, error| {
+///     println!("Failed to bind worker {index}: {error}");
+/// });
+/// let workers = (0..2)
+///     .map(|index| {
+///         let start_handler = start_handler.clone();
+///         std::thread::spawn(move || start_handler(index))
+///     })
+///     .collect::<Vec<_>>();
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct WorkerPinning {
+    /// Topology that the workers are bound to
+    topology: SharedTopology,
+
+    /// CPUs that each worker should be bound to
+    cpusets: Vec<CpuSet>,
+}
+//
+impl WorkerPinning {
+    /// Bind `num_workers` workers as recommended by
+    /// [`Topology::worker_cpusets()`]
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn new(
+        topology: SharedTopology,
+        num_workers: usize,
+        avoid_smt: bool,
+    ) -> Result<Self, DistributeError> {
+        let cpusets = topology.worker_cpusets(num_workers, avoid_smt)?;
+        Ok(Self { topology, cpusets })
+    }
+
+    /// Bind `num_workers` workers according to an [`AffinityPolicy`]
+    ///
+    /// Workers get the CPU sets computed by
+    /// [`Topology::affinity_assignments()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU, or if no
+    ///   object of the kind targeted by `policy` contains accessible CPUs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::pinning::{AffinityPolicy, WorkerPinning}, Topology};
+    /// # use std::sync::Arc;
+    /// let topology = Arc::new(Topology::new()?);
+    /// let pinning = WorkerPinning::with_policy(topology, 4, AffinityPolicy::PackWithinL3)?;
+    /// assert_eq!(pinning.cpusets().len(), 4);
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn with_policy(
+        topology: SharedTopology,
+        num_workers: usize,
+        policy: AffinityPolicy,
+    ) -> Result<Self, DistributeError> {
+        let cpusets = topology
+            .affinity_assignments(num_workers, policy)?
+            .collect();
+        Ok(Self { topology, cpusets })
+    }
+
+    /// CPUs that each worker will be bound to
+    pub fn cpusets(&self) -> &[CpuSet] {
+        &self.cpusets[..]
+    }
+
+    /// Bind the current thread as the worker with index `index`
+    ///
+    /// If `index` is greater than or equal to the number of workers, CPU sets
+    /// are reused in a round-robin fashion.
+    ///
+    /// # Errors
+    ///
+    /// Binding the current thread can fail for the reasons outlined in the
+    /// documentation of [`Topology::bind_cpu()`].
+    pub fn bind_worker(&self, index: usize) -> Result<(), BindingError<CpuBindingError>> {
+        let Some(cpuset) = self.cpusets.get(index % self.cpusets.len().max(1)) else {
+            return Ok(());
+        };
+        self.topology.bind_cpu(cpuset, CpuBindingFlags::THREAD)
+    }
+
+    /// Worker start callback, in the format expected by `rayon`'s
+    /// `ThreadPoolBuilder::start_handler()`
+    ///
+    /// The resulting callback binds each worker thread using
+    /// [`WorkerPinning::bind_worker()`]. Since the callback cannot report
+    /// errors to its caller, binding errors are forwarded to `on_error`
+    /// along with the index of the worker that could not be bound.
+    pub fn start_handler(
+        self,
+        on_error: impl Fn(usize, BindingError<CpuBindingError>) + Send + Sync + 'static,
+    ) -> impl Fn(usize) + Clone + Send + Sync + 'static {
+        let pinning = Arc::new(self);
+        let on_error = Arc::new(on_error);
+        move |index| {
+            if let Err(e) = pinning.bind_worker(index) {
+                on_error(index, e);
+            }
+        }
+    }
+
+    /// Configure a `rayon` thread pool whose workers are bound to these CPUs
+    ///
+    /// The resulting builder spawns one worker per CPU set of
+    /// [`WorkerPinning::cpusets()`], and binds each of them on startup using
+    /// the callback of [`WorkerPinning::start_handler()`]. Binding errors are
+    /// forwarded to `on_error` along with the index of the worker that could
+    /// not be bound. Other settings of the builder can be adjusted before
+    /// building the thread pool, but beware that overriding the start handler
+    /// or the number of threads will defeat the purpose of this method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::pinning::WorkerPinning, Topology};
+    /// # use std::sync::Arc;
+    /// let topology = Arc::new(Topology::new()?);
+    /// let pinning = WorkerPinning::new(topology, 2, false)?;
+    /// let pool = pinning
+    ///     .rayon_pool_builder(|index, error| {
+    ///         println!("Failed to bind worker {index}: {error}");
+    ///     })
+    ///     .build()?;
+    /// assert_eq!(pool.current_num_threads(), 2);
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn rayon_pool_builder(
+        self,
+        on_error: impl Fn(usize, BindingError<CpuBindingError>) + Send + Sync + 'static,
+    ) -> rayon::ThreadPoolBuilder {
+        let num_threads = self.cpusets.len();
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .start_handler(self.start_handler(on_error))
+    }
+
+    /// Worker start callback, in the format expected by `tokio`'s
+    /// `Builder::on_thread_start()`
+    ///
+    /// Unlike `rayon`, some thread pools do not tell their start callback
+    /// which worker is being started. The callback produced by this method
+    /// handles this by atomically handing out worker indices in the order in
+    /// which threads start, then binding each thread using
+    /// [`WorkerPinning::bind_worker()`]. This is race-free even if multiple
+    /// threads start concurrently.
+    ///
+    /// If more threads than workers are started, CPU sets are reused in a
+    /// round-robin fashion. Binding errors are forwarded to `on_error` along
+    /// with the index of the worker that could not be bound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::pinning::WorkerPinning, Topology};
+    /// # use std::sync::Arc;
+    /// let topology = Arc::new(Topology::new()?);
+    /// let pinning = WorkerPinning::new(topology, 2, true)?;
+    /// let on_thread_start = pinning.thread_binder(|index, error| {
+    ///     println!("Failed to bind worker {index}: {error}");
+    /// });
+    /// let workers = (0..2)
+    ///     .map(|_| std::thread::spawn(on_thread_start.clone()))
+    ///     .collect::<Vec<_>>();
+    /// for worker in workers {
+    ///     worker.join().unwrap();
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn thread_binder(
+        self,
+        on_error: impl Fn(usize, BindingError<CpuBindingError>) + Send + Sync + 'static,
+    ) -> impl Fn() + Clone + Send + Sync + 'static {
+        let start_handler = self.start_handler(on_error);
+        let next_index = Arc::new(AtomicUsize::new(0));
+        move || start_handler(next_index.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Set of OS threads, each bound to one object of a topology
+///
+/// This is the common skeleton of thread-per-core services: one OS thread is
+/// spawned per object of a given type (e.g. one per [`Core`], [`L3Cache`] or
+/// [`NUMANode`]), bound to the CPUs of that object, and runs until it is asked
+/// to stop via [`PinnedThreads::shutdown()`].
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{cpu::pinning::PinnedThreads, object::types::ObjectType, Topology};
+/// # use std::sync::Arc;
+/// let topology = Arc::new(Topology::new()?);
+/// let threads = PinnedThreads::spawn(topology, ObjectType::Core, |context| {
+///     while !context.should_stop() {
+///         // Process requests for this core...
+///         # break;
+///     }
+///     context.logical_index()
+/// })?;
+/// for thread in threads.threads() {
+///     let context = thread.context();
+///     println!(
+///         "Thread for core #{} runs on CPUs {}",
+///         context.logical_index(),
+///         context.cpuset()
+///     );
+/// }
+/// for result in threads.shutdown() {
+///     println!("Thread result: {:?}", result);
+/// }
+/// # Ok::<(), eyre::Report>(())
+/// ```
+///
+/// [`Core`]: ObjectType::Core
+/// [`L3Cache`]: ObjectType::L3Cache
+/// [`NUMANode`]: ObjectType::NUMANode
+#[derive(Debug)]
+pub struct PinnedThreads<T> {
+    /// Threads that were spawned
+    threads: Vec<PinnedThread<T>>,
+
+    /// Flag that tells threads to stop
+    stop: Arc<AtomicBool>,
+}
+//
+impl<T: Send + 'static> PinnedThreads<T> {
+    /// Spawn one thread per object of type `object_type`
+    ///
+    /// Each thread binds itself to the CPUs of its object, then runs `work`.
+    /// Objects without CPUs, like I/O objects, are ignored.
+    ///
+    /// The [`PinnedThreadContext`] that `work` receives tells which object the
+    /// thread is bound to, and whether the thread should stop. If a thread
+    /// fails to bind itself, it does not run `work` and the binding error is
+    /// reported when [`PinnedThreads::shutdown()`] is called.
+    ///
+    /// # Errors
+    ///
+    /// If the operating system fails to spawn a thread. In that case, the
+    /// threads that were already spawned are shut down.
+    pub fn spawn(
+        topology: SharedTopology,
+        object_type: ObjectType,
+        work: impl Fn(&PinnedThreadContext) -> T + Send + Sync + 'static,
+    ) -> io::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let contexts = topology
+            .objects_with_type(object_type)
+            .filter_map(|obj| {
+                let cpuset = obj.cpuset()?;
+                (!cpuset.is_empty()).then(|| PinnedThreadContext {
+                    object_type,
+                    logical_index: obj.logical_index(),
+                    cpuset: cpuset.clone_target(),
+                    nodeset: obj.nodeset().map(|nodeset| nodeset.clone_target()),
+                    stop: Arc::clone(&stop),
+                })
+            })
+            .collect::<Vec<_>>();
+        let work = Arc::new(work);
+        let mut result = Self {
+            threads: Vec::with_capacity(contexts.len()),
+            stop,
+        };
+        for context in contexts {
+            let topology = Arc::clone(&topology);
+            let work = Arc::clone(&work);
+            let thread_context = context.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("{object_type}#{}", context.logical_index))
+                .spawn(move || {
+                    topology.bind_cpu(&thread_context.cpuset, CpuBindingFlags::THREAD)?;
+                    Ok(work(&thread_context))
+                });
+            match spawned {
+                Ok(handle) => result.threads.push(PinnedThread { handle, context }),
+                Err(e) => {
+                    result.shutdown();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Threads that were spawned
+    pub fn threads(&self) -> &[PinnedThread<T>] {
+        &self.threads[..]
+    }
+
+    /// Ask all threads to stop
+    ///
+    /// This only sets the flag that [`PinnedThreadContext::should_stop()`]
+    /// reports. Threads are expected to check it regularly and exit.
+    pub fn request_stop(&self) {
+        self.stop.store(true, Ordering::Release);
+    }
+
+    /// Ask all threads to stop, then wait for them to finish
+    ///
+    /// The result of each thread is returned in the same order as in
+    /// [`PinnedThreads::threads()`]. It is an error if the thread panicked,
+    /// and the inner result is an error if the thread failed to bind itself.
+    ///
+    /// Dropping a `PinnedThreads` also stops and joins the threads, but
+    /// discards their results.
+    #[allow(clippy::type_complexity)]
+    pub fn shutdown(mut self) -> Vec<thread::Result<Result<T, BindingError<CpuBindingError>>>> {
+        self.request_stop();
+        std::mem::take(&mut self.threads)
+            .into_iter()
+            .map(|thread| thread.handle.join())
+            .collect()
+    }
+}
+//
+impl<T> Drop for PinnedThreads<T> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        for thread in self.threads.drain(..) {
+            // Thread panics and binding errors can't be reported from here
+            thread.handle.join().ok();
+        }
+    }
+}
+
+/// OS thread that was spawned by [`PinnedThreads::spawn()`]
+#[derive(Debug)]
+pub struct PinnedThread<T> {
+    /// Handle to the thread
+    handle: JoinHandle<Result<T, BindingError<CpuBindingError>>>,
+
+    /// Information about the topology object the thread is bound to
+    context: PinnedThreadContext,
+}
+//
+impl<T> PinnedThread<T> {
+    /// Underlying OS thread
+    pub fn thread(&self) -> &Thread {
+        self.handle.thread()
+    }
+
+    /// Information about the topology object the thread is bound to
+    pub fn context(&self) -> &PinnedThreadContext {
+        &self.context
+    }
+}
+
+/// Information about the topology object that a [`PinnedThread`] is bound to
+#[derive(Clone, Debug)]
+pub struct PinnedThreadContext {
+    /// Type of the object
+    object_type: ObjectType,
+
+    /// Logical index of the object
+    logical_index: usize,
+
+    /// CPUs of the object
+    cpuset: CpuSet,
+
+    /// NUMA nodes of the object
+    nodeset: Option<NodeSet>,
+
+    /// Flag that tells threads to stop
+    stop: Arc<AtomicBool>,
+}
+//
+impl PinnedThreadContext {
+    /// Type of the object that the thread is bound to
+    pub fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    /// Logical index of the object that the thread is bound to
+    ///
+    /// See [`TopologyObject::logical_index()`] for more information.
+    pub fn logical_index(&self) -> usize {
+        self.logical_index
+    }
+
+    /// CPUs that the thread is bound to
+    pub fn cpuset(&self) -> &CpuSet {
+        &self.cpuset
+    }
+
+    /// NUMA nodes that are local to the thread, if known
+    ///
+    /// This is a good default target for the memory allocations of the
+    /// thread.
+    pub fn nodeset(&self) -> Option<&NodeSet> {
+        self.nodeset.as_ref()
+    }
+
+    /// Truth that the thread was asked to stop
+    pub fn should_stop(&self) -> bool {
+        self.stop.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::support::{CpuBindingSupport, FeatureSupport};
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, time::Duration};
+
+    assert_impl_all!(PinnedThreads<u32>: Debug, Send, Sync);
+    assert_impl_all!(PinnedThread<u32>: Debug, Send, Sync);
+    assert_impl_all!(PinnedThreadContext: Clone, Debug, Send, Sync);
+
+    /// Shared copy of the test topology
+    fn shared_topology() -> SharedTopology {
+        Arc::new(Topology::test_instance().clone())
+    }
+
+    /// Truth that a thread should manage to bind itself to `cpuset`
+    fn can_bind_thread(topology: &Topology, cpuset: &CpuSet) -> bool {
+        topology.supports(
+            FeatureSupport::cpu_binding,
+            CpuBindingSupport::set_current_thread,
+        ) && cpuset.intersects(topology.allowed_cpuset())
+    }
+
+    /// CPU binding of the current thread, if it can be queried
+    fn current_thread_binding(topology: &Topology) -> Option<CpuSet> {
+        if !topology.supports(
+            FeatureSupport::cpu_binding,
+            CpuBindingSupport::get_current_thread,
+        ) {
+            return None;
+        }
+        topology.cpu_binding(CpuBindingFlags::THREAD).ok()
+    }
+
+    /// CPU sets of the cores of the test topology
+    fn core_cpusets(topology: &Topology) -> Vec<CpuSet> {
+        topology
+            .objects_with_type(ObjectType::Core)
+            .filter_map(TopologyObject::cpuset)
+            .filter(|cpuset| !cpuset.is_empty())
+            .map(|cpuset| cpuset.clone_target())
+            .collect()
+    }
+
+    #[test]
+    fn worker_cpusets() {
+        let topology = Topology::test_instance();
+        let cores = core_cpusets(topology);
+        assert!(topology.worker_cpusets(0, false).unwrap().is_empty());
+        for num_workers in [1, cores.len(), 2 * cores.len() + 1] {
+            for avoid_smt in [false, true] {
+                let cpusets = topology.worker_cpusets(num_workers, avoid_smt).unwrap();
+                assert_eq!(cpusets.len(), num_workers);
+                for cpuset in &cpusets {
+                    assert!(cores.iter().any(|core| core.includes(cpuset)));
+                    if avoid_smt {
+                        assert_eq!(cpuset.weight(), Some(1));
+                    } else if num_workers <= cores.len() {
+                        assert!(cores.contains(cpuset));
+                    }
+                }
+
+                // As long as there are enough cores, workers don't share them
+                if num_workers <= cores.len() {
+                    for (idx, cpuset) in cpusets.iter().enumerate() {
+                        for other in &cpusets[idx + 1..] {
+                            assert!(!cpuset.intersects(other));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Allowed CPU sets of objects of type `ty` in the test topology
+    fn allowed_cpusets(topology: &Topology, ty: ObjectType) -> Vec<CpuSet> {
+        topology
+            .objects_with_type(ty)
+            .filter_map(TopologyObject::cpuset)
+            .map(|cpuset| cpuset & topology.allowed_cpuset())
+            .filter(|cpuset| !cpuset.is_empty())
+            .collect()
+    }
+
+    /// Check that `assignments` assigns `domains` to threads in round-robin
+    fn check_round_robin(assignments: &[CpuSet], domains: &[CpuSet]) {
+        for (thread, cpuset) in assignments.iter().enumerate() {
+            assert_eq!(cpuset, &domains[thread % domains.len()]);
+        }
+    }
+
+    #[test]
+    fn affinity_assignments() {
+        let topology = Topology::test_instance();
+        let num_pus = allowed_cpusets(topology, ObjectType::PU).len();
+        let policies = [
+            AffinityPolicy::Compact,
+            AffinityPolicy::Scatter,
+            AffinityPolicy::PerCore,
+            AffinityPolicy::PerL3,
+            AffinityPolicy::PackWithinL3,
+            AffinityPolicy::PerNUMANode,
+        ];
+        for policy in policies {
+            for num_threads in [0, 1, num_pus, 2 * num_pus + 1] {
+                let assignments = topology.affinity_assignments(num_threads, policy).unwrap();
+                assert_eq!(assignments.len(), num_threads);
+                let reversed = assignments.clone().rev().collect::<Vec<_>>();
+                let assignments = assignments.collect::<Vec<_>>();
+                assert_eq!(
+                    reversed,
+                    assignments.iter().rev().cloned().collect::<Vec<_>>()
+                );
+                for cpuset in &assignments {
+                    assert!(!cpuset.is_empty());
+                }
+
+                match policy {
+                    AffinityPolicy::Compact => {
+                        check_round_robin(&assignments, &allowed_cpusets(topology, ObjectType::PU))
+                    }
+                    AffinityPolicy::Scatter => {
+                        if num_threads <= num_pus {
+                            for (idx, cpuset) in assignments.iter().enumerate() {
+                                for other in &assignments[idx + 1..] {
+                                    assert!(!cpuset.intersects(other));
+                                }
+                            }
+                        }
+                    }
+                    AffinityPolicy::PerCore => check_round_robin(
+                        &assignments,
+                        &allowed_cpusets(topology, ObjectType::Core),
+                    ),
+                    AffinityPolicy::PerL3 => {
+                        let l3_cpusets = allowed_cpusets(topology, ObjectType::L3Cache);
+                        if !l3_cpusets.is_empty() {
+                            check_round_robin(&assignments, &l3_cpusets);
+                        }
+                    }
+                    AffinityPolicy::PerNUMANode => check_round_robin(
+                        &assignments,
+                        &allowed_cpusets(topology, ObjectType::NUMANode),
+                    ),
+                    AffinityPolicy::PackWithinL3 => {
+                        let cores = allowed_cpusets(topology, ObjectType::Core);
+                        let l3_slots = allowed_cpusets(topology, ObjectType::L3Cache)
+                            .into_iter()
+                            .flat_map(|l3_cpuset| {
+                                let num_cores = cores
+                                    .iter()
+                                    .filter(|core| l3_cpuset.includes(*core))
+                                    .count();
+                                std::iter::repeat(l3_cpuset).take(num_cores.max(1))
+                            })
+                            .collect::<Vec<_>>();
+                        if !l3_slots.is_empty() {
+                            check_round_robin(&assignments, &l3_slots);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pack_within_l3() {
+        // Threads with neighboring indices should share an L3 cache, until
+        // it has one thread per core
+        let topology = Topology::test_instance();
+        let cores = allowed_cpusets(topology, ObjectType::Core);
+        let assignments = topology
+            .affinity_assignments(cores.len(), AffinityPolicy::PackWithinL3)
+            .unwrap()
+            .collect::<Vec<_>>();
+        let mut thread = 0;
+        while thread < assignments.len() {
+            let l3_cpuset = &assignments[thread];
+            let num_cores = cores
+                .iter()
+                .filter(|core| l3_cpuset.includes(*core))
+                .count()
+                .max(1);
+            for cpuset in assignments.iter().skip(thread).take(num_cores) {
+                assert_eq!(cpuset, l3_cpuset);
+            }
+            thread += num_cores;
+        }
+    }
+
+    #[test]
+    fn with_policy() {
+        let topology = shared_topology();
+        for policy in [AffinityPolicy::PerCore, AffinityPolicy::PackWithinL3] {
+            let pinning = WorkerPinning::with_policy(Arc::clone(&topology), 3, policy).unwrap();
+            let expected = topology
+                .affinity_assignments(3, policy)
+                .unwrap()
+                .collect::<Vec<_>>();
+            assert_eq!(pinning.cpusets(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn bind_worker() {
+        let topology = shared_topology();
+        let pinning = WorkerPinning::new(Arc::clone(&topology), 2, false).unwrap();
+        assert_eq!(
+            pinning.cpusets(),
+            &topology.worker_cpusets(2, false).unwrap()[..]
+        );
+
+        // Worker indices past the number of workers wrap around
+        for index in 0..2 * pinning.cpusets().len() {
+            let pinning = pinning.clone();
+            let topology = Arc::clone(&topology);
+            thread::spawn(move || {
+                let cpuset = &pinning.cpusets()[index % pinning.cpusets().len()];
+                let result = pinning.bind_worker(index);
+                if !can_bind_thread(&topology, cpuset) {
+                    return;
+                }
+                result.unwrap();
+                if let Some(binding) = current_thread_binding(&topology) {
+                    assert_eq!(binding, cpuset & topology.allowed_cpuset());
+                }
+            })
+            .join()
+            .unwrap();
+        }
+
+        // Pinning zero workers does not bind anything
+        let empty = WorkerPinning::new(topology, 0, false).unwrap();
+        assert!(empty.cpusets().is_empty());
+        assert_eq!(empty.bind_worker(42), Ok(()));
+    }
+
+    #[test]
+    fn start_handler() {
+        let topology = shared_topology();
+        let pinning = WorkerPinning::new(Arc::clone(&topology), 2, true).unwrap();
+        let cpusets = pinning.cpusets().to_vec();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let start_handler = {
+            let failures = Arc::clone(&failures);
+            pinning.start_handler(move |_index, _error| {
+                failures.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+        let workers = (0..cpusets.len())
+            .map(|index| {
+                let start_handler = start_handler.clone();
+                thread::spawn(move || start_handler(index))
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        let expected_failures = cpusets
+            .iter()
+            .filter(|cpuset| !can_bind_thread(&topology, cpuset))
+            .count();
+        assert_eq!(failures.load(Ordering::Relaxed), expected_failures);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn rayon_pool_builder() {
+        let topology = shared_topology();
+        let pinning = WorkerPinning::new(Arc::clone(&topology), 2, true).unwrap();
+        let cpusets = pinning.cpusets().to_vec();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let pool = {
+            let failures = Arc::clone(&failures);
+            pinning
+                .rayon_pool_builder(move |_index, _error| {
+                    failures.fetch_add(1, Ordering::Relaxed);
+                })
+                .build()
+                .unwrap()
+        };
+        assert_eq!(pool.current_num_threads(), cpusets.len());
+
+        // Each worker should be bound to the CPU set matching its index
+        let bindings =
+            pool.broadcast(|context| (context.index(), current_thread_binding(&topology)));
+        let expected_failures = cpusets
+            .iter()
+            .filter(|cpuset| !can_bind_thread(&topology, cpuset))
+            .count();
+        assert_eq!(failures.load(Ordering::Relaxed), expected_failures);
+        if expected_failures == 0 {
+            for (index, binding) in bindings {
+                if let Some(binding) = binding {
+                    assert_eq!(binding, &cpusets[index] & topology.allowed_cpuset());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn thread_binder() {
+        let topology = shared_topology();
+        let pinning = WorkerPinning::new(Arc::clone(&topology), 4, false).unwrap();
+        let cpusets = pinning.cpusets().to_vec();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let on_thread_start = {
+            let failures = Arc::clone(&failures);
+            pinning.thread_binder(move |_index, _error| {
+                failures.fetch_add(1, Ordering::Relaxed);
+            })
+        };
+
+        // Each started thread gets its own worker index, so if binding works,
+        // threads end up bound to all worker CPU sets
+        let bindings = (0..cpusets.len())
+            .map(|_| {
+                let on_thread_start = on_thread_start.clone();
+                let topology = Arc::clone(&topology);
+                thread::spawn(move || {
+                    on_thread_start();
+                    current_thread_binding(&topology)
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>();
+        if cpusets
+            .iter()
+            .all(|cpuset| can_bind_thread(&topology, cpuset))
+        {
+            assert_eq!(failures.load(Ordering::Relaxed), 0);
+            let mut expected = cpusets
+                .iter()
+                .map(|cpuset| cpuset & topology.allowed_cpuset())
+                .collect::<Vec<_>>();
+            let mut actual = bindings.into_iter().flatten().collect::<Vec<_>>();
+            if !actual.is_empty() {
+                expected.sort_by_key(CpuSet::first_set);
+                actual.sort_by_key(CpuSet::first_set);
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn spawn_and_shutdown() {
+        let topology = shared_topology();
+        let threads = PinnedThreads::spawn(Arc::clone(&topology), ObjectType::Core, |context| {
+            while !context.should_stop() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            context.logical_index()
+        })
+        .unwrap();
+
+        // There should be one thread per core with CPUs
+        let cores = topology
+            .objects_with_type(ObjectType::Core)
+            .filter(|core| core.cpuset().is_some_and(|cpuset| !cpuset.is_empty()))
+            .collect::<Vec<_>>();
+        assert_eq!(threads.threads().len(), cores.len());
+        for (thread, core) in threads.threads().iter().zip(&cores) {
+            let context = thread.context();
+            assert_eq!(context.object_type(), ObjectType::Core);
+            assert_eq!(context.logical_index(), core.logical_index());
+            assert_eq!(Some(context.cpuset()), core.cpuset().as_deref());
+            assert_eq!(context.nodeset(), core.nodeset().as_deref());
+            assert_eq!(
+                thread.thread().name(),
+                Some(format!("{}#{}", ObjectType::Core, core.logical_index()).as_str())
+            );
+        }
+
+        // Threads should stop when asked to, and report their result
+        let results = threads.shutdown();
+        assert_eq!(results.len(), cores.len());
+        for (result, core) in results.into_iter().zip(&cores) {
+            match result.unwrap() {
+                Ok(logical_index) => assert_eq!(logical_index, core.logical_index()),
+                Err(_) => assert!(!can_bind_thread(&topology, &core.cpuset().unwrap())),
+            }
+        }
+    }
+
+    #[test]
+    fn request_stop() {
+        let threads = PinnedThreads::spawn(shared_topology(), ObjectType::Core, |context| {
+            context.should_stop()
+        })
+        .unwrap();
+        for thread in threads.threads() {
+            assert!(!thread.context().should_stop());
+        }
+        threads.request_stop();
+        for thread in threads.threads() {
+            assert!(thread.context().should_stop());
+        }
+        threads.shutdown();
+    }
+
+    #[test]
+    fn drop_stops_threads() {
+        let stopped = Arc::new(AtomicUsize::new(0));
+        let threads = {
+            let stopped = Arc::clone(&stopped);
+            PinnedThreads::spawn(shared_topology(), ObjectType::Core, move |context| {
+                while !context.should_stop() {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                stopped.fetch_add(1, Ordering::Relaxed);
+            })
+            .unwrap()
+        };
+        // Threads that failed to bind themselves do not run their work
+        let expected = threads
+            .threads()
+            .iter()
+            .filter(|thread| can_bind_thread(Topology::test_instance(), thread.context().cpuset()))
+            .count();
+        std::mem::drop(threads);
+        assert_eq!(stopped.load(Ordering::Relaxed), expected);
+    }
+}
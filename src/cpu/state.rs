@@ -0,0 +1,255 @@
+//! PU availability
+//!
+//! hwloc describes the availability of processing units through three CPU
+//! sets: the complete CPU set, which includes offline PUs, the topology CPU
+//! set, which only includes online PUs, and the allowed CPU set, which only
+//! includes PUs that the process is allowed to run on. This module derives the
+//! [`PuState`] of each PU from these sets.
+
+#[cfg(doc)]
+use crate::topology::builder::BuildFlags;
+use crate::{
+    bitmap::BitmapIndex, cpu::cpuset::CpuSet, errors::ForeignObjectError, object::TopologyObject,
+    topology::Topology,
+};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Availability of a processing unit
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum PuState {
+    /// The PU is online and the process is allowed to run on it
+    #[display(fmt = "available")]
+    Available,
+
+    /// The PU is online, but the process is not allowed to run on it
+    ///
+    /// This state can only be observed if the topology was built with
+    /// [`BuildFlags::INCLUDE_DISALLOWED`]. Otherwise, disallowed PUs are
+    /// removed from the topology and reported as [`PuState::Offline`].
+    #[display(fmt = "disallowed")]
+    Disallowed,
+
+    /// The PU is known to exist, but is not usable
+    ///
+    /// This usually means that the PU was taken offline.
+    #[display(fmt = "offline")]
+    Offline,
+}
+
+/// # PU availability
+impl Topology {
+    /// Availability of the PU with OS index `pu`
+    ///
+    /// Returns `None` if this PU is not part of [`Topology::complete_cpuset()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::state::PuState, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let first_pu = topology.cpuset().first_set().unwrap();
+    /// assert_ne!(topology.pu_state(first_pu), Some(PuState::Offline));
+    /// ```
+    pub fn pu_state(&self, pu: BitmapIndex) -> Option<PuState> {
+        if !self.complete_cpuset().is_set(pu) {
+            None
+        } else if !self.cpuset().is_set(pu) {
+            Some(PuState::Offline)
+        } else if !self.allowed_cpuset().is_set(pu) {
+            Some(PuState::Disallowed)
+        } else {
+            Some(PuState::Available)
+        }
+    }
+
+    /// Availability of every PU of [`Topology::complete_cpuset()`], by
+    /// increasing OS index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::state::PuState, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let num_available = topology
+    ///     .pu_states()
+    ///     .filter(|&(_pu, state)| state == PuState::Available)
+    ///     .count();
+    /// assert_eq!(Some(num_available), topology.allowed_cpuset().weight());
+    /// ```
+    pub fn pu_states(&self) -> impl Iterator<Item = (BitmapIndex, PuState)> + '_ {
+        self.complete_cpuset().into_iter().map(|pu| {
+            (
+                pu,
+                self.pu_state(pu).expect("PU comes from complete_cpuset"),
+            )
+        })
+    }
+
+    /// PUs that are known to exist, but are not usable
+    ///
+    /// This is the difference between [`Topology::complete_cpuset()`] and
+    /// [`Topology::cpuset()`].
+    pub fn offline_cpuset(&self) -> CpuSet {
+        self.complete_cpuset() - self.cpuset()
+    }
+
+    /// PUs that are online, but that the process is not allowed to run on
+    ///
+    /// This is the difference between [`Topology::cpuset()`] and
+    /// [`Topology::allowed_cpuset()`], which is always empty unless the
+    /// topology was built with [`BuildFlags::INCLUDE_DISALLOWED`].
+    pub fn disallowed_cpuset(&self) -> CpuSet {
+        self.cpuset() - self.allowed_cpuset()
+    }
+}
+
+/// # PU availability
+impl TopologyObject {
+    /// Availability of every PU of [`TopologyObject::complete_cpuset()`], by
+    /// increasing OS index
+    ///
+    /// `topology` must be the topology that this object belongs to, as it is
+    /// used to tell which PUs are allowed. Objects that have no CPU set, like
+    /// I/O objects, have no PU.
+    ///
+    /// # Errors
+    ///
+    /// [`ForeignObjectError`] if this object does not belong to `topology`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{cpu::state::PuState, object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for package in topology.objects_with_type(ObjectType::Package) {
+    ///     let num_offline = package
+    ///         .pu_states(topology)?
+    ///         .filter(|&(_pu, state)| state == PuState::Offline)
+    ///         .count();
+    ///     println!("{package} has {num_offline} offline PU(s)");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn pu_states<'self_>(
+        &'self_ self,
+        topology: &'self_ Topology,
+    ) -> Result<impl Iterator<Item = (BitmapIndex, PuState)> + 'self_, ForeignObjectError> {
+        if !topology.contains(self) {
+            return Err(self.into());
+        }
+        let cpuset = self.cpuset();
+        let allowed_cpuset = topology.allowed_cpuset();
+        Ok(self.complete_cpuset().into_iter().flatten().map(move |pu| {
+            let state = if !cpuset.as_ref().is_some_and(|cpuset| cpuset.is_set(pu)) {
+                PuState::Offline
+            } else if !allowed_cpuset.is_set(pu) {
+                PuState::Disallowed
+            } else {
+                PuState::Available
+            };
+            (pu, state)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{
+        fmt::{Debug, Display},
+        hash::Hash,
+    };
+
+    assert_impl_all!(PuState: Copy, Debug, Display, Eq, Hash, Send, Sync);
+
+    /// Expected state of a PU of the complete CPU set, from first principles
+    fn expected_state(topology: &Topology, pu: BitmapIndex) -> PuState {
+        if topology.offline_cpuset().is_set(pu) {
+            PuState::Offline
+        } else if topology.disallowed_cpuset().is_set(pu) {
+            PuState::Disallowed
+        } else {
+            PuState::Available
+        }
+    }
+
+    #[test]
+    fn cpusets() {
+        let topology = Topology::test_instance();
+        let offline = topology.offline_cpuset();
+        let disallowed = topology.disallowed_cpuset();
+        assert!(!offline.intersects(&disallowed));
+        assert!(!offline.intersects(topology.cpuset()));
+        assert_eq!(&offline | topology.cpuset(), topology.complete_cpuset());
+        assert!(topology.cpuset().includes(&disallowed));
+        assert!(!disallowed.intersects(topology.allowed_cpuset()));
+        assert_eq!(
+            &disallowed | topology.allowed_cpuset(),
+            topology.cpuset().clone_target()
+        );
+    }
+
+    #[test]
+    fn pu_state() {
+        let topology = Topology::test_instance();
+        let complete = topology.complete_cpuset();
+        for pu in complete.iter_set() {
+            assert_eq!(topology.pu_state(pu), Some(expected_state(topology, pu)));
+        }
+        let last = complete.last_set().unwrap();
+        let outside = BitmapIndex::try_from(usize::from(last) + 1).unwrap();
+        assert_eq!(topology.pu_state(outside), None);
+    }
+
+    #[test]
+    fn pu_states() {
+        let topology = Topology::test_instance();
+        let states = topology.pu_states().collect::<Vec<_>>();
+        let expected = topology
+            .complete_cpuset()
+            .iter_set()
+            .map(|pu| (pu, expected_state(topology, pu)))
+            .collect::<Vec<_>>();
+        assert_eq!(states, expected);
+    }
+
+    #[test]
+    fn object_pu_states() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        assert_eq!(
+            root.pu_states(topology).unwrap().collect::<Vec<_>>(),
+            topology.pu_states().collect::<Vec<_>>()
+        );
+        for obj in topology.objects() {
+            let states = obj.pu_states(topology).unwrap().collect::<Vec<_>>();
+            let expected = obj
+                .complete_cpuset()
+                .map(|complete| {
+                    complete
+                        .iter_set()
+                        .map(|pu| (pu, topology.pu_state(pu).unwrap()))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            assert_eq!(states, expected);
+        }
+    }
+
+    #[test]
+    fn foreign_object_pu_states() {
+        let topology = Topology::test_instance();
+        let foreign = Topology::foreign_instance().root_object();
+        assert_eq!(
+            foreign.pu_states(topology).err(),
+            Some(ForeignObjectError::from(foreign))
+        );
+    }
+}
@@ -9,18 +9,23 @@
 // At the implementation level, this is also the place where all the low-level
 // handling of hwloc errors is implemented.
 
-use crate::object::{TopologyObject, TopologyObjectID};
 #[cfg(doc)]
 use crate::topology::Topology;
+use crate::{
+    cpu::{binding::CpuBindingError, cpuset::CpuSet},
+    memory::{binding::MemoryBindingError, nodeset::NodeSet},
+    object::{depth::TypeToDepthError, TopologyObject, TopologyObjectID},
+    topology::DistributeError,
+};
 use derive_more::From;
 use errno::Errno;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
-    error::Error,
+    error::Error as StdError,
     ffi::{c_int, c_uint},
-    fmt::Debug,
+    fmt::{self, Debug, Display},
     ptr::NonNull,
 };
 use thiserror::Error;
@@ -69,6 +74,44 @@ fn check_errno<R: Copy + Ord>(
     (result, interpret_errno(result < lowest_good_value))
 }
 
+/// Call an hwloc entry point with errno checking
+///
+/// This is [`check_errno()`] with the extra step of reporting the call to the
+/// call observer when the `trace-calls` feature is enabled, and emitting a
+/// `tracing` event when the `tracing` feature is enabled.
+fn observe_call<R: Copy + Ord>(
+    api: &'static str,
+    callback: impl FnOnce() -> R,
+    lowest_good_value: R,
+) -> (R, Option<Errno>) {
+    #[cfg(any(feature = "trace-calls", feature = "tracing"))]
+    {
+        let start = std::time::Instant::now();
+        let (result, errno) = check_errno(callback, lowest_good_value);
+        let succeeded = result >= lowest_good_value;
+        let duration = start.elapsed();
+        #[cfg(feature = "trace-calls")]
+        crate::trace::notify(&crate::trace::HwlocCall {
+            api,
+            succeeded,
+            errno,
+            duration,
+        });
+        #[cfg(feature = "tracing")]
+        if succeeded {
+            tracing::trace!(api, ?duration, "hwloc call succeeded");
+        } else {
+            tracing::debug!(api, ?errno, ?duration, "hwloc call failed");
+        }
+        (result, errno)
+    }
+    #[cfg(not(any(feature = "trace-calls", feature = "tracing")))]
+    {
+        let _ = api;
+        check_errno(callback, lowest_good_value)
+    }
+}
+
 /// Raw error emitted by hwloc functions that follow the usual convention
 ///
 /// Hwloc APIs almost always error out by returning -1 if they return an
@@ -101,7 +144,7 @@ pub(crate) fn call_hwloc_ptr_mut<T>(
     api: &'static str,
     call: impl FnOnce() -> *mut T,
 ) -> Result<NonNull<T>, RawHwlocError> {
-    let (result, errno) = check_errno(call, 1 as *mut T);
+    let (result, errno) = observe_call(api, call, 1 as *mut T);
     NonNull::new(result).ok_or(RawHwlocError { api, errno })
 }
 
@@ -207,7 +250,11 @@ pub(crate) fn call_hwloc_int_raw(
             .then_some(result)
             .ok_or(RawNegIntError { api, result, errno })
     }
-    check_raw_result(api, check_errno(call, lowest_good_value), lowest_good_value)
+    check_raw_result(
+        api,
+        observe_call(api, call, lowest_good_value),
+        lowest_good_value,
+    )
 }
 
 /// A function errored out either on the Rust or hwloc side
@@ -221,7 +268,7 @@ pub(crate) fn call_hwloc_int_raw(
 /// trust it and return a pure Rust error type, panicking if another hwloc
 /// error is observed.
 #[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
-pub enum HybridError<RustError: Error> {
+pub enum HybridError<RustError: StdError> {
     /// An error was caught on the Rust side
     #[error(transparent)]
     Rust(#[from] RustError),
@@ -295,6 +342,300 @@ impl<'topology> From<&'topology TopologyObject> for ForeignObjectError {
     }
 }
 
+/// Context of a CPU or memory binding operation
+///
+/// This records which hwloc entry point was called, on which target, and with
+/// which flags and CPU or node set, so that binding failures can be diagnosed
+/// from error messages alone. Flags and sets are recorded in textual form, as
+/// they would be displayed by their `Debug` and `Display` implementations.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BindingContext {
+    /// Hwloc entry point that was called
+    api: &'static str,
+
+    /// Object whose binding was queried or modified
+    target: String,
+
+    /// Binding flags
+    flags: String,
+
+    /// CPU or node set that the target was being bound to, if any
+    set: Option<String>,
+}
+//
+impl BindingContext {
+    /// Record the context of a binding operation
+    pub(crate) fn new(
+        api: &'static str,
+        target: impl Display,
+        flags: impl Debug,
+        set: Option<&dyn Display>,
+    ) -> Self {
+        Self {
+            api,
+            target: target.to_string(),
+            flags: format!("{flags:?}"),
+            set: set.map(ToString::to_string),
+        }
+    }
+
+    /// Hwloc entry point that was called
+    pub fn api(&self) -> &'static str {
+        self.api
+    }
+
+    /// Object whose binding was queried or modified
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Binding flags
+    pub fn flags(&self) -> &str {
+        &self.flags
+    }
+
+    /// CPU or node set that the target was being bound to, if any
+    ///
+    /// This is `None` for operations that query bindings, and for operations
+    /// that reset bindings to their default.
+    pub fn set(&self) -> Option<&str> {
+        self.set.as_deref()
+    }
+}
+//
+impl Display for BindingContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} with flags {}",
+            self.api, self.target, self.flags
+        )?;
+        if let Some(set) = &self.set {
+            write!(f, " and set {set}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Error from a CPU or memory binding operation, along with its context
+///
+/// The [`cause()`](Self::cause) tells what went wrong, while the
+/// [`context()`](Self::context) tells which operation failed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BindingError<Cause> {
+    /// Context of the operation that failed
+    context: Box<BindingContext>,
+
+    /// Cause of the failure
+    cause: Cause,
+}
+//
+impl<Cause> BindingError<Cause> {
+    /// Attach a context to a binding error
+    pub(crate) fn new(context: BindingContext, cause: impl Into<Cause>) -> Self {
+        Self {
+            context: Box::new(context),
+            cause: cause.into(),
+        }
+    }
+
+    /// Context of the operation that failed
+    pub fn context(&self) -> &BindingContext {
+        &self.context
+    }
+
+    /// Cause of the failure
+    pub fn cause(&self) -> &Cause {
+        &self.cause
+    }
+
+    /// Extract the cause of the failure, discarding the context
+    pub fn into_cause(self) -> Cause {
+        self.cause
+    }
+
+    /// Convert the cause of the failure, keeping the context
+    pub fn map_cause<NewCause>(self, f: impl FnOnce(Cause) -> NewCause) -> BindingError<NewCause> {
+        BindingError {
+            context: self.context,
+            cause: f(self.cause),
+        }
+    }
+}
+//
+impl<Cause: Display> Display for BindingError<Cause> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {}", self.context, self.cause)
+    }
+}
+//
+impl<Cause: StdError> StdError for BindingError<Cause> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        // The cause is already displayed along with the context, so only
+        // report its own source to avoid duplicate messages
+        self.cause.source()
+    }
+}
+
+/// Any error that can be emitted by hwlocality
+///
+/// Individual hwlocality functions return precise error types that only
+/// describe the failure modes of that function. This type can hold any of
+/// them, which is convenient when you want to propagate errors from several
+/// hwlocality calls with the `?` operator without losing their structure.
+///
+/// Errors that are common or carry useful structure get their own variant,
+/// all other errors are stored as a boxed [`Other`](Self::Other) error. This
+/// also applies to the Rust side of [`HybridError`]s, e.g. a
+/// `HybridError::Rust(NulError)` becomes an [`Error::Nul`].
+///
+/// Binding errors become an [`Error::Binding`], which keeps the
+/// [`BindingContext`] of the failed operation along with its cause.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{
+/// #     cpu::binding::CpuBindingFlags, object::types::ObjectType, Topology,
+/// # };
+/// /// Number of cores and CPUs that the current thread may run on
+/// fn binding_summary(topology: &Topology) -> Result<(usize, usize), hwlocality::Error> {
+///     let depth = topology.depth_for_type(ObjectType::Core)?;
+///     let binding = topology.cpu_binding(CpuBindingFlags::THREAD)?;
+///     let num_cores = topology
+///         .objects_at_depth(depth)
+///         .filter(|core| core.cpuset().is_some_and(|set| binding.intersects(&**set)))
+///         .count();
+///     Ok((num_cores, binding.weight().unwrap_or(usize::MAX)))
+/// }
+/// # let topology = Topology::test_instance();
+/// # let _ = binding_summary(topology);
+/// ```
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Raw error from an hwloc entry point
+    #[error(transparent)]
+    Hwloc(#[from] RawHwlocError),
+
+    /// Error from a CPU or memory binding operation, with its context
+    ///
+    /// The cause of the error is itself converted into an `Error`, so it will
+    /// typically be a [`CpuBinding`](Self::CpuBinding),
+    /// [`CpuSetMemoryBinding`](Self::CpuSetMemoryBinding),
+    /// [`NodeSetMemoryBinding`](Self::NodeSetMemoryBinding) or
+    /// [`Hwloc`](Self::Hwloc) error.
+    #[error(transparent)]
+    Binding(Box<BindingError<Self>>),
+
+    /// Error from a CPU binding operation
+    #[error(transparent)]
+    CpuBinding(#[from] CpuBindingError),
+
+    /// Error from a memory binding operation targeting a [`CpuSet`]
+    #[error(transparent)]
+    CpuSetMemoryBinding(#[from] MemoryBindingError<CpuSet>),
+
+    /// Error from a memory binding operation targeting a [`NodeSet`]
+    #[error(transparent)]
+    NodeSetMemoryBinding(#[from] MemoryBindingError<NodeSet>),
+
+    /// Error from a depth query
+    #[error(transparent)]
+    TypeToDepth(#[from] TypeToDepthError),
+
+    /// Error from [`Topology::distribute_items()`]
+    #[error(transparent)]
+    Distribute(#[from] DistributeError),
+
+    /// A string with inner NUL chars was passed to hwloc
+    #[error(transparent)]
+    Nul(#[from] NulError),
+
+    /// An object from another topology was passed to a [`Topology`] method
+    #[error(transparent)]
+    ForeignObject(#[from] ForeignObjectError),
+
+    /// Any other error
+    #[error(transparent)]
+    Other(Box<dyn StdError + Send + Sync + 'static>),
+}
+//
+impl Error {
+    /// Wrap an error that does not have a dedicated variant
+    pub fn other(error: impl StdError + Send + Sync + 'static) -> Self {
+        Self::Other(Box::new(error))
+    }
+
+    /// Hwloc entry point that failed, if known
+    ///
+    /// This is known for errors that were directly reported by hwloc without
+    /// any further interpretation by hwlocality, and for binding errors.
+    pub fn api(&self) -> Option<&'static str> {
+        match self {
+            Self::Hwloc(RawHwlocError { api, .. }) => Some(api),
+            Self::Binding(error) => Some(error.context().api()),
+            Self::CpuBinding(_)
+            | Self::CpuSetMemoryBinding(_)
+            | Self::NodeSetMemoryBinding(_)
+            | Self::TypeToDepth(_)
+            | Self::Distribute(_)
+            | Self::Nul(_)
+            | Self::ForeignObject(_)
+            | Self::Other(_) => None,
+        }
+    }
+
+    /// Context of the binding operation that failed, if this is a binding error
+    pub fn binding_context(&self) -> Option<&BindingContext> {
+        if let Self::Binding(error) = self {
+            Some(error.context())
+        } else {
+            None
+        }
+    }
+}
+//
+impl<Cause> From<BindingError<Cause>> for Error
+where
+    Self: From<Cause>,
+{
+    fn from(value: BindingError<Cause>) -> Self {
+        Self::Binding(Box::new(value.map_cause(Self::from)))
+    }
+}
+//
+impl<RustError: StdError + Send + Sync + 'static> From<HybridError<RustError>> for Error {
+    fn from(value: HybridError<RustError>) -> Self {
+        match value {
+            HybridError::Rust(error) => Self::from_rust(error),
+            HybridError::Hwloc(error) => Self::Hwloc(error),
+        }
+    }
+}
+//
+impl Error {
+    /// Wrap a Rust-side error, using its dedicated variant if it has one
+    fn from_rust(error: impl StdError + Send + Sync + 'static) -> Self {
+        /// Turn `error` into `variant` if it has the right type
+        fn downcast<T: StdError + 'static>(
+            error: Box<dyn StdError + Send + Sync + 'static>,
+            variant: fn(T) -> Error,
+        ) -> Result<Error, Box<dyn StdError + Send + Sync + 'static>> {
+            error.downcast::<T>().map(|error| variant(*error))
+        }
+        downcast(Box::new(error), Self::Hwloc)
+            .or_else(|error| downcast(error, Self::CpuBinding))
+            .or_else(|error| downcast(error, Self::CpuSetMemoryBinding))
+            .or_else(|error| downcast(error, Self::NodeSetMemoryBinding))
+            .or_else(|error| downcast(error, Self::TypeToDepth))
+            .or_else(|error| downcast(error, Self::Distribute))
+            .or_else(|error| downcast(error, Self::Nul))
+            .or_else(|error| downcast(error, Self::ForeignObject))
+            .unwrap_or_else(Self::Other)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,6 +645,7 @@ mod tests {
     use similar_asserts::assert_eq;
     use static_assertions::{assert_impl_all, assert_not_impl_any, assert_type_eq_all};
     use std::{
+        error::Error,
         fmt::{self, Binary, LowerExp, LowerHex, Octal, Pointer, UpperExp, UpperHex},
         hash::Hash,
         io::{self, Read},
@@ -316,6 +658,31 @@ mod tests {
     // Check that public types in this module keep implementing all expected
     // traits, in the interest of detecting future semver-breaking changes
     assert_type_eq_all!(FlagsError<()>, ParameterError<()>);
+    assert_impl_all!(BindingContext:
+        Clone, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(BindingContext:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(BindingError<NulError>:
+        Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(BindingError<NulError>:
+        Binary, Copy, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
+    assert_impl_all!(super::Error:
+        Debug, Error, From<RawHwlocError>, From<HybridError<NulError>>, Send,
+        Sized, Sync, Unpin
+    );
+    assert_not_impl_any!(super::Error:
+        Binary, Clone, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex,
+        Octal, PartialEq, Pointer, Read, UpperExp, UpperHex, fmt::Write,
+        io::Write
+    );
     assert_impl_all!(ForeignObjectError:
         Clone, Error, Hash, Sized, Sync, Unpin, UnwindSafe
     );
@@ -353,6 +720,74 @@ mod tests {
         PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
     );
 
+    #[test]
+    fn top_level_error() {
+        let raw = RawHwlocError {
+            api: "hwloc_foo",
+            errno: None,
+        };
+        let error = super::Error::from(raw);
+        assert_eq!(error.api(), Some("hwloc_foo"));
+        assert_eq!(error.to_string(), raw.to_string());
+
+        let error = super::Error::from(HybridError::<NulError>::Hwloc(raw));
+        assert!(matches!(error, super::Error::Hwloc(e) if e == raw));
+
+        // Rust errors with a dedicated variant should use it
+        let error = super::Error::from(HybridError::Rust(NulError));
+        assert_eq!(error.api(), None);
+        assert_eq!(error.to_string(), NulError.to_string());
+        assert!(matches!(error, super::Error::Nul(NulError)));
+        let error = super::Error::from(HybridError::Rust(DistributeError::EmptyRoots));
+        assert!(matches!(
+            error,
+            super::Error::Distribute(DistributeError::EmptyRoots)
+        ));
+
+        // Other Rust errors should be wrapped as is
+        let error = super::Error::from(HybridError::Rust(ParameterError(42u8)));
+        assert_eq!(error.to_string(), ParameterError(42u8).to_string());
+        let super::Error::Other(inner) = error else {
+            panic!("Expected Error::Other, got {error:?}");
+        };
+        assert!(inner.downcast_ref::<ParameterError<u8>>().is_some());
+    }
+
+    #[test]
+    fn binding_error() {
+        let context = BindingContext::new(
+            "hwloc_set_cpubind",
+            "the current thread",
+            42u8,
+            Some(&"0-3"),
+        );
+        assert_eq!(context.api(), "hwloc_set_cpubind");
+        assert_eq!(context.target(), "the current thread");
+        assert_eq!(context.flags(), "42");
+        assert_eq!(context.set(), Some("0-3"));
+        assert_eq!(
+            context.to_string(),
+            "hwloc_set_cpubind on the current thread with flags 42 and set 0-3"
+        );
+        let binding = BindingError::<HybridError<NulError>>::new(context.clone(), NulError);
+        assert_eq!(binding.context(), &context);
+        assert_eq!(binding.cause(), &HybridError::Rust(NulError));
+        assert_eq!(binding.to_string(), format!("{context} failed: {NulError}"));
+        let error = super::Error::from(binding);
+        assert_eq!(error.api(), Some("hwloc_set_cpubind"));
+        assert_eq!(error.binding_context(), Some(&context));
+        assert_eq!(error.to_string(), format!("{context} failed: {NulError}"));
+        let super::Error::Binding(inner) = error else {
+            panic!("Expected Error::Binding, got {error:?}");
+        };
+        assert!(matches!(
+            inner.as_ref().cause(),
+            super::Error::Nul(NulError)
+        ));
+        let error = super::Error::from(HybridError::Rust(NulError));
+        assert_eq!(error.binding_context(), None);
+    }
+
     proptest! {
         #[test]
         fn check_errno_normal(
@@ -2298,6 +2298,26 @@ impl Arbitrary for PositiveInt {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for PositiveInt {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PositiveInt {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = c_uint::deserialize(deserializer)?;
+        Self::const_try_from_c_uint(value).ok_or_else(|| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Unsigned(value.into()),
+                &"an integer between 0 and 2^31-1",
+            )
+        })
+    }
+}
+
 impl<B: Borrow<Self>> BitAnd<B> for PositiveInt {
     type Output = Self;
 
@@ -129,7 +129,10 @@ impl Arbitrary for LibcString {
                 .chars()
                 .map(|c| {
                     if c == '\0' {
-                        char::from(rng.gen_range(1..=127))
+                        // Map to a non-NUL ASCII char using only the RngCore API,
+                        // which is stable across proptest's rand versions
+                        let ascii = u8::try_from(rng.next_u32() % 127).expect("<127 fits in u8");
+                        char::from(ascii + 1)
                     } else {
                         c
                     }
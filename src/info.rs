@@ -5,6 +5,10 @@
 //! this information.
 
 use crate::ffi::{self, string::LibcString, transparent::TransparentNewtype};
+#[cfg(doc)]
+use crate::object::TopologyObject;
+#[cfg(any(test, feature = "proptest"))]
+use enum_iterator::Sequence;
 use hwlocality_sys::hwloc_info_s;
 #[allow(unused)]
 #[cfg(test)]
@@ -111,6 +115,281 @@ unsafe impl TransparentNewtype for TextualInfo {
     type Inner = hwloc_info_s;
 }
 
+/// Well-known [`TextualInfo`] name
+///
+/// hwloc documents a number of info names that it may attach to topology
+/// objects, see <https://hwloc.readthedocs.io/en/v2.9/attributes.html#attributes_info>.
+/// This enum lets you look them up with
+/// [`TopologyObject::info_typed()`] instead of spelling out their names, which
+/// makes typos a compile-time error.
+///
+/// Which of these infos are actually present depends on the object type, the
+/// operating system and the hwloc backends that were enabled.
+#[cfg_attr(any(test, feature = "proptest"), derive(Sequence))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum InfoKey {
+    /// Operating system name (`OSName`)
+    OSName,
+
+    /// Operating system release (`OSRelease`)
+    OSRelease,
+
+    /// Operating system version (`OSVersion`)
+    OSVersion,
+
+    /// Host name (`HostName`)
+    HostName,
+
+    /// Machine architecture (`Architecture`)
+    Architecture,
+
+    /// Name of the hwloc backend that discovered an object (`Backend`)
+    Backend,
+
+    /// hwloc version that built the topology (`hwlocVersion`)
+    HwlocVersion,
+
+    /// Name of the process that built the topology (`ProcessName`)
+    ProcessName,
+
+    /// Linux control group that restricted the topology (`LinuxCgroup`)
+    LinuxCgroup,
+
+    /// DMI product name (`DMIProductName`)
+    DMIProductName,
+
+    /// DMI product version (`DMIProductVersion`)
+    DMIProductVersion,
+
+    /// DMI product serial number (`DMIProductSerial`)
+    DMIProductSerial,
+
+    /// DMI product UUID (`DMIProductUUID`)
+    DMIProductUUID,
+
+    /// DMI board vendor (`DMIBoardVendor`)
+    DMIBoardVendor,
+
+    /// DMI board name (`DMIBoardName`)
+    DMIBoardName,
+
+    /// DMI board version (`DMIBoardVersion`)
+    DMIBoardVersion,
+
+    /// DMI board serial number (`DMIBoardSerial`)
+    DMIBoardSerial,
+
+    /// DMI board asset tag (`DMIBoardAssetTag`)
+    DMIBoardAssetTag,
+
+    /// DMI chassis vendor (`DMIChassisVendor`)
+    DMIChassisVendor,
+
+    /// DMI chassis type (`DMIChassisType`)
+    DMIChassisType,
+
+    /// DMI chassis version (`DMIChassisVersion`)
+    DMIChassisVersion,
+
+    /// DMI chassis serial number (`DMIChassisSerial`)
+    DMIChassisSerial,
+
+    /// DMI chassis asset tag (`DMIChassisAssetTag`)
+    DMIChassisAssetTag,
+
+    /// DMI BIOS vendor (`DMIBIOSVendor`)
+    DMIBIOSVendor,
+
+    /// DMI BIOS version (`DMIBIOSVersion`)
+    DMIBIOSVersion,
+
+    /// DMI BIOS date (`DMIBIOSDate`)
+    DMIBIOSDate,
+
+    /// DMI system vendor (`DMISysVendor`)
+    DMISysVendor,
+
+    /// CPU vendor (`CPUVendor`)
+    CPUVendor,
+
+    /// CPU model name (`CPUModel`)
+    CPUModel,
+
+    /// CPU model number (`CPUModelNumber`)
+    CPUModelNumber,
+
+    /// CPU family number (`CPUFamilyNumber`)
+    CPUFamilyNumber,
+
+    /// CPU stepping (`CPUStepping`)
+    CPUStepping,
+
+    /// CPU type (`CPUType`)
+    CPUType,
+
+    /// CPU revision (`CPURevision`)
+    CPURevision,
+
+    /// Type of CPU core on hybrid processors (`CoreType`)
+    CoreType,
+
+    /// Maximal CPU core frequency in MHz (`FrequencyMaxMHz`)
+    FrequencyMaxMHz,
+
+    /// Base CPU core frequency in MHz (`FrequencyBaseMHz`)
+    FrequencyBaseMHz,
+
+    /// PCI device vendor (`PCIVendor`)
+    PCIVendor,
+
+    /// PCI device name (`PCIDevice`)
+    PCIDevice,
+
+    /// PCI slot name (`PCISlot`)
+    PCISlot,
+
+    /// GPU vendor (`GPUVendor`)
+    GPUVendor,
+
+    /// GPU model (`GPUModel`)
+    GPUModel,
+
+    /// UUID of an NVIDIA GPU (`NVIDIAUUID`)
+    NVIDIAUUID,
+
+    /// Serial number of an NVIDIA GPU (`NVIDIASerial`)
+    NVIDIASerial,
+
+    /// UUID of an AMD GPU (`AMDUUID`)
+    AMDUUID,
+
+    /// Serial number of an AMD GPU (`AMDSerial`)
+    AMDSerial,
+
+    /// Vendor of a device or memory module (`Vendor`)
+    Vendor,
+
+    /// Model of a device (`Model`)
+    Model,
+
+    /// Revision of a device (`Revision`)
+    Revision,
+
+    /// Serial number of a device or memory module (`SerialNumber`)
+    SerialNumber,
+
+    /// Size of a storage device in kibibytes (`Size`)
+    Size,
+
+    /// Sector size of a storage device in bytes (`SectorSize`)
+    SectorSize,
+
+    /// Linux major/minor device number (`LinuxDeviceID`)
+    LinuxDeviceID,
+
+    /// Address of a network interface (`Address`)
+    Address,
+
+    /// Port of a network or OpenFabrics interface (`Port`)
+    Port,
+
+    /// GUID of an OpenFabrics node (`NodeGUID`)
+    NodeGUID,
+
+    /// GUID of an OpenFabrics system image (`SysImageGUID`)
+    SysImageGUID,
+
+    /// Location of a memory module on the motherboard (`DeviceLocation`)
+    DeviceLocation,
+
+    /// Bank of a memory module on the motherboard (`BankLocation`)
+    BankLocation,
+
+    /// Part number of a memory module (`PartNumber`)
+    PartNumber,
+
+    /// Asset tag of a memory module (`AssetTag`)
+    AssetTag,
+}
+//
+impl InfoKey {
+    /// Name of this info, as used by hwloc
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::OSName => "OSName",
+            Self::OSRelease => "OSRelease",
+            Self::OSVersion => "OSVersion",
+            Self::HostName => "HostName",
+            Self::Architecture => "Architecture",
+            Self::Backend => "Backend",
+            Self::HwlocVersion => "hwlocVersion",
+            Self::ProcessName => "ProcessName",
+            Self::LinuxCgroup => "LinuxCgroup",
+            Self::DMIProductName => "DMIProductName",
+            Self::DMIProductVersion => "DMIProductVersion",
+            Self::DMIProductSerial => "DMIProductSerial",
+            Self::DMIProductUUID => "DMIProductUUID",
+            Self::DMIBoardVendor => "DMIBoardVendor",
+            Self::DMIBoardName => "DMIBoardName",
+            Self::DMIBoardVersion => "DMIBoardVersion",
+            Self::DMIBoardSerial => "DMIBoardSerial",
+            Self::DMIBoardAssetTag => "DMIBoardAssetTag",
+            Self::DMIChassisVendor => "DMIChassisVendor",
+            Self::DMIChassisType => "DMIChassisType",
+            Self::DMIChassisVersion => "DMIChassisVersion",
+            Self::DMIChassisSerial => "DMIChassisSerial",
+            Self::DMIChassisAssetTag => "DMIChassisAssetTag",
+            Self::DMIBIOSVendor => "DMIBIOSVendor",
+            Self::DMIBIOSVersion => "DMIBIOSVersion",
+            Self::DMIBIOSDate => "DMIBIOSDate",
+            Self::DMISysVendor => "DMISysVendor",
+            Self::CPUVendor => "CPUVendor",
+            Self::CPUModel => "CPUModel",
+            Self::CPUModelNumber => "CPUModelNumber",
+            Self::CPUFamilyNumber => "CPUFamilyNumber",
+            Self::CPUStepping => "CPUStepping",
+            Self::CPUType => "CPUType",
+            Self::CPURevision => "CPURevision",
+            Self::CoreType => "CoreType",
+            Self::FrequencyMaxMHz => "FrequencyMaxMHz",
+            Self::FrequencyBaseMHz => "FrequencyBaseMHz",
+            Self::PCIVendor => "PCIVendor",
+            Self::PCIDevice => "PCIDevice",
+            Self::PCISlot => "PCISlot",
+            Self::GPUVendor => "GPUVendor",
+            Self::GPUModel => "GPUModel",
+            Self::NVIDIAUUID => "NVIDIAUUID",
+            Self::NVIDIASerial => "NVIDIASerial",
+            Self::AMDUUID => "AMDUUID",
+            Self::AMDSerial => "AMDSerial",
+            Self::Vendor => "Vendor",
+            Self::Model => "Model",
+            Self::Revision => "Revision",
+            Self::SerialNumber => "SerialNumber",
+            Self::Size => "Size",
+            Self::SectorSize => "SectorSize",
+            Self::LinuxDeviceID => "LinuxDeviceID",
+            Self::Address => "Address",
+            Self::Port => "Port",
+            Self::NodeGUID => "NodeGUID",
+            Self::SysImageGUID => "SysImageGUID",
+            Self::DeviceLocation => "DeviceLocation",
+            Self::BankLocation => "BankLocation",
+            Self::PartNumber => "PartNumber",
+            Self::AssetTag => "AssetTag",
+        }
+    }
+}
+//
+crate::impl_arbitrary_for_sequence!(InfoKey);
+//
+impl fmt::Display for InfoKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.name())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +421,23 @@ mod tests {
         UpperExp, UpperHex, fmt::Write, io::Write
     );
 
+    assert_impl_all!(InfoKey:
+        Copy, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(InfoKey:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
+
+    #[test]
+    fn info_key_names() {
+        let mut names = std::collections::HashSet::new();
+        for key in enum_iterator::all::<InfoKey>() {
+            assert_eq!(key.to_string(), key.name());
+            assert!(names.insert(key.name()), "duplicate info name {key}");
+        }
+    }
+
     proptest! {
         #[test]
         fn unary(name: LibcString, value: LibcString) {
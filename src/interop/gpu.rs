@@ -0,0 +1,218 @@
+//! GPU discovery
+//!
+//! hwloc exposes GPUs as [`OSDevice`] objects whose name depends on the
+//! software backend through which they were discovered (e.g. "cuda0" for the
+//! first CUDA device, "opencl0d1" for the second device of the first OpenCL
+//! platform...). This module provides a typed view of these objects, so that
+//! you do not need to scan OS devices and match their names yourself.
+//!
+//! [`OSDevice`]: ObjectType::OSDevice
+
+use crate::{
+    bitmap::BitmapRef,
+    cpu::cpuset::CpuSet,
+    object::{
+        attributes::ObjectAttributes,
+        types::{OSDeviceType, ObjectType},
+        TopologyObject,
+    },
+    topology::Topology,
+};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::iter::FusedIterator;
+
+/// # GPU discovery
+impl Topology {
+    /// Enumerate GPU devices in the system
+    ///
+    /// This lists all [`OSDevice`]s of type [`GPU`] or [`CoProcessor`], along
+    /// with the software backend through which they were discovered. Note
+    /// that a single physical GPU is usually exposed through multiple backends
+    /// (e.g. CUDA and NVML), and therefore appears multiple times in the
+    /// output. You can use [`GpuDevice::pci_device()`] to group GPU devices by
+    /// physical device.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// for gpu in topology.gpus() {
+    ///     println!(
+    ///         "{} device #{:?} is close to CPUs {:?}",
+    ///         gpu.backend(),
+    ///         gpu.ordinal(),
+    ///         gpu.cpuset()
+    ///     );
+    /// }
+    /// ```
+    ///
+    /// [`CoProcessor`]: OSDeviceType::CoProcessor
+    /// [`GPU`]: OSDeviceType::GPU
+    /// [`OSDevice`]: ObjectType::OSDevice
+    pub fn gpus(&self) -> impl DoubleEndedIterator<Item = GpuDevice<'_>> + Clone + FusedIterator {
+        self.os_devices().filter_map(GpuDevice::new)
+    }
+}
+
+/// GPU device, as discovered by hwloc
+#[derive(Copy, Clone, Debug)]
+pub struct GpuDevice<'topology> {
+    /// Underlying OS device object
+    object: &'topology TopologyObject,
+
+    /// Software backend through which the device was discovered
+    backend: GpuBackend,
+
+    /// Index of the device within its backend, if known
+    ordinal: Option<usize>,
+}
+//
+impl<'topology> GpuDevice<'topology> {
+    /// Interpret an OS device as a GPU device, if it is one
+    fn new(object: &'topology TopologyObject) -> Option<Self> {
+        let Some(ObjectAttributes::OSDevice(osdev)) = object.attributes() else {
+            return None;
+        };
+        if !matches!(
+            osdev.device_type(),
+            OSDeviceType::GPU | OSDeviceType::CoProcessor
+        ) {
+            return None;
+        }
+        let name = object.name().and_then(|name| name.to_str().ok());
+        let (backend, ordinal) = name.map_or((GpuBackend::Other, None), GpuBackend::parse_name);
+        Some(Self {
+            object,
+            backend,
+            ordinal,
+        })
+    }
+
+    /// Underlying [`OSDevice`](ObjectType::OSDevice) object
+    pub fn object(&self) -> &'topology TopologyObject {
+        self.object
+    }
+
+    /// Software backend through which this device was discovered
+    pub fn backend(&self) -> GpuBackend {
+        self.backend
+    }
+
+    /// Index of this device within its backend, if known
+    ///
+    /// This is the index that the backend's API uses to designate this device,
+    /// e.g. 1 for the "cuda1" CUDA device. For OpenCL devices, this is the
+    /// index of the device within its OpenCL platform.
+    pub fn ordinal(&self) -> Option<usize> {
+        self.ordinal
+    }
+
+    /// PCI device through which this GPU is connected, if known
+    pub fn pci_device(&self) -> Option<&'topology TopologyObject> {
+        self.object
+            .ancestors()
+            .find(|ancestor| ancestor.object_type() == ObjectType::PCIDevice)
+    }
+
+    /// CPUs that are close to this GPU, if known
+    ///
+    /// This is the cpuset of the first non-I/O ancestor of the device.
+    pub fn cpuset(&self) -> Option<BitmapRef<'topology, CpuSet>> {
+        self.object
+            .first_non_io_ancestor()
+            .and_then(TopologyObject::cpuset)
+    }
+}
+
+/// Software backend through which a GPU was discovered
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum GpuBackend {
+    /// NVIDIA CUDA (OS device names like "cuda0")
+    CUDA,
+
+    /// NVIDIA Management Library (OS device names like "nvml0")
+    NVML,
+
+    /// OpenCL (OS device names like "opencl0d0")
+    OpenCL,
+
+    /// AMD `ROCm` SMI (OS device names like "rsmi0")
+    RSMI,
+
+    /// oneAPI Level Zero (OS device names like "ze0")
+    LevelZero,
+
+    /// Other or unknown backend
+    Other,
+}
+//
+impl GpuBackend {
+    /// Deduce the backend and device ordinal from an OS device name
+    fn parse_name(name: &str) -> (Self, Option<usize>) {
+        let prefixes = [
+            ("cuda", Self::CUDA),
+            ("nvml", Self::NVML),
+            ("opencl", Self::OpenCL),
+            ("rsmi", Self::RSMI),
+            ("ze", Self::LevelZero),
+        ];
+        let Some((backend, rest)) = prefixes
+            .into_iter()
+            .find_map(|(prefix, backend)| Some((backend, name.strip_prefix(prefix)?)))
+        else {
+            return (Self::Other, None);
+        };
+        let digits = match backend {
+            // OpenCL devices are named after their platform and device index
+            Self::OpenCL => rest.split_once('d').map(|(_platform, device)| device),
+            // Level Zero subdevices are named after their parent device
+            Self::LevelZero => rest.split('.').next(),
+            Self::CUDA | Self::NVML | Self::RSMI | Self::Other => Some(rest),
+        };
+        (backend, digits.and_then(|digits| digits.parse().ok()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn parse_name() {
+        assert_eq!(GpuBackend::parse_name("cuda1"), (GpuBackend::CUDA, Some(1)));
+        assert_eq!(GpuBackend::parse_name("nvml0"), (GpuBackend::NVML, Some(0)));
+        assert_eq!(
+            GpuBackend::parse_name("opencl0d2"),
+            (GpuBackend::OpenCL, Some(2))
+        );
+        assert_eq!(GpuBackend::parse_name("rsmi3"), (GpuBackend::RSMI, Some(3)));
+        assert_eq!(
+            GpuBackend::parse_name("ze1.0"),
+            (GpuBackend::LevelZero, Some(1))
+        );
+        assert_eq!(GpuBackend::parse_name("card0"), (GpuBackend::Other, None));
+    }
+
+    #[test]
+    fn gpus() {
+        let topology = Topology::test_instance();
+        for gpu in topology.gpus() {
+            assert!(topology.os_devices().any(|obj| ptr_eq(obj, gpu.object())));
+            if let Some(pci) = gpu.pci_device() {
+                assert_eq!(pci.object_type(), ObjectType::PCIDevice);
+            }
+        }
+    }
+
+    /// Check if two objects are the same
+    fn ptr_eq(a: &TopologyObject, b: &TopologyObject) -> bool {
+        std::ptr::eq(a, b)
+    }
+}
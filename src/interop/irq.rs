@@ -0,0 +1,288 @@
+//! Linux interrupt affinity inspection
+//!
+//! Network-heavy applications often want to know which CPUs handle the
+//! interrupts of their devices, either to run polling threads right next to
+//! them for cache locality, or to keep latency-sensitive threads away from
+//! them. This module parses the Linux `/proc/interrupts` and
+//! `/proc/irq/<irq>/smp_affinity` files into [`CpuSet`]s that can be combined
+//! with the rest of the topology.
+
+use crate::{cpu::cpuset::CpuSet, topology::Topology};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{fs, io};
+
+/// # Linux interrupt affinity
+impl Topology {
+    /// List the interrupts of the system, with their CPU affinity
+    ///
+    /// This parses `/proc/interrupts` for per-CPU interrupt counts, then
+    /// reads the `smp_affinity` and `effective_affinity` files of each
+    /// numbered interrupt in `/proc/irq`. All CPU sets are restricted to the
+    /// CPUs of this topology.
+    ///
+    /// Interrupts are listed in the order of `/proc/interrupts`.
+    ///
+    /// Note that this function ignores the [HWLOC_FSROOT environment
+    /// variable](https://hwloc.readthedocs.io/en/v2.9/envvar.html).
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error that occurs while reading `/proc/interrupts`, or
+    /// [`InvalidData`] if that file does not have the expected format.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(target_os = "linux", doc = "```rust")]
+    #[cfg_attr(not(target_os = "linux"), doc = "```rust,ignore")]
+    /// # use hwlocality::topology::Topology;
+    /// #
+    /// # let topology = Topology::test_instance();
+    /// #
+    /// // Find out which CPUs handle interrupts from network devices
+    /// let mut net_cpus = hwlocality::cpu::cpuset::CpuSet::new();
+    /// for irq in topology.interrupts()? {
+    ///     if irq.description().contains("eth") {
+    ///         net_cpus |= irq.active_cpus();
+    ///     }
+    /// }
+    /// println!("Network interrupts are handled by CPUs {net_cpus}");
+    /// #
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`InvalidData`]: io::ErrorKind::InvalidData
+    pub fn interrupts(&self) -> io::Result<Vec<Interrupt>> {
+        let interrupts = fs::read_to_string("/proc/interrupts")?;
+        let mut result = parse_interrupts(&interrupts)?;
+        for irq in &mut result {
+            irq.active_cpus &= self.cpuset();
+            if irq.id.parse::<u32>().is_err() {
+                continue;
+            }
+            let read_affinity = |file: &str| {
+                let path = format!("/proc/irq/{}/{file}", irq.id);
+                // Affinity files may be missing, e.g. for inactive IRQs
+                self.read_path_as_cpumask(path)
+                    .ok()
+                    .map(|affinity| affinity & self.cpuset())
+            };
+            irq.affinity = read_affinity("smp_affinity");
+            irq.effective_affinity = read_affinity("effective_affinity");
+        }
+        Ok(result)
+    }
+}
+
+/// A system interrupt, as listed in `/proc/interrupts`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Interrupt {
+    /// Interrupt identifier (IRQ number or architecture-specific name)
+    id: String,
+
+    /// Number of times the interrupt was handled by each CPU (by OS index)
+    counts: Vec<(usize, u64)>,
+
+    /// CPUs which have handled this interrupt at least once
+    active_cpus: CpuSet,
+
+    /// Interrupt controller and device description
+    description: String,
+
+    /// CPUs which this interrupt may be routed to
+    affinity: Option<CpuSet>,
+
+    /// CPUs which this interrupt is actually routed to
+    effective_affinity: Option<CpuSet>,
+}
+//
+impl Interrupt {
+    /// Interrupt identifier
+    ///
+    /// This is the IRQ number for device interrupts, and a short
+    /// architecture-specific name (e.g. `NMI`, `LOC`) for other interrupts.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// IRQ number, if this is a numbered interrupt
+    pub fn irq_number(&self) -> Option<u32> {
+        self.id.parse().ok()
+    }
+
+    /// Description of the interrupt
+    ///
+    /// For device interrupts, this usually contains the interrupt controller,
+    /// the trigger type and the name of the device(s) that raise it, e.g.
+    /// `IR-PCI-MSI 524288-edge eth0-rx-0`.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Number of times CPU `cpu` (by OS index) handled this interrupt
+    ///
+    /// Returns `None` if the interrupt count is not reported for this CPU.
+    pub fn count_on(&self, cpu: usize) -> Option<u64> {
+        self.counts
+            .iter()
+            .find_map(|&(other_cpu, count)| (other_cpu == cpu).then_some(count))
+    }
+
+    /// Total number of times this interrupt was handled by any CPU
+    pub fn total_count(&self) -> u64 {
+        self.counts.iter().map(|&(_cpu, count)| count).sum()
+    }
+
+    /// CPUs which have handled this interrupt at least once
+    pub fn active_cpus(&self) -> &CpuSet {
+        &self.active_cpus
+    }
+
+    /// CPUs which this interrupt may be routed to, from `smp_affinity`
+    ///
+    /// This is only available for numbered interrupts.
+    pub fn affinity(&self) -> Option<&CpuSet> {
+        self.affinity.as_ref()
+    }
+
+    /// CPUs which this interrupt is actually routed to, from
+    /// `effective_affinity`
+    ///
+    /// This is only available for numbered interrupts, on kernels and
+    /// interrupt controllers that report it.
+    pub fn effective_affinity(&self) -> Option<&CpuSet> {
+        self.effective_affinity.as_ref()
+    }
+}
+
+/// Parse the contents of `/proc/interrupts`, without affinity information
+fn parse_interrupts(text: &str) -> io::Result<Vec<Interrupt>> {
+    let invalid = |what: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected /proc/interrupts format: {what}"),
+        )
+    };
+
+    // The header lists the OS indices of CPUs in column order
+    let mut lines = text.lines();
+    let header = lines.next().ok_or_else(|| invalid("missing header"))?;
+    let cpus = header
+        .split_whitespace()
+        .map(|column| {
+            column
+                .strip_prefix("CPU")
+                .and_then(|idx| idx.parse::<usize>().ok())
+                .ok_or_else(|| invalid("bad CPU column"))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // Each other line has an ID, up to one count per CPU and a description
+    let mut interrupts = Vec::new();
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (id, rest) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("missing interrupt ID"))?;
+        let mut fields = rest.split_whitespace().peekable();
+        let mut counts = Vec::with_capacity(cpus.len());
+        let mut active_cpus = CpuSet::new();
+        for &cpu in &cpus {
+            let Some(count) = fields.peek().and_then(|field| field.parse::<u64>().ok()) else {
+                break;
+            };
+            fields.next();
+            counts.push((cpu, count));
+            if count > 0 {
+                active_cpus.set(cpu);
+            }
+        }
+        interrupts.push(Interrupt {
+            id: id.trim().to_owned(),
+            counts,
+            active_cpus,
+            description: fields.collect::<Vec<_>>().join(" "),
+            affinity: None,
+            effective_affinity: None,
+        });
+    }
+    Ok(interrupts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::fmt::Debug;
+
+    assert_impl_all!(Interrupt: Clone, Debug, Eq, Send, Sync);
+
+    #[test]
+    fn parse() {
+        let text = "           CPU0       CPU1       CPU3\n\
+                    \x20 0:         35          0          0  IR-IO-APIC    2-edge      timer\n\
+                    \x20 24:         0       1234         12  IR-PCI-MSI 524288-edge      eth0-rx-0\n\
+                    NMI:          1          2          3   Non-maskable interrupts\n\
+                    ERR:          0\n";
+        let interrupts = parse_interrupts(text).unwrap();
+        assert_eq!(interrupts.len(), 4);
+
+        let timer = &interrupts[0];
+        assert_eq!(timer.id(), "0");
+        assert_eq!(timer.irq_number(), Some(0));
+        assert_eq!(timer.description(), "IR-IO-APIC 2-edge timer");
+        assert_eq!(timer.count_on(0), Some(35));
+        assert_eq!(timer.count_on(2), None);
+        assert_eq!(timer.total_count(), 35);
+        assert_eq!(timer.active_cpus(), &CpuSet::from_range(0..=0));
+
+        let eth = &interrupts[1];
+        assert_eq!(eth.irq_number(), Some(24));
+        assert_eq!(eth.description(), "IR-PCI-MSI 524288-edge eth0-rx-0");
+        assert_eq!(eth.count_on(3), Some(12));
+        let mut eth_cpus = CpuSet::from_range(1..=3);
+        eth_cpus.unset(2);
+        assert_eq!(eth.active_cpus(), &eth_cpus);
+
+        let nmi = &interrupts[2];
+        assert_eq!(nmi.id(), "NMI");
+        assert_eq!(nmi.irq_number(), None);
+        assert_eq!(nmi.description(), "Non-maskable interrupts");
+        assert_eq!(nmi.total_count(), 6);
+
+        let err = &interrupts[3];
+        assert_eq!(err.id(), "ERR");
+        assert_eq!(err.count_on(0), Some(0));
+        assert_eq!(err.count_on(1), None);
+        assert_eq!(err.description(), "");
+
+        for (text, what) in [
+            ("", "missing header"),
+            ("CPUx\n", "bad CPU column"),
+            ("CPU0\nno colon\n", "missing interrupt ID"),
+        ] {
+            let error = parse_interrupts(text).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+            assert_eq!(
+                error.to_string(),
+                format!("unexpected /proc/interrupts format: {what}")
+            );
+        }
+    }
+
+    #[test]
+    fn system_interrupts() {
+        let topology = Topology::test_instance();
+        for irq in topology.interrupts().unwrap() {
+            assert!(topology.cpuset().includes(irq.active_cpus()));
+            if let Some(affinity) = irq.affinity() {
+                assert!(topology.cpuset().includes(affinity));
+            }
+            if irq.irq_number().is_none() {
+                assert_eq!(irq.affinity(), None);
+            }
+        }
+    }
+}
@@ -5,13 +5,20 @@ use crate::cpu::binding::CpuBindingFlags;
 use crate::{
     cpu::cpuset::CpuSet,
     errors::{self, HybridError, RawHwlocError},
+    memory::nodeset::NodeSet,
+    object::{types::ObjectType, TopologyObject},
     path::{self, PathError},
     topology::Topology,
 };
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::{ops::Deref, path::Path};
+use std::{
+    ffi::c_ulong,
+    fmt, fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
 // This file is rustdoc-visible so we must provide a substitute for
 // linux-specific libc entities when people run rustdoc on Windows.
@@ -19,6 +26,7 @@ use std::{ops::Deref, path::Path};
 use libc::pid_t;
 #[cfg(all(doc, not(target_os = "linux")))]
 #[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct pid_t;
 
 /// # Linux-specific helpers
@@ -124,6 +132,79 @@ impl Topology {
         .map(|_| set)
     }
 
+    /// Report the CPU binding and last CPU location of every thread of `pid`
+    ///
+    /// This walks `/proc/<pid>/task` and, for each thread of the target
+    /// process, queries its name, its current CPU binding (as with
+    /// [`tid_cpu_binding()`]) and the PU which it last ran on (as with
+    /// [`tid_last_cpu_location()`]). This is handy when debugging
+    /// over-subscription in multi-threaded services, where one wants to see
+    /// at a glance which threads end up competing for the same PUs.
+    ///
+    /// Threads are reported in increasing TID order. Threads which exit while
+    /// the report is being built are silently left out. Per-thread hwloc
+    /// query failures do not abort the report, but are recorded in the
+    /// corresponding [`ThreadBindingReport`].
+    ///
+    /// Note that this function ignores the [HWLOC_FSROOT environment
+    /// variable](https://hwloc.readthedocs.io/en/v2.9/envvar.html).
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error that occurs while listing `/proc/<pid>/task`, most
+    /// notably [`NotFound`] if there is no process with this PID.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(target_os = "linux", doc = "```rust")]
+    #[cfg_attr(not(target_os = "linux"), doc = "```rust,ignore")]
+    /// # use hwlocality::topology::Topology;
+    /// #
+    /// # let topology = Topology::test_instance();
+    /// #
+    /// let pid = std::process::id().try_into()?;
+    /// for thread in topology.process_binding_report(pid)? {
+    ///     println!("{thread}");
+    /// }
+    /// #
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`NotFound`]: io::ErrorKind::NotFound
+    /// [`tid_cpu_binding()`]: Topology::tid_cpu_binding()
+    /// [`tid_last_cpu_location()`]: Topology::tid_last_cpu_location()
+    pub fn process_binding_report(&self, pid: pid_t) -> io::Result<Vec<ThreadBindingReport>> {
+        let task_dir = PathBuf::from(format!("/proc/{pid}/task"));
+        let mut tids = fs::read_dir(&task_dir)?
+            .filter_map(|entry| match entry {
+                Ok(entry) => entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.parse::<pid_t>().ok())
+                    .map(Ok),
+                Err(e) => Some(Err(e)),
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        tids.sort_unstable();
+
+        let mut report = Vec::with_capacity(tids.len());
+        for tid in tids {
+            let name = match fs::read_to_string(task_dir.join(format!("{tid}/comm"))) {
+                Ok(name) => name.trim_end_matches('\n').to_owned(),
+                // Thread exited since we listed the task directory
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            report.push(ThreadBindingReport {
+                tid,
+                name,
+                cpu_binding: self.tid_cpu_binding(tid),
+                last_cpu_location: self.tid_last_cpu_location(tid),
+            });
+        }
+        Ok(report)
+    }
+
     /// Convert a linux kernel cpumask file path into a hwloc bitmap set.
     ///
     /// Might be used when reading CPU sets from sysfs attributes such as
@@ -191,10 +272,343 @@ impl Topology {
     }
 }
 
+/// # Linux libnuma interoperability
+///
+/// These helpers convert hwloc CPU and NUMA node sets from and to the arrays
+/// of `unsigned long` that are used by libnuma-style NUMA policy APIs, like
+/// the `set_mempolicy` and `mbind` system calls.
+///
+/// A mask of `mask.len()` `unsigned long`s covers up to
+/// `mask.len() * c_ulong::BITS` NUMA nodes, and this is a valid `maxnode`
+/// value to pass to these APIs. Bit `i % c_ulong::BITS` of `mask[i /
+/// c_ulong::BITS]` designates the NUMA node with OS index `i`.
+//
+// --- Implementation details ---
+//
+// Upstream docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__linux__libnuma__ulongs.html
+// The upstream helpers are inline header functions, so they were rewritten in
+// Rust. The helpers based on libnuma's `struct bitmask` are not provided as
+// that would require linking against libnuma.
+impl Topology {
+    /// Convert a CPU set into a libnuma-style NUMA node mask
+    ///
+    /// `cpuset` can be a `&'_ CpuSet` or a `BitmapRef<'_, CpuSet>`.
+    ///
+    /// The output mask contains all NUMA nodes whose CPUs intersect with
+    /// `cpuset`, and is just long enough to cover the highest such node.
+    #[doc(alias = "hwloc_cpuset_to_linux_libnuma_ulongs")]
+    pub fn cpuset_to_libnuma_ulongs(&self, cpuset: impl Deref<Target = CpuSet>) -> Vec<c_ulong> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Topology, cpuset: &CpuSet) -> Vec<c_ulong> {
+            let nodes = self_
+                .objects_with_type(ObjectType::NUMANode)
+                .filter(|node| {
+                    node.cpuset()
+                        .is_some_and(|node_set| node_set.intersects(cpuset))
+                });
+            libnuma_ulongs_from_nodes(nodes)
+        }
+        polymorphized(self, &cpuset)
+    }
+
+    /// Convert a NUMA node set into a libnuma-style NUMA node mask
+    ///
+    /// `nodeset` can be a `&'_ NodeSet` or a `BitmapRef<'_, NodeSet>`.
+    ///
+    /// The output mask contains all NUMA nodes of the topology that are part
+    /// of `nodeset`, and is just long enough to cover the highest such node.
+    #[doc(alias = "hwloc_nodeset_to_linux_libnuma_ulongs")]
+    pub fn nodeset_to_libnuma_ulongs(&self, nodeset: impl Deref<Target = NodeSet>) -> Vec<c_ulong> {
+        /// Polymorphized version of this function (avoids generics code bloat)
+        fn polymorphized(self_: &Topology, nodeset: &NodeSet) -> Vec<c_ulong> {
+            let nodes = self_
+                .objects_with_type(ObjectType::NUMANode)
+                .filter(|node| node.os_index().is_some_and(|idx| nodeset.is_set(idx)));
+            libnuma_ulongs_from_nodes(nodes)
+        }
+        polymorphized(self, &nodeset)
+    }
+
+    /// Convert a libnuma-style NUMA node mask into a CPU set
+    ///
+    /// The output CPU set contains the CPUs of all NUMA nodes of the topology
+    /// that are part of `mask`.
+    #[doc(alias = "hwloc_cpuset_from_linux_libnuma_ulongs")]
+    pub fn cpuset_from_libnuma_ulongs(&self, mask: &[c_ulong]) -> CpuSet {
+        self.libnuma_ulongs_to_nodes(mask)
+            .fold(CpuSet::new(), |mut cpuset, node| {
+                if let Some(node_set) = node.cpuset() {
+                    cpuset |= node_set;
+                }
+                cpuset
+            })
+    }
+
+    /// Convert a libnuma-style NUMA node mask into a NUMA node set
+    ///
+    /// The output node set contains all NUMA nodes of the topology that are
+    /// part of `mask`.
+    #[doc(alias = "hwloc_nodeset_from_linux_libnuma_ulongs")]
+    pub fn nodeset_from_libnuma_ulongs(&self, mask: &[c_ulong]) -> NodeSet {
+        self.libnuma_ulongs_to_nodes(mask)
+            .fold(NodeSet::new(), |mut nodeset, node| {
+                if let Some(os_index) = node.os_index() {
+                    nodeset.set(os_index);
+                }
+                nodeset
+            })
+    }
+
+    /// NUMA nodes of this topology that are part of a libnuma-style mask
+    fn libnuma_ulongs_to_nodes<'self_>(
+        &'self_ self,
+        mask: &'self_ [c_ulong],
+    ) -> impl Iterator<Item = &'self_ TopologyObject> + 'self_ {
+        self.objects_with_type(ObjectType::NUMANode)
+            .filter(move |node| {
+                node.os_index().is_some_and(|os_index| {
+                    let bits = ULONG_BITS;
+                    mask.get(os_index / bits)
+                        .is_some_and(|word| word & (1 << (os_index % bits)) != 0)
+                })
+            })
+    }
+}
+
+/// # Linux cgroup resource limits
+///
+/// When a process runs inside of a Linux control group (e.g. in a container),
+/// the cgroup's cpuset controller may restrict the CPUs and NUMA nodes that it
+/// can use. The topology's allowed sets do not reflect these limits when the
+/// topology was loaded from another source than the current system (e.g. XML)
+/// without [`BuildFlags::GET_ALLOWED_RESOURCES_FROM_THIS_SYSTEM`], and topologies built
+/// with [`BuildFlags::INCLUDE_DISALLOWED`] even keep the disallowed objects.
+///
+/// These helpers read the limits of the current process' cgroup directly from
+/// the cgroup filesystem (both the v1 and v2 hierarchies are supported), and
+/// intersect them with the topology's allowed sets.
+///
+/// Note that these functions ignore the [HWLOC_FSROOT environment
+/// variable](https://hwloc.readthedocs.io/en/v2.9/envvar.html).
+///
+/// [`BuildFlags::INCLUDE_DISALLOWED`]: crate::topology::builder::BuildFlags::INCLUDE_DISALLOWED
+/// [`BuildFlags::GET_ALLOWED_RESOURCES_FROM_THIS_SYSTEM`]: crate::topology::builder::BuildFlags::GET_ALLOWED_RESOURCES_FROM_THIS_SYSTEM
+impl Topology {
+    /// Set of PUs that the current process may actually use
+    ///
+    /// This is the intersection of [`Topology::allowed_cpuset()`] with the
+    /// effective CPU limits of the cgroup that the current process belongs
+    /// to. If no cgroup cpuset controller applies to the current process,
+    /// this is [`Topology::allowed_cpuset()`].
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error that occurs while reading `/proc/self/cgroup` or the
+    /// cgroup filesystem, or [`InvalidData`] if the CPU limit file does not
+    /// contain a valid CPU list.
+    ///
+    /// # Example
+    ///
+    #[cfg_attr(target_os = "linux", doc = "```rust")]
+    #[cfg_attr(not(target_os = "linux"), doc = "```rust,ignore")]
+    /// # use hwlocality::topology::Topology;
+    /// #
+    /// # let topology = Topology::test_instance();
+    /// #
+    /// let cpuset = topology.effective_cpuset()?;
+    /// assert!(topology.allowed_cpuset().includes(&cpuset));
+    /// println!("This process may run on PUs {cpuset}");
+    /// #
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`InvalidData`]: io::ErrorKind::InvalidData
+    pub fn effective_cpuset(&self) -> io::Result<CpuSet> {
+        let mut cpuset = self.allowed_cpuset().clone_target();
+        if let Some(limit) = read_cgroup_limit("cpuset.cpus.effective", "cpuset.effective_cpus")? {
+            let mut limit_set = CpuSet::new();
+            for (first, last) in limit {
+                limit_set.set_range(first..=last);
+            }
+            cpuset &= limit_set;
+        }
+        Ok(cpuset)
+    }
+
+    /// Set of NUMA nodes that the current process may actually allocate from
+    ///
+    /// This is the intersection of [`Topology::allowed_nodeset()`] with the
+    /// effective memory node limits of the cgroup that the current process
+    /// belongs to. If no cgroup cpuset controller applies to the current
+    /// process, this is [`Topology::allowed_nodeset()`].
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error that occurs while reading `/proc/self/cgroup` or the
+    /// cgroup filesystem, or [`InvalidData`] if the memory node limit file
+    /// does not contain a valid node list.
+    ///
+    /// [`InvalidData`]: io::ErrorKind::InvalidData
+    pub fn effective_nodeset(&self) -> io::Result<NodeSet> {
+        let mut nodeset = self.allowed_nodeset().clone_target();
+        if let Some(limit) = read_cgroup_limit("cpuset.mems.effective", "cpuset.effective_mems")? {
+            let mut limit_set = NodeSet::new();
+            for (first, last) in limit {
+                limit_set.set_range(first..=last);
+            }
+            nodeset &= limit_set;
+        }
+        Ok(nodeset)
+    }
+}
+
+/// Read a cpuset controller limit of the current process' cgroup
+///
+/// `v2_file` and `v1_file` are the names of the file that holds the limit in
+/// the cgroup v2 and v1 hierarchies respectively. The limit is returned as a
+/// list of inclusive index ranges, or `None` if no cpuset controller applies.
+fn read_cgroup_limit(v2_file: &str, v1_file: &str) -> io::Result<Option<Vec<(usize, usize)>>> {
+    let cgroups = fs::read_to_string("/proc/self/cgroup")?;
+    for line in cgroups.lines() {
+        // Lines have the format hierarchy-ID:controller-list:cgroup-path
+        let mut fields = line.splitn(3, ':');
+        let (Some(_id), Some(controllers), Some(cgroup_path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let cgroup_path = cgroup_path.trim_start_matches('/');
+        let limit_path = if controllers.is_empty() {
+            Path::new("/sys/fs/cgroup").join(cgroup_path).join(v2_file)
+        } else if controllers
+            .split(',')
+            .any(|controller| controller == "cpuset")
+        {
+            Path::new("/sys/fs/cgroup/cpuset")
+                .join(cgroup_path)
+                .join(v1_file)
+        } else {
+            continue;
+        };
+        match fs::read_to_string(limit_path) {
+            Ok(limit) if !limit.trim().is_empty() => {
+                return parse_index_list(limit.trim()).map(Some);
+            }
+            // Empty limit file or cpuset controller not enabled for this cgroup
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+/// Parse a Linux kernel index list like `0-3,8,10-11`
+fn parse_index_list(list: &str) -> io::Result<Vec<(usize, usize)>> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid index list {list:?}"),
+        )
+    };
+    list.split(',')
+        .map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            let start = start.parse::<usize>().map_err(|_| invalid())?;
+            let end = end.parse::<usize>().map_err(|_| invalid())?;
+            if start > end {
+                return Err(invalid());
+            }
+            Ok((start, end))
+        })
+        .collect()
+}
+
+/// CPU binding state of one thread, as reported by
+/// [`Topology::process_binding_report()`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ThreadBindingReport {
+    /// Thread ID
+    tid: pid_t,
+
+    /// Thread name, as found in `/proc/<pid>/task/<tid>/comm`
+    name: String,
+
+    /// Result of querying the thread's CPU binding
+    cpu_binding: Result<CpuSet, RawHwlocError>,
+
+    /// Result of querying the thread's last CPU location
+    last_cpu_location: Result<CpuSet, RawHwlocError>,
+}
+//
+impl ThreadBindingReport {
+    /// Thread ID
+    pub fn tid(&self) -> pid_t {
+        self.tid
+    }
+
+    /// Thread name
+    ///
+    /// This is the name that was set via `pthread_setname_np()` or `prctl()`,
+    /// which defaults to the executable name. The kernel truncates it to 15
+    /// bytes.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set of PUs which the thread is allowed to run on
+    ///
+    /// # Errors
+    ///
+    /// Errors from [`Topology::tid_cpu_binding()`] are forwarded.
+    pub fn cpu_binding(&self) -> Result<&CpuSet, RawHwlocError> {
+        self.cpu_binding.as_ref().map_err(|e| *e)
+    }
+
+    /// PU which the thread last ran on, as a singleton set
+    ///
+    /// # Errors
+    ///
+    /// Errors from [`Topology::tid_last_cpu_location()`] are forwarded.
+    pub fn last_cpu_location(&self) -> Result<&CpuSet, RawHwlocError> {
+        self.last_cpu_location.as_ref().map_err(|e| *e)
+    }
+}
+//
+impl fmt::Display for ThreadBindingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "thread {} \"{}\": bound to ", self.tid, self.name)?;
+        match &self.cpu_binding {
+            Ok(set) => write!(f, "{set}")?,
+            Err(e) => write!(f, "<{e}>")?,
+        }
+        f.write_str(", last ran on ")?;
+        match &self.last_cpu_location {
+            Ok(set) => write!(f, "{set}"),
+            Err(e) => write!(f, "<{e}>"),
+        }
+    }
+}
+
+/// Number of bits in a [`c_ulong`]
+const ULONG_BITS: usize = c_ulong::BITS as usize;
+
+/// Build a libnuma-style NUMA node mask from a set of NUMA nodes
+fn libnuma_ulongs_from_nodes<'a>(nodes: impl Iterator<Item = &'a TopologyObject>) -> Vec<c_ulong> {
+    let mut mask = Vec::new();
+    for os_index in nodes.filter_map(TopologyObject::os_index) {
+        let word = os_index / ULONG_BITS;
+        if mask.len() <= word {
+            mask.resize(word + 1, 0);
+        }
+        mask[word] |= 1 << (os_index % ULONG_BITS);
+    }
+    mask
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::object::types::ObjectType;
     #[allow(unused)]
     use similar_asserts::assert_eq;
 
@@ -218,4 +632,89 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn libnuma_ulongs() {
+        let topology = Topology::test_instance();
+
+        // Full topology sets
+        let mask = topology.nodeset_to_libnuma_ulongs(topology.nodeset());
+        assert_eq!(
+            topology.nodeset_from_libnuma_ulongs(&mask),
+            *topology.nodeset()
+        );
+        assert_eq!(
+            topology.cpuset_to_libnuma_ulongs(topology.cpuset()),
+            topology.nodeset_to_libnuma_ulongs(&NodeSet::from_cpuset(topology, topology.cpuset()))
+        );
+
+        // Individual NUMA nodes
+        for node in topology.objects_with_type(ObjectType::NUMANode) {
+            let nodeset = node.nodeset().unwrap();
+            let mask = topology.nodeset_to_libnuma_ulongs(&*nodeset);
+            assert_eq!(topology.nodeset_from_libnuma_ulongs(&mask), nodeset);
+            assert_eq!(
+                topology.cpuset_from_libnuma_ulongs(&mask),
+                node.cpuset().unwrap()
+            );
+        }
+
+        // Empty mask
+        assert_eq!(topology.nodeset_from_libnuma_ulongs(&[]), NodeSet::new());
+        assert_eq!(topology.cpuset_from_libnuma_ulongs(&[]), CpuSet::new());
+    }
+
+    #[test]
+    fn index_list() {
+        assert_eq!(parse_index_list("0").unwrap(), vec![(0, 0)]);
+        assert_eq!(
+            parse_index_list("0-3,8,10-11").unwrap(),
+            vec![(0, 3), (8, 8), (10, 11)]
+        );
+        for invalid in ["", "a", "1-", "-1", "3-1", "0,,1", "0-1-2"] {
+            assert_eq!(
+                parse_index_list(invalid).unwrap_err().kind(),
+                std::io::ErrorKind::InvalidData
+            );
+        }
+    }
+
+    #[test]
+    fn effective_sets() {
+        let topology = Topology::test_instance();
+        let cpuset = topology.effective_cpuset().unwrap();
+        assert!(topology.allowed_cpuset().includes(&cpuset));
+        let nodeset = topology.effective_nodeset().unwrap();
+        assert!(topology.allowed_nodeset().includes(&nodeset));
+    }
+
+    #[test]
+    fn process_binding_report() {
+        let topology = Topology::test_instance();
+        let pid = pid_t::try_from(std::process::id()).unwrap();
+        let report = topology.process_binding_report(pid).unwrap();
+
+        // The current thread must be part of the report
+        // SAFETY: gettid has no safety preconditions
+        let tid = unsafe { libc::gettid() };
+        let current = report.iter().find(|thread| thread.tid() == tid).unwrap();
+        assert_eq!(
+            current.cpu_binding().unwrap(),
+            &topology.tid_cpu_binding(tid).unwrap()
+        );
+
+        // Threads are sorted by TID and have sensible binding state
+        assert!(report.windows(2).all(|w| w[0].tid() < w[1].tid()));
+        for thread in &report {
+            if let Ok(location) = thread.last_cpu_location() {
+                assert_eq!(location.weight(), Some(1));
+            }
+        }
+
+        // Nonexistent processes are reported as such
+        assert_eq!(
+            topology.process_binding_report(-1).unwrap_err().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
 }
@@ -6,7 +6,12 @@
 //! translations of hwloc concepts into the vocabulary of other APIs is useful.
 //! This is what the module you're looking at is about.
 
+pub mod gpu;
+#[cfg(any(doc, target_os = "linux"))]
+pub mod irq;
 #[cfg(any(doc, target_os = "linux"))]
 pub mod linux;
+#[cfg(any(doc, test, all(feature = "hwloc-2_5_0", target_os = "windows")))]
+mod processor_group;
 #[cfg(any(doc, all(feature = "hwloc-2_5_0", target_os = "windows")))]
 pub mod windows;
@@ -0,0 +1,79 @@
+//! Windows processor group affinity masks
+//!
+//! This translation does not call into Windows-specific hwloc APIs, and is
+//! therefore kept out of the `windows` module so that it can be tested on
+//! every operating system.
+
+use crate::cpu::cpuset::CpuSet;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Translate a group-relative `KAFFINITY` mask into a global [`CpuSet`]
+///
+/// hwloc numbers the PUs of processor group `g` from `g * KAFFINITY::BITS`,
+/// where `KAFFINITY` is a pointer-sized integer.
+pub(crate) fn cpuset_from_group_mask(group_index: usize, mask_in_group: usize) -> CpuSet {
+    let group_bits = usize::try_from(usize::BITS).expect("Can't fail, usize::BITS is small");
+    let base = group_index
+        .checked_mul(group_bits)
+        .expect("Processor group index is too large");
+    let mut set = CpuSet::new();
+    for bit in (0..group_bits).filter(|bit| mask_in_group & (1_usize << bit) != 0) {
+        set.set(base + bit);
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// Number of PUs per processor group
+    const GROUP_BITS: usize = usize::BITS as usize;
+
+    #[test]
+    fn empty_mask() {
+        assert_eq!(cpuset_from_group_mask(0, 0), CpuSet::new());
+        assert_eq!(cpuset_from_group_mask(3, 0), CpuSet::new());
+    }
+
+    #[test]
+    fn first_group() {
+        assert_eq!(cpuset_from_group_mask(0, 0b1), CpuSet::from_range(0..=0));
+        assert_eq!(
+            cpuset_from_group_mask(0, 0b1111_0000),
+            CpuSet::from_range(4..=7)
+        );
+        assert_eq!(
+            cpuset_from_group_mask(0, usize::MAX),
+            CpuSet::from_range(0..GROUP_BITS)
+        );
+    }
+
+    #[test]
+    fn other_groups() {
+        let mut expected = CpuSet::new();
+        expected.set(GROUP_BITS);
+        expected.set(GROUP_BITS + 2);
+        assert_eq!(cpuset_from_group_mask(1, 0b101), expected);
+
+        let last_bit = 1_usize << (GROUP_BITS - 1);
+        assert_eq!(
+            cpuset_from_group_mask(2, last_bit),
+            CpuSet::from_range((3 * GROUP_BITS - 1)..(3 * GROUP_BITS))
+        );
+        assert_eq!(
+            cpuset_from_group_mask(3, usize::MAX),
+            CpuSet::from_range(3 * GROUP_BITS..4 * GROUP_BITS)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn group_index_overflow() {
+        cpuset_from_group_mask(usize::MAX, 1);
+    }
+}
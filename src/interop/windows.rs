@@ -1,8 +1,12 @@
 //! Windows-specific helpers
 
+use super::processor_group::cpuset_from_group_mask;
 use crate::{
-    cpu::cpuset::CpuSet,
-    errors::{self, RawHwlocError},
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+    },
+    errors::{self, BindingError, RawHwlocError},
     ffi::int,
     topology::Topology,
 };
@@ -10,6 +14,7 @@ use crate::{
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{ffi::c_uint, iter::FusedIterator, num::NonZeroUsize};
+use thiserror::Error;
 
 /// # Windows-specific helpers
 ///
@@ -95,4 +100,79 @@ impl Topology {
             }),
         )
     }
+
+    /// Bind the current process or thread to PUs of a Windows processor group
+    ///
+    /// Windows APIs designate PUs by a processor group index and a
+    /// group-relative affinity mask (`KAFFINITY`), where bit `i` of the mask
+    /// designates the `i`-th logical processor of the group. hwloc, on the
+    /// other hand, uses global PU indices in its [`CpuSet`]s. This function
+    /// translates a group-relative `mask_in_group` for the processor group at
+    /// index `group_index` into the corresponding hwloc [`CpuSet`], then binds
+    /// to it using [`Topology::bind_cpu()`] with the specified `flags`.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadGroup`] if `group_index` is not the index of a processor group
+    ///   of this topology
+    /// - [`GroupQuery`] if the processor groups could not be queried, e.g.
+    ///   because the topology does not match the current system
+    /// - [`OutsideGroup`] if `mask_in_group` is empty or designates logical
+    ///   processors that do not exist in this processor group
+    /// - [`Binding`] if binding to the translated [`CpuSet`] failed
+    ///
+    /// [`BadGroup`]: ProcessorGroupBindingError::BadGroup
+    /// [`Binding`]: ProcessorGroupBindingError::Binding
+    /// [`GroupQuery`]: ProcessorGroupBindingError::GroupQuery
+    /// [`OutsideGroup`]: ProcessorGroupBindingError::OutsideGroup
+    #[doc(alias = "hwloc_windows_get_processor_group_cpuset")]
+    pub fn bind_cpu_windows_group(
+        &self,
+        group_index: usize,
+        mask_in_group: usize,
+        flags: CpuBindingFlags,
+    ) -> Result<(), ProcessorGroupBindingError> {
+        let group_cpuset = self
+            .processor_groups()
+            .map_err(ProcessorGroupBindingError::GroupQuery)?
+            .nth(group_index)
+            .ok_or(ProcessorGroupBindingError::BadGroup(group_index))?
+            .map_err(ProcessorGroupBindingError::GroupQuery)?;
+        let set = cpuset_from_group_mask(group_index, mask_in_group);
+        if set.is_empty() || !group_cpuset.includes(&set) {
+            return Err(ProcessorGroupBindingError::OutsideGroup {
+                group_index,
+                mask_in_group,
+            });
+        }
+        self.bind_cpu(&set, flags)?;
+        Ok(())
+    }
+}
+
+/// Error returned by [`Topology::bind_cpu_windows_group()`]
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ProcessorGroupBindingError {
+    /// There is no processor group with this index
+    #[error("there is no processor group with index {0}")]
+    BadGroup(usize),
+
+    /// Failed to query the processor groups of the topology
+    #[error("failed to query processor groups")]
+    GroupQuery(#[source] RawHwlocError),
+
+    /// The group-relative mask does not designate PUs of the processor group
+    #[error("mask {mask_in_group:#x} does not designate PUs of processor group {group_index}")]
+    OutsideGroup {
+        /// Requested processor group index
+        group_index: usize,
+
+        /// Requested group-relative affinity mask
+        mask_in_group: usize,
+    },
+
+    /// Binding to the translated CPU set failed
+    #[error(transparent)]
+    Binding(#[from] BindingError<CpuBindingError>),
 }
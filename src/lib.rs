@@ -219,6 +219,7 @@
 )]
 
 pub mod bitmap;
+pub mod compat;
 pub mod cpu;
 pub mod errors;
 pub mod ffi;
@@ -230,7 +231,11 @@ pub mod object;
 pub mod path;
 #[cfg(any(test, feature = "proptest"))]
 pub(crate) mod strategies;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod topology;
+#[cfg(feature = "trace-calls")]
+pub mod trace;
 
 /// Re-export `proptest` version we're built against
 #[cfg(feature = "proptest")]
@@ -247,6 +252,7 @@ use hwlocality_sys::hwloc_thread_t;
 use similar_asserts::assert_eq;
 #[cfg(doc)]
 use std::{process::Child, thread::JoinHandle};
+use thiserror::Error;
 
 /// Thread identifier (OS-specific)
 ///
@@ -315,12 +321,151 @@ pub fn hwloc_api_version() -> usize {
     int::expect_usize(unsafe { hwlocality_sys::hwloc_get_api_version() })
 }
 
+/// hwloc API version that is required by the enabled Cargo features
+///
+/// This uses the same `(X<<16)+(Y<<8)+Z` encoding as [`hwloc_api_version()`].
+pub const REQUIRED_HWLOC_API_VERSION: usize = if cfg!(feature = "hwloc-3_0_0") {
+    encode_api_version(3, 0, 0)
+} else if cfg!(feature = "hwloc-2_11_0") {
+    encode_api_version(2, 11, 0)
+} else if cfg!(feature = "hwloc-2_10_0") {
+    encode_api_version(2, 10, 0)
+} else if cfg!(feature = "hwloc-2_8_0") {
+    encode_api_version(2, 8, 0)
+} else if cfg!(feature = "hwloc-2_5_0") {
+    encode_api_version(2, 5, 0)
+} else if cfg!(feature = "hwloc-2_4_0") {
+    encode_api_version(2, 4, 0)
+} else if cfg!(feature = "hwloc-2_3_0") {
+    encode_api_version(2, 3, 0)
+} else if cfg!(feature = "hwloc-2_2_0") {
+    encode_api_version(2, 2, 0)
+} else if cfg!(feature = "hwloc-2_1_0") {
+    encode_api_version(2, 1, 0)
+} else if cfg!(feature = "hwloc-2_0_4") {
+    encode_api_version(2, 0, 4)
+} else {
+    encode_api_version(2, 0, 0)
+};
+
+/// Encode an hwloc version number like [`hwloc_api_version()`] does
+const fn encode_api_version(major: usize, minor: usize, patch: usize) -> usize {
+    (major << 16) + (minor << 8) + patch
+}
+
+/// Check that the hwloc library in use supports all enabled Cargo features
+///
+/// The `hwloc-2_x_y` Cargo features of this crate expose hwloc APIs that were
+/// introduced by newer hwloc releases. When the binary is linked against an
+/// hwloc library that is older than what these features require, for example
+/// because it was built on a different machine than the one it runs on, some
+/// of these APIs may misbehave. You can call this function at startup to
+/// detect this situation and report it cleanly.
+///
+/// # Errors
+///
+/// [`FeatureUnavailable`] if the hwloc library in use is older than
+/// [`REQUIRED_HWLOC_API_VERSION`].
+///
+/// # Examples
+///
+/// ```
+/// hwlocality::check_hwloc_api_version()?;
+/// # Ok::<(), eyre::Report>(())
+/// ```
+pub fn check_hwloc_api_version() -> Result<(), FeatureUnavailable> {
+    let actual = hwloc_api_version();
+    if actual >= REQUIRED_HWLOC_API_VERSION {
+        Ok(())
+    } else {
+        Err(FeatureUnavailable {
+            required: REQUIRED_HWLOC_API_VERSION,
+            actual,
+        })
+    }
+}
+
+/// Load the hwloc library at runtime, if this was not done already
+///
+/// With the `dlopen` Cargo feature, `libhwloc` is loaded at runtime rather
+/// than linked at build time. It is loaded automatically on first use, but a
+/// missing hwloc library is then a fatal error that aborts the process. Call
+/// this function at startup to detect this situation and degrade gracefully,
+/// e.g. by running without locality information when it fails.
+///
+/// # Errors
+///
+/// [`LoadError`](hwlocality_sys::LoadError) if no suitable hwloc library
+/// could be loaded.
+///
+/// # Examples
+///
+/// ```
+/// if let Err(e) = hwlocality::load_hwloc() {
+///     eprintln!("Running without hwloc: {e}");
+///     return;
+/// }
+/// let topology = hwlocality::Topology::new().unwrap();
+/// ```
+#[cfg(feature = "dlopen")]
+pub fn load_hwloc() -> Result<(), hwlocality_sys::LoadError> {
+    hwlocality_sys::load_hwloc()
+}
+
+/// The hwloc library in use is older than the enabled Cargo features require
+#[derive(Copy, Clone, Debug, Error, Eq, Hash, PartialEq)]
+#[error("enabled features require hwloc API version {required:#x}, but hwloc in use has API version {actual:#x}")]
+pub struct FeatureUnavailable {
+    /// hwloc API version required by the enabled Cargo features
+    pub required: usize,
+
+    /// API version of the hwloc library in use
+    pub actual: usize,
+}
+
 // Disable the alias in test builds to make sure the implementation does not
 // rely on it. It's better for use statements to point to the right place.
 #[cfg(not(test))]
 #[cfg_attr(docsrs, doc(cfg(all())))]
+pub use errors::Error;
+#[cfg(not(test))]
+#[cfg_attr(docsrs, doc(cfg(all())))]
 pub use topology::Topology;
 
+/// Process-wide topology, built on first use
+///
+/// Some libraries, like memory allocators and thread pools, would benefit from
+/// locality information but cannot easily get a [`Topology`] handle from
+/// their caller. This function gives them access to a topology of the current
+/// system that is shared by the entire process, and built the first time this
+/// function is called.
+///
+/// The topology is built using the default [`Topology::new()`]
+/// configuration. If you need a different configuration, build your own
+/// topology and share it using [`SharedTopology`](topology::SharedTopology).
+///
+/// # Errors
+///
+/// If the topology failed to build on the first call, the corresponding error
+/// is returned by this call and all subsequent calls.
+///
+/// # Examples
+///
+/// ```
+/// let topology = hwlocality::global_topology()?;
+/// println!("This system has {} CPUs", topology.cpuset().weight().unwrap());
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[cfg(feature = "global-topology")]
+pub fn global_topology() -> Result<&'static topology::Topology, errors::RawHwlocError> {
+    static INSTANCE: std::sync::OnceLock<Result<topology::Topology, errors::RawHwlocError>> =
+        std::sync::OnceLock::new();
+    INSTANCE
+        .get_or_init(topology::Topology::new)
+        .as_ref()
+        .map_err(|e| *e)
+}
+
 /// This module is an implementation detail of [`Sealed`]
 mod sealed {
     /// Traits with this bound can only be implemented inside this crate
@@ -415,7 +560,13 @@ pub(crate) mod tests {
             (major << 16) + (minor << 8) + patch
         }
         let v3 = api_version(3, 0, 0);
-        let version_range = if cfg!(feature = "hwloc-2_8_0") {
+        let version_range = if cfg!(feature = "hwloc-3_0_0") {
+            v3..api_version(4, 0, 0)
+        } else if cfg!(feature = "hwloc-2_11_0") {
+            api_version(2, 11, 0)..v3
+        } else if cfg!(feature = "hwloc-2_10_0") {
+            api_version(2, 10, 0)..v3
+        } else if cfg!(feature = "hwloc-2_8_0") {
             api_version(2, 8, 0)..v3
         } else if cfg!(feature = "hwloc-2_5_0") {
             api_version(2, 5, 0)..v3
@@ -441,6 +592,19 @@ pub(crate) mod tests {
         )
     }
 
+    #[cfg(feature = "global-topology")]
+    #[test]
+    fn global_topology() {
+        let topology = super::global_topology().unwrap();
+        assert!(std::ptr::eq(topology, super::global_topology().unwrap()));
+        assert!(topology.structurally_equal(&topology::Topology::new().unwrap()));
+    }
+
+    #[test]
+    fn check_api_version() {
+        check_hwloc_api_version().unwrap();
+    }
+
     /// This RAII guard silences the normal panic hook and is used when the
     /// unit test expects panics to occur and normal panic side-effects like
     /// spamming backtraces on stdio are not desired.
@@ -13,6 +13,8 @@
 //! The module itself only hosts type definitions that are related to this
 //! functionality.
 
+#[cfg(feature = "hwloc-2_10_0")]
+use crate::memory::nodeset::NodeSet;
 #[cfg(doc)]
 use crate::topology::support::DiscoverySupport;
 use crate::{
@@ -50,6 +52,7 @@ use similar_asserts::assert_eq;
 use std::{
     ffi::{c_int, c_uint, c_ulong, CStr},
     hash::Hash,
+    iter::FusedIterator,
     mem::MaybeUninit,
     ptr::{self, NonNull},
 };
@@ -133,6 +136,105 @@ impl Topology {
         }
     }
 
+    /// Enumerate all memory attributes known to this topology
+    ///
+    /// This includes both the attributes that are predefined by hwloc (see
+    /// the constructors of [`MemoryAttribute`]) and those that were registered
+    /// using [`TopologyEditor::register_memory_attribute()`]. It is mainly
+    /// useful for building generic tooling that must handle any memory
+    /// attribute, for other purposes you can directly use the predefined
+    /// attribute constructors or [`Topology::memory_attribute_named()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// for attribute in topology.memory_attributes() {
+    ///     println!(
+    ///         "{:?} has flags {:?}",
+    ///         attribute.name(),
+    ///         attribute.flags()
+    ///     );
+    /// }
+    /// ```
+    #[doc(alias = "hwloc_memattr_get_name")]
+    pub fn memory_attributes(&self) -> impl Clone + FusedIterator<Item = MemoryAttribute<'_>> {
+        // Memory attribute IDs are allocated contiguously from 0, so we can
+        // probe them in order until hwloc reports an invalid ID
+        (0..hwloc_memattr_id_t::MAX)
+            .map_while(move |id| {
+                let mut name = ptr::null();
+                // SAFETY: - Topology is trusted to contain a valid ptr (type
+                //           invariant)
+                //         - hwloc ops are trusted not to modify *const
+                //           parameters
+                //         - hwloc reports EINVAL on invalid IDs, so any ID is
+                //           safe to probe
+                //         - name is an out parameter, its initial value
+                //           doesn't matter
+                let res = errors::call_hwloc_int_normal("hwloc_memattr_get_name", || unsafe {
+                    hwlocality_sys::hwloc_memattr_get_name(self.as_ptr(), id, &mut name)
+                });
+                match res {
+                    // SAFETY: hwloc accepted this ID, so it is a valid memory
+                    //         attribute ID for this topology
+                    Ok(_positive) => Some(unsafe { MemoryAttribute::wrap(self, id) }),
+                    Err(RawHwlocError {
+                        errno: Some(Errno(EINVAL)),
+                        ..
+                    }) => None,
+                    Err(raw_err) => unreachable!("Unexpected hwloc error: {raw_err}"),
+                }
+            })
+            .fuse()
+    }
+
+    /// NUMA node with the best value of a memory attribute for a given
+    /// initiator, along with the associated attribute value
+    ///
+    /// This is a shortcut for [`MemoryAttribute::best_target()`] that lets
+    /// you directly ask questions like "which NUMA node has the highest
+    /// bandwidth from this CPU set". The `attribute` callback is used to pick
+    /// the memory attribute of interest, typically one of the predefined
+    /// memory attribute constructors like [`MemoryAttribute::bandwidth`].
+    ///
+    /// See [`MemoryAttribute::best_target()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignInitiator`] if the `initiator` parameter was set to a
+    ///   [`TopologyObject`] that does not belong to this topology
+    /// - [`NeedInitiator`] if no `initiator` was provided but this memory
+    ///   attribute needs one
+    /// - [`UnwantedInitiator`] if an `initiator` was provided but this memory
+    ///   attribute doesn't need one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::attribute::MemoryAttribute, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let cpuset = topology.cpuset();
+    /// if let Some((node, bandwidth)) =
+    ///     topology.best_numa_node_for(MemoryAttribute::bandwidth, Some(&*cpuset))?
+    /// {
+    ///     println!("{node} has the highest bandwidth ({bandwidth} MiB/s)");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`ForeignInitiator`]: InitiatorInputError::ForeignInitiator
+    /// [`NeedInitiator`]: InitiatorInputError::NeedInitiator
+    /// [`UnwantedInitiator`]: InitiatorInputError::UnwantedInitiator
+    pub fn best_numa_node_for<'initiator>(
+        &self,
+        attribute: impl FnOnce(&Self) -> MemoryAttribute<'_>,
+        initiator: Option<impl Into<MemoryAttributeLocation<'initiator>>>,
+    ) -> Result<Option<(&TopologyObject, u64)>, InitiatorInputError> {
+        attribute(self).best_target(initiator)
+    }
+
     /// Find local NUMA nodes
     ///
     /// If `target` is given as a [`TopologyObject`], its CPU set is used to
@@ -213,6 +315,44 @@ impl Topology {
         }
         polymorphized(self, target.into())
     }
+
+    /// Set of NUMA nodes that should be used by default for memory allocation
+    ///
+    /// On heterogeneous memory platforms, some NUMA nodes are not meant to be
+    /// used for regular memory allocations. This is for instance the case of
+    /// GPU memory, or of high-bandwidth memory that should be reserved for
+    /// the buffers that need it. This method returns the subset of
+    /// [`Topology::nodeset()`] that excludes such "non-default" NUMA nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let default_nodes = topology.default_nodeset();
+    /// assert!(topology.nodeset().includes(&default_nodes));
+    /// println!("Allocate memory on NUMA node(s) {default_nodes} by default");
+    /// ```
+    #[cfg(feature = "hwloc-2_10_0")]
+    #[doc(alias = "hwloc_topology_get_default_nodeset")]
+    pub fn default_nodeset(&self) -> NodeSet {
+        let mut nodeset = NodeSet::new();
+        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+        //         - Bitmap is trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - hwloc ops are trusted to keep *mut parameters in a
+        //           valid state unless stated otherwise
+        //         - There are no flags at the moment, so 0 is valid
+        errors::call_hwloc_int_normal("hwloc_topology_get_default_nodeset", || unsafe {
+            hwlocality_sys::hwloc_topology_get_default_nodeset(
+                self.as_ptr(),
+                nodeset.as_mut_ptr(),
+                0,
+            )
+        })
+        .expect("All known failure cases are prevented by API design");
+        nodeset
+    }
 }
 
 /// # Managing memory attributes
@@ -223,6 +363,12 @@ impl Topology {
 impl<'topology> TopologyEditor<'topology> {
     /// Register a new memory attribute
     ///
+    /// This lets you attach your own metrics (e.g. measured bandwidth) to the
+    /// topology's NUMA nodes. Once values have been set using the returned
+    /// [`MemoryAttributeBuilder`], the attribute can be retrieved using
+    /// [`Topology::memory_attribute_named()`] and queried like any predefined
+    /// memory attribute, including via [`MemoryAttribute::best_target()`].
+    ///
     /// # Errors
     ///
     /// - [`BadFlags`] if `flags` does not contain exactly one of the
@@ -231,10 +377,45 @@ impl<'topology> TopologyEditor<'topology> {
     /// - [`NameTaken`] if another attribute called `name` already exists.
     ///
     /// [`BadFlags`]: RegisterError::BadFlags
-    /// [`HIGHER_IS_BEST`]: [`MemoryAttributeFlags::HIGHER_IS_BEST`]
-    /// [`LOWER_IS_BEST`]: [`MemoryAttributeFlags::LOWER_IS_BEST`]
+    /// [`HIGHER_IS_BEST`]: MemoryAttributeFlags::HIGHER_IS_BEST
+    /// [`LOWER_IS_BEST`]: MemoryAttributeFlags::LOWER_IS_BEST
     /// [`NameContainsNul`]: RegisterError::NameContainsNul
     /// [`NameTaken`]: RegisterError::NameTaken
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     memory::attribute::{MemoryAttributeFlags, MemoryAttributeLocation},
+    /// #     object::{types::ObjectType, TopologyObject},
+    /// #     Topology,
+    /// # };
+    /// # fn measure_stream_bandwidth(_node: &TopologyObject) -> u64 { 42 }
+    /// let mut topology = Topology::test_instance().clone();
+    /// topology.edit(|editor| -> eyre::Result<()> {
+    ///     let mut builder = editor.register_memory_attribute(
+    ///         "StreamBandwidth",
+    ///         MemoryAttributeFlags::HIGHER_IS_BEST | MemoryAttributeFlags::NEED_INITIATOR,
+    ///     )?;
+    ///     builder.set_values(|topology| {
+    ///         let initiator = MemoryAttributeLocation::from(topology.cpuset());
+    ///         let targets_and_values = topology
+    ///             .objects_with_type(ObjectType::NUMANode)
+    ///             .map(|node| (node, measure_stream_bandwidth(node)))
+    ///             .collect::<Vec<_>>();
+    ///         (Some(vec![initiator; targets_and_values.len()]), targets_and_values)
+    ///     })?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let stream_bandwidth = topology
+    ///     .memory_attribute_named("StreamBandwidth")?
+    ///     .expect("Attribute was just registered");
+    /// if let Some((node, bandwidth)) = stream_bandwidth.best_target(Some(topology.cpuset()))? {
+    ///     println!("{node} has the best measured bandwidth ({bandwidth})");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
     #[doc(alias = "hwloc_memattr_register")]
     pub fn register_memory_attribute(
         &mut self,
@@ -295,8 +476,8 @@ pub enum RegisterError {
     /// You must specify exactly one of the [`HIGHER_IS_BEST`] and
     /// [`LOWER_IS_BEST`] flags.
     ///
-    /// [`HIGHER_IS_BEST`]: [`MemoryAttributeFlags::HIGHER_IS_BEST`]
-    /// [`LOWER_IS_BEST`]: [`MemoryAttributeFlags::LOWER_IS_BEST`]
+    /// [`HIGHER_IS_BEST`]: MemoryAttributeFlags::HIGHER_IS_BEST
+    /// [`LOWER_IS_BEST`]: MemoryAttributeFlags::LOWER_IS_BEST
     #[error(transparent)]
     BadFlags(#[from] FlagsError<MemoryAttributeFlags>),
 }
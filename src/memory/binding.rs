@@ -7,20 +7,26 @@
 //! struct](../../topology/struct.Topology.html#memory-binding). The module
 //! itself only hosts type definitions that are related to this functionality.
 
+#[cfg(doc)]
+use crate::topology::support::MemoryBindingSupport;
 use crate::{
     bitmap::{Bitmap, BitmapKind, OwnedSpecializedBitmap, SpecializedBitmap},
-    errors::{self, FlagsError, RawHwlocError},
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+    },
+    errors::{self, BindingContext, BindingError, FlagsError, HybridError, RawHwlocError},
     memory::nodeset::NodeSet,
     topology::Topology,
     ProcessId,
 };
-#[cfg(doc)]
-use crate::{cpu::cpuset::CpuSet, topology::support::MemoryBindingSupport};
 use bitflags::bitflags;
 use derive_more::Display;
 #[cfg(any(test, feature = "proptest"))]
 use enum_iterator::Sequence;
 use errno::Errno;
+#[cfg(feature = "hwloc-2_11_0")]
+use hwlocality_sys::HWLOC_MEMBIND_WEIGHTED_INTERLEAVE;
 use hwlocality_sys::{
     hwloc_bitmap_t, hwloc_const_bitmap_t, hwloc_const_topology_t, hwloc_membind_flags_t,
     hwloc_membind_policy_t, hwloc_pid_t, HWLOC_MEMBIND_BIND, HWLOC_MEMBIND_BYNODESET,
@@ -37,6 +43,7 @@ use std::{
     borrow::{Borrow, BorrowMut},
     ffi::{c_int, c_void},
     fmt::{self, Debug, Display},
+    marker::PhantomData,
     mem::MaybeUninit,
     ops::{Deref, DerefMut},
     ptr::{self, NonNull},
@@ -71,6 +78,12 @@ use thiserror::Error;
 /// CPU set cannot work for CPU-less NUMA memory nodes. Binding by node set
 /// should therefore be preferred whenever possible.
 ///
+/// Methods which are generic over the set type have `_by_nodeset` variants
+/// (e.g. [`bind_memory_by_nodeset()`], [`memory_binding_by_nodeset()`]) that
+/// spare you from specifying the set type, while methods which only make
+/// sense for NUMA nodes (e.g. [`migrate_memory()`], [`box_on_node()`]) only
+/// accept [`NodeSet`]s.
+///
 /// You should specify one of the [`ASSUME_SINGLE_THREAD`], [`PROCESS`] and
 /// [`THREAD`] flags (the former being best for portability) when using any of
 /// the methods that target a process, but some methods may only support a
@@ -85,6 +98,10 @@ use thiserror::Error;
 /// [`bind_memory_area()`]: Topology::bind_memory_area()
 /// [`bind_memory()`]: Topology::bind_memory()
 /// [`binding_allocate_memory()`]: Topology::binding_allocate_memory()
+/// [`bind_memory_by_nodeset()`]: Topology::bind_memory_by_nodeset()
+/// [`box_on_node()`]: Topology::box_on_node()
+/// [`memory_binding_by_nodeset()`]: Topology::memory_binding_by_nodeset()
+/// [`migrate_memory()`]: Topology::migrate_memory()
 /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
 /// [`PROCESS`]: MemoryBindingFlags::PROCESS
 /// [`STRICT`]: MemoryBindingFlags::STRICT
@@ -104,10 +121,25 @@ impl Topology {
     /// - [`AllocationFailed`] if memory allocation failed
     /// - [`Unsupported`] if the system cannot allocate page-aligned memory
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # use std::mem::MaybeUninit;
+    /// # let topology = Topology::test_instance();
+    /// let mut bytes = topology.allocate_memory(4096)?;
+    /// assert_eq!(bytes.len(), 4096);
+    /// bytes.fill(MaybeUninit::new(42));
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
     /// [`AllocationFailed`]: MemoryBindingError::AllocationFailed
     /// [`Unsupported`]: MemoryBindingError::Unsupported
     #[doc(alias = "hwloc_alloc")]
-    pub fn allocate_memory(&self, len: usize) -> Result<Bytes<'_>, MemoryAllocationError<NodeSet>> {
+    pub fn allocate_memory(
+        &self,
+        len: usize,
+    ) -> Result<Bytes<'_>, BindingError<MemoryAllocationError<NodeSet>>> {
         self.allocate_memory_generic(len)
     }
 
@@ -117,13 +149,24 @@ impl Topology {
     fn allocate_memory_generic<OwnedSet: OwnedSpecializedBitmap>(
         &self,
         len: usize,
-    ) -> Result<Bytes<'_>, MemoryAllocationError<OwnedSet>> {
+    ) -> Result<Bytes<'_>, BindingError<MemoryAllocationError<OwnedSet>>> {
         // SAFETY: - hwloc_alloc is accepted by definition
         //         - FFI is guaranteed to be passed valid (topology, len)
         unsafe {
-            self.allocate_memory_impl("hwloc_alloc", &|| None, len, |topology, len| {
-                hwlocality_sys::hwloc_alloc(topology, len)
-            })
+            self.allocate_memory_impl(
+                "hwloc_alloc",
+                &|| None,
+                &|| {
+                    BindingContext::new(
+                        "hwloc_alloc",
+                        MemoryBoundObject::Area,
+                        MemoryBindingFlags::empty(),
+                        None,
+                    )
+                },
+                len,
+                |topology, len| hwlocality_sys::hwloc_alloc(topology, len),
+            )
         }
     }
 
@@ -150,6 +193,32 @@ impl Topology {
     /// - [`Unsupported`] if the system cannot allocate bound memory with the
     ///   requested policy
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     memory::binding::{MemoryBindingFlags, MemoryBindingPolicy},
+    /// #     object::types::ObjectType,
+    /// #     Topology,
+    /// # };
+    /// # let topology = Topology::test_instance();
+    /// // Allocate a buffer on the first NUMA node of the system
+    /// let node = topology
+    ///     .objects_with_type(ObjectType::NUMANode)
+    ///     .next()
+    ///     .expect("There should be at least one NUMA node");
+    /// let nodeset = node.nodeset().expect("NUMA nodes should have a nodeset");
+    /// match topology.allocate_bound_memory(
+    ///     4096,
+    ///     &nodeset,
+    ///     MemoryBindingPolicy::Bind,
+    ///     MemoryBindingFlags::empty(),
+    /// ) {
+    ///     Ok(bytes) => println!("Allocated {} bytes on node {nodeset}", bytes.len()),
+    ///     Err(e) => println!("Could not allocate bound memory: {e}"),
+    /// }
+    /// ```
+    ///
     /// [`AllocationFailed`]: MemoryBindingError::AllocationFailed
     /// [`ASSUME_SINGLE_THREAD`]: MemoryBindingFlags::ASSUME_SINGLE_THREAD
     /// [`BadFlags`]: MemoryBindingError::BadFlags
@@ -164,12 +233,31 @@ impl Topology {
         len: usize,
         set: &Set,
         policy: MemoryBindingPolicy,
-        mut flags: MemoryBindingFlags,
-    ) -> Result<Bytes<'_>, MemoryAllocationError<Set::Owned>> {
-        Self::adjust_flags_for::<Set::Owned>(&mut flags);
+        flags: MemoryBindingFlags,
+    ) -> Result<Bytes<'_>, BindingError<MemoryAllocationError<Set::Owned>>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "allocate_bound_memory",
+            len,
+            %policy,
+            ?flags,
+            set_weight = ?set.as_ref().weight(),
+        )
+        .entered();
+        let context = || {
+            BindingContext::new(
+                "hwloc_alloc_membind",
+                MemoryBoundObject::Area,
+                flags,
+                Some(set.as_ref()),
+            )
+        };
         let Some(flags) = flags.validate(MemoryBoundObject::Area, MemoryBindingOperation::Allocate)
         else {
-            return Err(MemoryBindingError::BadFlags(flags.into()));
+            return Err(BindingError::new(
+                context(),
+                MemoryBindingError::BadFlags(flags.into()),
+            ));
         };
         // SAFETY: - Bitmap is trusted to contain a valid ptr (type invariant)
         //         - hwloc ops are trusted not to modify *const parameters
@@ -182,6 +270,7 @@ impl Topology {
             self.allocate_memory_impl(
                 "hwloc_alloc_membind",
                 &|| Some(set.to_owned()),
+                &context,
                 len,
                 |topology, len| {
                     hwlocality_sys::hwloc_alloc_membind(
@@ -189,7 +278,7 @@ impl Topology {
                         len,
                         set.as_ref().as_ptr(),
                         policy.into(),
-                        flags.bits(),
+                        flags.bits() | Self::set_kind_flag::<Set::Owned>(),
                     )
                 },
             )
@@ -208,6 +297,14 @@ impl Topology {
     /// is supported on more operating systems, so this is the most portable way
     /// to obtain a bound memory buffer.
     ///
+    /// Beware that if the rebinding path is taken, the new memory binding
+    /// policy of the current process or thread is not reverted after the
+    /// allocation has completed. Future allocations that are not explicitly
+    /// bound, including those performed by the Rust global allocator, will
+    /// therefore follow it too. If this side-effect is not acceptable, use
+    /// [`Topology::allocate_bound_memory()`] instead, or save the previous
+    /// binding with [`Topology::memory_binding()`] and restore it afterwards.
+    ///
     /// You must specify exactly one of the [`ASSUME_SINGLE_THREAD`],
     /// [`PROCESS`] and [`THREAD`] binding target flags when using this method.
     ///
@@ -224,6 +321,28 @@ impl Topology {
     /// - [`Unsupported`] if the system can neither allocate bound memory
     ///   nor rebind the current thread/process with the requested policy
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     memory::binding::{MemoryBindingFlags, MemoryBindingPolicy},
+    /// #     Topology,
+    /// # };
+    /// # let topology = Topology::test_instance();
+    /// // Allocate memory that is bound to the NUMA nodes of the machine,
+    /// // rebinding the current thread if there is no other way
+    /// let nodeset = topology.nodeset();
+    /// match topology.binding_allocate_memory(
+    ///     4096,
+    ///     &nodeset,
+    ///     MemoryBindingPolicy::Bind,
+    ///     MemoryBindingFlags::THREAD,
+    /// ) {
+    ///     Ok(bytes) => println!("Allocated {} bytes", bytes.len()),
+    ///     Err(e) => println!("Could not allocate bound memory: {e}"),
+    /// }
+    /// ```
+    ///
     /// [`AllocationFailed`]: MemoryBindingError::AllocationFailed
     /// [`ASSUME_SINGLE_THREAD`]: MemoryBindingFlags::ASSUME_SINGLE_THREAD
     /// [`BadFlags`]: MemoryBindingError::BadFlags
@@ -238,10 +357,12 @@ impl Topology {
         set: &Set,
         policy: MemoryBindingPolicy,
         flags: MemoryBindingFlags,
-    ) -> Result<Bytes<'_>, MemoryAllocationError<Set::Owned>> {
+    ) -> Result<Bytes<'_>, BindingError<MemoryAllocationError<Set::Owned>>> {
         // Try allocate_bound_memory first
         let set: &Set::Owned = set.borrow();
-        if let Ok(bytes) = self.allocate_bound_memory(len, set, policy, flags) {
+        if let Ok(bytes) =
+            self.allocate_bound_memory(len, set, policy, flags - MemoryBindingFlags::TARGET_FLAGS)
+        {
             return Ok(bytes);
         }
 
@@ -262,10 +383,217 @@ impl Topology {
             MemoryBindingPolicy::Bind | MemoryBindingPolicy::Interleave => {
                 bytes.fill(MaybeUninit::new(0));
             }
+            #[cfg(feature = "hwloc-2_11_0")]
+            MemoryBindingPolicy::WeightedInterleave => {
+                bytes.fill(MaybeUninit::new(0));
+            }
         }
         Ok(bytes)
     }
 
+    /// Allocate some memory on the NUMA nodes that are local to the CPUs that
+    /// the current thread is bound to
+    ///
+    /// This queries the CPU binding of the current thread with
+    /// [`Topology::cpu_binding()`], converts it into the set of NUMA nodes
+    /// that are local to these CPUs with [`NodeSet::from_cpuset()`], then
+    /// allocates memory that is bound to these nodes using
+    /// [`Topology::allocate_bound_memory()`] with the
+    /// [`MemoryBindingPolicy::Bind`] policy.
+    ///
+    /// If the current thread is not bound to specific CPUs, its CPU binding
+    /// covers the whole machine and memory is allocated on all NUMA nodes.
+    ///
+    /// `flags` are the memory binding flags of the allocation, so binding
+    /// target flags like [`THREAD`] must not be specified.
+    ///
+    /// Requires [`CpuBindingSupport::get_current_thread()`] and
+    /// [`MemoryBindingSupport::allocate_bound()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`CpuBinding`] if the CPU binding of the current thread could not be
+    ///   queried
+    /// - [`Allocation`] if the bound memory could not be allocated, see
+    ///   [`Topology::allocate_bound_memory()`] for details
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::binding::MemoryBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// match topology.allocate_memory_near_current_thread(4096, MemoryBindingFlags::empty()) {
+    ///     Ok(bytes) => println!("Allocated {} bytes near the current thread", bytes.len()),
+    ///     Err(e) => println!("Could not allocate local memory: {e}"),
+    /// }
+    /// ```
+    ///
+    /// [`Allocation`]: LocalAllocationError::Allocation
+    /// [`CpuBinding`]: LocalAllocationError::CpuBinding
+    /// [`CpuBindingSupport::get_current_thread()`]: crate::topology::support::CpuBindingSupport::get_current_thread()
+    /// [`THREAD`]: MemoryBindingFlags::THREAD
+    pub fn allocate_memory_near_current_thread(
+        &self,
+        len: usize,
+        flags: MemoryBindingFlags,
+    ) -> Result<Bytes<'_>, LocalAllocationError> {
+        let cpuset = self
+            .cpu_binding(CpuBindingFlags::THREAD)
+            .map_err(LocalAllocationError::CpuBinding)?;
+        let nodeset = NodeSet::from_cpuset(self, &cpuset);
+        Ok(self.allocate_bound_memory(len, &nodeset, MemoryBindingPolicy::Bind, flags)?)
+    }
+
+    /// Move `value` into memory that is bound to the NUMA nodes specified by
+    /// `nodeset`
+    ///
+    /// This is a typed convenience layer over
+    /// [`Topology::allocate_bound_memory()`] with the
+    /// [`MemoryBindingPolicy::Bind`] policy, which spares you from manipulating
+    /// raw [`Bytes`]. The value will be dropped and the underlying memory will
+    /// be liberated when the resulting [`BoundBox`] goes out of scope.
+    ///
+    /// Requires [`MemoryBindingSupport::allocate_bound()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocationFailed`] if memory allocation failed, or if the
+    ///   allocation does not satisfy the alignment requirements of `T` (which
+    ///   can only happen for types that are aligned to more than one page)
+    /// - [`BadSet`] if the system can't bind memory to that node set
+    /// - [`Unsupported`] if the system cannot allocate bound memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let nodeset = topology.nodeset();
+    /// match topology.box_on_node([0u64; 512], &nodeset) {
+    ///     Ok(mut data) => {
+    ///         data[42] = 24;
+    ///         assert_eq!(data[42], 24);
+    ///     }
+    ///     Err(e) => println!("Could not allocate bound memory: {e}"),
+    /// }
+    /// ```
+    ///
+    /// [`AllocationFailed`]: MemoryBindingError::AllocationFailed
+    /// [`BadSet`]: MemoryBindingError::BadSet
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    pub fn box_on_node<T>(
+        &self,
+        value: T,
+        nodeset: &NodeSet,
+    ) -> Result<BoundBox<'_, T>, BindingError<MemoryAllocationError<NodeSet>>> {
+        let (bytes, data) = self.allocate_bound_array::<T>(1, nodeset)?;
+        // SAFETY: data is valid and aligned for one T per allocate_bound_array
+        //         postcondition, and not aliased as it was just allocated
+        unsafe { data.as_ptr().write(value) };
+        Ok(BoundBox {
+            _bytes: bytes,
+            data,
+            _owned: PhantomData,
+        })
+    }
+
+    /// Allocate a vector-like container with room for `capacity` elements of
+    /// type `T`, in memory that is bound to the NUMA nodes specified by
+    /// `nodeset`
+    ///
+    /// This is a typed convenience layer over
+    /// [`Topology::allocate_bound_memory()`] with the
+    /// [`MemoryBindingPolicy::Bind`] policy, which spares you from manipulating
+    /// raw [`Bytes`]. Unlike [`Vec`], the resulting [`BoundVec`] cannot grow
+    /// beyond its initial capacity, since that would require reallocating
+    /// memory without knowing the binding that was used for the original
+    /// allocation.
+    ///
+    /// Requires [`MemoryBindingSupport::allocate_bound()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`AllocationFailed`] if memory allocation failed, if the requested
+    ///   capacity overflows `usize` in bytes, or if the allocation does not
+    ///   satisfy the alignment requirements of `T` (which can only happen for
+    ///   types that are aligned to more than one page)
+    /// - [`BadSet`] if the system can't bind memory to that node set
+    /// - [`Unsupported`] if the system cannot allocate bound memory
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let nodeset = topology.nodeset();
+    /// match topology.vec_on_node::<f32>(1024, &nodeset) {
+    ///     Ok(mut data) => {
+    ///         data.extend_from_within_capacity((0..1024).map(|i| i as f32));
+    ///         assert_eq!(data.len(), 1024);
+    ///         assert!(data.try_push(0.0).is_err());
+    ///     }
+    ///     Err(e) => println!("Could not allocate bound memory: {e}"),
+    /// }
+    /// ```
+    ///
+    /// [`AllocationFailed`]: MemoryBindingError::AllocationFailed
+    /// [`BadSet`]: MemoryBindingError::BadSet
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    pub fn vec_on_node<T>(
+        &self,
+        capacity: usize,
+        nodeset: &NodeSet,
+    ) -> Result<BoundVec<'_, T>, BindingError<MemoryAllocationError<NodeSet>>> {
+        let (bytes, data) = self.allocate_bound_array::<T>(capacity, nodeset)?;
+        Ok(BoundVec {
+            _bytes: bytes,
+            data,
+            capacity,
+            len: 0,
+            _owned: PhantomData,
+        })
+    }
+
+    /// Allocate uninitialized bound storage for `capacity` elements of type `T`
+    ///
+    /// On success, returns the underlying allocation along with a pointer
+    /// that is valid and suitably aligned for reads and writes of `capacity`
+    /// consecutive elements of type `T`.
+    fn allocate_bound_array<T>(
+        &self,
+        capacity: usize,
+        nodeset: &NodeSet,
+    ) -> Result<(Bytes<'_>, NonNull<T>), BindingError<MemoryAllocationError<NodeSet>>> {
+        let allocation_failed = || {
+            BindingError::new(
+                BindingContext::new(
+                    "hwloc_alloc_membind",
+                    MemoryBoundObject::Area,
+                    MemoryBindingFlags::empty(),
+                    Some(nodeset),
+                ),
+                MemoryBindingError::AllocationFailed,
+            )
+        };
+        let len = capacity
+            .checked_mul(std::mem::size_of::<T>())
+            .ok_or_else(allocation_failed)?;
+        let mut bytes = self.allocate_bound_memory(
+            len,
+            nodeset,
+            MemoryBindingPolicy::Bind,
+            MemoryBindingFlags::empty(),
+        )?;
+        if len == 0 {
+            return Ok((bytes, NonNull::dangling()));
+        }
+        let data = NonNull::from(&mut bytes[..]).cast::<T>();
+        if data.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+            return Err(allocation_failed());
+        }
+        Ok((bytes, data))
+    }
+
     /// Set the default memory binding policy of the current process or thread
     /// to prefer the NUMA node(s) specified by `set`.
     ///
@@ -299,7 +627,7 @@ impl Topology {
         set: &Set,
         policy: MemoryBindingPolicy,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<Set::Owned>> {
+    ) -> Result<(), BindingError<MemoryBindingError<Set::Owned>>> {
         // SAFETY: - ThisProgram is the correct target for this FFI
         //         - hwloc_set_membind is accepted by definition
         //         - FFI is guaranteed to be passed valid (topology,
@@ -318,6 +646,48 @@ impl Topology {
         }
     }
 
+    /// Set the default memory binding policy of the current process or thread
+    /// to prefer the NUMA nodes specified by `nodeset`
+    ///
+    /// This is the [`NodeSet`]-specific version of
+    /// [`Topology::bind_memory()`], which you should use when you want to make
+    /// sure that binding is performed by node set. It can be used to bind
+    /// memory to CPU-less NUMA nodes, which [`Topology::bind_memory_by_cpuset()`]
+    /// cannot do.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::bind_memory()`].
+    #[doc(alias = "HWLOC_MEMBIND_BYNODESET")]
+    pub fn bind_memory_by_nodeset(
+        &self,
+        nodeset: &NodeSet,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        self.bind_memory(nodeset, policy, flags)
+    }
+
+    /// Set the default memory binding policy of the current process or thread
+    /// to prefer the NUMA nodes near the CPUs specified by `cpuset`
+    ///
+    /// This is the [`CpuSet`]-specific version of [`Topology::bind_memory()`].
+    /// Beware that memory binding by CPU set cannot work for CPU-less NUMA
+    /// nodes, so you should prefer [`Topology::bind_memory_by_nodeset()`]
+    /// whenever possible.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::bind_memory()`].
+    pub fn bind_memory_by_cpuset(
+        &self,
+        cpuset: &CpuSet,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<(), BindingError<MemoryBindingError<CpuSet>>> {
+        self.bind_memory(cpuset, policy, flags)
+    }
+
     /// Reset the memory allocation policy of the current process or thread to
     /// the system default
     ///
@@ -351,7 +721,7 @@ impl Topology {
     pub fn unbind_memory(
         &self,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<NodeSet>> {
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
         // SAFETY: - ThisProgram is the correct target for this FFI
         //         - hwloc_set_membind is accepted by definition
         //         - FFI is guaranteed to be passed valid (topology,
@@ -420,7 +790,8 @@ impl Topology {
     pub fn memory_binding<OwnedSet: OwnedSpecializedBitmap>(
         &self,
         flags: MemoryBindingFlags,
-    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), MemoryBindingError<OwnedSet>> {
+    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<OwnedSet>>>
+    {
         // SAFETY: - ThisProgram is the correct target for this FFI
         //         - GetBinding is the correct operation for this FFI
         //         - hwloc_get_membind is accepted by definition
@@ -439,6 +810,135 @@ impl Topology {
         }
     }
 
+    /// Query the default memory binding policy and physical locality of the
+    /// current process or thread, as a [`NodeSet`]
+    ///
+    /// This is the [`NodeSet`]-specific version of
+    /// [`Topology::memory_binding()`]. Querying by [`NodeSet`] is the only way
+    /// to tell whether memory is bound to CPU-less NUMA nodes.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::memory_binding()`].
+    #[allow(clippy::type_complexity)]
+    pub fn memory_binding_by_nodeset(
+        &self,
+        flags: MemoryBindingFlags,
+    ) -> Result<(NodeSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<NodeSet>>>
+    {
+        self.memory_binding(flags)
+    }
+
+    /// Set the default memory binding policy of the current process or thread,
+    /// then query which binding it actually ended up with
+    ///
+    /// When [`STRICT`] is not set, the operating system is allowed to use a
+    /// different binding than the requested one, for example a larger set of
+    /// NUMA nodes or a different policy. This variant of
+    /// [`Topology::bind_memory()`] lets you detect such degraded placements by
+    /// querying the binding with [`Topology::memory_binding()`] right after
+    /// setting it, so that you can log them or react accordingly.
+    ///
+    /// Flags [`MIGRATE`] and [`NO_CPU_BINDING`] are only used when setting the
+    /// binding, and flag [`STRICT`] is only used when querying the binding if
+    /// [`PROCESS`] is also set.
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Topology::bind_memory()`] or
+    /// [`Topology::memory_binding()`].
+    ///
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
+    /// [`PROCESS`]: MemoryBindingFlags::PROCESS
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    #[allow(clippy::type_complexity)]
+    pub fn bind_memory_verified<Set: SpecializedBitmap>(
+        &self,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<
+        (Set::Owned, Option<MemoryBindingPolicy>),
+        BindingError<MemoryBindingError<Set::Owned>>,
+    > {
+        self.bind_memory(set, policy, flags)?;
+        let mut query_flags =
+            flags - MemoryBindingFlags::MIGRATE - MemoryBindingFlags::NO_CPU_BINDING;
+        if !query_flags.contains(MemoryBindingFlags::PROCESS) {
+            query_flags.remove(MemoryBindingFlags::STRICT);
+        }
+        self.memory_binding(query_flags)
+    }
+
+    /// Temporarily set the default memory binding policy of the current
+    /// process or thread, restoring the previous one when the returned guard
+    /// is dropped
+    ///
+    /// This is useful when a different memory binding is only desired for a
+    /// limited phase of the program's execution, e.g. interleaving the data
+    /// structures that are allocated during an initialization phase, without
+    /// affecting the memory allocations of the steady-state execution that
+    /// follows it.
+    ///
+    /// The current binding is first queried with
+    /// [`Topology::memory_binding()`], then the new binding is set with
+    /// [`Topology::bind_memory()`]. When the [`MemoryBindingGuard`] is dropped
+    /// or [`restore()`]d, the previous nodeset and policy are set again using
+    /// the same binding target flags as `flags`.
+    ///
+    /// Flags [`MIGRATE`] and [`NO_CPU_BINDING`] are only used when setting the
+    /// binding, and flag [`STRICT`] is only used when querying the current
+    /// binding if [`PROCESS`] is also set, and when setting the new binding.
+    ///
+    /// # Errors
+    ///
+    /// - [`MixedResults`] if flag [`PROCESS`] is set and the threads of the
+    ///   process do not share a common memory binding policy, which thus
+    ///   cannot be restored later on
+    /// - Any other error from [`Topology::memory_binding()`] or
+    ///   [`Topology::bind_memory()`]
+    ///
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`MixedResults`]: MemoryBindingError::MixedResults
+    /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
+    /// [`PROCESS`]: MemoryBindingFlags::PROCESS
+    /// [`restore()`]: MemoryBindingGuard::restore()
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    pub fn bind_memory_scoped(
+        &self,
+        nodeset: &NodeSet,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<MemoryBindingGuard<'_>, BindingError<MemoryBindingError<NodeSet>>> {
+        let mut query_flags =
+            flags - MemoryBindingFlags::MIGRATE - MemoryBindingFlags::NO_CPU_BINDING;
+        if !query_flags.contains(MemoryBindingFlags::PROCESS) {
+            query_flags.remove(MemoryBindingFlags::STRICT);
+        }
+        let (previous_nodeset, previous_policy) = self.memory_binding::<NodeSet>(query_flags)?;
+        let previous_policy = previous_policy.ok_or_else(|| {
+            BindingError::new(
+                BindingContext::new(
+                    "hwloc_get_membind",
+                    MemoryBoundObject::ThisProgram,
+                    query_flags,
+                    None,
+                ),
+                MemoryBindingError::MixedResults,
+            )
+        })?;
+        self.bind_memory(nodeset, policy, flags)?;
+        Ok(MemoryBindingGuard {
+            topology: self,
+            previous_nodeset,
+            previous_policy,
+            flags: flags & MemoryBindingFlags::TARGET_FLAGS,
+            restored: false,
+            _not_send: PhantomData,
+        })
+    }
+
     /// Set the default memory binding policy of the specified process to prefer
     /// the NUMA node(s) specified by `set`.
     ///
@@ -471,7 +971,7 @@ impl Topology {
         set: &Set,
         policy: MemoryBindingPolicy,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<Set::Owned>> {
+    ) -> Result<(), BindingError<MemoryBindingError<Set::Owned>>> {
         // SAFETY: - Process is the correct target for this FFI
         //         - hwloc_set_proc_membind with pid argument curried away
         //           behaves like hwloc_set_membind
@@ -529,7 +1029,7 @@ impl Topology {
         &self,
         pid: ProcessId,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<NodeSet>> {
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
         // SAFETY: - Process is the correct target for this FFI
         //         - hwloc_set_proc_membind with pid argument curried away
         //           behaves like hwloc_set_membind
@@ -592,7 +1092,8 @@ impl Topology {
         &self,
         pid: ProcessId,
         flags: MemoryBindingFlags,
-    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), MemoryBindingError<OwnedSet>> {
+    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<OwnedSet>>>
+    {
         // SAFETY: - Process is the correct target for this FFI
         //         - GetBinding is the correct operation for this FFI
         //         - hwloc_get_proc_membind with pid argument curried away
@@ -620,6 +1121,29 @@ impl Topology {
         }
     }
 
+    /// Query the default memory binding policy and physical locality of the
+    /// specified process, as a [`NodeSet`]
+    ///
+    /// This is the [`NodeSet`]-specific version of
+    /// [`Topology::process_memory_binding()`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::process_memory_binding()`].
+    ///
+    /// # Panics
+    ///
+    /// See [`Topology::process_memory_binding()`].
+    #[allow(clippy::type_complexity)]
+    pub fn process_memory_binding_by_nodeset(
+        &self,
+        pid: ProcessId,
+        flags: MemoryBindingFlags,
+    ) -> Result<(NodeSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<NodeSet>>>
+    {
+        self.process_memory_binding(pid, flags)
+    }
+
     /// Bind the memory identified by `target` to the NUMA node(s) specified by
     /// `set`
     ///
@@ -653,19 +1177,73 @@ impl Topology {
         set: &Set,
         policy: MemoryBindingPolicy,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<Set::Owned>> {
+    ) -> Result<(), BindingError<MemoryBindingError<Set::Owned>>> {
         let target_size = std::mem::size_of_val(target);
-        if target_size == 0 {
-            return Err(MemoryBindingError::BadTarget);
-        }
         let target_ptr: *const Target = target;
+        // SAFETY: target_ptr is valid over target_size bytes as it originates
+        //         from a & reference
+        unsafe {
+            self.bind_memory_area_raw(target_ptr.cast::<c_void>(), target_size, set, policy, flags)
+        }
+    }
+
+    /// Bind the memory area that starts at `base` and spans `len` bytes to the
+    /// NUMA node(s) specified by `set`
+    ///
+    /// This is a lower-level version of [`Topology::bind_memory_area()`] that
+    /// is meant for memory regions that cannot be easily expressed as a Rust
+    /// reference, such as buffers allocated by C code or memory-mapped files
+    /// that are only accessed through raw pointers.
+    ///
+    /// See also [`Topology::bind_memory()`] for general semantics, except
+    /// binding target flags should not be used with this method, and it
+    /// requires [`MemoryBindingSupport::set_area()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if a binding target flag was specified
+    /// - [`BadSet`] if the system can't bind memory to that CPU/node set
+    /// - [`BadTarget`] if `len` is zero
+    /// - [`Unsupported`] if the system cannot bind the specified memory area
+    ///   with the requested policy
+    ///
+    /// # Safety
+    ///
+    /// The memory region starting at `base` and spanning `len` bytes must be
+    /// part of this process' address space, and must remain so for the entire
+    /// duration of this method call.
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BadSet`]: MemoryBindingError::BadSet
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    #[doc(alias = "hwloc_set_area_membind")]
+    pub unsafe fn bind_memory_area_raw<Set: SpecializedBitmap>(
+        &self,
+        base: *const c_void,
+        len: usize,
+        set: &Set,
+        policy: MemoryBindingPolicy,
+        flags: MemoryBindingFlags,
+    ) -> Result<(), BindingError<MemoryBindingError<Set::Owned>>> {
+        if len == 0 {
+            return Err(BindingError::new(
+                BindingContext::new(
+                    "hwloc_set_area_membind",
+                    MemoryBoundObject::Area,
+                    flags,
+                    Some(set.as_ref()),
+                ),
+                MemoryBindingError::BadTarget,
+            ));
+        }
         // SAFETY: - Area is the correct target for this FFI
-        //         - hwloc_set_area_membind with target_ptr and target_size
-        //           arguments curried away behaves like hwloc_set_membind
+        //         - hwloc_set_area_membind with base and len arguments curried
+        //           away behaves like hwloc_set_membind
         //         - FFI is guaranteed to be passed valid (topology,
         //           set, policy, flags)
-        //         - target_ptr is valid as it originates from a & reference
-        //         - target_size has been checked not to be zero
+        //         - base and len are valid per input precondition
+        //         - len has been checked not to be zero
         unsafe {
             self.bind_memory_impl(
                 "hwloc_set_area_membind",
@@ -674,14 +1252,7 @@ impl Topology {
                 flags,
                 MemoryBoundObject::Area,
                 |topology, set, policy, flags| {
-                    hwlocality_sys::hwloc_set_area_membind(
-                        topology,
-                        target_ptr.cast::<c_void>(),
-                        target_size,
-                        set,
-                        policy,
-                        flags,
-                    )
+                    hwlocality_sys::hwloc_set_area_membind(topology, base, len, set, policy, flags)
                 },
             )
         }
@@ -713,33 +1284,69 @@ impl Topology {
         &self,
         target: &Target,
         flags: MemoryBindingFlags,
-    ) -> Result<(), MemoryBindingError<NodeSet>> {
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
         let target_size = std::mem::size_of_val(target);
-        if target_size == 0 {
-            return Err(MemoryBindingError::BadTarget);
-        }
         let target_ptr: *const Target = target;
+        // SAFETY: target_ptr is valid over target_size bytes as it originates
+        //         from a & reference
+        unsafe { self.unbind_memory_area_raw(target_ptr.cast::<c_void>(), target_size, flags) }
+    }
+
+    /// Reset the memory allocation policy of the memory area that starts at
+    /// `base` and spans `len` bytes to the system default
+    ///
+    /// This is the raw pointer version of [`Topology::unbind_memory_area()`],
+    /// see [`Topology::bind_memory_area_raw()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if one of flags [`MIGRATE`] and [`STRICT`] was specified,
+    ///   or if a binding target flag was specified.
+    /// - [`BadTarget`] if `len` is zero
+    /// - [`Unsupported`] if the system cannot unbind the specified memory area
+    ///
+    /// # Safety
+    ///
+    /// The memory region starting at `base` and spanning `len` bytes must be
+    /// part of this process' address space, and must remain so for the entire
+    /// duration of this method call.
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    pub unsafe fn unbind_memory_area_raw(
+        &self,
+        base: *const c_void,
+        len: usize,
+        flags: MemoryBindingFlags,
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        if len == 0 {
+            return Err(BindingError::new(
+                BindingContext::new(
+                    "hwloc_set_area_membind",
+                    MemoryBoundObject::Area,
+                    flags,
+                    None,
+                ),
+                MemoryBindingError::BadTarget,
+            ));
+        }
         // SAFETY: - Area is the correct target for this FFI
-        //         - hwloc_set_area_membind with target_ptr and target_size
-        //           arguments curried away behaves like hwloc_set_membind
+        //         - hwloc_set_area_membind with base and len arguments curried
+        //           away behaves like hwloc_set_membind
         //         - FFI is guaranteed to be passed valid (topology,
         //           set, policy, flags)
-        //         - target_ptr is valid as it originates from a & reference
-        //         - target_size has been checked not to be zero
+        //         - base and len are valid per input precondition
+        //         - len has been checked not to be zero
         unsafe {
             self.unbind_memory_impl(
                 "hwloc_set_area_membind",
                 flags,
                 MemoryBoundObject::Area,
                 |topology, set, policy, flags| {
-                    hwlocality_sys::hwloc_set_area_membind(
-                        topology,
-                        target_ptr.cast::<c_void>(),
-                        target_size,
-                        set,
-                        policy,
-                        flags,
-                    )
+                    hwlocality_sys::hwloc_set_area_membind(topology, base, len, set, policy, flags)
                 },
             )
         }
@@ -790,66 +1397,55 @@ impl Topology {
         &self,
         target: &Target,
         flags: MemoryBindingFlags,
-    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), MemoryBindingError<OwnedSet>> {
+    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<OwnedSet>>>
+    {
         let target_size = std::mem::size_of_val(target);
-        if target_size == 0 {
-            return Err(MemoryBindingError::BadTarget);
-        }
         let target_ptr: *const Target = target;
-        // SAFETY: - Area is the correct target for this FFI
-        //         - GetBinding is the correct operation for this FFI
-        //         - hwloc_get_area_membind with target_ptr and target_size
-        //           arguments curried away behaves like hwloc_get_membind
-        //         - FFI is guaranteed to be passed valid (topology,
-        //           out set, out policy, flags)
-        //         - target_ptr is valid as it originates from a & reference
-        //         - target_size has been checked not to be zero
-        unsafe {
-            self.memory_binding_impl(
-                "hwloc_get_area_membind",
-                flags,
-                MemoryBoundObject::Area,
-                MemoryBindingOperation::GetBinding,
-                |topology, set, policy, flags| {
-                    hwlocality_sys::hwloc_get_area_membind(
-                        topology,
-                        target_ptr.cast::<c_void>(),
-                        target_size,
-                        set,
-                        policy,
-                        flags,
-                    )
-                },
-            )
-        }
+        // SAFETY: target_ptr is valid over target_size bytes as it originates
+        //         from a & reference
+        unsafe { self.area_memory_binding_raw(target_ptr.cast::<c_void>(), target_size, flags) }
     }
 
-    /// Get the NUMA nodes where the memory identified by `target` is physically
-    /// allocated
+    /// Query the memory binding policy and physical locality of the memory
+    /// that backs `target`, as a [`NodeSet`]
     ///
-    /// The warning about `Target` coverage in the documentation of
-    /// [`Topology::bind_memory_area()`] also applies here.
+    /// This is the [`NodeSet`]-specific version of
+    /// [`Topology::area_memory_binding()`].
     ///
-    /// If pages spread to multiple nodes, it is not specified whether they
-    /// spread equitably, or whether most of them are on a single node, etc.
+    /// # Errors
     ///
-    /// The operating system may move memory pages from one processor to another
-    /// at any time according to their binding, so this method may return
-    /// something that is already outdated.
+    /// See [`Topology::area_memory_binding()`].
+    #[allow(clippy::type_complexity)]
+    pub fn area_memory_binding_by_nodeset<Target: ?Sized>(
+        &self,
+        target: &Target,
+        flags: MemoryBindingFlags,
+    ) -> Result<(NodeSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<NodeSet>>>
+    {
+        self.area_memory_binding(target, flags)
+    }
+
+    /// Query the memory binding policy and physical locality of the memory
+    /// area that starts at `base` and spans `len` bytes
     ///
-    /// See also [`Topology::memory_binding()`] for general semantics, except
-    /// binding target flags should not be used with this method, and it
-    /// requires [`MemoryBindingSupport::get_area_memory_location()`].
+    /// This is the raw pointer version of [`Topology::area_memory_binding()`],
+    /// see [`Topology::bind_memory_area_raw()`] for more information.
     ///
     /// # Errors
     ///
     /// - [`BadFlags`] if one of flags [`MIGRATE`] and [`NO_CPU_BINDING`] was
     ///   specified, or if a binding target flag was specified.
-    /// - [`BadTarget`] if `target` is a zero-sized object
+    /// - [`BadTarget`] if `len` is zero
     /// - [`MixedResults`] if flag [`STRICT`] was specified and memory binding
     ///   is inhomogeneous across target memory pages
     /// - [`Unsupported`] if the system cannot query the specified
-    ///   memory area's location
+    ///   memory area's binding
+    ///
+    /// # Safety
+    ///
+    /// The memory region starting at `base` and spanning `len` bytes must be
+    /// part of this process' address space, and must remain so for the entire
+    /// duration of this method call.
     ///
     /// [`BadFlags`]: MemoryBindingError::BadFlags
     /// [`BadTarget`]: MemoryBindingError::BadTarget
@@ -858,52 +1454,316 @@ impl Topology {
     /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
     /// [`STRICT`]: MemoryBindingFlags::STRICT
     /// [`Unsupported`]: MemoryBindingError::Unsupported
+    #[doc(alias = "hwloc_get_area_membind")]
+    pub unsafe fn area_memory_binding_raw<OwnedSet: OwnedSpecializedBitmap>(
+        &self,
+        base: *const c_void,
+        len: usize,
+        flags: MemoryBindingFlags,
+    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<OwnedSet>>>
+    {
+        if len == 0 {
+            return Err(BindingError::new(
+                BindingContext::new(
+                    "hwloc_get_area_membind",
+                    MemoryBoundObject::Area,
+                    flags,
+                    None,
+                ),
+                MemoryBindingError::BadTarget,
+            ));
+        }
+        // SAFETY: - Area is the correct target for this FFI
+        //         - GetBinding is the correct operation for this FFI
+        //         - hwloc_get_area_membind with base and len arguments curried
+        //           away behaves like hwloc_get_membind
+        //         - FFI is guaranteed to be passed valid (topology,
+        //           out set, out policy, flags)
+        //         - base and len are valid per input precondition
+        //         - len has been checked not to be zero
+        unsafe {
+            self.memory_binding_impl(
+                "hwloc_get_area_membind",
+                flags,
+                MemoryBoundObject::Area,
+                MemoryBindingOperation::GetBinding,
+                |topology, set, policy, flags| {
+                    hwlocality_sys::hwloc_get_area_membind(topology, base, len, set, policy, flags)
+                },
+            )
+        }
+    }
+
+    /// Get the NUMA nodes where the memory identified by `target` is physically
+    /// allocated
+    ///
+    /// The warning about `Target` coverage in the documentation of
+    /// [`Topology::bind_memory_area()`] also applies here.
+    ///
+    /// If pages spread to multiple nodes, it is not specified whether they
+    /// spread equitably, or whether most of them are on a single node, etc.
+    ///
+    /// The operating system may move memory pages from one processor to another
+    /// at any time according to their binding, so this method may return
+    /// something that is already outdated.
+    ///
+    /// Since memory pages are usually only allocated when they are first
+    /// accessed, you should make sure that `target` was written to before
+    /// calling this method, for example to check that first-touch
+    /// initialization put it on the intended NUMA nodes.
+    ///
+    /// See also [`Topology::memory_binding()`] for general semantics, except
+    /// binding target flags should not be used with this method, and it
+    /// requires [`MemoryBindingSupport::get_area_memory_location()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if one of flags [`MIGRATE`], [`NO_CPU_BINDING`] and
+    ///   [`STRICT`] was specified, or if a binding target flag was specified.
+    /// - [`BadTarget`] if `target` is a zero-sized object
+    /// - [`Unsupported`] if the system cannot query the specified
+    ///   memory area's location
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::{binding::MemoryBindingFlags, nodeset::NodeSet}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let data = vec![42u8; 4096];
+    /// match topology.area_memory_location::<[u8], NodeSet>(&data[..], MemoryBindingFlags::empty()) {
+    ///     Ok(nodes) => println!("Data lives on NUMA node(s) {nodes}"),
+    ///     Err(e) => println!("Could not locate data: {e}"),
+    /// }
+    /// ```
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
     #[doc(alias = "hwloc_get_area_memlocation")]
     pub fn area_memory_location<Target: ?Sized, OwnedSet: OwnedSpecializedBitmap>(
         &self,
         target: &Target,
         flags: MemoryBindingFlags,
-    ) -> Result<OwnedSet, MemoryBindingError<OwnedSet>> {
+    ) -> Result<OwnedSet, BindingError<MemoryBindingError<OwnedSet>>> {
         let target_size = std::mem::size_of_val(target);
-        if target_size == 0 {
-            return Err(MemoryBindingError::BadTarget);
-        }
         let target_ptr: *const Target = target;
+        // SAFETY: target_ptr is valid over target_size bytes as it originates
+        //         from a & reference
+        unsafe { self.area_memory_location_raw(target_ptr.cast::<c_void>(), target_size, flags) }
+    }
+
+    /// Get the NUMA nodes where the memory that backs `target` is physically
+    /// allocated, as a [`NodeSet`]
+    ///
+    /// This is the [`NodeSet`]-specific version of
+    /// [`Topology::area_memory_location()`], which spares you from specifying
+    /// the set type. It can report memory that lives on CPU-less NUMA nodes,
+    /// which querying by [`CpuSet`] cannot do.
+    ///
+    /// # Errors
+    ///
+    /// See [`Topology::area_memory_location()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::binding::MemoryBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let data = vec![42u8; 4096];
+    /// match topology.area_memory_location_by_nodeset(&data[..], MemoryBindingFlags::empty()) {
+    ///     Ok(nodes) => println!("Data lives on NUMA node(s) {nodes}"),
+    ///     Err(e) => println!("Could not locate data: {e}"),
+    /// }
+    /// ```
+    pub fn area_memory_location_by_nodeset<Target: ?Sized>(
+        &self,
+        target: &Target,
+        flags: MemoryBindingFlags,
+    ) -> Result<NodeSet, BindingError<MemoryBindingError<NodeSet>>> {
+        self.area_memory_location(target, flags)
+    }
+
+    /// Get the NUMA nodes where the memory area that starts at `base` and
+    /// spans `len` bytes is physically allocated
+    ///
+    /// This is the raw pointer version of [`Topology::area_memory_location()`],
+    /// see [`Topology::bind_memory_area_raw()`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if one of flags [`MIGRATE`], [`NO_CPU_BINDING`] and
+    ///   [`STRICT`] was specified, or if a binding target flag was specified.
+    /// - [`BadTarget`] if `len` is zero
+    /// - [`Unsupported`] if the system cannot query the specified
+    ///   memory area's location
+    ///
+    /// # Safety
+    ///
+    /// The memory region starting at `base` and spanning `len` bytes must be
+    /// part of this process' address space, and must remain so for the entire
+    /// duration of this method call.
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`NO_CPU_BINDING`]: MemoryBindingFlags::NO_CPU_BINDING
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    #[doc(alias = "hwloc_get_area_memlocation")]
+    pub unsafe fn area_memory_location_raw<OwnedSet: OwnedSpecializedBitmap>(
+        &self,
+        base: *const c_void,
+        len: usize,
+        flags: MemoryBindingFlags,
+    ) -> Result<OwnedSet, BindingError<MemoryBindingError<OwnedSet>>> {
+        if len == 0 {
+            return Err(BindingError::new(
+                BindingContext::new(
+                    "hwloc_get_area_memlocation",
+                    MemoryBoundObject::Area,
+                    flags,
+                    None,
+                ),
+                MemoryBindingError::BadTarget,
+            ));
+        }
         // SAFETY: - ThisProgram is the correct target for this FFI
         //         - GetLastLocation is the correct operation for this FFI
-        //         - hwloc_get_area_memlocation with target_ptr and target_size
-        //           arguments curried away and policy placeholder'd behaves
-        //           like hwloc_get_membind
+        //         - hwloc_get_area_memlocation with base and len arguments
+        //           curried away and policy placeholder'd behaves like
+        //           hwloc_get_membind
         //         - FFI is guaranteed to be passed valid (topology,
         //           out set, out policy, flags)
-        //         - target_ptr is valid as it originates from a & reference
-        //         - target_size has been checked not to be zero
+        //         - base and len are valid per input precondition
+        //         - len has been checked not to be zero
         unsafe {
             self.memory_binding_impl(
                 "hwloc_get_area_memlocation",
                 flags,
-                MemoryBoundObject::ThisProgram,
+                MemoryBoundObject::Area,
                 MemoryBindingOperation::GetLastLocation,
                 |topology, set, policy, flags| {
                     *policy = -1;
-                    hwlocality_sys::hwloc_get_area_memlocation(
-                        topology,
-                        target_ptr.cast::<c_void>(),
-                        target_size,
-                        set,
-                        flags,
-                    )
+                    hwlocality_sys::hwloc_get_area_memlocation(topology, base, len, set, flags)
                 },
             )
             .map(|(set, _policy)| set)
         }
     }
 
-    /// Adjust binding flags for a certain kind of Set
-    fn adjust_flags_for<OwnedSet: OwnedSpecializedBitmap>(flags: &mut MemoryBindingFlags) {
+    /// Get the NUMA nodes where each of the bytes pointed to by `ptrs` is
+    /// physically allocated
+    ///
+    /// This batches [`Topology::area_memory_location_raw()`] queries over many
+    /// addresses and gathers the outcome into a single
+    /// [`MemoryLocationSample`]. It is meant for profilers and runtime
+    /// monitors that periodically audit where the hot data structures of a
+    /// process ended up, e.g. by sampling one address per page or per object.
+    ///
+    /// Errors are reported for each address individually, so that a single
+    /// failed query (e.g. because a page was never touched yet and is thus not
+    /// backed by physical memory) does not discard the rest of the sample.
+    ///
+    /// # Safety
+    ///
+    /// Each pointer in `ptrs` must point to a byte that is part of this
+    /// process' address space, and must remain so for the entire duration of
+    /// this method call.
+    #[doc(alias = "hwloc_get_area_memlocation")]
+    pub unsafe fn sample_memory_locations(&self, ptrs: &[*const u8]) -> MemoryLocationSample {
+        let locations = ptrs
+            .iter()
+            .map(|&ptr| {
+                // SAFETY: ptr points to a valid byte per input precondition
+                unsafe {
+                    self.area_memory_location_raw::<NodeSet>(
+                        ptr.cast::<c_void>(),
+                        1,
+                        MemoryBindingFlags::empty(),
+                    )
+                }
+            })
+            .collect();
+        MemoryLocationSample { locations }
+    }
+
+    /// Move the memory identified by `target` to the NUMA node(s) specified by
+    /// `nodeset`
+    ///
+    /// This is a convenience wrapper around [`Topology::bind_memory_area()`]
+    /// with the [`MemoryBindingPolicy::Bind`] policy and the [`MIGRATE`] flag,
+    /// which is typically used to make long-lived buffers follow threads that
+    /// were moved to another NUMA node. `flags` may contain additional flags,
+    /// in which case the [`MIGRATE`] flag is added to them.
+    ///
+    /// If [`STRICT`] is part of `flags`, this method fails if some pages of
+    /// `target` could not be migrated. Otherwise, page migration is performed
+    /// on a best-effort basis: pages that cannot be moved are left where they
+    /// are and only the binding of future allocations within `target` is
+    /// affected, which you can check with
+    /// [`Topology::area_memory_location()`].
+    ///
+    /// The warning about `Target` coverage in the documentation of
+    /// [`Topology::bind_memory_area()`] also applies here.
+    ///
+    /// Requires [`MemoryBindingSupport::set_area()`] and
+    /// [`MemoryBindingSupport::migrate_flag()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`BadFlags`] if a binding target flag was specified
+    /// - [`BadSet`] if the system can't bind memory to that node set
+    /// - [`BadTarget`] if `target` is a zero-sized object
+    /// - [`Unsupported`] if the system cannot migrate the specified memory
+    ///   area, or, in [`STRICT`] mode, if some pages could not be migrated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::binding::MemoryBindingFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let data = vec![42u8; 1 << 20];
+    /// let nodeset = topology.nodeset();
+    /// // Best-effort migration, use MemoryBindingFlags::STRICT to fail if
+    /// // some pages could not be moved
+    /// if let Err(e) = topology.migrate_memory::<[u8]>(&data[..], &nodeset, MemoryBindingFlags::empty()) {
+    ///     println!("Could not migrate data: {e}");
+    /// }
+    /// ```
+    ///
+    /// [`BadFlags`]: MemoryBindingError::BadFlags
+    /// [`BadSet`]: MemoryBindingError::BadSet
+    /// [`BadTarget`]: MemoryBindingError::BadTarget
+    /// [`MIGRATE`]: MemoryBindingFlags::MIGRATE
+    /// [`STRICT`]: MemoryBindingFlags::STRICT
+    /// [`Unsupported`]: MemoryBindingError::Unsupported
+    pub fn migrate_memory<Target: ?Sized>(
+        &self,
+        target: &Target,
+        nodeset: &NodeSet,
+        flags: MemoryBindingFlags,
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        self.bind_memory_area(
+            target,
+            nodeset,
+            MemoryBindingPolicy::Bind,
+            flags | MemoryBindingFlags::MIGRATE,
+        )
+    }
+
+    /// Extra hwloc flag that tells which kind of Set is being passed in
+    ///
+    /// hwloc interprets bitmaps as cpusets by default, and as nodesets if the
+    /// `HWLOC_MEMBIND_BYNODESET` flag is set. This flag is not exposed as part
+    /// of [`MemoryBindingFlags`], instead it is added to the validated flags
+    /// right before calling into hwloc, as dictated by the Set type.
+    fn set_kind_flag<OwnedSet: OwnedSpecializedBitmap>() -> hwloc_membind_flags_t {
         match OwnedSet::BITMAP_KIND {
-            BitmapKind::CpuSet => flags.remove(MemoryBindingFlags::BY_NODE_SET),
-            BitmapKind::NodeSet => flags.insert(MemoryBindingFlags::BY_NODE_SET),
+            BitmapKind::CpuSet => 0,
+            BitmapKind::NodeSet => HWLOC_MEMBIND_BYNODESET,
         }
     }
 
@@ -918,9 +1778,10 @@ impl Topology {
         &self,
         api: &'static str,
         clone_set: &dyn Fn() -> Option<OwnedSet>,
+        context: &dyn Fn() -> BindingContext,
         len: usize,
         ffi: impl FnOnce(hwloc_const_topology_t, usize) -> *mut c_void,
-    ) -> Result<Bytes<'_>, MemoryBindingError<OwnedSet>> {
+    ) -> Result<Bytes<'_>, BindingError<MemoryBindingError<OwnedSet>>> {
         if len > 0 {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
             //         - hwloc ops are trusted not to modify *const parameters
@@ -935,6 +1796,7 @@ impl Topology {
                     )
                     .expect("Unexpected errno value")
                 })
+                .map_err(|e| BindingError::new(context(), e))
                 // SAFETY: If hwloc allocation successfully returns, this is
                 //         assumed to be a valid allocation pointer
                 .map(|base| unsafe { Bytes::wrap(self, base, len) })
@@ -958,7 +1820,7 @@ impl Topology {
         api: &'static str,
         set: &OwnedSet,
         policy: MemoryBindingPolicy,
-        mut flags: MemoryBindingFlags,
+        flags: MemoryBindingFlags,
         target: MemoryBoundObject,
         ffi: impl FnOnce(
             hwloc_const_topology_t,
@@ -966,11 +1828,24 @@ impl Topology {
             hwloc_membind_policy_t,
             hwloc_membind_flags_t,
         ) -> c_int,
-    ) -> Result<(), MemoryBindingError<OwnedSet>> {
+    ) -> Result<(), BindingError<MemoryBindingError<OwnedSet>>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "bind_memory",
+            api,
+            %target,
+            %policy,
+            ?flags,
+            set_weight = ?set.as_ref().weight(),
+        )
+        .entered();
+        let context = || BindingContext::new(api, target, flags, Some(set));
         let operation = MemoryBindingOperation::Bind;
-        Self::adjust_flags_for::<OwnedSet>(&mut flags);
         let Some(flags) = flags.validate(target, operation) else {
-            return Err(MemoryBindingError::BadFlags(flags.into()));
+            return Err(BindingError::new(
+                context(),
+                MemoryBindingError::BadFlags(flags.into()),
+            ));
         };
         call_hwloc_int(api, target, operation, &|| Some(set.clone()), || {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
@@ -982,9 +1857,10 @@ impl Topology {
                 self.as_ptr(),
                 set.as_ref().as_ptr(),
                 policy.into(),
-                flags.bits(),
+                flags.bits() | Self::set_kind_flag::<OwnedSet>(),
             )
         })
+        .map_err(|e| BindingError::new(context(), e))
     }
 
     /// Memory-unbinding interface for `hwloc_set_membind`-like functions
@@ -1007,10 +1883,16 @@ impl Topology {
             hwloc_membind_policy_t,
             hwloc_membind_flags_t,
         ) -> c_int,
-    ) -> Result<(), MemoryBindingError<NodeSet>> {
+    ) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("unbind_memory", api, %target, ?flags).entered();
+        let context = || BindingContext::new(api, target, flags, None);
         let operation = MemoryBindingOperation::Unbind;
         let Some(flags) = flags.validate(target, operation) else {
-            return Err(MemoryBindingError::BadFlags(flags.into()));
+            return Err(BindingError::new(
+                context(),
+                MemoryBindingError::BadFlags(flags.into()),
+            ));
         };
         call_hwloc_int::<NodeSet>(api, target, operation, &|| None, || {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
@@ -1026,6 +1908,7 @@ impl Topology {
                 flags.bits(),
             )
         })
+        .map_err(|e| BindingError::new(context(), e))
     }
 
     /// Binding for `hwloc_get_membind`-like functions
@@ -1042,7 +1925,7 @@ impl Topology {
     unsafe fn memory_binding_impl<OwnedSet: OwnedSpecializedBitmap>(
         &self,
         api: &'static str,
-        mut flags: MemoryBindingFlags,
+        flags: MemoryBindingFlags,
         target: MemoryBoundObject,
         operation: MemoryBindingOperation,
         ffi: impl FnOnce(
@@ -1051,10 +1934,17 @@ impl Topology {
             *mut hwloc_membind_policy_t,
             hwloc_membind_flags_t,
         ) -> c_int,
-    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), MemoryBindingError<OwnedSet>> {
-        Self::adjust_flags_for::<OwnedSet>(&mut flags);
+    ) -> Result<(OwnedSet, Option<MemoryBindingPolicy>), BindingError<MemoryBindingError<OwnedSet>>>
+    {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("get_memory_binding", api, %target, %operation, ?flags).entered();
+        let context = || BindingContext::new(api, target, flags, None);
         let Some(flags) = flags.validate(target, operation) else {
-            return Err(MemoryBindingError::BadFlags(flags.into()));
+            return Err(BindingError::new(
+                context(),
+                MemoryBindingError::BadFlags(flags.into()),
+            ));
         };
         let mut set = Bitmap::new();
         let mut raw_policy = hwloc_membind_policy_t::MAX;
@@ -1070,7 +1960,7 @@ impl Topology {
                 self.as_ptr(),
                 set.as_mut_ptr(),
                 &mut raw_policy,
-                flags.bits(),
+                flags.bits() | Self::set_kind_flag::<OwnedSet>(),
             )
         })
         .map(|()| {
@@ -1088,6 +1978,7 @@ impl Topology {
             }
             (set.into(), check_policy(raw_policy))
         })
+        .map_err(|e| BindingError::new(context(), e))
     }
 }
 
@@ -1172,40 +2063,28 @@ bitflags! {
         /// memory binding support.
         #[doc(alias = "HWLOC_MEMBIND_NOCPUBIND")]
         const NO_CPU_BINDING = HWLOC_MEMBIND_NOCPUBIND;
-
-        /// Consider the bitmap argument as a nodeset.
-        ///
-        /// The bitmap argument is considered a nodeset if this flag is given,
-        /// or a cpuset otherwise by default.
-        ///
-        /// Memory binding by CPU set cannot work for CPU-less NUMA memory nodes.
-        /// Binding by nodeset should therefore be preferred whenever possible.
-        //
-        // --- Implementation details ---
-        //
-        // This flag does not need to be visible as it is automatically set and
-        // cleared by the implementation as appropriate.
-        #[doc(hidden)]
-        #[doc(alias = "HWLOC_MEMBIND_BYNODESET")]
-        const BY_NODE_SET = HWLOC_MEMBIND_BYNODESET;
     }
 }
 //
 impl MemoryBindingFlags {
+    /// Binding target flags, of which exactly one must be specified when
+    /// targeting the current program or another process
+    const TARGET_FLAGS: Self = Self::PROCESS
+        .union(Self::THREAD)
+        .union(Self::ASSUME_SINGLE_THREAD);
+
     /// Truth that these flags are in a valid state
     pub(crate) fn validate(
         mut self,
         target: MemoryBoundObject,
         operation: MemoryBindingOperation,
     ) -> Option<Self> {
-        // Target flags should be specified for the Area and Process targets
-        // and only for these targets
-        let num_target_flags = (self & (Self::PROCESS | Self::THREAD | Self::ASSUME_SINGLE_THREAD))
-            .bits()
-            .count_ones();
+        // Exactly one target flag should be specified when targeting the
+        // current program or another process, and none when targeting an area
+        let num_target_flags = (self & Self::TARGET_FLAGS).bits().count_ones();
         let expected_num_target_flags = match target {
-            MemoryBoundObject::Area | MemoryBoundObject::Process(_) => 1,
-            MemoryBoundObject::ThisProgram => 0,
+            MemoryBoundObject::Area => 0,
+            MemoryBoundObject::ThisProgram | MemoryBoundObject::Process(_) => 1,
         };
         if num_target_flags != expected_num_target_flags {
             return None;
@@ -1372,6 +2251,23 @@ pub enum MemoryBindingPolicy {
     /// Requires [`MemoryBindingSupport::next_touch_policy()`].
     #[doc(alias = "HWLOC_MEMBIND_NEXTTOUCH")]
     NextTouch = HWLOC_MEMBIND_NEXTTOUCH,
+
+    /// Allocate memory on the given nodes in an interleaved weighted manner
+    ///
+    /// This works like [`Interleave`], but the share of pages that is
+    /// allocated on each NUMA node is proportional to a per-node weight, which
+    /// is configured at the operating system level (e.g. via
+    /// `/sys/kernel/mm/mempolicy/weighted_interleave/` on Linux). This is
+    /// useful on systems that mix memory with different bandwidth
+    /// capabilities, such as DDR and CXL memory, as it lets memory references
+    /// be balanced according to the bandwidth of each node.
+    ///
+    /// Requires [`MemoryBindingSupport::weighted_interleave_policy()`].
+    ///
+    /// [`Interleave`]: MemoryBindingPolicy::Interleave
+    #[cfg(feature = "hwloc-2_11_0")]
+    #[doc(alias = "HWLOC_MEMBIND_WEIGHTED_INTERLEAVE")]
+    WeightedInterleave = HWLOC_MEMBIND_WEIGHTED_INTERLEAVE,
 }
 //
 crate::impl_arbitrary_for_sequence!(MemoryBindingPolicy);
@@ -1473,6 +2369,18 @@ pub(crate) fn call_hwloc_int<OwnedSet: OwnedSpecializedBitmap>(
 /// Errors that can occur when allocating memory
 pub type MemoryAllocationError<OwnedSet> = MemoryBindingError<OwnedSet>;
 
+/// Error returned by [`Topology::allocate_memory_near_current_thread()`]
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum LocalAllocationError {
+    /// Failed to query the CPU binding of the current thread
+    #[error("failed to query the CPU binding of the current thread")]
+    CpuBinding(#[source] BindingError<HybridError<CpuBindingError>>),
+
+    /// Failed to allocate memory bound to the local NUMA nodes
+    #[error(transparent)]
+    Allocation(#[from] BindingError<MemoryAllocationError<NodeSet>>),
+}
+
 /// Translating hwloc errno into high-level errors
 fn decode_errno<OwnedSet: OwnedSpecializedBitmap>(
     object: MemoryBoundObject,
@@ -1502,6 +2410,133 @@ fn decode_errno<OwnedSet: OwnedSpecializedBitmap>(
     }
 }
 
+/// Physical location of many memory addresses, as returned by
+/// [`Topology::sample_memory_locations()`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MemoryLocationSample {
+    /// Location of each sampled address, in sampling order
+    locations: Vec<Result<NodeSet, BindingError<MemoryBindingError<NodeSet>>>>,
+}
+//
+impl MemoryLocationSample {
+    /// Location of each sampled address, in the order of the input pointers
+    pub fn locations(&self) -> &[Result<NodeSet, BindingError<MemoryBindingError<NodeSet>>>] {
+        &self.locations
+    }
+
+    /// Number of sampled addresses
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Truth that no address was sampled
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Number of addresses whose location could not be queried
+    pub fn num_failures(&self) -> usize {
+        self.locations.iter().filter(|loc| loc.is_err()).count()
+    }
+
+    /// NUMA nodes where at least one of the sampled addresses resides
+    pub fn nodeset(&self) -> NodeSet {
+        self.locations
+            .iter()
+            .filter_map(|loc| loc.as_ref().ok())
+            .fold(NodeSet::new(), |acc, nodeset| acc | nodeset)
+    }
+
+    /// Number of sampled addresses that reside on NUMA node `node_os_index`
+    ///
+    /// Addresses whose location spans several NUMA nodes are counted once for
+    /// each of these nodes.
+    pub fn count_on_node(&self, node_os_index: usize) -> usize {
+        self.locations
+            .iter()
+            .filter(|loc| {
+                loc.as_ref()
+                    .is_ok_and(|nodeset| nodeset.is_set(node_os_index))
+            })
+            .count()
+    }
+}
+
+/// Scoped memory binding, as returned by [`Topology::bind_memory_scoped()`]
+///
+/// The memory binding policy and nodeset that were in effect before
+/// [`Topology::bind_memory_scoped()`] was called are restored when this guard
+/// goes out of scope. Use [`restore()`](Self::restore) instead of dropping the
+/// guard if you want to handle restoration errors.
+///
+/// This guard cannot be sent to another thread, as restoring a thread's memory
+/// binding from another thread would affect the wrong thread.
+#[derive(Debug)]
+#[must_use = "the previous memory binding is restored when this guard is dropped"]
+pub struct MemoryBindingGuard<'topology> {
+    /// Underlying hwloc topology
+    topology: &'topology Topology,
+
+    /// Memory binding nodeset to be restored
+    previous_nodeset: NodeSet,
+
+    /// Memory binding policy to be restored
+    previous_policy: MemoryBindingPolicy,
+
+    /// Binding target flags
+    flags: MemoryBindingFlags,
+
+    /// Truth that the previous memory binding was already restored
+    restored: bool,
+
+    /// Thread binding must be restored from the bound thread
+    _not_send: PhantomData<*const ()>,
+}
+//
+impl MemoryBindingGuard<'_> {
+    /// Memory binding nodeset that will be restored
+    pub fn previous_nodeset(&self) -> &NodeSet {
+        &self.previous_nodeset
+    }
+
+    /// Memory binding policy that will be restored
+    pub fn previous_policy(&self) -> MemoryBindingPolicy {
+        self.previous_policy
+    }
+
+    /// Restore the previous memory binding now, reporting errors
+    ///
+    /// # Errors
+    ///
+    /// Any error from [`Topology::bind_memory()`].
+    pub fn restore(mut self) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        self.restore_impl()
+    }
+
+    /// Restore the previous memory binding, if not done already
+    fn restore_impl(&mut self) -> Result<(), BindingError<MemoryBindingError<NodeSet>>> {
+        if self.restored {
+            return Ok(());
+        }
+        self.restored = true;
+        self.topology
+            .bind_memory(&self.previous_nodeset, self.previous_policy, self.flags)
+    }
+}
+//
+impl Drop for MemoryBindingGuard<'_> {
+    #[allow(clippy::print_stderr)]
+    fn drop(&mut self) {
+        if let Err(e) = self.restore_impl() {
+            // Cannot panic in Drop
+            eprintln!("ERROR: Failed to restore previous memory binding ({e}).");
+        }
+    }
+}
+//
+// SAFETY: Exposes no internal mutability
+unsafe impl Sync for MemoryBindingGuard<'_> {}
+
 /// Bytes allocated through hwloc
 ///
 /// This behaves like a `Box<[MaybeUninit<u8>]>` and will similarly
@@ -1619,3 +2654,624 @@ unsafe impl Send for Bytes<'_> {}
 //
 // SAFETY: Exposes no internal mutability
 unsafe impl Sync for Bytes<'_> {}
+
+/// Single value stored in bound memory
+///
+/// This behaves like a `Box<T>` whose storage was allocated by
+/// [`Topology::box_on_node()`], and will similarly drop the inner value and
+/// liberate the allocated memory when it goes out of scope.
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// `data` points to an initialized `T` that is owned by this struct and lives
+// inside of the `_bytes` allocation (or is dangling if `T` is zero-sized).
+pub struct BoundBox<'topology, T> {
+    /// Underlying memory allocation
+    _bytes: Bytes<'topology>,
+
+    /// Inner value
+    data: NonNull<T>,
+
+    /// Tell dropck that we own a `T`
+    _owned: PhantomData<T>,
+}
+//
+impl<T> AsRef<T> for BoundBox<'_, T> {
+    fn as_ref(&self) -> &T {
+        // SAFETY: Per type invariant
+        unsafe { self.data.as_ref() }
+    }
+}
+//
+impl<T> AsMut<T> for BoundBox<'_, T> {
+    fn as_mut(&mut self) -> &mut T {
+        // SAFETY: Per type invariant
+        unsafe { self.data.as_mut() }
+    }
+}
+//
+impl<T> Borrow<T> for BoundBox<'_, T> {
+    fn borrow(&self) -> &T {
+        self.as_ref()
+    }
+}
+//
+impl<T> BorrowMut<T> for BoundBox<'_, T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
+//
+impl<T: Debug> Debug for BoundBox<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_ref(), f)
+    }
+}
+//
+impl<T> Deref for BoundBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.as_ref()
+    }
+}
+//
+impl<T> DerefMut for BoundBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.as_mut()
+    }
+}
+//
+impl<T: Display> Display for BoundBox<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_ref(), f)
+    }
+}
+//
+impl<T> Drop for BoundBox<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: - data is initialized and owned per type invariant
+        //         - data will not be usable again after Drop
+        //         - Bytes only liberates the storage after this has run
+        unsafe { ptr::drop_in_place(self.data.as_ptr()) }
+    }
+}
+//
+// SAFETY: BoundBox<T> owns a T, like Box<T>
+unsafe impl<T: Send> Send for BoundBox<'_, T> {}
+//
+// SAFETY: BoundBox<T> only hands out &T from &self, like Box<T>
+unsafe impl<T: Sync> Sync for BoundBox<'_, T> {}
+
+/// Fixed-capacity vector stored in bound memory
+///
+/// This behaves like a `Vec<T>` whose storage was allocated by
+/// [`Topology::vec_on_node()`], except it cannot grow beyond its initial
+/// capacity. It will drop the inner values and liberate the allocated memory
+/// when it goes out of scope.
+//
+// --- Implementation details ---
+//
+// # Safety
+//
+// - `data` points to storage for `capacity` elements of type `T` that lives
+//   inside of the `_bytes` allocation (or is dangling if the storage is
+//   zero-sized), is suitably aligned, and is owned by this struct.
+// - The first `len` elements of this storage are initialized, and `len` is
+//   never greater than `capacity`.
+pub struct BoundVec<'topology, T> {
+    /// Underlying memory allocation
+    _bytes: Bytes<'topology>,
+
+    /// Element storage
+    data: NonNull<T>,
+
+    /// Number of elements that `data` has room for
+    capacity: usize,
+
+    /// Number of initialized elements at the start of `data`
+    len: usize,
+
+    /// Tell dropck that we own some `T`s
+    _owned: PhantomData<T>,
+}
+//
+impl<T> BoundVec<'_, T> {
+    /// Number of elements that this vector can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Append an element to the back of the vector
+    ///
+    /// # Errors
+    ///
+    /// If the vector is already full, `value` is handed back.
+    pub fn try_push(&mut self, value: T) -> Result<(), T> {
+        if self.len == self.capacity {
+            return Err(value);
+        }
+        // SAFETY: - len < capacity so this is in bounds of the storage
+        //         - Element at index len is uninitialized so nothing is leaked
+        unsafe { self.data.as_ptr().add(self.len).write(value) };
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Append elements from an iterator until it is exhausted or the vector
+    /// is full, whichever comes first
+    ///
+    /// Elements which did not fit are left in the iterator.
+    pub fn extend_from_within_capacity(&mut self, iter: impl IntoIterator<Item = T>) {
+        let mut iter = iter.into_iter();
+        while self.len < self.capacity {
+            let Some(value) = iter.next() else {
+                break;
+            };
+            // SAFETY: Vector was checked not to be full
+            unsafe { self.data.as_ptr().add(self.len).write(value) };
+            self.len += 1;
+        }
+    }
+
+    /// Remove the last element from the vector and return it, or [`None`] if
+    /// the vector is empty
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // SAFETY: - Element at index len was initialized before decrement
+        //         - It is not considered initialized anymore, so it will not
+        //           be dropped again
+        Some(unsafe { self.data.as_ptr().add(self.len).read() })
+    }
+
+    /// Drop all elements, making the vector empty
+    pub fn clear(&mut self) {
+        let elements: *mut [T] = &mut **self;
+        // Set length first, so that a panicking Drop impl cannot cause
+        // elements to be dropped twice
+        self.len = 0;
+        // SAFETY: - Elements were initialized, and are not considered
+        //           initialized anymore, so they won't be dropped again
+        unsafe { ptr::drop_in_place(elements) }
+    }
+}
+//
+impl<T> AsRef<[T]> for BoundVec<'_, T> {
+    fn as_ref(&self) -> &[T] {
+        // SAFETY: Per type invariant
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr(), self.len) }
+    }
+}
+//
+impl<T> AsMut<[T]> for BoundVec<'_, T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        // SAFETY: Per type invariant
+        unsafe { std::slice::from_raw_parts_mut(self.data.as_ptr(), self.len) }
+    }
+}
+//
+impl<T> Borrow<[T]> for BoundVec<'_, T> {
+    fn borrow(&self) -> &[T] {
+        self.as_ref()
+    }
+}
+//
+impl<T> BorrowMut<[T]> for BoundVec<'_, T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self.as_mut()
+    }
+}
+//
+impl<T: Debug> Debug for BoundVec<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_ref(), f)
+    }
+}
+//
+impl<T> Deref for BoundVec<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_ref()
+    }
+}
+//
+impl<T> DerefMut for BoundVec<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut()
+    }
+}
+//
+impl<T> Drop for BoundVec<'_, T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+//
+// SAFETY: BoundVec<T> owns some Ts, like Vec<T>
+unsafe impl<T: Send> Send for BoundVec<'_, T> {}
+//
+// SAFETY: BoundVec<T> only hands out &[T] from &self, like Vec<T>
+unsafe impl<T: Sync> Sync for BoundVec<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        object::types::ObjectType,
+        topology::support::{CpuBindingSupport, FeatureSupport, MemoryBindingSupport},
+    };
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::{assert_impl_all, assert_not_impl_any};
+    use std::rc::Rc;
+
+    assert_impl_all!(MemoryBindingGuard<'static>: Debug, Sync);
+    assert_not_impl_any!(MemoryBindingGuard<'static>: Send);
+
+    /// Truth that the memory binding of the current thread can be set to the
+    /// `Bind` policy and queried
+    fn can_bind_thread_memory(topology: &Topology) -> bool {
+        topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::set_current_thread,
+        ) && topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::get_current_thread,
+        ) && topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::bind_policy,
+        )
+    }
+
+    /// Node set containing only the first allowed NUMA node
+    fn first_node(topology: &Topology) -> NodeSet {
+        let first = topology.allowed_nodeset().first_set().unwrap();
+        NodeSet::from_range(first..=first)
+    }
+
+    #[test]
+    fn validate_target_flags() {
+        use MemoryBindingOperation::{Allocate, Bind, GetBinding};
+        let process = MemoryBoundObject::Process(42);
+        let this = MemoryBoundObject::ThisProgram;
+        let area = MemoryBoundObject::Area;
+        let [none, thread, single, both] = [
+            MemoryBindingFlags::empty(),
+            MemoryBindingFlags::THREAD,
+            MemoryBindingFlags::ASSUME_SINGLE_THREAD,
+            MemoryBindingFlags::THREAD | MemoryBindingFlags::PROCESS,
+        ];
+
+        // Memory areas take no target flag...
+        assert_eq!(none.validate(area, Allocate), Some(none));
+        assert_eq!(none.validate(area, Bind), Some(none));
+        assert_eq!(thread.validate(area, Allocate), None);
+        assert_eq!(thread.validate(area, Bind), None);
+
+        // ...while programs and processes take exactly one
+        for target in [this, process] {
+            assert_eq!(none.validate(target, Bind), None);
+            assert_eq!(both.validate(target, Bind), None);
+            assert_eq!(single.validate(target, Bind), Some(none));
+        }
+        assert_eq!(thread.validate(this, Bind), Some(thread));
+        assert_eq!(thread.validate(this, GetBinding), Some(thread));
+
+        // ...but other processes cannot be targeted at the thread level
+        assert_eq!(thread.validate(process, Bind), None);
+    }
+
+    #[test]
+    fn scoped_binding_drop() {
+        let topology = Topology::test_instance();
+        if !can_bind_thread_memory(topology) {
+            return;
+        }
+        let flags = MemoryBindingFlags::THREAD;
+        let before = topology.memory_binding::<NodeSet>(flags).unwrap();
+        let target = first_node(topology);
+        {
+            let guard = topology
+                .bind_memory_scoped(&target, MemoryBindingPolicy::Bind, flags)
+                .unwrap();
+            assert_eq!(guard.previous_nodeset(), &before.0);
+            assert_eq!(Some(guard.previous_policy()), before.1);
+            assert_eq!(
+                topology.memory_binding::<NodeSet>(flags).unwrap(),
+                (target, Some(MemoryBindingPolicy::Bind))
+            );
+        }
+        assert_eq!(topology.memory_binding::<NodeSet>(flags).unwrap(), before);
+    }
+
+    #[test]
+    fn scoped_binding_restore() {
+        let topology = Topology::test_instance();
+        if !can_bind_thread_memory(topology) {
+            return;
+        }
+        let flags = MemoryBindingFlags::THREAD;
+        let before = topology.memory_binding::<NodeSet>(flags).unwrap();
+        let target = first_node(topology);
+        let guard = topology
+            .bind_memory_scoped(&target, MemoryBindingPolicy::Bind, flags)
+            .unwrap();
+        assert_eq!(
+            topology.memory_binding::<NodeSet>(flags).unwrap(),
+            (target, Some(MemoryBindingPolicy::Bind))
+        );
+        guard.restore().unwrap();
+        assert_eq!(topology.memory_binding::<NodeSet>(flags).unwrap(), before);
+    }
+
+    #[test]
+    fn bind_memory_verified() {
+        let topology = Topology::test_instance();
+        if !can_bind_thread_memory(topology) {
+            return;
+        }
+        let flags = MemoryBindingFlags::THREAD;
+        let (previous_nodeset, previous_policy) =
+            topology.memory_binding::<NodeSet>(flags).unwrap();
+        let target = first_node(topology);
+        let achieved = topology
+            .bind_memory_verified(&target, MemoryBindingPolicy::Bind, flags)
+            .unwrap();
+        assert_eq!(achieved, topology.memory_binding::<NodeSet>(flags).unwrap());
+        assert!(achieved.0.includes(&target));
+        topology
+            .bind_memory(&previous_nodeset, previous_policy.unwrap(), flags)
+            .unwrap();
+    }
+
+    #[test]
+    fn queries_by_nodeset() {
+        let topology = Topology::test_instance();
+        let supports = |feature: fn(&MemoryBindingSupport) -> bool| {
+            topology.supports(FeatureSupport::memory_binding, feature)
+        };
+
+        // Current thread binding
+        if can_bind_thread_memory(topology) {
+            let flags = MemoryBindingFlags::THREAD;
+            let target = first_node(topology);
+            let guard = topology
+                .bind_memory_scoped(&target, MemoryBindingPolicy::Bind, flags)
+                .unwrap();
+            assert_eq!(
+                topology.memory_binding_by_nodeset(flags).unwrap(),
+                (target, Some(MemoryBindingPolicy::Bind))
+            );
+            guard.restore().unwrap();
+            assert_eq!(
+                topology.memory_binding_by_nodeset(flags),
+                topology.memory_binding::<NodeSet>(flags)
+            );
+        }
+
+        // Process binding
+        if supports(MemoryBindingSupport::get_process) {
+            let pid = std::process::id();
+            let flags = MemoryBindingFlags::PROCESS;
+            assert_eq!(
+                topology.process_memory_binding_by_nodeset(pid, flags),
+                topology.process_memory_binding::<NodeSet>(pid, flags)
+            );
+        }
+
+        // Memory area binding and location
+        let data = vec![42u8; 4096];
+        let flags = MemoryBindingFlags::empty();
+        if supports(MemoryBindingSupport::get_area) {
+            assert_eq!(
+                topology.area_memory_binding_by_nodeset(&data[..], flags),
+                topology.area_memory_binding::<_, NodeSet>(&data[..], flags)
+            );
+        }
+        if supports(MemoryBindingSupport::get_area_memory_location) {
+            let location = topology
+                .area_memory_location_by_nodeset(&data[..], flags)
+                .unwrap();
+            assert!(topology.complete_nodeset().includes(&location));
+        }
+
+        // Invalid flags are rejected like in the generic versions
+        let invalid = MemoryBindingFlags::THREAD | MemoryBindingFlags::PROCESS;
+        assert_eq!(
+            topology
+                .memory_binding_by_nodeset(invalid)
+                .unwrap_err()
+                .cause(),
+            &MemoryBindingError::BadFlags(invalid.into())
+        );
+    }
+
+    /// Truth that memory can be allocated with the `Bind` policy
+    fn can_allocate_bound(topology: &Topology) -> bool {
+        topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::allocate_bound,
+        ) && topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::bind_policy,
+        )
+    }
+
+    /// Check that some memory lives on the expected NUMA nodes, if supported
+    fn check_location<Target: ?Sized>(topology: &Topology, target: &Target, nodeset: &NodeSet) {
+        if !topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::get_area_memory_location,
+        ) {
+            return;
+        }
+        let location = topology
+            .area_memory_location::<Target, NodeSet>(target, MemoryBindingFlags::empty())
+            .unwrap();
+        assert!(nodeset.includes(&location));
+    }
+
+    #[test]
+    fn box_on_node() {
+        let topology = Topology::test_instance();
+        if !can_allocate_bound(topology) {
+            return;
+        }
+        let nodeset = first_node(topology);
+
+        let mut data = topology.box_on_node([0u64; 512], &nodeset).unwrap();
+        assert!(data.iter().all(|&x| x == 0));
+        data[42] = 24;
+        assert_eq!(data[42], 24);
+        assert_eq!(data.iter().sum::<u64>(), 24);
+        check_location(topology, &*data, &nodeset);
+
+        // Boxed values should be dropped along with the box
+        let counter = Rc::new(());
+        let boxed = topology.box_on_node(Rc::clone(&counter), &nodeset).unwrap();
+        assert_eq!(Rc::strong_count(&counter), 2);
+        assert!(Rc::ptr_eq(&boxed, &counter));
+        drop(boxed);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn vec_on_node() {
+        let topology = Topology::test_instance();
+        if !can_allocate_bound(topology) {
+            return;
+        }
+        let nodeset = first_node(topology);
+
+        let mut data = topology.vec_on_node::<u32>(1024, &nodeset).unwrap();
+        assert_eq!(data.capacity(), 1024);
+        assert!(data.is_empty());
+        data.extend_from_within_capacity(0..2000);
+        assert_eq!(data.len(), 1024);
+        assert!(data.iter().copied().eq(0..1024));
+        assert_eq!(data.try_push(1024), Err(1024));
+        assert_eq!(data.pop(), Some(1023));
+        assert_eq!(data.try_push(4242), Ok(()));
+        assert_eq!(data[1023], 4242);
+        check_location(topology, &*data, &nodeset);
+        data.clear();
+        assert!(data.is_empty());
+        assert_eq!(data.pop(), None);
+
+        // Elements should be dropped on clear() and along with the vector
+        let counter = Rc::new(());
+        let mut counters = topology.vec_on_node::<Rc<()>>(4, &nodeset).unwrap();
+        counters.extend_from_within_capacity(std::iter::repeat(Rc::clone(&counter)).take(3));
+        assert_eq!(Rc::strong_count(&counter), 4);
+        counters.clear();
+        assert_eq!(Rc::strong_count(&counter), 1);
+        counters.extend_from_within_capacity(std::iter::repeat(Rc::clone(&counter)).take(4));
+        assert_eq!(Rc::strong_count(&counter), 5);
+        drop(counters);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn allocate_memory_near_current_thread() {
+        let topology = Topology::test_instance();
+
+        // Binding target flags are rejected
+        let flags = MemoryBindingFlags::THREAD;
+        assert_eq!(
+            topology
+                .allocate_memory_near_current_thread(4096, flags)
+                .unwrap_err(),
+            LocalAllocationError::Allocation(BindingError::new(
+                BindingContext::new(
+                    "hwloc_alloc_membind",
+                    MemoryBoundObject::Area,
+                    flags,
+                    Some(&topology.nodeset()),
+                ),
+                MemoryBindingError::BadFlags(flags.into())
+            ))
+        );
+
+        if !can_allocate_bound(topology)
+            || !topology.supports(
+                FeatureSupport::cpu_binding,
+                CpuBindingSupport::get_current_thread,
+            )
+        {
+            return;
+        }
+        let len = 64 * 1024;
+        let mut bytes = topology
+            .allocate_memory_near_current_thread(len, MemoryBindingFlags::empty())
+            .unwrap();
+        assert_eq!(bytes.len(), len);
+        bytes.fill(MaybeUninit::new(42));
+        // SAFETY: All bytes were initialized above
+        assert!(bytes.iter().all(|byte| unsafe { byte.assume_init() } == 42));
+
+        let cpuset = topology.cpu_binding(CpuBindingFlags::THREAD).unwrap();
+        let nodeset = NodeSet::from_cpuset(topology, &cpuset);
+        check_location(topology, &bytes[..], &nodeset);
+    }
+
+    #[test]
+    fn empty_memory_location_sample() {
+        let topology = Topology::test_instance();
+        // SAFETY: There is no pointer to check
+        let sample = unsafe { topology.sample_memory_locations(&[]) };
+        assert!(sample.is_empty());
+        assert_eq!(sample.len(), 0);
+        assert_eq!(sample.num_failures(), 0);
+        assert_eq!(sample.nodeset(), NodeSet::new());
+    }
+
+    #[test]
+    fn memory_location_sample() {
+        let topology = Topology::test_instance();
+        if !topology.supports(
+            FeatureSupport::memory_binding,
+            MemoryBindingSupport::get_area_memory_location,
+        ) {
+            return;
+        }
+
+        // Touch some memory, then sample one address every 4 KiB
+        let data = vec![1u8; 64 * 1024];
+        let ptrs = (0..data.len())
+            .step_by(4096)
+            .map(|offset| data.as_ptr().wrapping_add(offset))
+            .collect::<Vec<_>>();
+        // SAFETY: All pointers point into data, which outlives the sampling
+        let sample = unsafe { topology.sample_memory_locations(&ptrs) };
+        assert!(!sample.is_empty());
+        assert_eq!(sample.len(), ptrs.len());
+        assert_eq!(sample.locations().len(), ptrs.len());
+
+        // Failure count and aggregated locations should match the details
+        let successes = sample
+            .locations()
+            .iter()
+            .filter_map(|location| location.as_ref().ok())
+            .collect::<Vec<_>>();
+        assert_eq!(sample.num_failures(), sample.len() - successes.len());
+        let nodeset = successes
+            .iter()
+            .fold(NodeSet::new(), |acc, &nodeset| acc | nodeset);
+        assert_eq!(sample.nodeset(), nodeset);
+        assert!(topology.nodeset().includes(&nodeset));
+        for node in topology.objects_with_type(ObjectType::NUMANode) {
+            let os_index = node.os_index().unwrap();
+            let expected = successes
+                .iter()
+                .filter(|nodeset| nodeset.is_set(os_index))
+                .count();
+            assert_eq!(sample.count_on_node(os_index), expected);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Memory-side caches
+//!
+//! Some systems put a cache in front of their main memory, which hwloc
+//! exposes as [`MemCache`] objects sitting between NUMA nodes and their normal
+//! parent. For example, on systems where high-bandwidth memory is configured
+//! as a cache for DRAM, the HBM appears as a [`MemCache`] above the DRAM NUMA
+//! nodes instead of having its own NUMA nodes.
+//!
+//! This module provides helpers to find the memory-side caches in front of a
+//! NUMA node and their size. Most of its functionality is exposed via methods
+//! of [`Topology`] and [`TopologyObject`].
+//!
+//! [`MemCache`]: ObjectType::MemCache
+
+use crate::{
+    object::{attributes::ObjectAttributes, types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{iter::FusedIterator, num::NonZeroU64};
+
+/// # Memory-side caches
+impl Topology {
+    /// Memory-side caches of this topology
+    ///
+    /// This is a shorthand for `objects_with_type(ObjectType::MemCache)`.
+    pub fn memory_side_caches(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + ExactSizeIterator + FusedIterator
+    {
+        self.objects_with_type(ObjectType::MemCache)
+    }
+}
+
+/// # Memory-side caches
+impl TopologyObject {
+    /// Memory-side caches in front of this NUMA node, from the closest to the
+    /// farthest from memory
+    ///
+    /// Nothing is returned if this object is not a NUMA node, or if no
+    /// memory-side cache was detected in front of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for node in topology.objects_with_type(ObjectType::NUMANode) {
+    ///     for cache in node.memory_side_caches() {
+    ///         println!("{node} is behind {cache}");
+    ///     }
+    /// }
+    /// ```
+    pub fn memory_side_caches(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        let is_numa_node = self.object_type() == ObjectType::NUMANode;
+        self.ancestors().take_while(move |ancestor| {
+            is_numa_node && ancestor.object_type() == ObjectType::MemCache
+        })
+    }
+
+    /// Memory-side cache that is closest to this NUMA node, if any
+    ///
+    /// This is the first item of
+    /// [`memory_side_caches()`](Self::memory_side_caches()).
+    pub fn memory_side_cache(&self) -> Option<&Self> {
+        self.memory_side_caches().next()
+    }
+
+    /// Total size of the memory-side caches in front of this NUMA node, in
+    /// bytes
+    ///
+    /// Returns `None` if this object is not a NUMA node, if there is no
+    /// memory-side cache in front of it, or if the size of these caches is
+    /// unknown.
+    ///
+    /// Beware that a memory-side cache may be shared by several NUMA nodes,
+    /// which you can check by looking at its nodeset.
+    pub fn memory_side_cache_size(&self) -> Option<NonZeroU64> {
+        self.memory_side_caches()
+            .map(|cache| {
+                let Some(ObjectAttributes::Cache(attributes)) = cache.attributes() else {
+                    unreachable!("Memory-side caches should have cache attributes")
+                };
+                attributes.size()
+            })
+            .reduce(|acc, size| {
+                let (acc, size) = (acc?, size?);
+                acc.checked_add(size.get())
+            })
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn memory_side_caches() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            let caches = obj.memory_side_caches().collect::<Vec<_>>();
+            if obj.object_type() != ObjectType::NUMANode {
+                assert!(caches.is_empty());
+                assert!(obj.memory_side_cache().is_none());
+                assert_eq!(obj.memory_side_cache_size(), None);
+                continue;
+            }
+            let nodeset = obj.nodeset().unwrap();
+            for cache in &caches {
+                assert_eq!(cache.object_type(), ObjectType::MemCache);
+                assert!(cache.nodeset().unwrap().includes(nodeset));
+                assert!(topology
+                    .memory_side_caches()
+                    .any(|other| std::ptr::eq(other, *cache)));
+            }
+            match (obj.memory_side_cache(), caches.first()) {
+                (Some(cache), Some(first)) => assert!(std::ptr::eq(cache, *first)),
+                (None, None) => {}
+                _ => panic!("memory_side_cache() should be the first memory_side_caches()"),
+            }
+            if caches.is_empty() {
+                assert_eq!(obj.memory_side_cache_size(), None);
+            }
+        }
+    }
+}
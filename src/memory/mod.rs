@@ -7,7 +7,10 @@
 #[cfg(feature = "hwloc-2_3_0")]
 pub mod attribute;
 pub mod binding;
+#[cfg(feature = "hwloc-2_1_0")]
+pub mod cache;
 pub mod nodeset;
+pub mod tier;
 
 #[cfg(doc)]
 use crate::topology::Topology;
@@ -0,0 +1,200 @@
+//! Memory tiers
+//!
+//! On heterogeneous memory systems, NUMA nodes may be backed by different
+//! kinds of memory (DRAM, HBM, CXL-attached memory, non-volatile memory...)
+//! with very different performance characteristics.
+//!
+//! This module provides a convenience API for classifying the NUMA nodes of a
+//! topology into such memory tiers, so that you do not need to interpret
+//! hwloc's NUMA node subtypes and memory attributes by yourself. Most of its
+//! functionality is exposed via [`Topology::memory_tiers()`].
+
+#[cfg(feature = "hwloc-2_3_0")]
+use crate::memory::attribute::MemoryAttribute;
+use crate::{
+    memory::nodeset::NodeSet,
+    object::{types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::collections::BTreeMap;
+
+/// Kind of memory that backs a NUMA node
+///
+/// Tiers are ordered from the most common kind of memory to the most exotic
+/// ones, which is the order in which [`Topology::memory_tiers()`] lists them.
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum MemoryTier {
+    /// Normal DRAM
+    #[display(fmt = "DRAM")]
+    Dram,
+
+    /// High-bandwidth memory (HBM, MCDRAM...)
+    #[display(fmt = "HBM")]
+    Hbm,
+
+    /// Volatile memory attached through CXL
+    #[display(fmt = "CXL")]
+    Cxl,
+
+    /// Non-volatile memory, possibly attached through CXL
+    #[display(fmt = "NVM")]
+    Nvm,
+
+    /// Specific-purpose memory, usually hidden from the operating system's
+    /// default allocator
+    #[display(fmt = "SPM")]
+    Spm,
+
+    /// Memory of a GPU that is exposed as a NUMA node
+    #[display(fmt = "GPU memory")]
+    GpuMemory,
+}
+//
+impl MemoryTier {
+    /// Memory tier associated with a NUMA node subtype string
+    ///
+    /// See [`TopologyObject::subtype()`] for more information about subtypes.
+    /// Returns `None` if the subtype does not designate a known memory tier.
+    pub fn from_subtype(subtype: &str) -> Option<Self> {
+        match subtype {
+            "DRAM" => Some(Self::Dram),
+            "HBM" | "MCDRAM" => Some(Self::Hbm),
+            "CXL-DRAM" => Some(Self::Cxl),
+            "NVM" | "CXL-NVM" => Some(Self::Nvm),
+            "SPM" => Some(Self::Spm),
+            "GPUMemory" => Some(Self::GpuMemory),
+            _ => None,
+        }
+    }
+}
+
+/// # Memory tiers
+impl Topology {
+    /// Classify NUMA nodes into memory tiers
+    ///
+    /// The result lists the set of NUMA nodes that belong to each memory tier
+    /// which is present in the topology. Tiers without any NUMA node are not
+    /// listed.
+    ///
+    /// NUMA nodes are primarily classified according to their
+    /// [`subtype()`](TopologyObject::subtype), which hwloc sets on platforms
+    /// where the kind of memory is known. NUMA nodes that have no recognized
+    /// subtype are considered to be DRAM, except when memory attributes
+    /// indicate that their bandwidth is at least twice that of the slowest
+    /// such node with local CPUs, in which case they are considered to be HBM.
+    /// Bandwidths are measured from the CPUs that are local to each node, or
+    /// for CPU-less nodes, from the CPUs of the closest ancestor that has some.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::tier::MemoryTier, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for (tier, nodeset) in topology.memory_tiers() {
+    ///     println!("{tier} NUMA nodes: {nodeset}");
+    /// }
+    /// ```
+    pub fn memory_tiers(&self) -> BTreeMap<MemoryTier, NodeSet> {
+        let mut tiers = BTreeMap::<MemoryTier, NodeSet>::new();
+        let mut unlabeled = Vec::new();
+        for node in self.objects_with_type(ObjectType::NUMANode) {
+            let tier = node
+                .subtype()
+                .and_then(|subtype| subtype.to_str().ok())
+                .and_then(MemoryTier::from_subtype);
+            if let Some(tier) = tier {
+                add_node(&mut tiers, tier, node);
+            } else {
+                unlabeled.push(node);
+            }
+        }
+        for (node, tier) in unlabeled.iter().zip(self.guess_memory_tiers(&unlabeled)) {
+            add_node(&mut tiers, tier, node);
+        }
+        tiers
+    }
+
+    /// Guess the memory tier of NUMA nodes that have no recognized subtype
+    /// using memory attributes
+    #[cfg(feature = "hwloc-2_3_0")]
+    fn guess_memory_tiers(&self, nodes: &[&TopologyObject]) -> Vec<MemoryTier> {
+        let attribute = MemoryAttribute::bandwidth(self);
+        let bandwidths = nodes
+            .iter()
+            .map(|node| {
+                // Query bandwidth from the node's local CPUs, as querying it
+                // from the whole machine would average away locality
+                let initiator = std::iter::once(*node)
+                    .chain(node.ancestors())
+                    .filter_map(TopologyObject::cpuset)
+                    .find(|cpuset| !cpuset.is_empty())?;
+                attribute.value(Some(initiator), node).ok()
+            })
+            .collect::<Vec<_>>();
+        let reference = nodes
+            .iter()
+            .zip(&bandwidths)
+            .filter(|(node, _)| node.cpuset().is_some_and(|cpuset| !cpuset.is_empty()))
+            .filter_map(|(_, bandwidth)| *bandwidth)
+            .min();
+        bandwidths
+            .into_iter()
+            .map(|bandwidth| match (bandwidth, reference) {
+                (Some(bandwidth), Some(reference)) if bandwidth >= reference.saturating_mul(2) => {
+                    MemoryTier::Hbm
+                }
+                _ => MemoryTier::Dram,
+            })
+            .collect()
+    }
+
+    /// Guess the memory tier of NUMA nodes that have no recognized subtype
+    #[cfg(not(feature = "hwloc-2_3_0"))]
+    fn guess_memory_tiers(&self, nodes: &[&TopologyObject]) -> Vec<MemoryTier> {
+        vec![MemoryTier::Dram; nodes.len()]
+    }
+}
+
+/// Record that a NUMA node belongs to a certain memory tier
+fn add_node(tiers: &mut BTreeMap<MemoryTier, NodeSet>, tier: MemoryTier, node: &TopologyObject) {
+    let os_index = node.os_index().expect("NUMA nodes should have an OS index");
+    tiers.entry(tier).or_default().set(os_index);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn from_subtype() {
+        assert_eq!(MemoryTier::from_subtype("DRAM"), Some(MemoryTier::Dram));
+        assert_eq!(MemoryTier::from_subtype("MCDRAM"), Some(MemoryTier::Hbm));
+        assert_eq!(MemoryTier::from_subtype("CXL-DRAM"), Some(MemoryTier::Cxl));
+        assert_eq!(MemoryTier::from_subtype("CXL-NVM"), Some(MemoryTier::Nvm));
+        assert_eq!(
+            MemoryTier::from_subtype("GPUMemory"),
+            Some(MemoryTier::GpuMemory)
+        );
+        assert_eq!(MemoryTier::from_subtype("Unknown"), None);
+    }
+
+    #[test]
+    fn memory_tiers() {
+        let topology = Topology::test_instance();
+        let tiers = topology.memory_tiers();
+        let all_nodes = tiers.values().fold(NodeSet::new(), |mut acc, nodeset| {
+            assert!(!nodeset.is_empty());
+            assert!(!acc.intersects(nodeset));
+            acc |= nodeset;
+            acc
+        });
+        assert_eq!(all_nodes, *topology.nodeset());
+    }
+}
@@ -37,9 +37,15 @@ impl GroupAttributes {
         int::expect_usize(self.0.depth)
     }
 
-    /// Internally-used kind of group
-    #[allow(unused)]
-    pub(crate) fn kind(&self) -> usize {
+    /// Kind of group
+    ///
+    /// This is mostly used internally by hwloc to tell apart groups that were
+    /// created by different discovery mechanisms. When two groups have the
+    /// same locality and may be merged, hwloc keeps the one with the lowest
+    /// kind, so a high kind means that a group is easily discarded.
+    #[doc(alias = "hwloc_group_attr_s::kind")]
+    #[doc(alias = "hwloc_obj_attr_u::hwloc_group_attr_s::kind")]
+    pub fn kind(&self) -> usize {
         int::expect_usize(self.0.kind)
     }
 
@@ -51,15 +57,24 @@ impl GroupAttributes {
         self.0.dont_merge = 0;
     }
 
-    /// Internally-used subkind to distinguish different levels of groups with
-    /// the same kind
-    #[allow(unused)]
+    /// Subkind used to distinguish different levels of groups with the same
+    /// [`kind()`](Self::kind())
+    ///
+    /// Groups of the same kind with a lower subkind are placed above those
+    /// with a higher subkind in the topology tree.
     #[doc(alias = "hwloc_group_attr_s::subkind")]
     #[doc(alias = "hwloc_obj_attr_u::hwloc_group_attr_s::subkind")]
-    pub(crate) fn subkind(&self) -> usize {
+    pub fn subkind(&self) -> usize {
         int::expect_usize(self.0.subkind)
     }
 
+    /// Set the kind and subkind of a group object that is about to be inserted
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub(crate) fn set_kind(&mut self, kind: c_uint, subkind: c_uint) {
+        self.0.kind = kind;
+        self.0.subkind = subkind;
+    }
+
     /// Flag preventing groups from being automatically merged with identical
     /// parent or children
     #[cfg(feature = "hwloc-2_0_4")]
@@ -283,6 +298,12 @@ pub(super) mod tests {
             expected.0.kind = buf.0.kind;
             prop_assert_eq!(buf, expected);
             prop_assert!(!buf.merging_prevented());
+
+            buf.set_kind(42, 24);
+            expected.0.kind = 42;
+            expected.0.subkind = 24;
+            prop_assert_eq!(buf, expected);
+            prop_assert_eq!((buf.kind(), buf.subkind()), (42, 24));
         }
 
         Ok(())
@@ -73,6 +73,35 @@ impl<'object> NUMANodeAttributes<'object> {
             )
         }
     }
+
+    /// Memory page type with a page size of `page_size` bytes, if any
+    ///
+    /// This can be used by hugepage-aware allocators to check how much memory
+    /// of a given page size is available on this NUMA node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::{attributes::ObjectAttributes, types::ObjectType}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// const HUGE_PAGE_SIZE: u64 = 2 * 1024 * 1024;
+    /// for node in topology.objects_with_type(ObjectType::NUMANode) {
+    ///     let Some(ObjectAttributes::NUMANode(attr)) = node.attributes() else {
+    ///         unreachable!("NUMA nodes should have NUMA node attributes")
+    ///     };
+    ///     let huge_memory = attr
+    ///         .page_type(HUGE_PAGE_SIZE)
+    ///         .map_or(0, |page_type| page_type.total_size());
+    ///     println!("{node} has {huge_memory} bytes of 2MB pages");
+    /// }
+    /// ```
+    pub fn page_type(&self, page_size: u64) -> Option<MemoryPageType> {
+        let page_types = self.page_types();
+        page_types
+            .binary_search_by_key(&page_size, |page_type| page_type.0.size)
+            .ok()
+            .map(|idx| page_types[idx])
+    }
 }
 //
 impl Debug for NUMANodeAttributes<'_> {
@@ -144,6 +173,14 @@ impl MemoryPageType {
     pub fn count(&self) -> u64 {
         self.0.count
     }
+
+    /// Total amount of memory in pages of this size, in bytes
+    ///
+    /// This is [`size()`](Self::size()) times [`count()`](Self::count()),
+    /// saturating to `u64::MAX` in the unlikely event of overflow.
+    pub fn total_size(&self) -> u64 {
+        self.size().get().saturating_mul(self.count())
+    }
 }
 //
 #[cfg(any(test, feature = "proptest"))]
@@ -268,6 +305,35 @@ pub(super) mod tests {
         }
     }
 
+    #[test]
+    fn total_size() {
+        let page_type = |size, count| MemoryPageType(hwloc_memory_page_type_s { size, count });
+        assert_eq!(page_type(4096, 3).total_size(), 3 * 4096);
+        assert_eq!(page_type(2, u64::MAX).total_size(), u64::MAX);
+    }
+
+    proptest! {
+        /// Check page type lookup on a sorted page type list, like hwloc's
+        #[test]
+        fn page_type(mut page_types: Vec<MemoryPageType>, page_size: u64) {
+            page_types.retain(|page_type| page_type.0.size != 0);
+            page_types.sort_by_key(|page_type| page_type.0.size);
+            page_types.dedup_by_key(|page_type| page_type.0.size);
+            let numa_attr = make_numa_attributes(0, &page_types);
+
+            for page_type in &page_types {
+                prop_assert_eq!(numa_attr.page_type(page_type.size().get()), Some(*page_type));
+            }
+            prop_assert_eq!(
+                numa_attr.page_type(page_size),
+                page_types
+                    .iter()
+                    .find(|page_type| page_type.0.size == page_size)
+                    .copied()
+            );
+        }
+    }
+
     /// Pick a pair of NUMA nodes in the test topology if possible
     fn numa_pair() -> impl Strategy<Value = Option<[&'static TopologyObject; 2]>> {
         let numa_nodes = &ObjectsWithAttrs::instance().numa_nodes;
@@ -297,6 +363,7 @@ pub(super) mod tests {
                 prop_assert!(page_size > prev_page_size);
             }
             prev_page_size = Some(page_size);
+            prop_assert_eq!(attr.page_type(page_size.get()), Some(*page_type));
 
             prop_assert_eq!(
                 format!("{page_type:?}"),
@@ -372,8 +439,10 @@ pub(super) mod tests {
         #[allow(clippy::option_if_let_else)]
         if let Some(size) = NonZeroU64::new(size) {
             prop_assert_eq!(page_type.size(), size);
+            prop_assert_eq!(page_type.total_size(), size.get().saturating_mul(count));
         } else {
             assert_panics(|| page_type.size())?;
+            assert_panics(|| page_type.total_size())?;
             prop_assert_eq!(
                 format!("{page_type:?}"),
                 format!(
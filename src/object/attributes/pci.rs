@@ -11,7 +11,7 @@ use proptest::prelude::*;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 
 /// PCI domain width (depends on hwloc version)
 #[cfg(feature = "hwloc-3_0_0")]
@@ -104,11 +104,100 @@ impl PCIDeviceAttributes {
     }
 
     /// Link speed in GB/s
+    ///
+    /// This is the theoretical bandwidth of the PCI link, accounting for the
+    /// link encoding overhead, or 0 if hwloc could not determine it.
     #[doc(alias = "hwloc_pcidev_attr_s::linkspeed")]
     #[doc(alias = "hwloc_obj_attr_u::hwloc_pcidev_attr_s::linkspeed")]
     pub fn link_speed(&self) -> f32 {
         self.0.linkspeed
     }
+
+    /// PCI Express link configurations that match [`link_speed()`]
+    ///
+    /// hwloc only reports the total link speed, not the PCI Express
+    /// generation and lane count that it was computed from. This function
+    /// lists all the standard link configurations which would result in the
+    /// reported link speed, from the lowest to the highest generation.
+    ///
+    /// Several configurations may match a single link speed (e.g. a Gen4 x1
+    /// link has the same speed as a Gen3 x2 link), and no configuration is
+    /// returned if the link speed is unknown or non-standard.
+    ///
+    /// [`link_speed()`]: Self::link_speed()
+    pub fn link_configs(&self) -> impl Iterator<Item = PCILinkConfig> {
+        let link_speed = self.link_speed();
+        PCILinkConfig::all().filter(move |config| {
+            config.link_speed().is_some_and(|config_speed| {
+                (config_speed - link_speed).abs() <= config_speed * 1e-3
+            })
+        })
+    }
+}
+
+/// PCI Express link generation and width
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PCILinkConfig {
+    /// PCI Express generation (e.g. 3 for PCI Express 3.0)
+    pub generation: u8,
+
+    /// Number of lanes in the link (e.g. 16 for a x16 link)
+    pub lanes: u8,
+}
+//
+impl PCILinkConfig {
+    /// Highest PCI Express generation that is known to this crate
+    pub const MAX_GENERATION: u8 = 6;
+
+    /// Standard PCI Express link widths
+    pub const LANE_COUNTS: [u8; 7] = [1, 2, 4, 8, 12, 16, 32];
+
+    /// All standard link configurations, from the lowest to the highest
+    /// generation and lane count
+    fn all() -> impl Iterator<Item = Self> {
+        (1..=Self::MAX_GENERATION).flat_map(|generation| {
+            Self::LANE_COUNTS
+                .into_iter()
+                .map(move |lanes| Self { generation, lanes })
+        })
+    }
+
+    /// Bandwidth of a single lane of this generation in GB/s
+    ///
+    /// This accounts for the `8b/10b` encoding overhead of generations 1 and 2,
+    /// and the `128b/130b` encoding overhead of later generations.
+    ///
+    /// Returns `None` if [`generation`](Self::generation) is not a PCI Express
+    /// generation that is known to this crate, i.e. not in range
+    /// `1..=MAX_GENERATION`.
+    pub fn lane_speed(&self) -> Option<f32> {
+        // Same computation as hwloc's PCI discovery code
+        let transfer_rate = match self.generation {
+            1 | 2 => 2.5 * f32::from(self.generation) * 0.8,
+            3..=Self::MAX_GENERATION => {
+                let speedup = 1u8.checked_shl(u32::from(self.generation - 3))?;
+                8.0 * f32::from(speedup) * 128.0 / 130.0
+            }
+            _ => return None,
+        };
+        Some(transfer_rate / 8.0)
+    }
+
+    /// Bandwidth of the link in GB/s, as reported by
+    /// [`PCIDeviceAttributes::link_speed()`]
+    ///
+    /// Returns `None` if the [`generation`](Self::generation) is unknown, see
+    /// [`lane_speed()`](Self::lane_speed()).
+    pub fn link_speed(&self) -> Option<f32> {
+        self.lane_speed()
+            .map(|lane_speed| lane_speed * f32::from(self.lanes))
+    }
+}
+//
+impl Display for PCILinkConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PCIe Gen{} x{}", self.generation, self.lanes)
+    }
 }
 //
 #[cfg(any(test, feature = "proptest"))]
@@ -205,6 +294,13 @@ pub(super) mod tests {
         Octal, PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write,
         io::Write
     );
+    assert_impl_all!(PCILinkConfig:
+        Copy, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_not_impl_any!(PCILinkConfig:
+        Binary, Default, Deref, Drop, IntoIterator, LowerExp, LowerHex, Octal,
+        PartialOrd, Pointer, Read, UpperExp, UpperHex, fmt::Write, io::Write
+    );
     assert_impl_all!(PCIDomain:
         Add, AddAssign, Binary, BitAnd, BitAndAssign, BitOr, BitOrAssign,
         BitXor, BitXorAssign, Copy, Debug, Default, Display, Div, DivAssign,
@@ -235,6 +331,34 @@ pub(super) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn link_configs() {
+        let mut attr = PCIDeviceAttributes::default();
+        assert_eq!(attr.link_configs().count(), 0);
+        for config in PCILinkConfig::all() {
+            attr.0.linkspeed = config.link_speed().unwrap();
+            assert!(attr.link_configs().any(|candidate| candidate == config));
+        }
+        let gen3_x16 = PCILinkConfig {
+            generation: 3,
+            lanes: 16,
+        };
+        assert!((gen3_x16.link_speed().unwrap() - 15.753_846).abs() < 1e-4);
+        assert_eq!(gen3_x16.to_string(), "PCIe Gen3 x16");
+    }
+
+    #[test]
+    fn unknown_generations() {
+        for generation in [0, PCILinkConfig::MAX_GENERATION + 1, 11, u8::MAX] {
+            let config = PCILinkConfig {
+                generation,
+                lanes: 16,
+            };
+            assert_eq!(config.lane_speed(), None);
+            assert_eq!(config.link_speed(), None);
+        }
+    }
+
     proptest! {
         #[test]
         fn unary_pci(pcidev_attr: PCIDeviceAttributes) {
@@ -24,9 +24,10 @@
 
 // Main docs: https://hwloc.readthedocs.io/en/v2.9/group__hwlocality__levels.html
 
-use crate::ffi::int::PositiveInt;
+use crate::{ffi::int::PositiveInt, object::types::ObjectType};
 #[cfg(doc)]
-use crate::object::{types::ObjectType, TopologyObject};
+use crate::{object::TopologyObject, topology::Topology};
+use derive_more::Display;
 #[cfg(feature = "hwloc-2_1_0")]
 use hwlocality_sys::HWLOC_TYPE_DEPTH_MEMCACHE;
 use hwlocality_sys::{
@@ -49,6 +50,7 @@ pub type NormalDepth = PositiveInt;
 ///
 /// See the [module-level documentation](self) for context.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[doc(alias = "hwloc_get_type_depth_e")]
 pub enum Depth {
     /// Depth of a normal object (not Memory, I/O or Misc)
@@ -110,8 +112,21 @@ impl Depth {
     /// List of I/O object virtual depths
     pub const IO_DEPTHS: &'static [Self] = &[Self::Bridge, Self::PCIDevice, Self::OSDevice];
 
-    /// Decode depth results from hwloc
-    pub(crate) fn from_raw(value: hwloc_get_type_depth_e) -> Result<Self, TypeToDepthError> {
+    /// Decode a raw depth value, as used by the hwloc C API
+    ///
+    /// This is mostly useful when interacting with hwloc APIs that are not
+    /// exposed by hwlocality, via the `hwlocality-sys` crate.
+    ///
+    /// # Errors
+    ///
+    /// - [`Nonexistent`] for `HWLOC_TYPE_DEPTH_UNKNOWN`
+    /// - [`Multiple`] for `HWLOC_TYPE_DEPTH_MULTIPLE`
+    /// - [`Unexpected`] for negative values that are not a known virtual depth
+    ///
+    /// [`Multiple`]: TypeToDepthError::Multiple
+    /// [`Nonexistent`]: TypeToDepthError::Nonexistent
+    /// [`Unexpected`]: TypeToDepthError::Unexpected
+    pub fn from_raw(value: hwloc_get_type_depth_e) -> Result<Self, TypeToDepthError> {
         match value {
             normal if normal >= 0 => {
                 let normal = NormalDepth::try_from_c_int(normal)
@@ -131,8 +146,8 @@ impl Depth {
         }
     }
 
-    /// Convert back to the hwloc depth format
-    pub(crate) fn to_raw(self) -> hwloc_get_type_depth_e {
+    /// Convert back to the raw depth format of the hwloc C API
+    pub fn to_raw(self) -> hwloc_get_type_depth_e {
         match self {
             Self::Normal(value) => value.to_c_int(),
             Self::NUMANode => HWLOC_TYPE_DEPTH_NUMANODE,
@@ -270,6 +285,48 @@ pub enum TypeToDepthError {
     Unexpected(c_int),
 }
 
+/// Object type and number of objects at one depth of the topology
+///
+/// This is returned by [`Topology::level_summary()`].
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[display(fmt = "depth {depth}: {count} {object_type}")]
+pub struct LevelSummary {
+    /// Depth of this level
+    depth: Depth,
+
+    /// Type of objects at this depth
+    object_type: ObjectType,
+
+    /// Number of objects at this depth
+    count: usize,
+}
+//
+impl LevelSummary {
+    /// Set up a level summary
+    pub(crate) fn new(depth: Depth, object_type: ObjectType, count: usize) -> Self {
+        Self {
+            depth,
+            object_type,
+            count,
+        }
+    }
+
+    /// Depth of this level
+    pub fn depth(&self) -> Depth {
+        self.depth
+    }
+
+    /// Type of objects at this depth
+    pub fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    /// Number of objects at this depth
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::assert_panics;
@@ -302,6 +359,9 @@ mod tests {
         io::Write
     );
     assert_type_eq_all!(NormalDepth, PositiveInt);
+    assert_impl_all!(LevelSummary:
+        Copy, Debug, Display, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
     assert_impl_all!(TypeToDepthError:
         Copy, Error, Hash, Sized, Sync, Unpin, UnwindSafe
     );
@@ -334,7 +394,8 @@ mod tests {
         ];
         assert_eq!(RAW_DEPTHS.len(), Depth::VIRTUAL_DEPTHS.len());
         for (&raw, &depth) in RAW_DEPTHS.iter().zip(Depth::VIRTUAL_DEPTHS) {
-            assert_eq!(Depth::from_raw(raw), Ok(depth))
+            assert_eq!(Depth::from_raw(raw), Ok(depth));
+            assert_eq!(depth.to_raw(), raw);
         }
     }
 
@@ -363,6 +424,26 @@ mod tests {
             prop_assert_eq!(Depth::from(normal), normal);
             prop_assert_eq!(normal, Depth::from(normal));
         }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde_roundtrip(depth: Depth) {
+            let json = serde_json::to_string(&depth).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Depth>(&json).unwrap(), depth);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_out_of_range() {
+        let max = u64::from(NormalDepth::MAX.to_c_uint());
+        let json = format!("{{\"Normal\":{max}}}");
+        assert_eq!(
+            serde_json::from_str::<Depth>(&json).unwrap(),
+            NormalDepth::MAX
+        );
+        let json = format!("{{\"Normal\":{}}}", max + 1);
+        serde_json::from_str::<Depth>(&json).unwrap_err();
     }
 
     /// Generate [`usize`] values that are mostly in [`NormalDepth`] range to
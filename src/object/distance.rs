@@ -21,16 +21,15 @@ use crate::object::depth::NormalDepth;
 #[cfg(feature = "hwloc-2_3_0")]
 use crate::topology::editor::TopologyEditor;
 use crate::{
-    errors::{self, ForeignObjectError, RawHwlocError},
+    cpu::cpuset::CpuSet,
+    errors::{self, ForeignObjectError, HybridError, RawHwlocError},
     ffi::{self, int, transparent::TransparentNewtype},
+    memory::nodeset::NodeSet,
     object::{depth::Depth, types::ObjectType, TopologyObject},
     topology::Topology,
 };
 #[cfg(feature = "hwloc-2_1_0")]
-use crate::{
-    errors::{HybridError, NulError},
-    ffi::string::LibcString,
-};
+use crate::{errors::NulError, ffi::string::LibcString};
 use bitflags::bitflags;
 #[cfg(all(feature = "hwloc-2_5_0", any(test, feature = "proptest")))]
 use enum_iterator::Sequence;
@@ -52,6 +51,7 @@ use hwlocality_sys::{
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
+    collections::HashMap,
     ffi::{c_int, c_uint, c_ulong},
     fmt::{self, Debug},
     iter::FusedIterator,
@@ -219,6 +219,111 @@ impl Topology {
         }
     }
 
+    /// NUMA nodes sorted by increasing distance from `obj`
+    ///
+    /// NUMA nodes are first sorted according to the NUMA latency matrix, if
+    /// the topology has one: the distance between `obj` and a NUMA node is
+    /// taken to be the smallest latency from one of the NUMA nodes that are
+    /// local to `obj` to that NUMA node. This gives a much more accurate notion
+    /// of proximity than the topology tree on multi-socket machines, where
+    /// some remote sockets are closer than others.
+    ///
+    /// NUMA nodes which are not covered by the latency matrix, or all NUMA
+    /// nodes if there is no such matrix, are ordered after the others based on
+    /// their position in the topology tree, from the NUMA nodes whose first
+    /// common ancestor with `obj` is deepest to those whose first common
+    /// ancestor with `obj` is closest to the root.
+    ///
+    /// If `obj` does not have a NUMA node set (e.g. I/O objects), the node set
+    /// of its first ancestor that has one is used instead.
+    ///
+    /// # Errors
+    ///
+    /// - [`ForeignObjectError`] if `obj` does not belong to this topology
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+    /// let nodes = topology.closest_numa_nodes_by_distance(pu)?;
+    /// assert_eq!(nodes.len(), topology.objects_with_type(ObjectType::NUMANode).count());
+    /// println!("NUMA nodes by increasing distance from {pu}:");
+    /// for node in nodes {
+    ///     println!("- {node}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn closest_numa_nodes_by_distance<'self_>(
+        &'self_ self,
+        obj: &TopologyObject,
+    ) -> Result<Vec<&'self_ TopologyObject>, HybridError<ForeignObjectError>> {
+        if !self.contains(obj) {
+            return Err(HybridError::Rust(obj.into()));
+        }
+        let (local_obj, local_nodeset) = std::iter::once(obj)
+            .chain(obj.ancestors())
+            .find_map(|candidate| Some((candidate, candidate.nodeset()?)))
+            .expect("The root object should have a nodeset");
+        let nodes = self
+            .objects_with_type(ObjectType::NUMANode)
+            .collect::<Vec<_>>();
+
+        // Compute latencies from the nodes that are local to obj, if known
+        let latency_matrix = self
+            .distances_with_type(DistancesKind::MEANS_LATENCY, ObjectType::NUMANode)
+            .map_err(HybridError::Hwloc)?
+            .into_iter()
+            .next();
+        let latencies = latency_matrix.map_or_else(
+            || vec![None; nodes.len()],
+            |matrix| {
+                let node_indices = nodes
+                    .iter()
+                    .map(|node| matrix.object_idx(node))
+                    .collect::<Vec<_>>();
+                let local_indices = nodes
+                    .iter()
+                    .zip(&node_indices)
+                    .filter_map(|(node, idx)| {
+                        let node_nodeset = node.nodeset()?;
+                        local_nodeset.intersects(node_nodeset).then_some((*idx)?)
+                    })
+                    .collect::<Vec<_>>();
+                node_indices
+                    .iter()
+                    .map(|node_idx| {
+                        let node_idx = (*node_idx)?;
+                        local_indices
+                            .iter()
+                            .filter_map(|&local_idx| matrix.get((local_idx, node_idx)))
+                            .min()
+                    })
+                    .collect()
+            },
+        );
+
+        // Sort nodes by latency, falling back to tree distance
+        let mut nodes = nodes.into_iter().zip(latencies).collect::<Vec<_>>();
+        nodes.sort_by_cached_key(|(node, latency)| {
+            let is_local = node
+                .nodeset()
+                .is_some_and(|node_nodeset| local_nodeset.intersects(node_nodeset));
+            let common_ancestor_depth = local_obj
+                .first_common_ancestor(node)
+                .map_or(0, |ancestor| ancestor.ancestors().count());
+            (
+                latency.is_none(),
+                *latency,
+                !is_local,
+                std::cmp::Reverse(common_ancestor_depth),
+                node.logical_index(),
+            )
+        });
+        Ok(nodes.into_iter().map(|(node, _latency)| node).collect())
+    }
+
     /// Call one of the `hwloc_distances_get(_by)?` APIs
     ///
     /// # Safety
@@ -332,6 +437,194 @@ impl Topology {
     }
 }
 
+/// # Distance-based clustering
+impl Topology {
+    /// Group NUMA nodes into clusters of nodes that are close to each other
+    ///
+    /// Two NUMA nodes are considered close if a latency distance matrix
+    /// ([`DistancesKind::MEANS_LATENCY`]) reports a latency lower than or
+    /// equal to `max_distance` between them, in both directions. Clusters are
+    /// then built by transitivity: if node A is close to node B, and node B is
+    /// close to node C, then A, B and C end up in the same cluster.
+    ///
+    /// This is useful on multi-die systems, where the topology tree does not
+    /// always reflect which NUMA nodes actually form a low-latency domain.
+    ///
+    /// The result contains one [`NodeSet`] per cluster, and every NUMA node of
+    /// the topology belongs to exactly one cluster. NUMA nodes for which no
+    /// latency information is available form single-node clusters. Clusters
+    /// are ordered by logical index of their first NUMA node.
+    ///
+    /// Beware that latency units are not specified by hwloc, so a good
+    /// `max_distance` threshold must be picked with knowledge of the source of
+    /// the distance matrix. For ACPI SLIT-derived matrices, which are the most
+    /// common on Linux, the local node distance is normalized to 10.
+    ///
+    /// # Errors
+    ///
+    /// Errors from [`Topology::distances_with_type()`] are forwarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let clusters = topology.cluster_numa_nodes(12)?;
+    /// for (idx, nodeset) in clusters.iter().enumerate() {
+    ///     println!("Latency domain #{idx}: NUMA nodes {nodeset}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn cluster_numa_nodes(&self, max_distance: u64) -> Result<Vec<NodeSet>, RawHwlocError> {
+        // Union-find forest over NUMA node logical indices
+        let num_nodes = self.objects_with_type(ObjectType::NUMANode).count();
+        let mut parents = (0..num_nodes).collect::<Vec<_>>();
+        fn root(parents: &mut [usize], mut idx: usize) -> usize {
+            while parents[idx] != idx {
+                parents[idx] = parents[parents[idx]];
+                idx = parents[idx];
+            }
+            idx
+        }
+
+        // Merge the clusters of NUMA nodes that are close to each other
+        for distances in
+            self.distances_with_type(DistancesKind::MEANS_LATENCY, ObjectType::NUMANode)?
+        {
+            let nodes = distances
+                .objects()
+                .enumerate()
+                .filter_map(|(matrix_idx, obj)| Some((matrix_idx, obj?.logical_index())))
+                .collect::<Vec<_>>();
+            for (pos, &(sender_idx, sender_node)) in nodes.iter().enumerate() {
+                for &(receiver_idx, receiver_node) in &nodes[pos + 1..] {
+                    let there = distances[(sender_idx, receiver_idx)];
+                    let back = distances[(receiver_idx, sender_idx)];
+                    if there.max(back) <= max_distance {
+                        let sender_root = root(&mut parents, sender_node);
+                        let receiver_root = root(&mut parents, receiver_node);
+                        parents[receiver_root.max(sender_root)] = receiver_root.min(sender_root);
+                    }
+                }
+            }
+        }
+
+        // Collect clusters, ordered by logical index of their first node
+        let mut clusters = Vec::<NodeSet>::new();
+        let mut root_to_cluster = HashMap::new();
+        for node in self.objects_with_type(ObjectType::NUMANode) {
+            let node_root = root(&mut parents, node.logical_index());
+            let cluster_idx = *root_to_cluster.entry(node_root).or_insert_with(|| {
+                clusters.push(NodeSet::new());
+                clusters.len() - 1
+            });
+            let os_index = node.os_index().expect("NUMA nodes should have an OS index");
+            clusters[cluster_idx].set(os_index);
+        }
+        Ok(clusters)
+    }
+}
+
+/// # Distances between CPU sets
+impl Topology {
+    /// Aggregate NUMA node distances into a matrix of distances between
+    /// CPU sets
+    ///
+    /// This is useful to estimate the communication cost between groups of
+    /// CPUs that are not single topology objects, such as the CPUs of worker
+    /// pools or candidate placements of a distributed job.
+    ///
+    /// The NUMA nodes that are local to each CPU set are computed as in
+    /// [`NodeSet::from_cpuset()`]. Then the distance from CPU set `i` to CPU
+    /// set `j`, which is stored in `matrix[i][j]`, is the average of the NUMA
+    /// node distances from the nodes that are local to `i` to the nodes that
+    /// are local to `j`. Distances are taken from the first NUMA node distance
+    /// matrix that matches `kind`, which should usually contain
+    /// [`DistancesKind::MEANS_LATENCY`] or [`DistancesKind::MEANS_BANDWIDTH`].
+    ///
+    /// A distance is `None` if one of the two CPU sets has no local NUMA node
+    /// in the distance matrix, or if no NUMA node distance matrix matches
+    /// `kind`.
+    ///
+    /// # Errors
+    ///
+    /// Errors from [`Topology::distances_with_type()`] are forwarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::{distance::DistancesKind, types::ObjectType}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// // Consider one worker pool per package
+    /// let pools = topology
+    ///     .objects_with_type(ObjectType::Package)
+    ///     .filter_map(|package| package.cpuset())
+    ///     .map(|cpuset| cpuset.clone_target())
+    ///     .collect::<Vec<_>>();
+    /// let latencies = topology.cpuset_distance_matrix(DistancesKind::MEANS_LATENCY, &pools)?;
+    /// assert_eq!(latencies.len(), pools.len());
+    /// for (sender, row) in latencies.iter().enumerate() {
+    ///     for (receiver, latency) in row.iter().enumerate() {
+    ///         if let Some(latency) = latency {
+    ///             println!("Latency from pool {sender} to pool {receiver}: {latency}");
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn cpuset_distance_matrix<'set>(
+        &self,
+        kind: DistancesKind,
+        cpusets: impl IntoIterator<Item = &'set CpuSet>,
+    ) -> Result<Vec<Vec<Option<u64>>>, RawHwlocError> {
+        let nodesets = cpusets
+            .into_iter()
+            .map(|cpuset| NodeSet::from_cpuset(self, cpuset))
+            .collect::<Vec<_>>();
+        let mut matrix = vec![vec![None; nodesets.len()]; nodesets.len()];
+        let Some(distances) = self
+            .distances_with_type(kind, ObjectType::NUMANode)?
+            .into_iter()
+            .next()
+        else {
+            return Ok(matrix);
+        };
+
+        // Find the distance matrix indices of the nodes local to each CPU set
+        let local_indices = nodesets
+            .iter()
+            .map(|nodeset| {
+                distances
+                    .objects()
+                    .enumerate()
+                    .filter_map(|(matrix_idx, obj)| {
+                        let os_index = obj?.os_index()?;
+                        nodeset.is_set(os_index).then_some(matrix_idx)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Average node distances between each pair of CPU sets
+        for (sender_indices, row) in local_indices.iter().zip(&mut matrix) {
+            for (receiver_indices, distance) in local_indices.iter().zip(row) {
+                let mut sum = 0u128;
+                let mut count = 0u128;
+                for &sender_idx in sender_indices {
+                    for &receiver_idx in receiver_indices {
+                        sum += u128::from(distances[(sender_idx, receiver_idx)]);
+                        count += 1;
+                    }
+                }
+                *distance = sum.checked_div(count).map(|average| {
+                    u64::try_from(average).expect("Average of u64s should fit in u64")
+                });
+            }
+        }
+        Ok(matrix)
+    }
+}
+
 /// # Add distances between objects
 //
 // --- Implementation details ---
@@ -370,6 +663,44 @@ impl TopologyEditor<'_> {
     ///   belong to this topology
     /// - [`NameContainsNul`] if the provided `name` contains NUL chars
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     object::{
+    /// #         distance::{AddDistancesFlags, DistancesKind},
+    /// #         types::ObjectType,
+    /// #     },
+    /// #     topology::export::xml::XMLExportFlags,
+    /// #     Topology,
+    /// # };
+    /// # let mut topology = Topology::test_instance().clone();
+    /// // Attach a (made-up) measured latency matrix between all PUs
+    /// topology.edit(|editor| {
+    ///     editor.add_distances(
+    ///         Some("MeasuredPULatency"),
+    ///         DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+    ///         AddDistancesFlags::empty(),
+    ///         |topology| {
+    ///             let pus = topology
+    ///                 .objects_with_type(ObjectType::PU)
+    ///                 .map(Some)
+    ///                 .collect::<Vec<_>>();
+    ///             let num_pus = pus.len();
+    ///             let latencies = (0..num_pus.pow(2))
+    ///                 .map(|idx| if idx / num_pus == idx % num_pus { 10 } else { 20 })
+    ///                 .collect();
+    ///             (pus, latencies)
+    ///         },
+    ///     )
+    /// })?;
+    ///
+    /// // The new distance matrix is now part of the topology's XML export
+    /// let xml = topology.export_xml(XMLExportFlags::empty())?;
+    /// assert!(xml.as_str().contains("MeasuredPULatency"));
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
     /// [`InconsistentData`]: AddDistancesError::InconsistentData
     /// [`BadKind`]: AddDistancesError::BadKind
     /// [`BadObjectsCount`]: AddDistancesError::BadObjectsCount
@@ -607,6 +938,29 @@ impl TopologyEditor<'_> {
     ///
     /// The distances matrix to be removed can be selected using the
     /// `find_distances` callback.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let mut topology = Topology::test_instance().clone();
+    /// // Remove the hardware-provided NUMA latency matrix, if any
+    /// let name = "NUMALatency";
+    /// if !topology.distances_with_name(name)?.is_empty() {
+    ///     topology.edit(|editor| {
+    ///         editor.remove_distances(|topology| {
+    ///             topology
+    ///                 .distances_with_name(name)
+    ///                 .expect("Name is valid and query succeeded before")
+    ///                 .into_iter()
+    ///                 .next()
+    ///                 .expect("Distance matrix was found before")
+    ///         })
+    ///     })?;
+    /// }
+    /// assert!(topology.distances_with_name(name)?.is_empty());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
     #[allow(clippy::missing_errors_doc)]
     #[doc(alias = "hwloc_distances_release_remove")]
     pub fn remove_distances(
@@ -670,7 +1024,7 @@ impl TopologyEditor<'_> {
     ///
     /// `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`].
     ///
-    /// [`remove_all_distances()`]: [`TopologyEditor::remove_all_distances()`]
+    /// [`remove_all_distances()`]: TopologyEditor::remove_all_distances()
     #[allow(clippy::missing_errors_doc)]
     #[doc(alias = "hwloc_distances_remove_by_depth")]
     pub fn remove_distances_at_depth<DepthLike>(
@@ -711,7 +1065,7 @@ impl TopologyEditor<'_> {
     /// Identical to [`remove_all_distances()`], but only applies to one level
     /// of the topology.
     ///
-    /// [`remove_all_distances()`]: [`TopologyEditor::remove_all_distances()`]
+    /// [`remove_all_distances()`]: TopologyEditor::remove_all_distances()
     #[allow(clippy::missing_errors_doc)]
     #[doc(alias = "hwloc_distances_remove_by_type")]
     pub fn remove_distances_with_type(&mut self, ty: ObjectType) -> Result<(), RawHwlocError> {
@@ -1132,6 +1486,66 @@ impl<'topology> Distances<'topology> {
         unsafe { std::slice::from_raw_parts_mut(self.inner_mut().values, self.num_distances()) }
     }
 
+    /// Copy the distances into a sender-major matrix
+    ///
+    /// `matrix[sender][receiver]` is the distance from the object at index
+    /// `sender` to the object at index `receiver` in [`Distances::objects()`].
+    /// This is handy when handing over distances to numerical code that
+    /// expects a nested matrix layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::distance::DistancesKind, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for distances in topology.distances(DistancesKind::empty())? {
+    ///     let matrix = distances.to_matrix();
+    ///     assert_eq!(matrix.len(), distances.num_objects());
+    ///     for (sender, row) in matrix.iter().enumerate() {
+    ///         for (receiver, &distance) in row.iter().enumerate() {
+    ///             assert_eq!(distance, distances[(sender, receiver)]);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn to_matrix(&self) -> Vec<Vec<u64>> {
+        let num_objects = self.num_objects();
+        if num_objects == 0 {
+            return Vec::new();
+        }
+        self.distances()
+            .chunks_exact(num_objects)
+            .map(<[u64]>::to_vec)
+            .collect()
+    }
+
+    /// Copy the distances into a sender-major [`ndarray::Array2`]
+    ///
+    /// `array[[sender, receiver]]` is the distance from the object at index
+    /// `sender` to the object at index `receiver` in [`Distances::objects()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::distance::DistancesKind, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for distances in topology.distances(DistancesKind::empty())? {
+    ///     let array = distances.to_array2();
+    ///     assert_eq!(array.dim(), (distances.num_objects(), distances.num_objects()));
+    ///     for ((sender, receiver), &distance) in array.indexed_iter() {
+    ///         assert_eq!(distance, distances[(sender, receiver)]);
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn to_array2(&self) -> ndarray::Array2<u64> {
+        let num_objects = self.num_objects();
+        ndarray::Array2::from_shape_vec((num_objects, num_objects), self.distances().to_vec())
+            .expect("Distances should hold num_objects^2 values")
+    }
+
     /// Iteration over ((sender index, receiver index), distance) tuples
     ///
     /// See also [`Distances::distances()`].
@@ -1240,6 +1654,65 @@ impl<'topology> Distances<'topology> {
         }
     }
 
+    /// Distances between two objects, in both directions
+    ///
+    /// This is a convenience shorthand for [`object_pair_distance()`] that
+    /// takes the two objects as separate arguments. It returns the distance
+    /// from `obj_a` to `obj_b`, followed by the distance from `obj_b` to
+    /// `obj_a`, or `None` if one of the objects is not covered by this
+    /// distance matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::{distance::DistancesKind, types::ObjectType}, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let first = topology.objects_with_type(ObjectType::NUMANode).next().unwrap();
+    /// let last = topology.objects_with_type(ObjectType::NUMANode).last().unwrap();
+    /// for distances in topology.distances_with_type(DistancesKind::empty(), ObjectType::NUMANode)? {
+    ///     if let Some((there, back)) = distances.distance_between(first, last) {
+    ///         println!("{first} -> {last}: {there}, {last} -> {first}: {back}");
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`object_pair_distance()`]: Distances::object_pair_distance()
+    pub fn distance_between(
+        &self,
+        obj_a: &TopologyObject,
+        obj_b: &TopologyObject,
+    ) -> Option<(u64, u64)> {
+        self.object_pair_distance((obj_a, obj_b))
+    }
+
+    /// Distance from the object at index `sender` to the object at index
+    /// `receiver`, if these indices are in bounds
+    ///
+    /// This is a non-panicking alternative to indexing via
+    /// `distances[(sender, receiver)]`.
+    pub fn get(&self, (sender, receiver): (usize, usize)) -> Option<u64> {
+        let idx = self.linear_idx((sender, receiver))?;
+        // SAFETY: index validity is checked by linear_idx
+        Some(unsafe { *self.distances().get_unchecked(idx) })
+    }
+
+    /// Mutable access to the distance from the object at index `sender` to
+    /// the object at index `receiver`, if these indices are in bounds
+    ///
+    /// See also [`Distances::get()`].
+    pub fn get_mut(&mut self, (sender, receiver): (usize, usize)) -> Option<&mut u64> {
+        let idx = self.linear_idx((sender, receiver))?;
+        // SAFETY: index validity is checked by linear_idx
+        Some(unsafe { self.distances_mut().get_unchecked_mut(idx) })
+    }
+
+    /// Distance matrix indexing, returns `None` on out-of-bounds indices
+    fn linear_idx(&self, (sender, receiver): (usize, usize)) -> Option<usize> {
+        let num_objects = self.num_objects();
+        (sender < num_objects && receiver < num_objects).then(|| sender * num_objects + receiver)
+    }
+
     /// Checked distance matrix indexing
     fn checked_idx(&self, (sender, receiver): (usize, usize)) -> usize {
         assert!(sender < self.num_objects(), "Invalid sender index");
@@ -1512,3 +1985,270 @@ crate::impl_arbitrary_for_sequence!(DistancesTransform);
 #[derive(Copy, Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
 #[error("can't empty a distance matrix using DistancesTransform::RemoveNone")]
 pub struct TransformError;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::synthetic::SyntheticBuilder;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    /// 4 packages with one NUMA node, core and PU each
+    fn four_nodes() -> Topology {
+        SyntheticBuilder::new()
+            .packages(4)
+            .numa_nodes(1)
+            .cores(1)
+            .pus(1)
+            .build()
+            .unwrap()
+    }
+
+    /// [`four_nodes()`] with a latency matrix between NUMA nodes
+    ///
+    /// Nodes 0 and 1 are close to each other, node 2 is a bit further away,
+    /// and node 3 is far from everyone. Latencies between nodes 2 and 3 are
+    /// asymmetrical.
+    #[cfg(feature = "hwloc-2_5_0")]
+    fn four_nodes_with_latencies() -> Topology {
+        let mut topology = four_nodes();
+        topology
+            .edit(|editor| {
+                editor.add_distances(
+                    Some("TestLatency"),
+                    DistancesKind::FROM_USER | DistancesKind::MEANS_LATENCY,
+                    AddDistancesFlags::empty(),
+                    |topology| {
+                        let nodes = topology
+                            .objects_with_type(ObjectType::NUMANode)
+                            .map(Some)
+                            .collect();
+                        #[rustfmt::skip]
+                        let latencies = vec![
+                            10, 12, 20, 30,
+                            12, 10, 20, 30,
+                            20, 20, 10, 15,
+                            30, 30, 25, 10,
+                        ];
+                        (nodes, latencies)
+                    },
+                )
+            })
+            .unwrap();
+        topology
+    }
+
+    /// Turn lists of NUMA node OS indices into node sets
+    fn nodesets<const N: usize>(clusters: [&[usize]; N]) -> Vec<NodeSet> {
+        clusters
+            .iter()
+            .map(|nodes| {
+                let mut nodeset = NodeSet::new();
+                for &node in *nodes {
+                    nodeset.set(node);
+                }
+                nodeset
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cluster_without_distances() {
+        let clusters = four_nodes().cluster_numa_nodes(u64::MAX).unwrap();
+        assert_eq!(clusters, nodesets([&[0], &[1], &[2], &[3]]));
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn cluster_with_latencies() {
+        let topology = four_nodes_with_latencies();
+        let clusters = |max_distance| topology.cluster_numa_nodes(max_distance).unwrap();
+        assert_eq!(clusters(10), nodesets([&[0], &[1], &[2], &[3]]));
+        assert_eq!(clusters(12), nodesets([&[0, 1], &[2], &[3]]));
+        assert_eq!(clusters(15), nodesets([&[0, 1], &[2], &[3]]));
+        assert_eq!(clusters(20), nodesets([&[0, 1, 2], &[3]]));
+        assert_eq!(clusters(25), nodesets([&[0, 1, 2, 3]]));
+        assert_eq!(clusters(u64::MAX), nodesets([&[0, 1, 2, 3]]));
+    }
+
+    /// CPU sets of worker pools covering packages 0, 0-1, 2, 3 and nothing
+    fn pools(topology: &Topology) -> Vec<CpuSet> {
+        let packages = topology
+            .objects_with_type(ObjectType::Package)
+            .map(|package| package.cpuset().unwrap().clone_target())
+            .collect::<Vec<_>>();
+        vec![
+            packages[0].clone(),
+            &packages[0] | &packages[1],
+            packages[2].clone(),
+            packages[3].clone(),
+            CpuSet::new(),
+        ]
+    }
+
+    #[test]
+    fn cpuset_distances_without_matrix() {
+        let topology = four_nodes();
+        let matrix = topology
+            .cpuset_distance_matrix(DistancesKind::MEANS_LATENCY, &pools(&topology))
+            .unwrap();
+        assert_eq!(matrix, vec![vec![None; 5]; 5]);
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn cpuset_distances_with_latencies() {
+        let topology = four_nodes_with_latencies();
+        let pools = pools(&topology);
+        let latencies = topology
+            .cpuset_distance_matrix(DistancesKind::MEANS_LATENCY, &pools)
+            .unwrap();
+        assert_eq!(
+            latencies,
+            vec![
+                vec![Some(10), Some(11), Some(20), Some(30), None],
+                vec![Some(11), Some(11), Some(20), Some(30), None],
+                vec![Some(20), Some(20), Some(10), Some(15), None],
+                vec![Some(30), Some(30), Some(25), Some(10), None],
+                vec![None; 5],
+            ]
+        );
+
+        // There is no bandwidth matrix
+        let bandwidths = topology
+            .cpuset_distance_matrix(DistancesKind::MEANS_BANDWIDTH, &pools)
+            .unwrap();
+        assert_eq!(bandwidths, vec![vec![None; 5]; 5]);
+    }
+
+    /// Latency matrix injected by [`four_nodes_with_latencies()`]
+    #[cfg(feature = "hwloc-2_5_0")]
+    fn injected_latencies(topology: &Topology) -> Distances<'_> {
+        let mut distances = topology.distances_with_name("TestLatency").unwrap();
+        assert_eq!(distances.len(), 1);
+        distances.remove(0)
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn to_matrix() {
+        let topology = four_nodes_with_latencies();
+        let distances = injected_latencies(&topology);
+        assert_eq!(
+            distances.to_matrix(),
+            vec![
+                vec![10, 12, 20, 30],
+                vec![12, 10, 20, 30],
+                vec![20, 20, 10, 15],
+                vec![30, 30, 25, 10],
+            ]
+        );
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn indexed_access() {
+        let topology = four_nodes_with_latencies();
+        let mut distances = injected_latencies(&topology);
+        let matrix = distances.to_matrix();
+        for sender in 0..=matrix.len() {
+            for receiver in 0..=matrix.len() {
+                let expected = matrix
+                    .get(sender)
+                    .and_then(|row| row.get(receiver))
+                    .copied();
+                assert_eq!(distances.get((sender, receiver)), expected);
+                assert_eq!(distances.get_mut((sender, receiver)).copied(), expected);
+            }
+        }
+        *distances.get_mut((2, 3)).unwrap() = 42;
+        assert_eq!(distances.get((2, 3)), Some(42));
+        assert_eq!(distances[(2, 3)], 42);
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn distance_between() {
+        let topology = four_nodes_with_latencies();
+        let distances = injected_latencies(&topology);
+        let matrix = distances.to_matrix();
+        for node_a in topology.objects_with_type(ObjectType::NUMANode) {
+            for node_b in topology.objects_with_type(ObjectType::NUMANode) {
+                let idx_a = distances.object_idx(node_a).unwrap();
+                let idx_b = distances.object_idx(node_b).unwrap();
+                assert_eq!(
+                    distances.distance_between(node_a, node_b),
+                    Some((matrix[idx_a][idx_b], matrix[idx_b][idx_a]))
+                );
+            }
+        }
+
+        // Objects outside of the distance matrix have no distance
+        let node = topology
+            .objects_with_type(ObjectType::NUMANode)
+            .next()
+            .unwrap();
+        let pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+        assert_eq!(distances.distance_between(node, pu), None);
+        assert_eq!(distances.distance_between(pu, node), None);
+    }
+
+    /// OS indices of the NUMA nodes closest to the PU with OS index `pu`
+    fn closest_nodes_to_pu(topology: &Topology, pu: usize) -> Vec<usize> {
+        let pu = topology.pu_with_os_index(pu).unwrap();
+        topology
+            .closest_numa_nodes_by_distance(pu)
+            .unwrap()
+            .into_iter()
+            .map(|node| node.os_index().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn closest_numa_nodes_without_distances() {
+        let topology = four_nodes();
+        assert_eq!(closest_nodes_to_pu(&topology, 0), [0, 1, 2, 3]);
+        assert_eq!(closest_nodes_to_pu(&topology, 2), [2, 0, 1, 3]);
+
+        // Foreign objects are rejected
+        let other = four_nodes();
+        assert!(matches!(
+            topology.closest_numa_nodes_by_distance(other.root_object()),
+            Err(HybridError::Rust(_))
+        ));
+    }
+
+    #[cfg(feature = "hwloc-2_5_0")]
+    #[test]
+    fn closest_numa_nodes_with_latencies() {
+        let topology = four_nodes_with_latencies();
+        assert_eq!(closest_nodes_to_pu(&topology, 0), [0, 1, 2, 3]);
+        assert_eq!(closest_nodes_to_pu(&topology, 2), [2, 3, 0, 1]);
+        assert_eq!(closest_nodes_to_pu(&topology, 3), [3, 2, 0, 1]);
+
+        // All nodes are local to the root, so only the tree order matters
+        let from_root = topology
+            .closest_numa_nodes_by_distance(topology.root_object())
+            .unwrap()
+            .into_iter()
+            .map(|node| node.os_index().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(from_root, [0, 1, 2, 3]);
+    }
+
+    #[cfg(all(feature = "hwloc-2_5_0", feature = "ndarray"))]
+    #[test]
+    fn to_array2() {
+        let topology = four_nodes_with_latencies();
+        let distances = injected_latencies(&topology);
+        assert_eq!(
+            distances.to_array2(),
+            ndarray::arr2(&[
+                [10, 12, 20, 30],
+                [12, 10, 20, 30],
+                [20, 20, 10, 15],
+                [30, 30, 25, 10],
+            ])
+        );
+    }
+}
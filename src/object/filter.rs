@@ -0,0 +1,531 @@
+//! Composable object filters
+//!
+//! Many topology queries boil down to "find all objects with such and such
+//! properties". While these can always be expressed using iterator adapters
+//! over [`Topology::objects()`], the resulting nested closures quickly become
+//! hard to read when several criteria are involved.
+//!
+//! This module provides [`ObjectFilter`], a builder of object predicates that
+//! can be combined freely and then applied to the output of any object
+//! iteration API, or directly to the whole topology via
+//! [`Topology::objects_matching()`].
+
+#[cfg(doc)]
+use crate::object::types::ObjectType::OSDevice;
+use crate::{
+    memory::nodeset::NodeSet,
+    object::{
+        attributes::ObjectAttributes,
+        depth::{Depth, NormalDepth},
+        types::{OSDeviceType, ObjectType},
+        TopologyObject,
+    },
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    iter::FusedIterator,
+    ops::{Bound, RangeBounds},
+};
+
+/// # Filtering objects
+impl Topology {
+    /// Objects of the topology that match `filter`
+    ///
+    /// Objects are yielded in the same order as [`Topology::objects()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     object::{filter::ObjectFilter, types::ObjectType},
+    /// #     Topology,
+    /// # };
+    /// # let topology = Topology::test_instance();
+    /// let filter = ObjectFilter::new()
+    ///     .with_type(ObjectType::Core)
+    ///     .min_cpuset_weight(2);
+    /// for core in topology.objects_matching(&filter) {
+    ///     println!("{core} has SMT");
+    /// }
+    /// ```
+    pub fn objects_matching<'self_>(
+        &'self_ self,
+        filter: &'self_ ObjectFilter,
+    ) -> impl FusedIterator<Item = &'self_ TopologyObject> + Clone {
+        self.objects().filter(filter.predicate())
+    }
+}
+
+/// Composable predicate over [`TopologyObject`]s
+///
+/// A filter is built by starting from [`ObjectFilter::new()`], which matches
+/// every object, and adding criteria via builder methods. An object matches
+/// the filter if it fulfills all criteria.
+///
+/// Once built, a filter can be applied to any object iterator using
+/// `iter.filter(filter.predicate())`, or to a full topology using
+/// [`Topology::objects_matching()`].
+///
+/// # Examples
+///
+/// Find all network devices that are attached near NUMA node 1:
+///
+/// ```
+/// # use hwlocality::{
+/// #     memory::nodeset::NodeSet,
+/// #     object::{filter::ObjectFilter, types::OSDeviceType},
+/// #     Topology,
+/// # };
+/// # let topology = Topology::test_instance();
+/// let filter = ObjectFilter::new()
+///     .os_device_type(OSDeviceType::Network)
+///     .near_nodeset(NodeSet::from(1));
+/// for nic in topology.os_devices().filter(filter.predicate()) {
+///     println!("{nic} is close to NUMA node 1");
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObjectFilter {
+    /// Accepted object types (any type if empty)
+    types: Vec<ObjectType>,
+
+    /// Accepted normal depths
+    depths: (Bound<NormalDepth>, Bound<NormalDepth>),
+
+    /// Pattern that object names must match
+    name_pattern: Option<NamePattern>,
+
+    /// Textual infos that objects must have, with an optional expected value
+    infos: Vec<(String, Option<String>)>,
+
+    /// Minimal number of PUs in the object's cpuset
+    min_cpuset_weight: Option<usize>,
+
+    /// Accepted OS device type
+    os_device_type: Option<OSDeviceType>,
+
+    /// NUMA nodes that the object must be close to
+    near_nodeset: Option<NodeSet>,
+}
+//
+impl ObjectFilter {
+    /// Filter that matches every object
+    pub fn new() -> Self {
+        Self {
+            types: Vec::new(),
+            depths: (Bound::Unbounded, Bound::Unbounded),
+            name_pattern: None,
+            infos: Vec::new(),
+            min_cpuset_weight: None,
+            os_device_type: None,
+            near_nodeset: None,
+        }
+    }
+
+    /// Only accept objects of type `ty`
+    ///
+    /// This can be called multiple times to accept objects of several types.
+    pub fn with_type(mut self, ty: ObjectType) -> Self {
+        if !self.types.contains(&ty) {
+            self.types.push(ty);
+        }
+        self
+    }
+
+    /// Only accept normal objects whose depth is within `range`
+    ///
+    /// Memory, I/O and Misc objects, which live at virtual depths, never match
+    /// a filter with a depth range.
+    pub fn depth_range(mut self, range: impl RangeBounds<NormalDepth>) -> Self {
+        self.depths = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Only accept objects whose name matches the glob `pattern`
+    ///
+    /// In `pattern`, `*` matches any sequence of characters (including the
+    /// empty sequence) and `?` matches exactly one character. All other
+    /// characters must match literally. Objects without a name never match.
+    ///
+    /// This replaces any name criterion set by a previous call to this
+    /// method or to `ObjectFilter::name_regex()`.
+    pub fn name_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.name_pattern = Some(NamePattern::Glob(pattern.into()));
+        self
+    }
+
+    /// Only accept objects whose name matches the regular expression `regex`
+    ///
+    /// As with [`Regex::is_match()`](regex::Regex::is_match), the regex may
+    /// match any part of the name, use `^` and `$` anchors to match the whole
+    /// name. Objects without a name never match.
+    ///
+    /// This replaces any name criterion set by a previous call to this
+    /// method or to [`ObjectFilter::name_glob()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::filter::ObjectFilter, Topology};
+    /// # let topology = Topology::test_instance();
+    /// use regex::Regex;
+    ///
+    /// let filter = ObjectFilter::new().name_regex(Regex::new(r"^(eth|enp)\d")?);
+    /// for nic in topology.os_devices().filter(filter.predicate()) {
+    ///     println!("{nic} looks like an Ethernet interface");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn name_regex(mut self, regex: regex::Regex) -> Self {
+        self.name_pattern = Some(NamePattern::Regex(regex));
+        self
+    }
+
+    /// Only accept objects that have a textual info with key `key`
+    ///
+    /// If `value` is specified, the info must also have this value.
+    ///
+    /// This can be called multiple times to require several infos.
+    pub fn with_info(mut self, key: impl Into<String>, value: Option<&str>) -> Self {
+        self.infos.push((key.into(), value.map(String::from)));
+        self
+    }
+
+    /// Only accept objects whose cpuset contains at least `weight` PUs
+    ///
+    /// Objects without a cpuset, like I/O and Misc objects, never match a
+    /// filter with a minimal cpuset weight.
+    pub fn min_cpuset_weight(mut self, weight: usize) -> Self {
+        self.min_cpuset_weight = Some(weight);
+        self
+    }
+
+    /// Only accept [`OSDevice`] objects of type `ty`
+    pub fn os_device_type(mut self, ty: OSDeviceType) -> Self {
+        self.os_device_type = Some(ty);
+        self
+    }
+
+    /// Only accept objects that are close to one of the NUMA nodes in `nodeset`
+    ///
+    /// An object is considered close to a NUMA node if the nodeset of the
+    /// object, or of its first non-I/O ancestor for I/O objects, contains it.
+    pub fn near_nodeset(mut self, nodeset: NodeSet) -> Self {
+        self.near_nodeset = Some(nodeset);
+        self
+    }
+
+    /// Truth that `obj` matches this filter
+    pub fn matches(&self, obj: &TopologyObject) -> bool {
+        self.matches_type(obj)
+            && self.matches_depth(obj)
+            && self.matches_name(obj)
+            && self.matches_infos(obj)
+            && self.matches_cpuset_weight(obj)
+            && self.matches_os_device_type(obj)
+            && self.matches_nodeset(obj)
+    }
+
+    /// Predicate suitable for use with [`Iterator::filter()`]
+    pub fn predicate(&self) -> impl Fn(&&TopologyObject) -> bool + Clone + '_ {
+        |obj| self.matches(obj)
+    }
+
+    /// Check the object type criterion
+    fn matches_type(&self, obj: &TopologyObject) -> bool {
+        self.types.is_empty() || self.types.contains(&obj.object_type())
+    }
+
+    /// Check the depth range criterion
+    fn matches_depth(&self, obj: &TopologyObject) -> bool {
+        if self.depths == (Bound::Unbounded, Bound::Unbounded) {
+            return true;
+        }
+        matches!(obj.depth(), Depth::Normal(depth) if self.depths.contains(&depth))
+    }
+
+    /// Check the name pattern criterion
+    fn matches_name(&self, obj: &TopologyObject) -> bool {
+        let Some(pattern) = &self.name_pattern else {
+            return true;
+        };
+        obj.name()
+            .is_some_and(|name| pattern.matches(&name.to_string_lossy()))
+    }
+
+    /// Check the textual info criteria
+    fn matches_infos(&self, obj: &TopologyObject) -> bool {
+        self.infos.iter().all(|(key, expected)| {
+            obj.infos().iter().any(|info| {
+                info.name().to_str() == Ok(key)
+                    && expected
+                        .as_deref()
+                        .map_or(true, |expected| info.value().to_str() == Ok(expected))
+            })
+        })
+    }
+
+    /// Check the cpuset weight criterion
+    fn matches_cpuset_weight(&self, obj: &TopologyObject) -> bool {
+        let Some(min_weight) = self.min_cpuset_weight else {
+            return true;
+        };
+        obj.cpuset()
+            .is_some_and(|cpuset| cpuset.weight().map_or(true, |weight| weight >= min_weight))
+    }
+
+    /// Check the OS device type criterion
+    fn matches_os_device_type(&self, obj: &TopologyObject) -> bool {
+        let Some(expected) = self.os_device_type else {
+            return true;
+        };
+        matches!(
+            obj.attributes(),
+            Some(ObjectAttributes::OSDevice(attr)) if attr.device_type() == expected
+        )
+    }
+
+    /// Check the NUMA locality criterion
+    fn matches_nodeset(&self, obj: &TopologyObject) -> bool {
+        let Some(expected) = &self.near_nodeset else {
+            return true;
+        };
+        let local = if obj.object_type().is_io() {
+            obj.first_non_io_ancestor()
+        } else {
+            Some(obj)
+        };
+        local
+            .and_then(TopologyObject::nodeset)
+            .is_some_and(|nodeset| nodeset.intersects(expected))
+    }
+}
+
+//
+impl Default for ObjectFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pattern that object names must match
+#[derive(Clone, Debug)]
+enum NamePattern {
+    /// Glob pattern, see [`ObjectFilter::name_glob()`]
+    Glob(String),
+
+    /// Regular expression, see `ObjectFilter::name_regex()`
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+//
+impl NamePattern {
+    /// Truth that `name` matches this pattern
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Glob(pattern) => glob_match(
+                &pattern.chars().collect::<Vec<_>>(),
+                &name.chars().collect::<Vec<_>>(),
+            ),
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+//
+impl Eq for NamePattern {}
+//
+impl PartialEq for NamePattern {
+    fn eq(&self, other: &Self) -> bool {
+        // Regexes are compared by source pattern, as regex::Regex does not
+        // implement PartialEq
+        match (self, other) {
+            (Self::Glob(pattern1), Self::Glob(pattern2)) => pattern1 == pattern2,
+            #[cfg(feature = "regex")]
+            (Self::Regex(regex1), Self::Regex(regex2)) => regex1.as_str() == regex2.as_str(),
+            #[cfg(feature = "regex")]
+            (Self::Glob(_), Self::Regex(_)) | (Self::Regex(_), Self::Glob(_)) => false,
+        }
+    }
+}
+
+/// Truth that `text` matches the glob `pattern`, where `*` matches any
+/// sequence of characters and `?` matches a single character
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    // Iterative matching with backtracking to the last `*` wildcard
+    let (mut p, mut t) = (0, 0);
+    let mut last_star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                last_star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star_p, star_t)) = last_star else {
+                    return false;
+                };
+                p = star_p + 1;
+                t = star_t + 1;
+                last_star = Some((star_p, star_t + 1));
+            }
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::fmt::Debug;
+
+    assert_impl_all!(ObjectFilter: Clone, Debug, Default, Eq, PartialEq, Send, Sync);
+
+    #[test]
+    fn glob() {
+        let matches = |pattern: &str, text: &str| {
+            glob_match(
+                &pattern.chars().collect::<Vec<_>>(),
+                &text.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert!(matches("", ""));
+        assert!(matches("*", ""));
+        assert!(matches("*", "eth0"));
+        assert!(matches("eth?", "eth0"));
+        assert!(matches("e*0", "eth0"));
+        assert!(matches("*th*", "eth0"));
+        assert!(matches("a*b*c", "aXbYbc"));
+        assert!(!matches("eth?", "eth"));
+        assert!(!matches("eth", "eth0"));
+        assert!(!matches("", "eth0"));
+        assert!(!matches("a*c", "abd"));
+    }
+
+    #[test]
+    fn empty_filter() {
+        let topology = Topology::test_instance();
+        let filter = ObjectFilter::new();
+        assert!(topology.objects().all(|obj| filter.matches(obj)));
+        assert_eq!(
+            topology.objects_matching(&filter).count(),
+            topology.objects().count()
+        );
+        assert_eq!(ObjectFilter::default(), filter);
+    }
+
+    #[test]
+    fn type_and_depth_filters() {
+        let topology = Topology::test_instance();
+
+        // Type filtering should be equivalent to objects_with_type
+        let filter = ObjectFilter::new()
+            .with_type(ObjectType::Core)
+            .with_type(ObjectType::PU);
+        let expected = topology
+            .objects()
+            .filter(|obj| matches!(obj.object_type(), ObjectType::Core | ObjectType::PU))
+            .map(TopologyObject::global_persistent_index)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            topology
+                .objects_matching(&filter)
+                .map(TopologyObject::global_persistent_index)
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        // Depth filtering should only select normal objects at these depths
+        let filter = ObjectFilter::new().depth_range(NormalDepth::MIN..=NormalDepth::MIN);
+        let matching = topology.objects_matching(&filter).collect::<Vec<_>>();
+        assert_eq!(matching.len(), 1);
+        assert_eq!(
+            matching[0].global_persistent_index(),
+            topology.root_object().global_persistent_index()
+        );
+    }
+
+    #[test]
+    fn cpuset_and_nodeset_filters() {
+        let topology = Topology::test_instance();
+
+        // Cpuset weight filtering
+        let filter = ObjectFilter::new().min_cpuset_weight(2);
+        for obj in topology.objects() {
+            let expected = obj
+                .cpuset()
+                .is_some_and(|cpuset| cpuset.weight().unwrap() >= 2);
+            assert_eq!(filter.matches(obj), expected);
+        }
+
+        // NUMA locality filtering
+        let filter = ObjectFilter::new().near_nodeset(topology.nodeset().clone_target());
+        for obj in topology.objects() {
+            if obj.object_type().is_normal() || obj.object_type().is_memory() {
+                assert!(filter.matches(obj));
+            }
+        }
+        let filter = ObjectFilter::new().near_nodeset(NodeSet::new());
+        assert_eq!(topology.objects_matching(&filter).count(), 0);
+    }
+
+    #[test]
+    fn name_and_info_filters() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            if let Some(name) = obj.name().and_then(|name| name.to_str().ok()) {
+                assert!(ObjectFilter::new().name_glob(name).matches(obj));
+                assert!(ObjectFilter::new().name_glob("*").matches(obj));
+            } else {
+                assert!(!ObjectFilter::new().name_glob("*").matches(obj));
+            }
+            for info in obj.infos() {
+                let (Ok(key), Ok(value)) = (info.name().to_str(), info.value().to_str()) else {
+                    continue;
+                };
+                assert!(ObjectFilter::new().with_info(key, None).matches(obj));
+                assert!(ObjectFilter::new().with_info(key, Some(value)).matches(obj));
+            }
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn name_regex() {
+        use regex::Regex;
+
+        let topology = Topology::test_instance();
+        let filter = |pattern: &str| ObjectFilter::new().name_regex(Regex::new(pattern).unwrap());
+        for obj in topology.objects() {
+            if let Some(name) = obj.name().and_then(|name| name.to_str().ok()) {
+                assert!(filter(&format!("^{}$", regex::escape(name))).matches(obj));
+                assert!(filter("").matches(obj));
+                assert!(!filter(r"^$\A.").matches(obj));
+            } else {
+                assert!(!filter("").matches(obj));
+            }
+        }
+
+        // Unlike globs, regexes can match any part of the name
+        let pattern = NamePattern::Regex(Regex::new("t.0").unwrap());
+        assert!(pattern.matches("eth0"));
+        assert!(!NamePattern::Glob("t?0".to_owned()).matches("eth0"));
+        assert!(!pattern.matches("ib0"));
+
+        // Filters are compared by pattern source
+        assert_eq!(filter("eth."), filter("eth."));
+        assert_ne!(filter("eth."), filter("eth"));
+        assert_ne!(filter("eth"), ObjectFilter::new().name_glob("eth"));
+    }
+}
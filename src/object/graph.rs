@@ -0,0 +1,286 @@
+//! Graph view of the topology
+//!
+//! The hwloc topology is a tree of objects, but some algorithms (partitioning,
+//! shortest paths...) are more conveniently expressed over a general graph
+//! that also accounts for the distance matrices attached to the topology.
+//!
+//! [`Topology::to_graph()`] produces such a graph in the form of a node list
+//! and an edge list, which can easily be fed into graph libraries. With the
+//! `petgraph` feature, `Topology::to_petgraph()` directly produces a
+//! `petgraph::Graph` that petgraph's algorithms can be run on.
+
+use crate::{
+    errors::RawHwlocError,
+    object::{distance::DistancesKind, TopologyObject, TopologyObjectID},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::collections::HashMap;
+
+/// # Graph view
+impl Topology {
+    /// Graph view of this topology
+    ///
+    /// Every [`TopologyObject`] of the topology becomes a node of the graph.
+    /// Every parent/child relationship becomes an edge from the parent to the
+    /// child, and every entry of the topology's distance matrices becomes an
+    /// edge from the sender to the receiver object, carrying the associated
+    /// distance value. Distances from an object to itself are not listed.
+    ///
+    /// # Errors
+    ///
+    /// This function only fails if hwloc fails to enumerate the distance
+    /// matrices of the topology, which is not expected to happen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::graph::EdgeRelation, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let graph = topology.to_graph()?;
+    /// for edge in graph.edges() {
+    ///     if edge.relation == EdgeRelation::Child {
+    ///         let [parent, child] = [edge.source, edge.target].map(|idx| graph.nodes()[idx]);
+    ///         println!("{child} is a child of {parent}");
+    ///     }
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn to_graph(&self) -> Result<TopologyGraph<'_>, RawHwlocError> {
+        // Assign one node to each object
+        let nodes = self.objects().collect::<Vec<_>>();
+        let node_indices = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, obj)| (obj.global_persistent_index(), idx))
+            .collect::<HashMap<TopologyObjectID, usize>>();
+        let node_index = |obj: &TopologyObject| node_indices.get(&obj.global_persistent_index());
+
+        // Add parent/child relationships
+        let mut edges = Vec::new();
+        for (source, obj) in nodes.iter().enumerate() {
+            for child in obj.all_children() {
+                let target = *node_index(child).expect("Children should be part of the topology");
+                edges.push(TopologyEdge {
+                    source,
+                    target,
+                    relation: EdgeRelation::Child,
+                });
+            }
+        }
+
+        // Add distance relationships
+        for distances in self.distances(DistancesKind::empty())? {
+            let kind = distances.kind();
+            let objects = distances.objects().collect::<Vec<_>>();
+            for ((sender, receiver), value) in distances.enumerate_distances() {
+                if sender == receiver {
+                    continue;
+                }
+                let (Some(Some(sender)), Some(Some(receiver))) =
+                    (objects.get(sender), objects.get(receiver))
+                else {
+                    continue;
+                };
+                let (Some(&source), Some(&target)) = (node_index(sender), node_index(receiver))
+                else {
+                    continue;
+                };
+                edges.push(TopologyEdge {
+                    source,
+                    target,
+                    relation: EdgeRelation::Distance { kind, value },
+                });
+            }
+        }
+        Ok(TopologyGraph { nodes, edges })
+    }
+
+    /// Graph view of this topology, as a [`petgraph::Graph`]
+    ///
+    /// This is the graph produced by [`Topology::to_graph()`], converted via
+    /// [`TopologyGraph::into_petgraph()`].
+    ///
+    /// # Errors
+    ///
+    /// This function only fails if hwloc fails to enumerate the distance
+    /// matrices of the topology, which is not expected to happen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// use petgraph::algo::dijkstra;
+    ///
+    /// let graph = topology.to_petgraph()?;
+    /// let root = graph
+    ///     .node_indices()
+    ///     .find(|&idx| graph[idx].object_type() == ObjectType::Machine)
+    ///     .expect("Topologies have a root Machine object");
+    ///
+    /// // Number of parent/child hops between the root and each object
+    /// let hops = dijkstra(&graph, root, None, |_| 1);
+    /// assert_eq!(hops.len(), topology.objects().count());
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(
+        &self,
+    ) -> Result<petgraph::Graph<&TopologyObject, EdgeRelation>, RawHwlocError> {
+        self.to_graph().map(TopologyGraph::into_petgraph)
+    }
+}
+
+/// Graph view of a [`Topology`], as produced by [`Topology::to_graph()`]
+#[derive(Clone, Debug)]
+pub struct TopologyGraph<'topology> {
+    /// Objects of the topology
+    nodes: Vec<&'topology TopologyObject>,
+
+    /// Relationships between objects
+    edges: Vec<TopologyEdge>,
+}
+//
+impl<'topology> TopologyGraph<'topology> {
+    /// Nodes of the graph, i.e. objects of the topology
+    ///
+    /// Edges designate nodes by their index in this list.
+    pub fn nodes(&self) -> &[&'topology TopologyObject] {
+        &self.nodes[..]
+    }
+
+    /// Edges of the graph, i.e. relationships between objects
+    pub fn edges(&self) -> &[TopologyEdge] {
+        &self.edges[..]
+    }
+
+    /// Convert into a [`petgraph::Graph`]
+    ///
+    /// Node `i` of [`TopologyGraph::nodes()`] becomes the node of index
+    /// `petgraph::graph::NodeIndex::new(i)`, and edges are weighted by their
+    /// [`EdgeRelation`].
+    #[cfg(feature = "petgraph")]
+    pub fn into_petgraph(self) -> petgraph::Graph<&'topology TopologyObject, EdgeRelation> {
+        let mut graph = petgraph::Graph::with_capacity(self.nodes.len(), self.edges.len());
+        for node in self.nodes {
+            graph.add_node(node);
+        }
+        for edge in self.edges {
+            graph.add_edge(
+                petgraph::graph::NodeIndex::new(edge.source),
+                petgraph::graph::NodeIndex::new(edge.target),
+                edge.relation,
+            );
+        }
+        graph
+    }
+}
+//
+#[cfg(feature = "petgraph")]
+impl<'topology> From<TopologyGraph<'topology>>
+    for petgraph::Graph<&'topology TopologyObject, EdgeRelation>
+{
+    fn from(graph: TopologyGraph<'topology>) -> Self {
+        graph.into_petgraph()
+    }
+}
+
+/// Edge of a [`TopologyGraph`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TopologyEdge {
+    /// Index of the source node in [`TopologyGraph::nodes()`]
+    pub source: usize,
+
+    /// Index of the target node in [`TopologyGraph::nodes()`]
+    pub target: usize,
+
+    /// Relationship between the source and target nodes
+    pub relation: EdgeRelation,
+}
+
+/// Relationship between two objects, as represented by a [`TopologyEdge`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum EdgeRelation {
+    /// The target object is a child of the source object
+    Child,
+
+    /// The source and target objects are associated by a distance matrix
+    Distance {
+        /// Kind of the distance matrix
+        kind: DistancesKind,
+
+        /// Distance from the source to the target object
+        value: u64,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn to_graph() {
+        let topology = Topology::test_instance();
+        let graph = topology.to_graph().unwrap();
+        assert_eq!(graph.nodes().len(), topology.objects().count());
+
+        // Every object except the root has exactly one parent
+        let mut num_parents = vec![0; graph.nodes().len()];
+        for edge in graph.edges() {
+            assert!(edge.source < graph.nodes().len());
+            assert!(edge.target < graph.nodes().len());
+            if edge.relation == EdgeRelation::Child {
+                num_parents[edge.target] += 1;
+            } else {
+                assert_ne!(edge.source, edge.target);
+            }
+        }
+        for (obj, num_parents) in graph.nodes().iter().zip(num_parents) {
+            assert_eq!(num_parents, usize::from(obj.parent().is_some()));
+        }
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn to_petgraph() {
+        use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction};
+
+        let topology = Topology::test_instance();
+        let graph = topology.to_graph().unwrap();
+        let petgraph = topology.to_petgraph().unwrap();
+        assert_eq!(petgraph.node_count(), graph.nodes().len());
+        assert_eq!(petgraph.edge_count(), graph.edges().len());
+
+        // Nodes keep their index and edges keep their endpoints and relation
+        for (idx, obj) in graph.nodes().iter().enumerate() {
+            assert!(std::ptr::eq(petgraph[NodeIndex::new(idx)], *obj));
+        }
+        for (petgraph_edge, edge) in petgraph.edge_references().zip(graph.edges()) {
+            assert_eq!(petgraph_edge.source().index(), edge.source);
+            assert_eq!(petgraph_edge.target().index(), edge.target);
+            assert_eq!(*petgraph_edge.weight(), edge.relation);
+        }
+
+        // Child edges of each node match the object's children
+        for idx in petgraph.node_indices() {
+            let obj = petgraph[idx];
+            let mut children = petgraph
+                .edges_directed(idx, Direction::Outgoing)
+                .filter(|edge| *edge.weight() == EdgeRelation::Child)
+                .map(|edge| petgraph[edge.target()].global_persistent_index())
+                .collect::<Vec<_>>();
+            let mut expected = obj
+                .all_children()
+                .map(TopologyObject::global_persistent_index)
+                .collect::<Vec<_>>();
+            children.sort_unstable();
+            expected.sort_unstable();
+            assert_eq!(children, expected);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Lightweight object handles
+//!
+//! [`TopologyObject`]s can only be accessed through references that are bound
+//! to the lifetime of the parent [`Topology`]. This is sometimes inconvenient,
+//! for example when one wants to record placement decisions in a long-lived
+//! struct that does not borrow from the topology.
+//!
+//! [`ObjectHandle`] addresses this use case by providing a `Copy`, `Send` and
+//! `'static` identifier of an object, which can be resolved back into a
+//! `&TopologyObject` using [`Topology::object_by_handle()`].
+
+use crate::{
+    object::{depth::Depth, TopologyObject, TopologyObjectID},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Lightweight identifier of a [`TopologyObject`]
+///
+/// Handles are obtained using [`TopologyObject::handle()`] and resolved using
+/// [`Topology::object_by_handle()`]. They can be freely copied, stored and
+/// sent across threads, as they do not borrow from the topology.
+///
+/// A handle records the depth and logical index of the object, which allow
+/// quick lookup, along with its global persistent index, which protects
+/// against resolving a handle to the wrong object if the topology was
+/// modified since the handle was created.
+///
+/// Handles do not record which topology they come from. They can be resolved
+/// using clones of the original topology, where objects keep their global
+/// persistent index. But global persistent indices are small per-topology
+/// counters, so resolving a handle using an unrelated topology may return an
+/// unrelated object instead of `None`.
+///
+/// With the `serde` feature, handles can be serialized, for example in order
+/// to persist placement decisions. The same caveats apply to deserialized
+/// handles, which should only be resolved using the topology that they were
+/// created from or a clone of it.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct ObjectHandle {
+    /// Depth of the object
+    depth: Depth,
+
+    /// Logical index of the object at this depth
+    logical_index: usize,
+
+    /// Global persistent index of the object
+    global_persistent_index: TopologyObjectID,
+}
+//
+impl ObjectHandle {
+    /// Depth of the object
+    ///
+    /// See [`TopologyObject::depth()`] for more information.
+    pub fn depth(&self) -> Depth {
+        self.depth
+    }
+
+    /// Logical index of the object at its depth
+    ///
+    /// See [`TopologyObject::logical_index()`] for more information.
+    pub fn logical_index(&self) -> usize {
+        self.logical_index
+    }
+
+    /// Global persistent index of the object
+    ///
+    /// See [`TopologyObject::global_persistent_index()`] for more information.
+    pub fn global_persistent_index(&self) -> TopologyObjectID {
+        self.global_persistent_index
+    }
+}
+
+/// # Object handles
+impl TopologyObject {
+    /// Lightweight handle to this object
+    ///
+    /// See [`ObjectHandle`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let root = topology.root_object();
+    /// let handle = root.handle();
+    /// assert!(std::ptr::eq(topology.object_by_handle(handle).unwrap(), root));
+    /// ```
+    pub fn handle(&self) -> ObjectHandle {
+        ObjectHandle {
+            depth: self.depth(),
+            logical_index: self.logical_index(),
+            global_persistent_index: self.global_persistent_index(),
+        }
+    }
+}
+
+/// # Object handles
+impl Topology {
+    /// Resolve an [`ObjectHandle`] back into the object it designates
+    ///
+    /// The object is first looked up by depth and logical index, which is
+    /// fast. If no object with the expected global persistent index is found
+    /// there, for example because the topology was modified since the handle
+    /// was created, the search falls back to scanning all objects of the
+    /// topology for the expected global persistent index.
+    ///
+    /// `None` is returned if no object of this topology matches the handle.
+    /// The handle should come from this topology or from a clone of it, as
+    /// handles from unrelated topologies may match unrelated objects.
+    pub fn object_by_handle(&self, handle: ObjectHandle) -> Option<&TopologyObject> {
+        let matches =
+            |obj: &&TopologyObject| obj.global_persistent_index() == handle.global_persistent_index;
+        self.object_at_depth_and_index(handle.depth, handle.logical_index)
+            .filter(matches)
+            .or_else(|| self.objects().find(matches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(ObjectHandle:
+        Copy, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    #[test]
+    fn handle_roundtrip() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            let handle = obj.handle();
+            assert_eq!(handle.depth(), obj.depth());
+            assert_eq!(handle.logical_index(), obj.logical_index());
+            assert_eq!(
+                handle.global_persistent_index(),
+                obj.global_persistent_index()
+            );
+            let resolved = topology.object_by_handle(handle).unwrap();
+            assert!(std::ptr::eq(resolved, obj));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        assert_impl_all!(ObjectHandle: serde::Serialize, serde::de::DeserializeOwned);
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            let handle = obj.handle();
+            let json = serde_json::to_string(&handle).unwrap();
+            let deserialized = serde_json::from_str::<ObjectHandle>(&json).unwrap();
+            assert_eq!(deserialized, handle);
+            let resolved = topology.object_by_handle(deserialized).unwrap();
+            assert!(std::ptr::eq(resolved, obj));
+        }
+    }
+}
@@ -2,20 +2,26 @@
 
 use super::{
     attributes::ObjectAttributes,
-    depth::{Depth, NormalDepth, TypeToDepthError},
-    types::{CacheType, ObjectType},
+    depth::{Depth, LevelSummary, NormalDepth, TypeToDepthError},
+    types::{BridgeType, CacheType, ObjectType},
     TopologyObject,
 };
 use crate::{
     ffi::{int, transparent::AsNewtype},
     topology::Topology,
 };
-use hwlocality_sys::hwloc_obj_type_t;
+use hwlocality_sys::{hwloc_obj_type_t, HWLOC_TYPE_DEPTH_MULTIPLE, HWLOC_TYPE_DEPTH_UNKNOWN};
 use num_enum::TryFromPrimitiveError;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
-use std::{ffi::c_uint, fmt::Debug, iter::FusedIterator, num::NonZeroUsize};
+use std::{
+    ffi::{c_int, c_uint},
+    fmt::Debug,
+    iter::FusedIterator,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicI32, Ordering},
+};
 
 /// # Object levels, depths and types
 ///
@@ -124,16 +130,19 @@ impl Topology {
     /// [`Group`]: ObjectType::Group
     #[doc(alias = "hwloc_get_type_depth")]
     pub fn depth_for_type(&self, object_type: ObjectType) -> Result<Depth, TypeToDepthError> {
-        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
-        //         - hwloc ops are trusted not to modify *const parameters
-        //         - By construction, ObjectType only exposes values that map into
-        //           hwloc_obj_type_t values understood by the configured version
-        //           of hwloc, and build.rs checks that the active version of
-        //           hwloc is not older than that, so into() may only generate
-        //           valid hwloc_obj_type_t values for current hwloc
-        Depth::from_raw(unsafe {
-            hwlocality_sys::hwloc_get_type_depth(self.as_ptr(), object_type.into())
-        })
+        self.depth_cache()
+            .get_or_insert(DepthQuery::Exact, object_type, || {
+                // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+                //         - hwloc ops are trusted not to modify *const parameters
+                //         - By construction, ObjectType only exposes values that map into
+                //           hwloc_obj_type_t values understood by the configured version
+                //           of hwloc, and build.rs checks that the active version of
+                //           hwloc is not older than that, so into() may only generate
+                //           valid hwloc_obj_type_t values for current hwloc
+                Depth::from_raw(unsafe {
+                    hwlocality_sys::hwloc_get_type_depth(self.as_ptr(), object_type.into())
+                })
+            })
     }
 
     /// Depth for the given [`ObjectType`] or below
@@ -182,6 +191,17 @@ impl Topology {
         );
 
         // Normal object type case
+        self.depth_cache()
+            .get_or_insert(DepthQuery::OrBelow, object_type, || {
+                self.depth_or_below_for_type_uncached(object_type)
+            })
+    }
+
+    /// Uncached part of [`Topology::depth_or_below_for_type()`]
+    fn depth_or_below_for_type_uncached(
+        &self,
+        object_type: ObjectType,
+    ) -> Result<Depth, TypeToDepthError> {
         match self.depth_for_type(object_type) {
             Ok(d) => Ok(d),
             Err(TypeToDepthError::Nonexistent) => {
@@ -246,6 +266,17 @@ impl Topology {
         );
 
         // Normal object type case
+        self.depth_cache()
+            .get_or_insert(DepthQuery::OrAbove, object_type, || {
+                self.depth_or_above_for_type_uncached(object_type)
+            })
+    }
+
+    /// Uncached part of [`Topology::depth_or_above_for_type()`]
+    fn depth_or_above_for_type_uncached(
+        &self,
+        object_type: ObjectType,
+    ) -> Result<Depth, TypeToDepthError> {
         match self.depth_for_type(object_type) {
             Ok(d) => Ok(d),
             Err(TypeToDepthError::Nonexistent) => {
@@ -363,6 +394,44 @@ impl Topology {
         result
     }
 
+    /// Returns the depth of the topology level that contains I/O bridges whose
+    /// upstream side is of type `upstream_type`
+    ///
+    /// All bridges live at the virtual [`Depth::Bridge`] depth, so
+    /// [`depth_for_type()`] cannot tell whether host bridges or PCI-to-PCI
+    /// bridges are present. This function only returns [`Depth::Bridge`] if at
+    /// least one bridge with the requested upstream type exists.
+    ///
+    /// # Errors
+    ///
+    /// - [`TypeToDepthError::Nonexistent`] if no bridge has an upstream side of
+    ///   type `upstream_type`, which is always the case if I/O objects were
+    ///   filtered out of the topology
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::{depth::Depth, types::BridgeType};
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// if let Ok(depth) = topology.depth_for_bridge(BridgeType::Host) {
+    ///     assert_eq!(depth, Depth::Bridge);
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`depth_for_type()`]: Self::depth_for_type()
+    pub fn depth_for_bridge(&self, upstream_type: BridgeType) -> Result<Depth, TypeToDepthError> {
+        self.objects_at_depth(Depth::Bridge)
+            .any(|obj| {
+                matches!(
+                    obj.attributes(),
+                    Some(ObjectAttributes::Bridge(bridge)) if bridge.upstream_type() == upstream_type
+                )
+            })
+            .then_some(Depth::Bridge)
+            .ok_or(TypeToDepthError::Nonexistent)
+    }
+
     /// Type of objects at the given `depth`, if any
     ///
     /// `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`].
@@ -452,6 +521,9 @@ impl Topology {
     ///
     /// `depth` can be a [`Depth`], a [`NormalDepth`] or an [`usize`].
     ///
+    /// Objects are yielded in increasing logical index order, i.e. the n-th
+    /// object that is yielded has [logical index] n.
+    ///
     /// # Examples
     ///
     /// ```
@@ -479,6 +551,8 @@ impl Topology {
     /// #
     /// # Ok::<(), eyre::Report>(())
     /// ```
+    ///
+    /// [logical index]: TopologyObject::logical_index()
     #[doc(alias = "hwloc_get_obj_by_depth")]
     #[doc(alias = "hwloc_get_next_obj_by_depth")]
     pub fn objects_at_depth<DepthLike>(
@@ -532,6 +606,80 @@ impl Topology {
         polymorphized(self, depth)
     }
 
+    /// [`TopologyObject`] with a given [`ObjectType`] and logical index
+    ///
+    /// This is equivalent to `self.objects_with_type(object_type).nth(logical_index)`,
+    /// but runs in constant time for object types that only exist at a single
+    /// depth, by directly looking up hwloc's per-depth object arrays instead
+    /// of iterating over preceding objects.
+    ///
+    /// For object types that exist at multiple depths (which can happen with
+    /// [`ObjectType::Group`]), objects are numbered in the same order as
+    /// [`Topology::objects_with_type()`], and the lookup cost is proportional
+    /// to the number of depths.
+    ///
+    /// Returns `None` if there is no such object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let first_pu = topology
+    ///     .object_with_type_and_index(ObjectType::PU, 0)
+    ///     .expect("there should be at least one PU");
+    /// assert_eq!(first_pu.object_type(), ObjectType::PU);
+    /// assert_eq!(first_pu.logical_index(), 0);
+    /// ```
+    #[doc(alias = "hwloc_get_obj_by_type")]
+    pub fn object_with_type_and_index(
+        &self,
+        object_type: ObjectType,
+        logical_index: usize,
+    ) -> Option<&TopologyObject> {
+        match self.depth_for_type(object_type) {
+            Ok(depth) => self.object_at_depth_and_index(depth, logical_index),
+            Err(TypeToDepthError::Multiple) => {
+                let mut index = logical_index;
+                for depth in NormalDepth::iter_range(NormalDepth::MIN, self.depth()) {
+                    if self.type_at_depth(depth) != Some(object_type) {
+                        continue;
+                    }
+                    let num_objects = self.num_objects_at_depth(depth);
+                    if index < num_objects {
+                        return self.object_at_depth_and_index(depth.into(), index);
+                    }
+                    index -= num_objects;
+                }
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// [`TopologyObject`] at a given depth and logical index, if any
+    pub(crate) fn object_at_depth_and_index(
+        &self,
+        depth: Depth,
+        logical_index: usize,
+    ) -> Option<&TopologyObject> {
+        if logical_index >= self.num_objects_at_depth(depth) {
+            return None;
+        }
+        let idx = c_uint::try_from(logical_index).ok()?;
+        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+        //         - hwloc ops are trusted not to modify *const parameters
+        //         - Depth only exposes values that are understood by the
+        //           active version of hwloc (see objects_at_depth)
+        //         - idx is in bounds per the check above
+        let ptr =
+            unsafe { hwlocality_sys::hwloc_get_obj_by_depth(self.as_ptr(), depth.to_raw(), idx) };
+        // SAFETY: If hwloc_get_obj_by_depth returns a non-null pointer, it's
+        //         assumed to be successful and thus that the output pointer
+        //         and its target are valid
+        (!ptr.is_null()).then(|| unsafe { (&*ptr).as_newtype() })
+    }
+
     /// [`TopologyObject`] at the root of the topology
     ///
     /// Its type is [`ObjectType::Machine`].
@@ -566,6 +714,10 @@ impl Topology {
 
     /// [`TopologyObject`]s with the given [`ObjectType`]
     ///
+    /// Objects are yielded by increasing depth, and in increasing logical index
+    /// order within each depth. For all object types except [`Group`], which
+    /// may span multiple depths, this is simply logical index order.
+    ///
     /// # Examples
     ///
     /// ```
@@ -593,6 +745,8 @@ impl Topology {
     /// #
     /// # Ok::<(), eyre::Report>(())
     /// ```
+    ///
+    /// [`Group`]: ObjectType::Group
     #[doc(alias = "hwloc_get_obj_by_type")]
     #[doc(alias = "hwloc_get_nbobjs_by_type")]
     #[doc(alias = "hwloc_get_next_obj_by_type")]
@@ -620,6 +774,41 @@ impl Topology {
             inner: depth_iter.flat_map(move |depth| self.objects_at_depth(depth)),
         }
     }
+
+    /// Object type and number of objects at each depth of the topology
+    ///
+    /// Normal depths are listed first, from the root to the PUs, followed by
+    /// the virtual depths that contain at least one object. This provides the
+    /// same one-glance overview of the topology structure as `lstopo -s`.
+    ///
+    /// This functionality is an hwlocality-specific extension to the hwloc API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::object::types::ObjectType;
+    /// # let topology = hwlocality::Topology::test_instance();
+    /// let levels = topology.level_summary();
+    /// for level in &levels {
+    ///     println!("{level}");
+    /// }
+    /// assert_eq!(levels[0].object_type(), ObjectType::Machine);
+    /// assert_eq!(levels[0].count(), 1);
+    /// ```
+    pub fn level_summary(&self) -> Vec<LevelSummary> {
+        NormalDepth::iter_range(NormalDepth::MIN, self.depth())
+            .map(Depth::from)
+            .chain(Depth::VIRTUAL_DEPTHS.iter().copied())
+            .map(|depth| {
+                LevelSummary::new(
+                    depth,
+                    self.type_at_depth(depth).expect("Depth should exist"),
+                    self.num_objects_at_depth(depth),
+                )
+            })
+            .filter(|level| level.count() > 0)
+            .collect()
+    }
 }
 
 /// Iterator emitted by [`TopologyObject::objects_with_type()`]
@@ -675,6 +864,108 @@ impl<'topology, Inner: FusedIterator<Item = &'topology TopologyObject>> FusedIte
 {
 }
 
+/// Upper bound on the number of object types, beyond which types are not
+/// cached by [`DepthCache`] (only 20 object types exist at the time of writing)
+const NUM_CACHED_TYPES: usize = 32;
+
+/// Number of query kinds that are cached by [`DepthCache`]
+const NUM_DEPTH_QUERIES: usize = 3;
+
+/// Marker for [`DepthCache`] slots whose result has not been computed yet
+const NOT_CACHED: c_int = c_int::MIN;
+
+/// Memoized results of type-to-depth queries
+///
+/// Type-to-depth queries cross the FFI boundary and, in the case of
+/// [`Topology::depth_or_below_for_type()`] and
+/// [`Topology::depth_or_above_for_type()`], may need to scan all depths of the
+/// topology. Since they are frequently used in hot loops, their results are
+/// cached inside of the [`Topology`], and this cache is cleared whenever the
+/// topology is modified.
+//
+// --- Implementation details ---
+//
+// Each slot holds the raw hwloc encoding of a query result (including errors),
+// or NOT_CACHED if the result has not been computed yet. Since query results
+// only depend on the topology, which cannot change while it is shared, racing
+// threads can only ever write the same value, so relaxed atomics suffice.
+//
+// In debug builds, cached results are checked against a fresh query, which
+// detects modifications of the underlying hwloc topology while it is shared
+// (e.g. by FFI code that casts away the constness of Topology::as_ptr()).
+#[derive(Debug)]
+pub(crate) struct DepthCache([[AtomicI32; NUM_CACHED_TYPES]; NUM_DEPTH_QUERIES]);
+//
+impl DepthCache {
+    /// Set up an empty cache
+    pub(crate) fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY_SLOT: AtomicI32 = AtomicI32::new(NOT_CACHED);
+        #[allow(clippy::declare_interior_mutable_const)]
+        const EMPTY_QUERY: [AtomicI32; NUM_CACHED_TYPES] = [EMPTY_SLOT; NUM_CACHED_TYPES];
+        Self([EMPTY_QUERY; NUM_DEPTH_QUERIES])
+    }
+
+    /// Clear the cache after the topology has been modified
+    #[cfg(feature = "hwloc-2_3_0")]
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Look up a query result, computing it if needed
+    fn get_or_insert(
+        &self,
+        query: DepthQuery,
+        object_type: ObjectType,
+        compute: impl FnOnce() -> Result<Depth, TypeToDepthError>,
+    ) -> Result<Depth, TypeToDepthError> {
+        let Some(slot) = usize::try_from(hwloc_obj_type_t::from(object_type))
+            .ok()
+            .and_then(|idx| self.0[query as usize].get(idx))
+        else {
+            return compute();
+        };
+        let cached = slot.load(Ordering::Relaxed);
+        if cached != NOT_CACHED {
+            let result = Depth::from_raw(cached);
+            debug_assert_eq!(
+                result,
+                compute(),
+                "Topology should not be modified while shared"
+            );
+            return result;
+        }
+        let result = compute();
+        let raw = match result {
+            Ok(depth) => depth.to_raw(),
+            Err(TypeToDepthError::Nonexistent) => HWLOC_TYPE_DEPTH_UNKNOWN,
+            Err(TypeToDepthError::Multiple) => HWLOC_TYPE_DEPTH_MULTIPLE,
+            Err(TypeToDepthError::Unexpected(raw)) => raw,
+        };
+        slot.store(raw, Ordering::Relaxed);
+        result
+    }
+}
+//
+impl Default for DepthCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kind of type-to-depth query that is cached by [`DepthCache`]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+enum DepthQuery {
+    /// [`Topology::depth_for_type()`]
+    Exact = 0,
+
+    /// [`Topology::depth_or_below_for_type()`]
+    OrBelow,
+
+    /// [`Topology::depth_or_above_for_type()`]
+    OrAbove,
+}
+
 #[allow(clippy::cognitive_complexity)]
 #[cfg(test)]
 pub(crate) mod tests {
@@ -730,6 +1021,36 @@ pub(crate) mod tests {
             .chain(Depth::VIRTUAL_DEPTHS.iter().copied())
     }
 
+    /// Check that the level summary matches per-depth queries
+    #[test]
+    fn level_summary() {
+        let topology = Topology::test_instance();
+        let levels = topology.level_summary();
+        let expected = valid_depths()
+            .filter(|&depth| topology.num_objects_at_depth(depth) > 0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            levels.iter().map(LevelSummary::depth).collect::<Vec<_>>(),
+            expected
+        );
+        for level in levels {
+            assert_eq!(
+                Some(level.object_type()),
+                topology.type_at_depth(level.depth())
+            );
+            assert_eq!(level.count(), topology.num_objects_at_depth(level.depth()));
+            assert_eq!(
+                level.to_string(),
+                format!(
+                    "depth {}: {} {}",
+                    level.depth(),
+                    level.count(),
+                    level.object_type()
+                )
+            );
+        }
+    }
+
     /// Check the mapping from types to depths
     fn type_to_depths() -> HashMap<ObjectType, Vec<Depth>> {
         let topology = Topology::test_instance();
@@ -803,6 +1124,62 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Check that cached type -> depth queries match uncached ones
+    #[test]
+    fn depth_cache() {
+        let topology = Topology::test_instance().clone();
+        for _ in 0..2 {
+            for ty in enum_iterator::all::<ObjectType>() {
+                // SAFETY: Same as in depth_for_type
+                let uncached = Depth::from_raw(unsafe {
+                    hwlocality_sys::hwloc_get_type_depth(topology.as_ptr(), ty.into())
+                });
+                assert_eq!(topology.depth_for_type(ty), uncached);
+                if ty.is_normal() {
+                    assert_eq!(
+                        topology.depth_or_above_for_type(ty),
+                        topology.depth_or_above_for_type_uncached(ty)
+                    );
+                    assert_eq!(
+                        topology.depth_or_below_for_type(ty),
+                        topology.depth_or_below_for_type_uncached(ty)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check that modifying a shared topology is detected in debug builds
+    #[cfg(debug_assertions)]
+    #[test]
+    fn depth_cache_detects_modification() {
+        let topology = Topology::test_instance().clone();
+        topology.depth_for_type(ObjectType::PU).unwrap();
+
+        // Simulate a modification of the hwloc topology that did not go
+        // through &mut Topology, and thus did not clear the depth cache
+        let slot = usize::try_from(hwloc_obj_type_t::from(ObjectType::PU)).unwrap();
+        topology.depth_cache().0[DepthQuery::Exact as usize][slot]
+            .store(HWLOC_TYPE_DEPTH_UNKNOWN, Ordering::Relaxed);
+        assert_panics(|| topology.depth_for_type(ObjectType::PU)).unwrap();
+    }
+
+    /// Check that object lookup by type and index matches iteration
+    #[test]
+    fn object_with_type_and_index() {
+        let topology = Topology::test_instance();
+        for ty in enum_iterator::all::<ObjectType>() {
+            let num_objects = topology.objects_with_type(ty).count();
+            for (idx, expected) in topology.objects_with_type(ty).enumerate() {
+                let actual = topology.object_with_type_and_index(ty, idx).unwrap();
+                assert!(ptr::eq(actual, expected));
+            }
+            assert!(topology
+                .object_with_type_and_index(ty, num_objects)
+                .is_none());
+        }
+    }
+
     /// Check the root object
     ///
     /// It's the top of the topology, so we know a lot about it.
@@ -1065,6 +1442,28 @@ pub(crate) mod tests {
         }
     }
 
+    // --- Check that bridge search is correct ---
+
+    proptest! {
+        /// Check that bridge search is correct
+        #[test]
+        fn depth_for_bridge(upstream_type: BridgeType) {
+            let topology = Topology::test_instance();
+            let has_match = topology.objects_with_type(ObjectType::Bridge).any(|obj| {
+                let Some(ObjectAttributes::Bridge(bridge)) = obj.attributes() else {
+                    unreachable!("bridges should have bridge attributes")
+                };
+                bridge.upstream_type() == upstream_type
+            });
+            let result = topology.depth_for_bridge(upstream_type);
+            if has_match {
+                prop_assert_eq!(result, Ok(Depth::Bridge));
+            } else {
+                prop_assert_eq!(result, Err(TypeToDepthError::Nonexistent));
+            }
+        }
+    }
+
     // --- Test operations with a depth parameter ---
 
     /// Depths that are mostly valid, but may be invalid too
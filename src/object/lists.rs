@@ -17,14 +17,38 @@ use std::sync::OnceLock;
 /// For some use cases, especially testing, it is convenient to have a full list
 /// of all objects contained within a topology. These methods provide just that.
 ///
+/// All of these lists are deterministically ordered: objects are grouped by
+/// depth as described in each method's documentation, and objects from the
+/// same depth are always yielded in increasing [logical index] order. Use
+/// [`SortedByOsIndex::sorted_by_os_index()`] if you need OS index order
+/// instead, e.g. to build tables that are indexed by OS index.
+///
 /// This functionality is unique to the Rust hwloc bindings
+///
+/// [logical index]: TopologyObject::logical_index()
 impl Topology {
     /// Full list of objects in the topology, first normal objects ordered by
-    /// increasing depth then virtual objects ordered by type
+    /// increasing depth then virtual objects ordered by type, with objects of
+    /// each depth ordered by increasing logical index
     pub fn objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         self.normal_objects().chain(self.virtual_objects())
     }
 
+    /// Total number of objects in the topology
+    ///
+    /// This is the number of objects that [`Topology::objects()`] yields,
+    /// computed from per-depth object counts without a full traversal.
+    pub fn total_object_count(&self) -> usize {
+        NormalDepth::iter_range(NormalDepth::MIN, self.depth())
+            .map(|depth| self.num_objects_at_depth(depth))
+            .chain(
+                Depth::VIRTUAL_DEPTHS
+                    .iter()
+                    .map(|&depth| self.num_objects_at_depth(depth)),
+            )
+            .sum()
+    }
+
     /// Pre-computed list of objects from the test instance
     #[cfg(test)]
     pub(crate) fn test_objects() -> &'static [&'static TopologyObject] {
@@ -40,27 +64,30 @@ impl Topology {
     }
 
     /// Full list of objects contains in the normal hierarchy of the topology,
-    /// ordered by increasing depth
+    /// ordered by increasing depth, then by increasing logical index
     pub fn normal_objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         NormalDepth::iter_range(NormalDepth::MIN, self.depth())
             .flat_map(|depth| self.objects_at_depth(depth))
     }
 
-    /// Full list of virtual bjects in the topology, ordered by type
+    /// Full list of virtual objects in the topology, ordered by type, then by
+    /// increasing logical index
     pub fn virtual_objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         Depth::VIRTUAL_DEPTHS
             .iter()
             .flat_map(|&depth| self.objects_at_depth(depth))
     }
 
-    /// Full list of memory objects in the topology, ordered by type
+    /// Full list of memory objects in the topology, ordered by type, then by
+    /// increasing logical index
     pub fn memory_objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         Depth::MEMORY_DEPTHS
             .iter()
             .flat_map(|&depth| self.objects_at_depth(depth))
     }
 
-    /// Full list of I/O objects in the topology, ordered by type
+    /// Full list of I/O objects in the topology, ordered by type, then by
+    /// increasing logical index
     pub fn io_objects(&self) -> impl FusedIterator<Item = &TopologyObject> + Clone {
         Depth::IO_DEPTHS
             .iter()
@@ -68,10 +95,45 @@ impl Topology {
     }
 }
 
+/// Iterator extension trait for listing [`TopologyObject`]s by OS index
+///
+/// Object iterators yield objects in logical index order, which is the order
+/// that hwloc deems most meaningful for locality. But some applications need to
+/// match objects against operating system identifiers, e.g. to build lookup
+/// tables that are indexed by CPU or NUMA node number.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::object::{lists::SortedByOsIndex, types::ObjectType};
+/// # let topology = hwlocality::Topology::test_instance();
+/// let pus = topology.objects_with_type(ObjectType::PU).sorted_by_os_index();
+/// let os_indices = pus.map(|pu| pu.os_index().unwrap()).collect::<Vec<_>>();
+/// assert!(os_indices.windows(2).all(|pair| pair[0] < pair[1]));
+/// # Ok::<(), eyre::Report>(())
+/// ```
+pub trait SortedByOsIndex<'topology>: Iterator<Item = &'topology TopologyObject> + Sized {
+    /// Yield the same objects, sorted by increasing OS index
+    ///
+    /// The sort is stable, so objects with the same OS index (e.g. objects of
+    /// different types) keep their relative order. Objects which have no OS
+    /// index come last, in their original order.
+    fn sorted_by_os_index(self) -> std::vec::IntoIter<&'topology TopologyObject> {
+        let mut objects = self.collect::<Vec<_>>();
+        objects.sort_by_key(|obj| (obj.os_index().is_none(), obj.os_index()));
+        objects.into_iter()
+    }
+}
+//
+impl<'topology, I: Iterator<Item = &'topology TopologyObject>> SortedByOsIndex<'topology> for I {}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::*;
-    use crate::object::ObjectType;
+    use crate::{
+        object::{filter::ObjectFilter, ObjectType},
+        topology::synthetic::SyntheticBuilder,
+    };
     use proptest::prelude::*;
     use similar_asserts::assert_eq;
     use std::collections::{HashMap, HashSet};
@@ -114,6 +176,13 @@ pub(crate) mod tests {
 
         let objects = checked_object_set(topology.objects());
         let keys = object_ids_from_set(&objects);
+        assert_eq!(topology.total_object_count(), objects.len());
+        assert_eq!(
+            topology
+                .root_object()
+                .descendant_count(&ObjectFilter::new()),
+            objects.len() - 1
+        );
 
         let normal_objects = checked_object_set(topology.normal_objects());
         assert!(normal_objects
@@ -152,4 +221,87 @@ pub(crate) mod tests {
         assert_eq!(io_keys, &(&virtual_keys - &memory_keys) - &misc_keys);
         assert_eq!(misc_keys, &(&virtual_keys - &memory_keys) - &io_keys);
     }
+
+    /// Check that object lists are in logical index order within each depth
+    #[test]
+    fn object_counts() {
+        // 2 packages with one NUMA node and 2 cores each, and 2 PUs per core
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(1)
+            .cores(2)
+            .pus(2)
+            .build()
+            .unwrap();
+        assert_eq!(topology.total_object_count(), 1 + 2 + 2 + 4 + 8);
+
+        let count =
+            |obj: &TopologyObject, ty| obj.descendant_count(&ObjectFilter::new().with_type(ty));
+        let root = topology.root_object();
+        assert_eq!(
+            root.descendant_count(&ObjectFilter::new()),
+            topology.total_object_count() - 1
+        );
+        assert_eq!(count(root, ObjectType::Machine), 0);
+        assert_eq!(count(root, ObjectType::NUMANode), 2);
+        assert_eq!(count(root, ObjectType::PU), 8);
+        for package in topology.objects_with_type(ObjectType::Package) {
+            assert_eq!(package.descendant_count(&ObjectFilter::new()), 1 + 2 + 4);
+            assert_eq!(count(package, ObjectType::Core), 2);
+            assert_eq!(count(package, ObjectType::PU), 4);
+        }
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            assert_eq!(pu.descendant_count(&ObjectFilter::new()), 0);
+        }
+    }
+
+    #[test]
+    fn object_order() {
+        let topology = Topology::test_instance();
+        let check_depth_then_logical = |objects: Vec<&TopologyObject>| {
+            for pair in objects.windows(2) {
+                if pair[0].depth() == pair[1].depth() {
+                    assert!(pair[0].logical_index() < pair[1].logical_index());
+                }
+            }
+        };
+        check_depth_then_logical(topology.objects().collect());
+        check_depth_then_logical(topology.memory_objects().collect());
+        check_depth_then_logical(topology.io_objects().collect());
+
+        let normal_depths = topology
+            .normal_objects()
+            .map(|obj| obj.depth().expect_normal())
+            .collect::<Vec<_>>();
+        assert!(normal_depths.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        for depth in NormalDepth::iter_range(NormalDepth::MIN, topology.depth())
+            .map(Depth::from)
+            .chain(Depth::VIRTUAL_DEPTHS.iter().copied())
+        {
+            for (idx, obj) in topology.objects_at_depth(depth).enumerate() {
+                assert_eq!(obj.logical_index(), idx);
+            }
+        }
+
+        for ty in enum_iterator::all::<ObjectType>() {
+            check_depth_then_logical(topology.objects_with_type(ty).collect());
+        }
+    }
+
+    /// Check that objects can be sorted by OS index
+    #[test]
+    fn sorted_by_os_index() {
+        let topology = Topology::test_instance();
+        let sorted = topology.objects().sorted_by_os_index().collect::<Vec<_>>();
+        assert_eq!(sorted.len(), topology.objects().count());
+        for pair in sorted.windows(2) {
+            match (pair[0].os_index(), pair[1].os_index()) {
+                (Some(first), Some(second)) => assert!(first <= second),
+                (None, Some(_)) => panic!("objects without an OS index should come last"),
+                (_, None) => {}
+            }
+        }
+        compare_object_sets(sorted.into_iter(), topology.objects()).unwrap();
+    }
 }
@@ -15,14 +15,19 @@
 pub mod attributes;
 pub mod depth;
 pub mod distance;
+pub mod filter;
+pub mod graph;
+pub mod handle;
 pub(crate) mod hierarchy;
-pub(crate) mod lists;
+pub mod lists;
+pub mod owned;
 pub mod search;
 pub mod types;
 
 use self::{
     attributes::{DownstreamAttributes, ObjectAttributes, PCIDomain},
     depth::{Depth, NormalDepth},
+    filter::ObjectFilter,
     types::ObjectType,
 };
 #[cfg(doc)]
@@ -34,7 +39,7 @@ use crate::{
         self, int,
         transparent::{AsNewtype, TransparentNewtype},
     },
-    info::TextualInfo,
+    info::{InfoKey, TextualInfo},
     memory::nodeset::NodeSet,
 };
 #[cfg(feature = "hwloc-2_3_0")]
@@ -42,12 +47,14 @@ use crate::{
     errors::{self, HybridError, NulError},
     ffi::string::LibcString,
 };
+#[cfg(any(test, feature = "proptest"))]
+use enum_iterator::Sequence;
 use hwlocality_sys::{hwloc_obj, HWLOC_UNKNOWN_INDEX};
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
-    ffi::{c_char, CStr},
+    ffi::{c_char, c_void, CStr},
     fmt::{self, Debug, Display},
     iter::FusedIterator,
     ops::Deref,
@@ -115,7 +122,7 @@ use std::{
 // - memory_arity is in sync with memory_first_child
 // - io_arity is in sync with io_first_child
 // - misc_arity is in sync with misc_first_child
-// - infos_count is in sync with infos
+// - infos_count (infos.count in hwloc v3.0) is in sync with infos
 // - userdata should not be touched as topology duplication aliases it
 // - gp_index is stable by API contract
 #[allow(clippy::non_send_fields_in_send_ty, missing_copy_implementations)]
@@ -204,6 +211,20 @@ impl TopologyObject {
     pub fn global_persistent_index(&self) -> TopologyObjectID {
         self.0.gp_index
     }
+
+    /// Application-given private data pointer
+    ///
+    /// This pointer is initialized to null by hwloc, and may only be set by
+    /// C code that shares this topology, since hwlocality does not provide a
+    /// way to modify it. Its meaning is entirely application-defined, so it
+    /// is only exposed as an opaque pointer here.
+    ///
+    /// Beware that topology duplication copies this pointer as-is, so the
+    /// same target may be shared by several topologies.
+    #[doc(alias = "hwloc_obj::userdata")]
+    pub fn userdata(&self) -> *const c_void {
+        self.0.userdata.cast_const()
+    }
 }
 
 /// Global persistent [`TopologyObject`] ID
@@ -245,7 +266,22 @@ impl TopologyObject {
     }
 
     /// Chain of parent objects up to the topology root
-    pub fn ancestors(&self) -> impl FusedIterator<Item = &Self> + Clone {
+    ///
+    /// Ancestors are yielded from the parent of this object to the root
+    /// [`Machine`](ObjectType::Machine) object. Use [`Ancestors::with_type()`]
+    /// to only inspect the enclosing levels of a certain type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let pu = topology.objects_with_type(ObjectType::PU).next().unwrap();
+    /// let root = pu.ancestors().last().unwrap();
+    /// assert_eq!(root.object_type(), ObjectType::Machine);
+    /// assert_eq!(pu.ancestors().with_type(ObjectType::Core).count(), 1);
+    /// ```
+    pub fn ancestors(&self) -> Ancestors<'_> {
         Ancestors(self)
     }
 
@@ -294,8 +330,7 @@ impl TopologyObject {
     /// [`Group`]: ObjectType::Group
     #[doc(alias = "hwloc_get_ancestor_obj_by_type")]
     pub fn first_ancestor_with_type(&self, ty: ObjectType) -> Option<&Self> {
-        self.ancestors()
-            .find(|ancestor| ancestor.object_type() == ty)
+        self.ancestors().with_type(ty).next()
     }
 
     /// Search for the first ancestor that is shared with another object
@@ -430,8 +465,23 @@ impl TopologyObject {
 }
 
 /// Iterator over ancestors of a topology object
+///
+/// This is returned by [`TopologyObject::ancestors()`].
 #[derive(Copy, Clone, Debug)]
-struct Ancestors<'object>(&'object TopologyObject);
+pub struct Ancestors<'object>(&'object TopologyObject);
+//
+impl<'object> Ancestors<'object> {
+    /// Only yield the remaining ancestors that have a certain type
+    ///
+    /// There can be more than one of these ancestors when `ty` is
+    /// [`ObjectType::Group`].
+    pub fn with_type(
+        self,
+        ty: ObjectType,
+    ) -> impl FusedIterator<Item = &'object TopologyObject> + Clone {
+        self.filter(move |ancestor| ancestor.object_type() == ty)
+    }
+}
 //
 impl<'object> Iterator for Ancestors<'object> {
     type Item = &'object TopologyObject;
@@ -481,6 +531,37 @@ impl TopologyObject {
         unsafe { ffi::deref_ptr_mut(&self.0.prev_cousin).map(|raw| raw.as_newtype()) }
     }
 
+    /// Objects of same type and depth that come after this one, by increasing
+    /// logical index
+    pub fn next_cousins(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        std::iter::successors(self.next_cousin(), |cousin| cousin.next_cousin())
+    }
+
+    /// Objects of same type and depth that come before this one, by decreasing
+    /// logical index
+    pub fn prev_cousins(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        std::iter::successors(self.prev_cousin(), |cousin| cousin.prev_cousin())
+    }
+
+    /// All objects of same type and depth, including this one, by increasing
+    /// logical index
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let pu = topology.objects_with_type(ObjectType::PU).last().unwrap();
+    /// assert_eq!(
+    ///     pu.cousins().count(),
+    ///     topology.objects_with_type(ObjectType::PU).count()
+    /// );
+    /// ```
+    pub fn cousins(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        let first = self.prev_cousins().last().unwrap_or(self);
+        std::iter::successors(Some(first), |cousin| cousin.next_cousin())
+    }
+
     /// Index in the parent's relevant child list for this object type
     #[doc(alias = "hwloc_obj::sibling_rank")]
     pub fn sibling_rank(&self) -> usize {
@@ -504,6 +585,34 @@ impl TopologyObject {
         //           from &self, which itself is derived from &Topology
         unsafe { ffi::deref_ptr_mut(&self.0.prev_sibling).map(|raw| raw.as_newtype()) }
     }
+
+    /// Objects below the same parent, in the same child list, that come after
+    /// this one
+    pub fn next_siblings(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        std::iter::successors(self.next_sibling(), |sibling| sibling.next_sibling())
+    }
+
+    /// Objects below the same parent, in the same child list, that come before
+    /// this one, in reverse order
+    pub fn prev_siblings(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        std::iter::successors(self.prev_sibling(), |sibling| sibling.prev_sibling())
+    }
+
+    /// All objects below the same parent, in the same child list, including
+    /// this one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let core = topology.objects_with_type(ObjectType::Core).next().unwrap();
+    /// assert!(core.siblings().any(|sibling| std::ptr::eq(sibling, core)));
+    /// ```
+    pub fn siblings(&self) -> impl FusedIterator<Item = &Self> + Clone {
+        let first = self.prev_siblings().last().unwrap_or(self);
+        std::iter::successors(Some(first), |sibling| sibling.next_sibling())
+    }
 }
 
 /// # Children
@@ -653,6 +762,73 @@ impl TopologyObject {
         unsafe { self.singly_linked_children(self.0.misc_first_child, self.misc_arity()) }
     }
 
+    /// Total number of children (normal, memory, I/O and Misc)
+    ///
+    /// This is the number of objects that [`all_children()`] yields.
+    ///
+    /// [`all_children()`]: Self::all_children()
+    pub fn total_arity(&self) -> usize {
+        self.normal_arity() + self.memory_arity() + self.io_arity() + self.misc_arity()
+    }
+
+    /// Number of children in the child list of kind `kind`
+    ///
+    /// This is equivalent to calling [`normal_arity()`], [`memory_arity()`],
+    /// [`io_arity()`] or [`misc_arity()`], and is mostly useful in code that
+    /// handles all kinds of children in a generic way.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{object::ChildKind, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for obj in topology.objects() {
+    ///     if let Some(parent) = obj.parent() {
+    ///         let kind = ChildKind::of_type(obj.object_type());
+    ///         assert!(parent.arity(kind) >= 1);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`io_arity()`]: Self::io_arity()
+    /// [`memory_arity()`]: Self::memory_arity()
+    /// [`misc_arity()`]: Self::misc_arity()
+    /// [`normal_arity()`]: Self::normal_arity()
+    pub fn arity(&self, kind: ChildKind) -> usize {
+        match kind {
+            ChildKind::Normal => self.normal_arity(),
+            ChildKind::Memory => self.memory_arity(),
+            ChildKind::IO => self.io_arity(),
+            ChildKind::Misc => self.misc_arity(),
+        }
+    }
+
+    /// Number of descendants of this object that match `filter`
+    ///
+    /// All descendants are considered, including memory, I/O and Misc
+    /// objects, but this object itself is not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{
+    /// #     object::{filter::ObjectFilter, types::ObjectType},
+    /// #     Topology,
+    /// # };
+    /// # let topology = Topology::test_instance();
+    /// let cores = ObjectFilter::new().with_type(ObjectType::Core);
+    /// for package in topology.objects_with_type(ObjectType::Package) {
+    ///     if package.descendant_count(&cores) < 64 {
+    ///         println!("{package} has less than 64 cores");
+    ///     }
+    /// }
+    /// ```
+    pub fn descendant_count(&self, filter: &ObjectFilter) -> usize {
+        self.all_children()
+            .map(|child| usize::from(filter.matches(child)) + child.descendant_count(filter))
+            .sum()
+    }
+
     /// Full list of children (normal, then memory, then I/O, then Misc)
     #[doc(alias = "hwloc_get_next_child")]
     pub fn all_children(&self) -> impl FusedIterator<Item = &Self> + Clone {
@@ -869,22 +1045,18 @@ impl TopologyObject {
     /// exist, although sane users should not leverage this possibility.
     #[doc(alias = "hwloc_obj::infos")]
     pub fn infos(&self) -> &[TextualInfo] {
-        if self.0.infos.is_null() {
-            assert_eq!(
-                self.0.infos_count, 0,
-                "Got null infos pointer with nonzero info count"
-            );
+        #[cfg(not(feature = "hwloc-3_0_0"))]
+        let (infos, count) = (self.0.infos, self.0.infos_count);
+        #[cfg(feature = "hwloc-3_0_0")]
+        let (infos, count) = (self.0.infos.array, self.0.infos.count);
+        if infos.is_null() {
+            assert_eq!(count, 0, "Got null infos pointer with nonzero info count");
             return &[];
         }
         // SAFETY: - infos and count are assumed in sync per type invariant
         //         - infos are assumed to be valid per type invariant
         //         - AsNewtype is trusted to be implemented correctly
-        unsafe {
-            std::slice::from_raw_parts(
-                self.0.infos.as_newtype(),
-                int::expect_usize(self.0.infos_count),
-            )
-        }
+        unsafe { std::slice::from_raw_parts(infos.as_newtype(), int::expect_usize(count)) }
     }
 
     /// Search the given key name in object infos and return the corresponding value
@@ -908,6 +1080,24 @@ impl TopologyObject {
         })
     }
 
+    /// Search a well-known info in object infos and return its value
+    ///
+    /// This is a typo-proof version of [`TopologyObject::info()`] for the info
+    /// names that are documented by hwloc, with the same caveats.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{info::InfoKey, Topology};
+    /// # let topology = Topology::test_instance();
+    /// if let Some(model) = topology.root_object().info_typed(InfoKey::DMIProductName) {
+    ///     println!("Running on a {model:?}");
+    /// }
+    /// ```
+    pub fn info_typed(&self, key: InfoKey) -> Option<&CStr> {
+        self.info(key.name())
+    }
+
     /// Add the given info name and value pair to the given object
     ///
     /// The info is appended to the existing info array even if another key with
@@ -941,57 +1131,265 @@ impl TopologyObject {
     }
 }
 
-// # Internal utilities
+/// # Textual description
 impl TopologyObject {
-    /// Display this object's type and attributes
-    fn display(&self, f: &mut fmt::Formatter<'_>, verbose: bool) -> fmt::Result {
-        // SAFETY: - These are indeed snprintf-like APIs
+    /// Multi-line description of this object, in the style of `hwloc-info`
+    ///
+    /// The first line is a header of the form `<type> L#<logical index>`, and
+    /// it is followed by one indented `key = value` line per property of the
+    /// object. The amount of properties that are listed is controlled by the
+    /// `verbosity` parameter, see [`DescriptionVerbosity`] for details.
+    ///
+    /// Unlike the [`Display`] and [`Debug`] implementations, which are
+    /// designed to fit in log messages, this is meant for human-oriented
+    /// inspection of a single object, e.g. in diagnostic tools.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{object::DescriptionVerbosity, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let root = topology.root_object();
+    /// let description = root.describe(DescriptionVerbosity::Normal);
+    /// assert!(description.starts_with("Machine L#0"));
+    /// println!("{description}");
+    /// ```
+    #[doc(alias = "hwloc_obj_attr_snprintf")]
+    #[doc(alias = "hwloc_obj_type_snprintf")]
+    pub fn describe(&self, verbosity: DescriptionVerbosity) -> String {
+        use std::fmt::Write;
+        let mut out = format!("{} L#{}", self.type_string(false), self.logical_index());
+        if verbosity == DescriptionVerbosity::Summary {
+            return out;
+        }
+        let verbose = verbosity == DescriptionVerbosity::Verbose;
+        let mut line = |key: &str, value: &dyn Display| {
+            write!(out, "\n {key} = {value}").expect("Writing to a String can't fail");
+        };
+
+        // Basic identity
+        line("type", &self.object_type());
+        line("full type", &self.type_string(true));
+        line("logical index", &self.logical_index());
+        if let Some(os_index) = self.os_index() {
+            line("os index", &os_index);
+        }
+        if verbose {
+            line("gp index", &self.global_persistent_index());
+        }
+        if let Some(name) = self.name() {
+            line("name", &name.to_string_lossy());
+        }
+        if let Some(subtype) = self.subtype() {
+            line("subtype", &subtype.to_string_lossy());
+        }
+
+        // Position in the topology tree
+        if verbose {
+            line("depth", &self.depth());
+            line("sibling rank", &self.sibling_rank());
+            line("children", &self.normal_arity());
+            line("memory children", &self.memory_arity());
+            line("i/o children", &self.io_arity());
+            line("misc children", &self.misc_arity());
+        }
+
+        // Type-specific attributes
+        let attributes = self.attributes_string("\n", verbose);
+        for attribute in attributes.lines().filter(|attr| !attr.is_empty()) {
+            match attribute.split_once('=') {
+                Some((key, value)) => line(key, &value),
+                None => line("attribute", &attribute),
+            }
+        }
+
+        // CPU and NUMA node sets
+        if let Some(cpuset) = self.cpuset() {
+            line("cpuset", &cpuset);
+        }
+        if let (true, Some(complete_cpuset)) = (verbose, self.complete_cpuset()) {
+            line("complete cpuset", &complete_cpuset);
+        }
+        if let Some(nodeset) = self.nodeset() {
+            line("nodeset", &nodeset);
+        }
+        if let (true, Some(complete_nodeset)) = (verbose, self.complete_nodeset()) {
+            line("complete nodeset", &complete_nodeset);
+        }
+
+        // Textual infos
+        for info in self.infos() {
+            let key = format!("info {}", info.name().to_string_lossy());
+            line(&key, &info.value().to_string_lossy());
+        }
+        out
+    }
+
+    /// hwloc's textual representation of this object's type
+    ///
+    /// This is the type name that hwloc uses in its own textual output. If
+    /// `verbose` is not set, shorter names are used where available, e.g.
+    /// `L1Cache` becomes `L1`.
+    ///
+    /// Together with [`attributes_string()`](Self::attributes_string), this
+    /// lets you lay out object descriptions differently from the [`Display`]
+    /// implementation, which combines both strings with the object's cpuset.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let root = topology.root_object();
+    /// assert_eq!(root.type_string(false), "Machine");
+    /// ```
+    #[doc(alias = "hwloc_obj_type_snprintf")]
+    pub fn type_string(&self, verbose: bool) -> String {
+        // SAFETY: - This is indeed an snprintf-like API
         //         - Object validity trusted by type invariant
         //         - verbose translates nicely into a C-style boolean
-        //         - separators are valid C strings
-        let (type_chars, attr_chars) = unsafe {
-            let type_chars = ffi::call_snprintf(|buf, len| {
+        let type_chars = unsafe {
+            ffi::call_snprintf(|buf, len| {
                 hwlocality_sys::hwloc_obj_type_snprintf(buf, len, &self.0, verbose.into())
-            });
+            })
+        };
+        // SAFETY: Output of call_snprintf should be a valid C string
+        unsafe { CStr::from_ptr(type_chars.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
 
-            let separator = if f.alternate() {
-                b",\n  \0".as_ptr()
-            } else {
-                b", \0".as_ptr()
-            }
-            .cast::<c_char>();
-            let attr_chars = ffi::call_snprintf(|buf, len| {
+    /// hwloc's textual representation of this object's attributes
+    ///
+    /// Attributes are printed as `key=value` pairs, with `separator` inserted
+    /// between consecutive attributes. If `verbose` is set, more attributes
+    /// are printed. An empty string is returned if the object has no
+    /// attribute worth printing.
+    ///
+    /// Since hwloc works with C strings, `separator` is truncated at its
+    /// first NUL character, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for obj in topology.objects_with_type(ObjectType::NUMANode) {
+    ///     println!("{}: {}", obj.type_string(false), obj.attributes_string(" | ", false));
+    /// }
+    /// ```
+    #[doc(alias = "hwloc_obj_attr_snprintf")]
+    pub fn attributes_string(&self, separator: &str, verbose: bool) -> String {
+        let mut separator = separator
+            .bytes()
+            .take_while(|&byte| byte != 0)
+            .collect::<Vec<u8>>();
+        separator.push(0);
+        // SAFETY: - This is indeed an snprintf-like API
+        //         - Object validity trusted by type invariant
+        //         - verbose translates nicely into a C-style boolean
+        //         - separator is a valid C string (NUL-terminated above)
+        let attr_chars = unsafe {
+            ffi::call_snprintf(|buf, len| {
                 hwlocality_sys::hwloc_obj_attr_snprintf(
                     buf,
                     len,
                     &self.0,
-                    separator,
+                    separator.as_ptr().cast::<c_char>(),
                     verbose.into(),
                 )
-            });
-            (type_chars, attr_chars)
+            })
         };
+        // SAFETY: Output of call_snprintf should be a valid C string
+        unsafe { CStr::from_ptr(attr_chars.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// Amount of detail in [`TopologyObject::describe()`] output
+#[cfg_attr(any(test, feature = "proptest"), derive(Sequence))]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum DescriptionVerbosity {
+    /// Only the `<type> L#<logical index>` header line
+    Summary,
+
+    /// Object identity, type-specific attributes, cpuset, nodeset and
+    /// textual infos
+    ///
+    /// This roughly matches the default output of `hwloc-info`.
+    #[default]
+    Normal,
+
+    /// Like [`Normal`](Self::Normal), plus the object's global persistent
+    /// index, position in the topology tree, complete cpuset and nodeset, and
+    /// verbose type-specific attributes
+    Verbose,
+}
+
+/// Kind of child list that an object belongs to
+///
+/// Objects are listed in one of four children lists of their parent,
+/// depending on their type. See [`TopologyObject::arity()`].
+#[cfg_attr(any(test, feature = "proptest"), derive(Sequence))]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ChildKind {
+    /// Normal children, see [`TopologyObject::normal_children()`]
+    Normal,
+
+    /// Memory children, see [`TopologyObject::memory_children()`]
+    Memory,
+
+    /// I/O children, see [`TopologyObject::io_children()`]
+    #[doc(alias = "Io")]
+    IO,
+
+    /// Misc children, see [`TopologyObject::misc_children()`]
+    Misc,
+}
+//
+impl ChildKind {
+    /// Kind of child list that objects of type `object_type` belong to
+    pub fn of_type(object_type: ObjectType) -> Self {
+        if object_type.is_normal() {
+            Self::Normal
+        } else if object_type.is_memory() {
+            Self::Memory
+        } else if object_type.is_io() {
+            Self::IO
+        } else {
+            debug_assert_eq!(
+                object_type,
+                ObjectType::Misc,
+                "Objects should be normal, memory, I/O or Misc objects"
+            );
+            Self::Misc
+        }
+    }
+}
+
+// # Internal utilities
+impl TopologyObject {
+    /// Display this object's type and attributes
+    fn display(&self, f: &mut fmt::Formatter<'_>, verbose: bool) -> fmt::Result {
+        let type_str = self.type_string(verbose);
+        let separator = if f.alternate() { ",\n  " } else { ", " };
+        let attr_str = self.attributes_string(separator, verbose);
 
         let cpuset_str = self
             .cpuset()
             .map_or_else(String::new, |cpuset| format!(" with {cpuset}"));
 
-        // SAFETY: - Output of call_snprintf should be valid C strings
-        //         - We're not touching type_chars and attr_chars while type_str
-        //           and attr_str are live.
-        unsafe {
-            let type_str = CStr::from_ptr(type_chars.as_ptr()).to_string_lossy();
-            let attr_str = CStr::from_ptr(attr_chars.as_ptr()).to_string_lossy();
-            let type_and_cpuset = format!("{type_str}{cpuset_str}");
-            if attr_str.is_empty() {
-                f.pad(&type_and_cpuset)
-            } else if f.alternate() {
-                let s = format!("{type_and_cpuset} (\n  {attr_str}\n)");
-                f.pad(&s)
-            } else {
-                let s = format!("{type_and_cpuset} ({attr_str})");
-                f.pad(&s)
-            }
+        let type_and_cpuset = format!("{type_str}{cpuset_str}");
+        if attr_str.is_empty() {
+            f.pad(&type_and_cpuset)
+        } else if f.alternate() {
+            let s = format!("{type_and_cpuset} (\n  {attr_str}\n)");
+            f.pad(&s)
+        } else {
+            let s = format!("{type_and_cpuset} ({attr_str})");
+            f.pad(&s)
         }
     }
 
@@ -1098,7 +1496,7 @@ pub(crate) mod tests {
     use super::*;
     use crate::{
         strategies::{any_object, any_string, set_with_reference, test_object},
-        topology::Topology,
+        topology::{synthetic::SyntheticBuilder, Topology},
     };
     use proptest::prelude::*;
     use similar_asserts::assert_eq;
@@ -1123,6 +1521,8 @@ pub(crate) mod tests {
         check_children(obj)?;
         check_infos(obj)?;
         check_displays(obj)?;
+        check_description(obj)?;
+        check_userdata(obj)?;
         Ok(())
     }
 
@@ -1157,6 +1557,13 @@ pub(crate) mod tests {
         let first_ancestor = obj.ancestors().next().unwrap();
         prop_assert!(ptr::eq(parent, first_ancestor));
 
+        for ancestor in obj.ancestors() {
+            let ty = ancestor.object_type();
+            let mut with_type = obj.ancestors().with_type(ty);
+            prop_assert!(with_type.clone().all(|other| other.object_type() == ty));
+            prop_assert!(with_type.any(|other| ptr::eq(other, ancestor)));
+        }
+
         if let (Depth::Normal(parent_depth), Depth::Normal(obj_depth)) =
             (parent.depth(), obj.depth())
         {
@@ -1207,21 +1614,9 @@ pub(crate) mod tests {
 
     /// Check that an object's cousin and siblings have the expected properties
     fn check_cousins_and_siblings(obj: &TopologyObject) -> Result<(), TestCaseError> {
-        let siblings_len = if let Some(parent) = obj.parent() {
-            let ty = obj.object_type();
-            if ty.is_normal() {
-                parent.normal_arity()
-            } else if ty.is_memory() {
-                parent.memory_arity()
-            } else if ty.is_io() {
-                parent.io_arity()
-            } else {
-                prop_assert_eq!(ty, ObjectType::Misc);
-                parent.misc_arity()
-            }
-        } else {
-            1
-        };
+        let siblings_len = obj.parent().map_or(1, |parent| {
+            parent.arity(ChildKind::of_type(obj.object_type()))
+        });
         let topology = Topology::test_instance();
         let cousins_len = topology.num_objects_at_depth(obj.depth());
 
@@ -1255,6 +1650,26 @@ pub(crate) mod tests {
             prop_assert_eq!(obj.sibling_rank(), siblings_len - 1);
         }
 
+        prop_assert_eq!(
+            obj.next_cousins().count(),
+            cousins_len - obj.logical_index() - 1
+        );
+        prop_assert_eq!(obj.prev_cousins().count(), obj.logical_index());
+        prop_assert_eq!(obj.cousins().count(), cousins_len);
+        for (idx, cousin) in obj.cousins().enumerate() {
+            prop_assert_eq!(cousin.logical_index(), idx);
+        }
+
+        prop_assert_eq!(
+            obj.next_siblings().count(),
+            siblings_len - obj.sibling_rank() - 1
+        );
+        prop_assert_eq!(obj.prev_siblings().count(), obj.sibling_rank());
+        prop_assert_eq!(obj.siblings().count(), siblings_len);
+        for (idx, sibling) in obj.siblings().enumerate() {
+            prop_assert_eq!(sibling.sibling_rank(), idx);
+        }
+
         Ok(())
     }
 
@@ -1291,6 +1706,24 @@ pub(crate) mod tests {
             obj.all_children().count(),
             obj.normal_arity() + obj.memory_arity() + obj.io_arity() + obj.misc_arity()
         );
+        prop_assert_eq!(obj.total_arity(), obj.all_children().count());
+        prop_assert_eq!(
+            enum_iterator::all::<ChildKind>()
+                .map(|kind| obj.arity(kind))
+                .sum::<usize>(),
+            obj.total_arity()
+        );
+        for child in obj.all_children() {
+            prop_assert!(obj.arity(ChildKind::of_type(child.object_type())) >= 1);
+        }
+        prop_assert!(obj.descendant_count(&ObjectFilter::new()) >= obj.total_arity());
+        if obj.object_type().is_normal() && obj.object_type() != ObjectType::PU {
+            let pus = ObjectFilter::new().with_type(ObjectType::PU);
+            prop_assert_eq!(
+                Some(obj.descendant_count(&pus)),
+                obj.cpuset().unwrap().weight()
+            );
+        }
 
         // NOTE: Most parent-child relations are checked when checking the
         //       parent, since that's agnostic to the kind of child we deal with
@@ -1314,6 +1747,12 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    /// Check that hwlocality does not set object userdata
+    fn check_userdata(obj: &TopologyObject) -> Result<(), TestCaseError> {
+        prop_assert!(obj.userdata().is_null());
+        Ok(())
+    }
+
     /// Check that an object's info metadata matches expectations
     fn check_infos(obj: &TopologyObject) -> Result<(), TestCaseError> {
         for info in obj.infos() {
@@ -1351,9 +1790,240 @@ pub(crate) mod tests {
         // Alternate displays should be longer than or identical to the norm
         prop_assert!(debug_alternate.len() >= debug.len());
         prop_assert!(display_alternate.len() >= display.len());
+
+        // Display output is built from the type and attribute strings
+        prop_assert!(display.starts_with(&obj.type_string(false)));
+        prop_assert!(debug.starts_with(&obj.type_string(true)));
+        let attributes = obj.attributes_string(", ", false);
+        if attributes.is_empty() {
+            prop_assert!(!display.ends_with(')'));
+        } else {
+            let suffix = format!(" ({attributes})");
+            prop_assert!(display.ends_with(&suffix));
+        }
+
+        // Separators are truncated at the first NUL
+        prop_assert_eq!(
+            obj.attributes_string("; \0ignored", true),
+            obj.attributes_string("; ", true)
+        );
+        Ok(())
+    }
+
+    /// Check that an object's multi-line description has the expected format
+    fn check_description(obj: &TopologyObject) -> Result<(), TestCaseError> {
+        let summary = obj.describe(DescriptionVerbosity::Summary);
+        let normal = obj.describe(DescriptionVerbosity::Normal);
+        let verbose = obj.describe(DescriptionVerbosity::Verbose);
+
+        // The summary is a single header line, which starts other descriptions
+        prop_assert!(!summary.contains('\n'));
+        let logical_suffix = format!(" L#{}", obj.logical_index());
+        prop_assert!(summary.ends_with(&logical_suffix));
+        let header = format!("{summary}\n");
+        prop_assert!(normal.starts_with(&header));
+        prop_assert!(verbose.starts_with(&header));
+
+        // Other lines are indented key-value pairs
+        for description in [&normal, &verbose] {
+            for line in description.lines().skip(1) {
+                prop_assert!(line.starts_with(' '));
+                prop_assert!(line.contains(" = "));
+            }
+        }
+        let has_line = |description: &str, line: String| description.lines().any(|l| l == line);
+        let type_line = format!(" type = {}", obj.object_type());
+        prop_assert!(has_line(&normal, type_line));
+        if let Some(cpuset) = obj.cpuset() {
+            let cpuset_line = format!(" cpuset = {cpuset}");
+            prop_assert!(has_line(&normal, cpuset_line));
+        }
+        for info in obj.infos() {
+            let info_line = format!(
+                " info {} = {}",
+                info.name().to_string_lossy(),
+                info.value().to_string_lossy()
+            );
+            prop_assert!(has_line(&normal, info_line.clone()));
+            prop_assert!(has_line(&verbose, info_line));
+        }
+
+        // Verbose descriptions are a superset of normal ones
+        prop_assert!(verbose.lines().count() >= normal.lines().count());
+        let depth_line = format!(" depth = {}", obj.depth());
+        prop_assert!(has_line(&verbose, depth_line));
         Ok(())
     }
 
+    /// Truth that a multi-line object description contains a certain line
+    fn has_line(description: &str, line: &str) -> bool {
+        description.lines().any(|l| l == line)
+    }
+
+    /// 2 packages with one NUMA node and 2 cores each, and 2 PUs per core
+    fn two_packages() -> Topology {
+        SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(1)
+            .cores(2)
+            .pus(2)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn arity() {
+        let topology = two_packages();
+        let root = topology.root_object();
+        assert_eq!(root.arity(ChildKind::Normal), 2);
+        assert_eq!(root.arity(ChildKind::Memory), 0);
+        assert_eq!(root.arity(ChildKind::IO), 0);
+        assert_eq!(root.arity(ChildKind::Misc), 0);
+        for package in topology.objects_with_type(ObjectType::Package) {
+            assert_eq!(package.arity(ChildKind::Normal), 2);
+            assert_eq!(package.arity(ChildKind::Memory), 1);
+            assert_eq!(package.total_arity(), 3);
+        }
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            for kind in enum_iterator::all::<ChildKind>() {
+                assert_eq!(pu.arity(kind), 0);
+            }
+        }
+    }
+
+    #[cfg(feature = "hwloc-2_3_0")]
+    #[test]
+    fn misc_arity() {
+        let mut topology = two_packages();
+        topology.edit(|editor| {
+            editor
+                .insert_misc_object("misc", Topology::root_object)
+                .unwrap();
+        });
+        let root = topology.root_object();
+        assert_eq!(root.arity(ChildKind::Misc), 1);
+        assert_eq!(root.arity(ChildKind::Normal), 2);
+        assert_eq!(root.total_arity(), 3);
+    }
+
+    #[test]
+    fn type_string() {
+        let topology = Topology::test_instance();
+        for (ty, name) in [
+            (ObjectType::Machine, "Machine"),
+            (ObjectType::Package, "Package"),
+            (ObjectType::Core, "Core"),
+            (ObjectType::PU, "PU"),
+        ] {
+            for obj in topology.objects_with_type(ty) {
+                assert_eq!(obj.type_string(false), name);
+                assert_eq!(obj.type_string(true), name);
+            }
+        }
+    }
+
+    #[test]
+    fn attributes_string() {
+        let topology = Topology::test_instance();
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            assert_eq!(pu.attributes_string(", ", false), "");
+        }
+        for obj in topology.objects() {
+            for verbose in [false, true] {
+                // Only the separator between attributes changes
+                let expected = obj.attributes_string(", ", verbose);
+                if !expected.contains('|') {
+                    assert_eq!(
+                        obj.attributes_string("|", verbose).replace('|', ", "),
+                        expected
+                    );
+                }
+            }
+            // Verbose output never has fewer attributes
+            assert!(
+                obj.attributes_string("|", true).split('|').count()
+                    >= obj.attributes_string("|", false).split('|').count()
+            );
+        }
+    }
+
+    /// Check [`DescriptionVerbosity::Summary`] output on the root object
+    #[test]
+    fn describe_summary() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        let summary = root.describe(DescriptionVerbosity::Summary);
+        assert_eq!(summary, format!("{} L#0", root.type_string(false)));
+    }
+
+    /// Check [`DescriptionVerbosity::Normal`] output on the root object
+    #[test]
+    fn describe_normal() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        let normal = root.describe(DescriptionVerbosity::Normal);
+        assert!(normal.starts_with(&format!("{} L#0\n", root.type_string(false))));
+        assert!(has_line(&normal, " type = Machine"));
+        assert!(has_line(&normal, " logical index = 0"));
+        assert!(has_line(
+            &normal,
+            &format!(" cpuset = {}", topology.cpuset())
+        ));
+        assert!(has_line(
+            &normal,
+            &format!(" nodeset = {}", topology.nodeset())
+        ));
+        for info in root.infos() {
+            assert!(has_line(
+                &normal,
+                &format!(
+                    " info {} = {}",
+                    info.name().to_string_lossy(),
+                    info.value().to_string_lossy()
+                )
+            ));
+        }
+        for verbose_only in [" depth = ", " gp index = ", " complete cpuset = "] {
+            assert!(!normal.lines().any(|l| l.starts_with(verbose_only)));
+        }
+    }
+
+    /// Check [`DescriptionVerbosity::Verbose`] output on the root object
+    #[test]
+    fn describe_verbose() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        let verbose = root.describe(DescriptionVerbosity::Verbose);
+        assert!(has_line(&verbose, " type = Machine"));
+        assert!(has_line(&verbose, &format!(" depth = {}", root.depth())));
+        assert!(has_line(
+            &verbose,
+            &format!(" children = {}", root.normal_arity())
+        ));
+        assert!(has_line(
+            &verbose,
+            &format!(" cpuset = {}", topology.cpuset())
+        ));
+        assert!(has_line(
+            &verbose,
+            &format!(" complete cpuset = {}", topology.complete_cpuset())
+        ));
+        assert!(has_line(
+            &verbose,
+            &format!(" complete nodeset = {}", topology.complete_nodeset())
+        ));
+        for info in root.infos() {
+            assert!(has_line(
+                &verbose,
+                &format!(
+                    " info {} = {}",
+                    info.name().to_string_lossy(),
+                    info.value().to_string_lossy()
+                )
+            ));
+        }
+    }
+
     /// Check that [`TopologyObject::is_symmetric_subtree()`] is correct
     #[test]
     fn is_symmetric_subtree() {
@@ -1463,6 +2133,99 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    proptest! {
+        /// Check that ancestors filtered by type are the matching subset of all
+        /// ancestors, in the same order
+        #[test]
+        fn ancestors_with_type(obj in test_object(), ty: ObjectType) {
+            let expected = obj
+                .ancestors()
+                .filter(|ancestor| ancestor.object_type() == ty)
+                .map(TopologyObject::global_persistent_index)
+                .collect::<Vec<_>>();
+            let actual = obj
+                .ancestors()
+                .with_type(ty)
+                .map(TopologyObject::global_persistent_index)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(&actual, &expected);
+            prop_assert_eq!(
+                obj.first_ancestor_with_type(ty)
+                    .map(TopologyObject::global_persistent_index),
+                expected.first().copied()
+            );
+        }
+    }
+
+    proptest! {
+        /// Check that cousins and siblings iterators enumerate the expected
+        /// objects, in the expected order
+        #[test]
+        fn cousins_and_siblings(obj in test_object()) {
+            let gp_indices = |objs: &mut dyn Iterator<Item = &TopologyObject>| {
+                objs.map(TopologyObject::global_persistent_index)
+                    .collect::<Vec<_>>()
+            };
+            let around = |prev: Vec<u64>, next: Vec<u64>| {
+                prev.into_iter()
+                    .rev()
+                    .chain(std::iter::once(obj.global_persistent_index()))
+                    .chain(next)
+                    .collect::<Vec<_>>()
+            };
+
+            let topology = Topology::test_instance();
+            let cousins = gp_indices(&mut obj.cousins());
+            prop_assert_eq!(
+                &cousins,
+                &gp_indices(&mut topology.objects_at_depth(obj.depth()))
+            );
+            prop_assert_eq!(
+                &cousins,
+                &around(
+                    gp_indices(&mut obj.prev_cousins()),
+                    gp_indices(&mut obj.next_cousins())
+                )
+            );
+
+            let siblings = gp_indices(&mut obj.siblings());
+            let expected_siblings = obj.parent().map_or_else(
+                || vec![obj.global_persistent_index()],
+                |parent| match ChildKind::of_type(obj.object_type()) {
+                    ChildKind::Normal => gp_indices(&mut parent.normal_children()),
+                    ChildKind::Memory => gp_indices(&mut parent.memory_children()),
+                    ChildKind::IO => gp_indices(&mut parent.io_children()),
+                    ChildKind::Misc => gp_indices(&mut parent.misc_children()),
+                },
+            );
+            prop_assert_eq!(&siblings, &expected_siblings);
+            prop_assert_eq!(
+                &siblings,
+                &around(
+                    gp_indices(&mut obj.prev_siblings()),
+                    gp_indices(&mut obj.next_siblings())
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn pu_ancestors_with_type() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        for pu in topology.objects_with_type(ObjectType::PU) {
+            let mut machines = pu.ancestors().with_type(ObjectType::Machine);
+            assert!(ptr::eq(machines.next().unwrap(), root));
+            assert!(machines.next().is_none());
+            assert_eq!(pu.ancestors().with_type(ObjectType::PU).count(), 0);
+            if let Some(core) = pu.parent().filter(|p| p.object_type() == ObjectType::Core) {
+                let mut cores = pu.ancestors().with_type(ObjectType::Core);
+                assert!(ptr::eq(cores.next().unwrap(), core));
+                assert!(cores.next().is_none());
+            }
+        }
+    }
+
     proptest! {
         // Probe ancestors by depth at valid and invalid depths
         #[test]
@@ -0,0 +1,445 @@
+//! Owned object subtrees
+//!
+//! [`TopologyObject`]s belong to their parent [`Topology`], and can only be
+//! accessed for as long as that topology is alive and not modified. This is
+//! sometimes inconvenient, for example when one wants to send a description
+//! of a part of the topology to another thread, or to cache it for longer
+//! than the topology lives.
+//!
+//! [`OwnedObject`] addresses this use case by deep-copying an object and its
+//! descendants into plain Rust data structures, which do not borrow from the
+//! topology. Use [`TopologyObject::to_owned_subtree()`] to build one.
+//!
+//! [`Topology`]: crate::topology::Topology
+
+use crate::{
+    cpu::cpuset::CpuSet,
+    memory::nodeset::NodeSet,
+    object::{
+        attributes::{
+            BridgeAttributes, CacheAttributes, GroupAttributes, MemoryPageType, OSDeviceAttributes,
+            ObjectAttributes, PCIDeviceAttributes,
+        },
+        depth::Depth,
+        types::ObjectType,
+        TopologyObject, TopologyObjectID,
+    },
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    ffi::{CStr, CString},
+    num::NonZeroU64,
+};
+
+/// # Owned copies
+impl TopologyObject {
+    /// Deep copy of this object and all of its descendants
+    ///
+    /// The resulting [`OwnedObject`] does not borrow from the topology, so it
+    /// can be sent to other threads or kept around after the topology has
+    /// been modified or dropped. It is not updated when the topology changes.
+    ///
+    /// Normal, memory, I/O and Misc children are all copied. The parent and
+    /// siblings of this object are not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let package = topology
+    ///     .objects_with_type(ObjectType::Package)
+    ///     .next()
+    ///     .unwrap_or_else(|| topology.root_object());
+    /// let owned = package.to_owned_subtree();
+    /// std::thread::spawn(move || {
+    ///     println!("{} has {} descendants", owned.object_type(), owned.descendants().count());
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// ```
+    pub fn to_owned_subtree(&self) -> OwnedObject {
+        OwnedObject {
+            object_type: self.object_type(),
+            subtype: self.subtype().map(CStr::to_owned),
+            name: self.name().map(CStr::to_owned),
+            depth: self.depth(),
+            logical_index: self.logical_index(),
+            os_index: self.os_index(),
+            global_persistent_index: self.global_persistent_index(),
+            total_memory: self.total_memory(),
+            attributes: self.attributes().map(OwnedAttributes::from),
+            cpuset: self.cpuset().map(|set| set.clone_target()),
+            complete_cpuset: self.complete_cpuset().map(|set| set.clone_target()),
+            nodeset: self.nodeset().map(|set| set.clone_target()),
+            complete_nodeset: self.complete_nodeset().map(|set| set.clone_target()),
+            infos: self
+                .infos()
+                .iter()
+                .map(|info| (info.name().to_owned(), info.value().to_owned()))
+                .collect(),
+            normal_children: self.normal_children().map(Self::to_owned_subtree).collect(),
+            memory_children: self.memory_children().map(Self::to_owned_subtree).collect(),
+            io_children: self.io_children().map(Self::to_owned_subtree).collect(),
+            misc_children: self.misc_children().map(Self::to_owned_subtree).collect(),
+        }
+    }
+}
+
+/// Owned deep copy of a [`TopologyObject`] and its descendants
+///
+/// Built using [`TopologyObject::to_owned_subtree()`]. Accessors mirror those
+/// of [`TopologyObject`], see their documentation for more information.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedObject {
+    /// Type of object
+    object_type: ObjectType,
+
+    /// Subtype string
+    subtype: Option<CString>,
+
+    /// Object name
+    name: Option<CString>,
+
+    /// Depth of the object in the topology it was copied from
+    depth: Depth,
+
+    /// Logical index of the object at its depth
+    logical_index: usize,
+
+    /// OS-provided physical index
+    os_index: Option<usize>,
+
+    /// Global persistent index
+    global_persistent_index: TopologyObjectID,
+
+    /// Total memory in bytes in NUMA nodes below this object
+    total_memory: u64,
+
+    /// Type-specific attributes
+    attributes: Option<OwnedAttributes>,
+
+    /// CPUs covered by this object
+    cpuset: Option<CpuSet>,
+
+    /// Complete CPU set of this object
+    complete_cpuset: Option<CpuSet>,
+
+    /// NUMA nodes covered by this object
+    nodeset: Option<NodeSet>,
+
+    /// Complete NUMA node set of this object
+    complete_nodeset: Option<NodeSet>,
+
+    /// Textual (name, value) information pairs
+    infos: Vec<(CString, CString)>,
+
+    /// Copies of the normal children
+    normal_children: Vec<Self>,
+
+    /// Copies of the memory children
+    memory_children: Vec<Self>,
+
+    /// Copies of the I/O children
+    io_children: Vec<Self>,
+
+    /// Copies of the Misc children
+    misc_children: Vec<Self>,
+}
+//
+impl OwnedObject {
+    /// Type of object
+    ///
+    /// See [`TopologyObject::object_type()`].
+    pub fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    /// Subtype string to better describe the type field
+    ///
+    /// See [`TopologyObject::subtype()`].
+    pub fn subtype(&self) -> Option<&CStr> {
+        self.subtype.as_deref()
+    }
+
+    /// Object-specific name, if any
+    ///
+    /// See [`TopologyObject::name()`].
+    pub fn name(&self) -> Option<&CStr> {
+        self.name.as_deref()
+    }
+
+    /// Depth of the object in the topology it was copied from
+    ///
+    /// See [`TopologyObject::depth()`].
+    pub fn depth(&self) -> Depth {
+        self.depth
+    }
+
+    /// Horizontal index in the whole list of similar objects
+    ///
+    /// See [`TopologyObject::logical_index()`].
+    pub fn logical_index(&self) -> usize {
+        self.logical_index
+    }
+
+    /// The OS-provided physical index number
+    ///
+    /// See [`TopologyObject::os_index()`].
+    pub fn os_index(&self) -> Option<usize> {
+        self.os_index
+    }
+
+    /// Global persistent index
+    ///
+    /// See [`TopologyObject::global_persistent_index()`].
+    pub fn global_persistent_index(&self) -> TopologyObjectID {
+        self.global_persistent_index
+    }
+
+    /// Total memory (in bytes) in NUMA nodes below this object
+    ///
+    /// See [`TopologyObject::total_memory()`].
+    pub fn total_memory(&self) -> u64 {
+        self.total_memory
+    }
+
+    /// Object type-specific attributes, if any
+    ///
+    /// See [`TopologyObject::attributes()`].
+    pub fn attributes(&self) -> Option<&OwnedAttributes> {
+        self.attributes.as_ref()
+    }
+
+    /// CPUs covered by this object
+    ///
+    /// See [`TopologyObject::cpuset()`].
+    pub fn cpuset(&self) -> Option<&CpuSet> {
+        self.cpuset.as_ref()
+    }
+
+    /// Complete CPU set of this object
+    ///
+    /// See [`TopologyObject::complete_cpuset()`].
+    pub fn complete_cpuset(&self) -> Option<&CpuSet> {
+        self.complete_cpuset.as_ref()
+    }
+
+    /// NUMA nodes covered by this object or containing this object
+    ///
+    /// See [`TopologyObject::nodeset()`].
+    pub fn nodeset(&self) -> Option<&NodeSet> {
+        self.nodeset.as_ref()
+    }
+
+    /// Complete NUMA node set of this object
+    ///
+    /// See [`TopologyObject::complete_nodeset()`].
+    pub fn complete_nodeset(&self) -> Option<&NodeSet> {
+        self.complete_nodeset.as_ref()
+    }
+
+    /// Complete list of (name, value) textual info pairs
+    ///
+    /// See [`TopologyObject::infos()`].
+    pub fn infos(&self) -> &[(CString, CString)] {
+        &self.infos[..]
+    }
+
+    /// Search the given key name in object infos and return the
+    /// corresponding value
+    ///
+    /// If multiple keys match the given name, only the first one is returned.
+    pub fn info(&self, key: &str) -> Option<&CStr> {
+        self.infos
+            .iter()
+            .find(|(name, _value)| name.as_bytes() == key.as_bytes())
+            .map(|(_name, value)| value.as_c_str())
+    }
+
+    /// Normal children of this object
+    ///
+    /// See [`TopologyObject::normal_children()`].
+    pub fn normal_children(&self) -> &[Self] {
+        &self.normal_children[..]
+    }
+
+    /// Memory children of this object
+    ///
+    /// See [`TopologyObject::memory_children()`].
+    pub fn memory_children(&self) -> &[Self] {
+        &self.memory_children[..]
+    }
+
+    /// I/O children of this object
+    ///
+    /// See [`TopologyObject::io_children()`].
+    pub fn io_children(&self) -> &[Self] {
+        &self.io_children[..]
+    }
+
+    /// Misc children of this object
+    ///
+    /// See [`TopologyObject::misc_children()`].
+    pub fn misc_children(&self) -> &[Self] {
+        &self.misc_children[..]
+    }
+
+    /// All children of this object
+    ///
+    /// Normal children are listed first, followed by memory, I/O and Misc
+    /// children, as in [`TopologyObject::all_children()`].
+    pub fn all_children(&self) -> impl DoubleEndedIterator<Item = &Self> + Clone {
+        self.normal_children
+            .iter()
+            .chain(&self.memory_children)
+            .chain(&self.io_children)
+            .chain(&self.misc_children)
+    }
+
+    /// All descendants of this object, in depth-first order
+    ///
+    /// This object itself is not included.
+    pub fn descendants(&self) -> impl Iterator<Item = &Self> {
+        let mut stack = self.all_children().rev().collect::<Vec<_>>();
+        std::iter::from_fn(move || {
+            let next = stack.pop()?;
+            stack.extend(next.all_children().rev());
+            Some(next)
+        })
+    }
+}
+
+/// Owned copy of [`ObjectAttributes`]
+///
+/// Most attribute types do not borrow from the topology and are stored as-is.
+/// [`NUMANode`] attributes are the exception, their memory page types are
+/// copied into a [`Vec`].
+///
+/// [`NUMANode`]: ObjectType::NUMANode
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum OwnedAttributes {
+    /// [`NUMANode`]-specific attributes
+    ///
+    /// [`NUMANode`]: ObjectType::NUMANode
+    NUMANode {
+        /// Node-local memory in bytes
+        local_memory: Option<NonZeroU64>,
+
+        /// Memory page types, sorted by increasing page size
+        page_types: Vec<MemoryPageType>,
+    },
+
+    /// CPU cache-specific attributes
+    Cache(CacheAttributes),
+
+    /// [`Group`]-specific attributes
+    ///
+    /// [`Group`]: ObjectType::Group
+    Group(GroupAttributes),
+
+    /// [`PCIDevice`]-specific attributes
+    ///
+    /// [`PCIDevice`]: ObjectType::PCIDevice
+    PCIDevice(PCIDeviceAttributes),
+
+    /// [`Bridge`]-specific attributes
+    ///
+    /// [`Bridge`]: ObjectType::Bridge
+    Bridge(BridgeAttributes),
+
+    /// [`OSDevice`]-specific attributes
+    ///
+    /// [`OSDevice`]: ObjectType::OSDevice
+    OSDevice(OSDeviceAttributes),
+}
+//
+impl From<ObjectAttributes<'_>> for OwnedAttributes {
+    fn from(attributes: ObjectAttributes<'_>) -> Self {
+        match attributes {
+            ObjectAttributes::NUMANode(numa) => Self::NUMANode {
+                local_memory: numa.local_memory(),
+                page_types: numa.page_types().to_vec(),
+            },
+            ObjectAttributes::Cache(cache) => Self::Cache(*cache),
+            ObjectAttributes::Group(group) => Self::Group(*group),
+            ObjectAttributes::PCIDevice(pci) => Self::PCIDevice(*pci),
+            ObjectAttributes::Bridge(bridge) => Self::Bridge(*bridge),
+            ObjectAttributes::OSDevice(osdev) => Self::OSDevice(*osdev),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::Topology;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(OwnedObject:
+        Clone, Debug, PartialEq, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(OwnedAttributes:
+        Clone, Debug, PartialEq, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    /// Check that an owned copy matches the original object
+    fn check_copy(owned: &OwnedObject, obj: &TopologyObject) {
+        assert_eq!(owned.object_type(), obj.object_type());
+        assert_eq!(owned.subtype(), obj.subtype());
+        assert_eq!(owned.name(), obj.name());
+        assert_eq!(owned.depth(), obj.depth());
+        assert_eq!(owned.logical_index(), obj.logical_index());
+        assert_eq!(owned.os_index(), obj.os_index());
+        assert_eq!(
+            owned.global_persistent_index(),
+            obj.global_persistent_index()
+        );
+        assert_eq!(owned.total_memory(), obj.total_memory());
+        assert_eq!(
+            owned.attributes(),
+            obj.attributes().map(OwnedAttributes::from).as_ref()
+        );
+        assert_eq!(owned.cpuset(), obj.cpuset().as_deref());
+        assert_eq!(owned.complete_cpuset(), obj.complete_cpuset().as_deref());
+        assert_eq!(owned.nodeset(), obj.nodeset().as_deref());
+        assert_eq!(owned.complete_nodeset(), obj.complete_nodeset().as_deref());
+        assert_eq!(owned.infos().len(), obj.infos().len());
+        for ((name, value), info) in owned.infos().iter().zip(obj.infos()) {
+            assert_eq!(name.as_c_str(), info.name());
+            assert_eq!(value.as_c_str(), info.value());
+            if let Ok(name) = name.to_str() {
+                assert_eq!(owned.info(name), obj.info(name));
+            }
+        }
+        assert_eq!(owned.all_children().count(), obj.all_children().count());
+        for (owned_child, child) in owned.all_children().zip(obj.all_children()) {
+            check_copy(owned_child, child);
+        }
+    }
+
+    #[test]
+    fn whole_topology() {
+        let topology = Topology::test_instance();
+        let root = topology.root_object();
+        let owned = root.to_owned_subtree();
+        check_copy(&owned, root);
+        assert_eq!(owned.descendants().count(), topology.objects().count() - 1);
+    }
+
+    #[test]
+    fn every_subtree() {
+        let topology = Topology::test_instance();
+        for obj in topology.objects() {
+            check_copy(&obj.to_owned_subtree(), obj);
+        }
+    }
+}
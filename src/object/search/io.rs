@@ -1,10 +1,14 @@
 //! Looking for I/O objects
 
 use crate::{
+    bitmap::BitmapRef,
+    cpu::cpuset::CpuSet,
     errors::ParameterError,
+    memory::nodeset::NodeSet,
     object::{
         attributes::{ObjectAttributes, PCIDomain},
         depth::Depth,
+        types::OSDeviceType,
         TopologyObject,
     },
     topology::Topology,
@@ -33,6 +37,21 @@ impl Topology {
 
     /// Find the PCI device object matching the PCI bus id given domain, bus
     /// device and function PCI bus id
+    ///
+    /// Once a device has been found, the CPUs that are close to it can be
+    /// found via its first non-I/O ancestor, see
+    /// [`TopologyObject::first_non_io_ancestor()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// // Look up the device at PCI address 0000:3b:00.0
+    /// if let Some(device) = topology.pci_device_by_bus_id(0, 0x3b, 0x00, 0) {
+    ///     println!("Found PCI device {device}");
+    /// }
+    /// ```
     #[doc(alias = "hwloc_get_pcidev_by_busid")]
     pub fn pci_device_by_bus_id(
         &self,
@@ -60,6 +79,25 @@ impl Topology {
     ///
     /// - [`ParameterError`] if the given string does not match the PCI bus id
     ///   format given above
+    ///
+    /// # Examples
+    ///
+    /// This is handy for resolving a device that is known by its PCI address
+    /// in other tools (e.g. storage, network or GPU tooling) and finding which
+    /// CPUs are local to it:
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// if let Some(device) = topology.pci_device_by_bus_id_string("0000:3b:00.0")? {
+    ///     let cpuset = device
+    ///         .first_non_io_ancestor()
+    ///         .and_then(|ancestor| ancestor.cpuset())
+    ///         .expect("PCI devices should have a non-I/O ancestor with a cpuset");
+    ///     println!("{device} is local to CPUs {cpuset}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
     #[doc(alias = "hwloc_get_pcidev_by_busidstring")]
     pub fn pci_device_by_bus_id_string(
         &self,
@@ -98,6 +136,101 @@ impl Topology {
         self.objects_at_depth(Depth::OSDevice)
     }
 
+    /// Enumerate OS devices of a certain type in the system
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::OSDeviceType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// for gpu in topology.os_devices_with_type(OSDeviceType::GPU) {
+    ///     println!("Found GPU {gpu}");
+    /// }
+    /// ```
+    pub fn os_devices_with_type(
+        &self,
+        device_type: OSDeviceType,
+    ) -> impl DoubleEndedIterator<Item = &TopologyObject> + Clone + FusedIterator {
+        self.os_devices().filter(move |obj| {
+            let Some(ObjectAttributes::OSDevice(osdev)) = obj.attributes() else {
+                #[cfg(not(tarpaulin_include))]
+                unreachable!("All OS devices should have OS device attributes")
+            };
+            osdev.device_type() == device_type
+        })
+    }
+
+    /// Find the OS device with a certain name
+    ///
+    /// OS device names are those exposed by the operating system, for
+    /// instance "eth0" for a network interface or "nvme0n1" for a storage
+    /// device on Linux.
+    ///
+    /// If several OS devices share the same name, the first one is returned.
+    pub fn os_device_with_name(&self, name: &str) -> Option<&TopologyObject> {
+        self.os_devices().find(|obj| {
+            obj.name()
+                .is_some_and(|obj_name| obj_name.to_bytes() == name.as_bytes())
+        })
+    }
+
+    /// CPUs that are close to the OS device with a certain name
+    ///
+    /// This combines [`Topology::os_device_with_name()`] with a lookup of the
+    /// device's [first non-I/O ancestor](TopologyObject::first_non_io_ancestor()),
+    /// which allows pinning network or storage threads close to their device
+    /// in a single call.
+    ///
+    /// Returns `None` if no OS device with this name was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// if let Some(cpuset) = topology.cpuset_near_os_device("eth0") {
+    ///     println!("eth0 is close to CPUs {cpuset}");
+    /// }
+    /// ```
+    pub fn cpuset_near_os_device(&self, name: &str) -> Option<BitmapRef<'_, CpuSet>> {
+        self.os_device_with_name(name)?
+            .first_non_io_ancestor()
+            .and_then(TopologyObject::cpuset)
+    }
+
+    /// NUMA nodes that are close to the PCI device with a certain bus id
+    ///
+    /// The PCI bus id should be specified as a string of format "xxxx:yy:zz.t"
+    /// (with domain) or "yy:zz.t" (without domain), see
+    /// [`Topology::pci_device_by_bus_id_string()`].
+    ///
+    /// Returns `None` if no PCI device with this bus id was found.
+    ///
+    /// # Errors
+    ///
+    /// - [`ParameterError`] if the given string does not match the PCI bus id
+    ///   format given above
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// if let Some(nodeset) = topology.numa_node_of_pci_device("0000:3b:00.0")? {
+    ///     println!("0000:3b:00.0 is close to NUMA nodes {nodeset}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn numa_node_of_pci_device(
+        &self,
+        bus_id: &str,
+    ) -> Result<Option<BitmapRef<'_, NodeSet>>, ParameterError<String>> {
+        Ok(self
+            .pci_device_by_bus_id_string(bus_id)?
+            .and_then(TopologyObject::first_non_io_ancestor)
+            .and_then(TopologyObject::nodeset))
+    }
+
     /// Enumerate bridges in the system
     #[doc(alias = "hwloc_get_next_bridge")]
     pub fn bridges(
@@ -140,6 +273,43 @@ mod tests {
         Ok(())
     }
 
+    proptest! {
+        /// Test for [`Topology::os_devices_with_type()`]
+        #[test]
+        fn os_devices_with_type(device_type: OSDeviceType) {
+            let topology = Topology::test_instance();
+            compare_object_sets(
+                topology.os_devices_with_type(device_type),
+                topology.os_devices().filter(|obj| {
+                    matches!(
+                        obj.attributes(),
+                        Some(ObjectAttributes::OSDevice(osdev)) if osdev.device_type() == device_type
+                    )
+                }),
+            )?;
+        }
+    }
+
+    #[test]
+    fn os_device_with_name() {
+        let topology = Topology::test_instance();
+        for device in topology.os_devices() {
+            let Some(name) = device.name() else {
+                continue;
+            };
+            let name = name.to_str().unwrap();
+            let result = topology.os_device_with_name(name).unwrap();
+            assert_eq!(result.name(), device.name());
+            assert_eq!(
+                topology.cpuset_near_os_device(name),
+                result
+                    .first_non_io_ancestor()
+                    .and_then(TopologyObject::cpuset)
+            );
+        }
+        assert!(topology.os_device_with_name("").is_none());
+    }
+
     // --- Find PCI devices by address ---
 
     /// PCI device address
@@ -22,6 +22,8 @@ use crate::{
 use derive_more::Display;
 #[cfg(any(test, feature = "proptest"))]
 use enum_iterator::Sequence;
+#[cfg(feature = "hwloc-3_0_0")]
+use hwlocality_sys::HWLOC_OBJ_OSDEV_MEMORY;
 use hwlocality_sys::{
     hwloc_obj_type_t, HWLOC_OBJ_BRIDGE, HWLOC_OBJ_BRIDGE_HOST, HWLOC_OBJ_BRIDGE_PCI,
     HWLOC_OBJ_CACHE_DATA, HWLOC_OBJ_CACHE_INSTRUCTION, HWLOC_OBJ_CACHE_UNIFIED, HWLOC_OBJ_CORE,
@@ -40,7 +42,8 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use similar_asserts::assert_eq;
 use std::{
     cmp::{Ordering, PartialOrd},
-    ffi::c_int,
+    ffi::{c_int, CStr},
+    fmt::{self, Display},
 };
 
 /// Type of one side (upstream or downstream) of an I/O bridge
@@ -154,7 +157,7 @@ crate::impl_arbitrary_for_sequence!(OSDeviceType);
 /// a `ObjectType::Machine` will be smaller than a `ObjectType::PU` since the machine
 /// contains processing units.
 #[cfg_attr(any(test, feature = "proptest"), derive(Sequence))]
-#[derive(Copy, Clone, Debug, Display, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, IntoPrimitive, TryFromPrimitive, PartialEq)]
 #[doc(alias = "hwloc_obj_type_e")]
 #[doc(alias = "hwloc_obj_type_t")]
 #[non_exhaustive]
@@ -467,6 +470,29 @@ impl ObjectType {
 //
 crate::impl_arbitrary_for_sequence!(ObjectType);
 //
+impl Display for ObjectType {
+    /// Display the name that hwloc uses for this object type
+    ///
+    /// This is the name that is used by `lstopo`, in XML exports and in
+    /// synthetic topology descriptions, e.g. `L1iCache` for
+    /// [`ObjectType::L1ICache`].
+    #[doc(alias = "hwloc_obj_type_string")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // SAFETY: By construction, ObjectType only exposes values that map into
+        //         hwloc_obj_type_t values understood by the configured version
+        //         of hwloc, and build.rs checks that the active version of
+        //         hwloc is not older than that, so to_raw may only generate
+        //         valid hwloc_obj_type_t values for current hwloc
+        let name = errors::call_hwloc_ptr("hwloc_obj_type_string", || unsafe {
+            hwlocality_sys::hwloc_obj_type_string(self.to_raw())
+        })
+        .expect("Object type names should always be available");
+        // SAFETY: hwloc is trusted to return a valid static C string
+        let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+        f.pad(&name.to_string_lossy())
+    }
+}
+//
 impl PartialOrd for ObjectType {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         let result =
@@ -547,6 +573,14 @@ mod tests {
         io::Write
     );
 
+    #[test]
+    fn object_type_names() {
+        assert_eq!(ObjectType::Machine.to_string(), "Machine");
+        assert_eq!(ObjectType::L1ICache.to_string(), "L1iCache");
+        assert_eq!(ObjectType::PCIDevice.to_string(), "PCIDev");
+        assert_eq!(format!("{:>8}", ObjectType::PU), "      PU");
+    }
+
     proptest! {
         // For object subtypes, the only logic we implement is arbitrary, so just
         // exercise that it doesn't crash and we're good to go
@@ -580,6 +614,9 @@ mod tests {
                     ty.is_cpu_data_cache() || ty.is_cpu_instruction_cache()
                 );
                 prop_assert!(!(ty.is_cpu_data_cache() && ty.is_cpu_instruction_cache()));
+                let name = ty.to_string();
+                prop_assert!(!name.is_empty());
+                prop_assert!(name.chars().all(|c| c.is_ascii_alphanumeric()));
                 Ok(())
             }
             fn check_normal(ty: ObjectType) -> Result<(), TestCaseError> {
@@ -0,0 +1,186 @@
+//! Reference topologies for testing
+//!
+//! Code that makes placement decisions based on a [`Topology`] is hard to test
+//! using the topology of the machine that the tests happen to run on, as that
+//! machine is usually neither representative of production hardware nor
+//! identical across developers and CI runners.
+//!
+//! This module, which is enabled by the `testing` feature, embeds XML
+//! descriptions of a few realistic machines, which can be loaded as
+//! [`Topology`]s to test placement logic deterministically. Topologies that
+//! are loaded from XML do not describe the system on which the program is
+//! running, so binding operations on them succeed without actually binding
+//! anything.
+//!
+//! # Examples
+//!
+//! ```
+//! # use hwlocality::{object::types::ObjectType, testing::ReferenceTopology};
+//! for reference in ReferenceTopology::ALL {
+//!     let topology = reference.build();
+//!     println!(
+//!         "{reference} has {} cores",
+//!         topology.objects_with_type(ObjectType::Core).count()
+//!     );
+//! }
+//! ```
+
+use crate::topology::{builder::TopologyBuilder, Topology};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Realistic machine topology that is embedded into hwlocality
+///
+/// Reference topologies are loaded with the default [`TopologyBuilder`]
+/// settings, so instruction caches are filtered out. Use
+/// [`ReferenceTopology::builder()`] if you need different settings.
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ReferenceTopology {
+    /// Dual-socket Intel Xeon Gold 6248 server
+    ///
+    /// Each of the 2 packages has 20 cores with 2 hardware threads, private L2
+    /// caches, a shared L3 cache and a NUMA node. As is usual on Intel
+    /// servers, the second hardware thread of each core gets a PU OS index
+    /// that is offset by 40 from that of the first one.
+    #[display(fmt = "dual-socket Intel Xeon Gold 6248")]
+    DualSocketXeon,
+
+    /// Single-socket AMD EPYC 7763 server
+    ///
+    /// The package has 8 core complex dies (CCDs), each of which has 8 cores
+    /// with 2 hardware threads sharing an L3 cache. The whole package is one
+    /// NUMA node (NPS1 configuration). The second hardware thread of each core
+    /// gets a PU OS index that is offset by 64 from that of the first one.
+    #[display(fmt = "AMD EPYC 7763 with 8 CCDs")]
+    Epyc8Ccd,
+
+    /// Intel Core i9-12900K desktop (Alder Lake)
+    ///
+    /// This hybrid CPU has 8 performance cores with 2 hardware threads and a
+    /// private L2 cache each (PUs 0 to 15), and 8 efficiency cores without
+    /// hardware threads, which share an L2 cache in clusters of 4 (PUs 16 to
+    /// 23). The topology reports a CPU kind for each type of core.
+    #[display(fmt = "Intel Core i9-12900K (Alder Lake)")]
+    AlderLake,
+
+    /// Dual-socket IBM POWER9 server in SMT8 mode
+    ///
+    /// Each of the 2 packages has 12 cores with 8 hardware threads, and pairs
+    /// of cores share their L2 and L3 caches. As is usual on POWER machines,
+    /// the NUMA nodes have OS indices 0 and 8.
+    #[display(fmt = "dual-socket IBM POWER9 in SMT8 mode")]
+    Power9Smt8,
+
+    /// Apple M1-style laptop
+    ///
+    /// This hybrid CPU has a cluster of 4 efficiency cores (PUs 0 to 3) and a
+    /// cluster of 4 performance cores (PUs 4 to 7), each of which shares an L2
+    /// cache. There is no hardware threading and no L3 cache. The topology
+    /// reports a CPU kind for each type of core.
+    #[display(fmt = "Apple M1-style")]
+    AppleM1,
+}
+//
+impl ReferenceTopology {
+    /// All reference topologies
+    pub const ALL: [Self; 5] = [
+        Self::DualSocketXeon,
+        Self::Epyc8Ccd,
+        Self::AlderLake,
+        Self::Power9Smt8,
+        Self::AppleM1,
+    ];
+
+    /// XML description of this topology
+    ///
+    /// This can be used with [`TopologyBuilder::from_xml()`], or written to a
+    /// file and inspected with tools like `lstopo`.
+    pub fn xml(self) -> &'static str {
+        match self {
+            Self::DualSocketXeon => include_str!("dual_socket_xeon.xml"),
+            Self::Epyc8Ccd => include_str!("epyc_8ccd.xml"),
+            Self::AlderLake => include_str!("alder_lake.xml"),
+            Self::Power9Smt8 => include_str!("power9_smt8.xml"),
+            Self::AppleM1 => include_str!("apple_m1.xml"),
+        }
+    }
+
+    /// Start building this topology
+    ///
+    /// Further building configuration can be applied before calling
+    /// [`TopologyBuilder::build()`].
+    ///
+    /// # Panics
+    ///
+    /// If the hwloc library that is in use was built without XML support.
+    pub fn builder(self) -> TopologyBuilder {
+        TopologyBuilder::new()
+            .from_xml(self.xml())
+            .expect("Reference topology XML should be accepted by hwloc")
+    }
+
+    /// Build this topology with the default settings
+    ///
+    /// # Panics
+    ///
+    /// If the hwloc library that is in use was built without XML support.
+    pub fn build(self) -> Topology {
+        self.builder()
+            .build()
+            .expect("Reference topology XML should be loadable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::types::ObjectType;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(ReferenceTopology:
+        Copy, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    #[test]
+    fn reference_topologies() {
+        for (reference, packages, cores, pus, numa_nodes) in [
+            (ReferenceTopology::DualSocketXeon, 2, 40, 80, 2),
+            (ReferenceTopology::Epyc8Ccd, 1, 64, 128, 1),
+            (ReferenceTopology::AlderLake, 1, 16, 24, 1),
+            (ReferenceTopology::Power9Smt8, 2, 24, 192, 2),
+            (ReferenceTopology::AppleM1, 1, 8, 8, 1),
+        ] {
+            let topology = reference.build();
+            assert!(!topology.is_this_system());
+            let count = |ty| topology.objects_with_type(ty).count();
+            assert_eq!(count(ObjectType::Package), packages, "{reference}");
+            assert_eq!(count(ObjectType::Core), cores, "{reference}");
+            assert_eq!(count(ObjectType::PU), pus, "{reference}");
+            assert_eq!(count(ObjectType::NUMANode), numa_nodes, "{reference}");
+        }
+    }
+
+    #[cfg(feature = "hwloc-2_4_0")]
+    #[test]
+    fn hybrid_cpu_kinds() {
+        for reference in ReferenceTopology::ALL {
+            let topology = reference.build();
+            let num_kinds = topology.num_cpu_kinds().map_or(0, usize::from);
+            let expected = match reference {
+                ReferenceTopology::AlderLake | ReferenceTopology::AppleM1 => 2,
+                ReferenceTopology::DualSocketXeon
+                | ReferenceTopology::Epyc8Ccd
+                | ReferenceTopology::Power9Smt8 => 0,
+            };
+            assert_eq!(num_kinds, expected, "{reference}");
+        }
+    }
+}
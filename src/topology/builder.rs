@@ -15,10 +15,10 @@ use super::Topology;
 use crate::object::TopologyObject;
 #[cfg(all(doc, feature = "hwloc-2_5_0"))]
 use crate::topology::editor::TopologyEditor;
-#[cfg(doc)]
-use crate::topology::support::DiscoverySupport;
 #[cfg(all(doc, feature = "hwloc-2_3_0"))]
 use crate::topology::support::MiscSupport;
+#[cfg(doc)]
+use crate::topology::{support::DiscoverySupport, synthetic::SyntheticBuilder};
 use crate::{
     errors::{self, FlagsError, HybridError, NulError, RawHwlocError},
     ffi::string::LibcString,
@@ -102,6 +102,62 @@ impl TopologyBuilder {
         Self(NonNull::new(topology).expect("Got null pointer from hwloc_topology_init"))
     }
 
+    /// Start building a [`Topology`] that only contains what is needed for
+    /// CPU and memory pinning
+    ///
+    /// This preset is meant for programs that only care about PUs, cores and
+    /// NUMA nodes, and want the topology to be built as quickly as possible at
+    /// startup. It behaves like [`TopologyBuilder::new()`], except that...
+    ///
+    /// - I/O objects and CPU instruction caches are not discovered
+    /// - CPU data caches, memory-side caches, Group and Misc objects are
+    ///   filtered out of the topology
+    /// - If supported by hwloc, distances, memory attributes and CPU kinds are
+    ///   not gathered from the operating system
+    ///
+    /// You can run the `topology_build` benchmark to compare the build time of
+    /// this preset with that of a default topology on your machine.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, topology::builder::TopologyBuilder};
+    /// let topology = TopologyBuilder::minimal_cpu().build()?;
+    /// assert!(topology.objects_with_type(ObjectType::PU).count() > 0);
+    /// assert_eq!(topology.objects_with_type(ObjectType::L1Cache).count(), 0);
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn minimal_cpu() -> Self {
+        let mut builder = Self::new()
+            .with_io_type_filter(TypeFilter::KeepNone)
+            .expect("Known to be a supported filter")
+            .with_cpu_icache_type_filter(TypeFilter::KeepNone)
+            .expect("Known to be a supported filter")
+            .with_cpu_cache_type_filter(TypeFilter::KeepNone)
+            .expect("Known to be a supported filter");
+        for ty in [
+            ObjectType::Group,
+            ObjectType::Misc,
+            #[cfg(feature = "hwloc-2_1_0")]
+            ObjectType::MemCache,
+        ] {
+            builder = builder
+                .with_type_filter(ty, TypeFilter::KeepNone)
+                .expect("Known to be a supported filter");
+        }
+        #[cfg(feature = "hwloc-2_8_0")]
+        {
+            builder = builder
+                .with_flags(
+                    BuildFlags::IGNORE_DISTANCES
+                        | BuildFlags::IGNORE_MEMORY_ATTRIBUTES
+                        | BuildFlags::IGNORE_CPU_KINDS,
+                )
+                .expect("Known to be valid build flags");
+        }
+        builder
+    }
+
     /// Load the topology with the previously specified parameters
     ///
     /// The binding of the current thread or process may temporarily change
@@ -139,7 +195,9 @@ impl TopologyBuilder {
         // Transfer hwloc_topology ownership to a Topology
         let inner = self.0;
         std::mem::forget(self);
-        Ok(Topology(inner))
+        // SAFETY: Topology was successfully loaded and ownership is
+        //         transferred as the builder is forgotten
+        Ok(unsafe { Topology::from_non_null(inner) })
     }
 }
 
@@ -215,6 +273,9 @@ impl TopologyBuilder {
     ///
     /// CPU and memory binding operations will not do anything with this backend.
     ///
+    /// [`SyntheticBuilder`] can be used to generate such descriptions in a
+    /// type-checked manner.
+    ///
     /// # Errors
     ///
     /// - [`ContainsNul`] if `description` contains NUL chars.
@@ -1222,6 +1283,33 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn minimal_cpu() -> Result<(), TestCaseError> {
+        let builder = TopologyBuilder::minimal_cpu();
+        let build_flags = builder.flags();
+        let topology = builder.build().unwrap();
+        let predicted_filter = |ty: ObjectType| {
+            #[cfg(feature = "hwloc-2_1_0")]
+            if ty == ObjectType::MemCache {
+                return Ok(TypeFilter::KeepNone);
+            }
+            if ty.is_cpu_cache() || ty.is_io() || ty == ObjectType::Group || ty == ObjectType::Misc
+            {
+                Ok(TypeFilter::KeepNone)
+            } else {
+                default_type_filter(ty)
+            }
+        };
+        check_topology(
+            &topology,
+            DataSource::ThisSystem,
+            build_flags,
+            predicted_filter,
+        )?;
+        prop_assert!(topology.objects_with_type(ObjectType::PU).count() > 0);
+        Ok(())
+    }
+
     /// Set up a [`TopologyBuilder`] with random flags from proptest, if the
     /// flags are right
     /// FIXME: Test more aspects of build flags
@@ -0,0 +1,242 @@
+//! Structural topology comparison
+//!
+//! Unlike hwloc's topology difference API, which is only able to describe
+//! differences between topologies that share the same structure, the
+//! functionality of this module works on arbitrary pairs of topologies. It is
+//! mostly meant for testing purposes, e.g. checking that a topology that was
+//! rebuilt or filtered in some way matches expectations.
+
+use crate::{object::TopologyObject, topology::Topology};
+use derive_more::Display;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// # Structural comparison
+impl Topology {
+    /// Truth that this topology has the same structure as another
+    ///
+    /// Two topologies are considered structurally equal if their object trees
+    /// have the same shape, and matching objects have the same type, subtype,
+    /// name, OS index, CPU and NUMA node sets, and type-specific attributes.
+    ///
+    /// This is a shortcut for checking that [`Topology::differences()`] is
+    /// empty, which will tell you where the topologies differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let clone = topology.clone();
+    /// assert!(topology.structurally_equal(&clone));
+    /// ```
+    pub fn structurally_equal(&self, other: &Self) -> bool {
+        let mut equal = true;
+        compare_objects(self.root_object(), other.root_object(), &mut |_| {
+            equal = false;
+        });
+        equal
+    }
+
+    /// Structural differences between this topology and another
+    ///
+    /// Object trees are walked in parallel starting from the root object, and
+    /// every pair of matching objects that differ in some way is reported.
+    /// See [`Topology::structurally_equal()`] for the list of object
+    /// properties that are compared.
+    ///
+    /// When two matching objects do not have the same number of children,
+    /// this is reported as an [`ObjectProperty::Children`] difference and
+    /// only the children that are present on both sides are compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// # let topology = Topology::test_instance();
+    /// let clone = topology.clone();
+    /// for difference in topology.differences(&clone) {
+    ///     println!(
+    ///         "{} differs between {} and {}",
+    ///         difference.property, difference.left, difference.right
+    ///     );
+    /// }
+    /// ```
+    pub fn differences<'left, 'right>(
+        &'left self,
+        other: &'right Self,
+    ) -> Vec<TopologyDifference<'left, 'right>> {
+        let mut differences = Vec::new();
+        compare_objects(self.root_object(), other.root_object(), &mut |difference| {
+            differences.push(difference);
+        });
+        differences
+    }
+}
+
+/// Difference between two matching objects of two topologies, as reported by
+/// [`Topology::differences()`]
+#[derive(Copy, Clone, Debug)]
+pub struct TopologyDifference<'left, 'right> {
+    /// Object from the topology on which [`Topology::differences()`] was called
+    pub left: &'left TopologyObject,
+
+    /// Matching object from the topology that was passed as a parameter
+    pub right: &'right TopologyObject,
+
+    /// Property that differs between these two objects
+    pub property: ObjectProperty,
+}
+
+/// Object property that can differ between two topologies
+#[derive(Copy, Clone, Debug, Display, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ObjectProperty {
+    /// [`TopologyObject::object_type()`]
+    #[display(fmt = "object type")]
+    ObjectType,
+
+    /// [`TopologyObject::subtype()`]
+    #[display(fmt = "subtype")]
+    Subtype,
+
+    /// [`TopologyObject::name()`]
+    #[display(fmt = "name")]
+    Name,
+
+    /// [`TopologyObject::os_index()`]
+    #[display(fmt = "OS index")]
+    OSIndex,
+
+    /// [`TopologyObject::cpuset()`]
+    #[display(fmt = "CPU set")]
+    CpuSet,
+
+    /// [`TopologyObject::nodeset()`]
+    #[display(fmt = "NUMA node set")]
+    NodeSet,
+
+    /// [`TopologyObject::attributes()`]
+    #[display(fmt = "attributes")]
+    Attributes,
+
+    /// Number of normal, memory, I/O or Misc children
+    #[display(fmt = "number of children")]
+    Children,
+}
+
+/// Compare two matching objects and their descendants, reporting differences
+/// to the `report` callback
+fn compare_objects<'left, 'right>(
+    left: &'left TopologyObject,
+    right: &'right TopologyObject,
+    report: &mut dyn FnMut(TopologyDifference<'left, 'right>),
+) {
+    // Compare object properties
+    let mut check = |equal: bool, property| {
+        if !equal {
+            report(TopologyDifference {
+                left,
+                right,
+                property,
+            });
+        }
+    };
+    check(
+        left.object_type() == right.object_type(),
+        ObjectProperty::ObjectType,
+    );
+    check(left.subtype() == right.subtype(), ObjectProperty::Subtype);
+    check(left.name() == right.name(), ObjectProperty::Name);
+    check(left.os_index() == right.os_index(), ObjectProperty::OSIndex);
+    check(left.cpuset() == right.cpuset(), ObjectProperty::CpuSet);
+    check(left.nodeset() == right.nodeset(), ObjectProperty::NodeSet);
+    check(
+        left.attributes() == right.attributes(),
+        ObjectProperty::Attributes,
+    );
+    check(
+        left.normal_arity() == right.normal_arity()
+            && left.memory_arity() == right.memory_arity()
+            && left.io_arity() == right.io_arity()
+            && left.misc_arity() == right.misc_arity(),
+        ObjectProperty::Children,
+    );
+
+    // Compare children
+    let children = left
+        .normal_children()
+        .zip(right.normal_children())
+        .chain(left.memory_children().zip(right.memory_children()))
+        .chain(left.io_children().zip(right.io_children()))
+        .chain(left.misc_children().zip(right.misc_children()));
+    for (left_child, right_child) in children {
+        compare_objects(left_child, right_child, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{object::types::ObjectType, topology::synthetic::SyntheticBuilder};
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn self_comparison() {
+        let topology = Topology::test_instance();
+        assert!(topology.structurally_equal(topology));
+        assert!(topology.differences(topology).is_empty());
+        let clone = topology.clone();
+        assert!(topology.structurally_equal(&clone));
+        assert!(clone.differences(topology).is_empty());
+    }
+
+    #[test]
+    fn different_topologies() {
+        /// Single-package topology with one NUMA node and `cores` cores
+        fn topology(cores: usize) -> Topology {
+            SyntheticBuilder::new()
+                .packages(1)
+                .numa_nodes(1)
+                .cores(cores)
+                .pus(1)
+                .build()
+                .unwrap()
+        }
+        let small = topology(2);
+        let large = topology(3);
+        assert!(!small.structurally_equal(&large));
+        assert!(!large.structurally_equal(&small));
+
+        // The extra core changes the CPU set of all of its ancestors and of
+        // the NUMA node, and the number of children of the package
+        let expected = [
+            (ObjectType::Machine, ObjectProperty::CpuSet),
+            (ObjectType::Package, ObjectProperty::CpuSet),
+            (ObjectType::Package, ObjectProperty::Children),
+            (ObjectType::NUMANode, ObjectProperty::CpuSet),
+        ];
+        let summary = |differences: Vec<TopologyDifference<'_, '_>>| {
+            differences
+                .into_iter()
+                .map(|difference| {
+                    assert_eq!(
+                        difference.left.object_type(),
+                        difference.right.object_type()
+                    );
+                    (difference.left.object_type(), difference.property)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(small.differences(&large)), expected);
+        assert_eq!(summary(large.differences(&small)), expected);
+
+        // Differing objects are reported from the topology they come from
+        for difference in small.differences(&large) {
+            assert!(small.contains(difference.left));
+            assert!(large.contains(difference.right));
+        }
+    }
+}
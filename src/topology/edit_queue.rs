@@ -0,0 +1,471 @@
+//! Queueing topology edits from multiple threads
+//!
+//! Modifying a [`Topology`] requires exclusive access to it through
+//! [`Topology::edit()`], which does not play well with concurrent analysis
+//! phases where many threads inspect a shared `&Topology` and come up with
+//! edits that they would like to make, such as annotating objects of interest
+//! or grouping objects that turned out to be related.
+//!
+//! [`EditQueue`] lets such threads propose edits through a shared reference,
+//! designating topology objects using [`ObjectHandle`]s. Once the analysis
+//! phase is over and exclusive access to the topology is available again,
+//! all queued edits are applied at once by [`EditQueue::commit()`].
+
+use crate::{
+    errors::{HybridError, RawHwlocError},
+    object::{handle::ObjectHandle, TopologyObject},
+    topology::{
+        editor::{GroupKind, GroupMerge, InsertMiscError, InsertedGroup, TopologyEditor},
+        Topology,
+    },
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::sync::{Mutex, PoisonError};
+use thiserror::Error;
+
+/// Thread-safe queue of pending topology edits
+///
+/// Edits can be queued from any number of threads through a shared reference,
+/// without holding any lock on the topology, then applied in queuing order by
+/// [`commit()`](Self::commit).
+///
+/// # Examples
+///
+/// ```rust
+/// # use hwlocality::{object::types::ObjectType, topology::edit_queue::EditQueue, Topology};
+/// let mut topology = Topology::test_instance().clone();
+/// let queue = EditQueue::new();
+///
+/// // Analysis threads share the topology and the queue
+/// std::thread::scope(|scope| {
+///     for core in topology.objects_with_type(ObjectType::Core) {
+///         let queue = &queue;
+///         scope.spawn(move || {
+///             let name = format!("Analyzed core #{}", core.logical_index());
+///             queue.insert_misc_object(core.handle(), name, []);
+///         });
+///     }
+/// });
+///
+/// // Edits are then applied with exclusive access to the topology
+/// let num_cores = topology.objects_with_type(ObjectType::Core).count();
+/// let failures = queue.commit(&mut topology);
+/// assert!(failures.is_empty());
+/// assert_eq!(topology.objects_with_type(ObjectType::Misc).count(), num_cores);
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct EditQueue(Mutex<Vec<QueuedEdit>>);
+//
+impl EditQueue {
+    /// Set up an empty edit queue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an edit
+    pub fn push(&self, edit: QueuedEdit) {
+        self.edits().push(edit);
+    }
+
+    /// Queue the insertion of a [`Misc`] object below `parent`, with textual
+    /// info pairs `infos`
+    ///
+    /// See [`TopologyEditor::insert_misc_object()`] for more information.
+    ///
+    /// [`Misc`]: crate::object::types::ObjectType::Misc
+    pub fn insert_misc_object(
+        &self,
+        parent: ObjectHandle,
+        name: impl Into<String>,
+        infos: impl IntoIterator<Item = (String, String)>,
+    ) {
+        self.push(QueuedEdit::InsertMisc {
+            parent,
+            name: name.into(),
+            infos: infos.into_iter().collect(),
+        });
+    }
+
+    /// Queue the insertion of a [`Group`] object covering `children`, with
+    /// textual info pairs `infos`
+    ///
+    /// See [`TopologyEditor::insert_group_object()`] for more information.
+    ///
+    /// [`Group`]: crate::object::types::ObjectType::Group
+    pub fn insert_group_object(
+        &self,
+        children: impl IntoIterator<Item = ObjectHandle>,
+        merge: Option<GroupMerge>,
+        infos: impl IntoIterator<Item = (String, String)>,
+    ) {
+        self.push(QueuedEdit::InsertGroup {
+            children: children.into_iter().collect(),
+            kind: None,
+            merge,
+            infos: infos.into_iter().collect(),
+        });
+    }
+
+    /// Number of queued edits
+    pub fn len(&self) -> usize {
+        self.edits().len()
+    }
+
+    /// Truth that no edit is queued
+    pub fn is_empty(&self) -> bool {
+        self.edits().is_empty()
+    }
+
+    /// Apply all queued edits to `topology`, in queuing order
+    ///
+    /// All edits are applied within a single [`Topology::edit()`] session,
+    /// and the queue is empty afterwards. Edits that could not be applied are
+    /// skipped, and returned along with the reason why they failed. Failed
+    /// edits leave the topology unchanged, except for those that failed with
+    /// [`QueuedEditError::PartiallyApplied`]. Handles
+    /// are resolved using [`Topology::object_by_handle()`], so they should
+    /// come from `topology` or from a clone of it.
+    pub fn commit(&self, topology: &mut Topology) -> Vec<(QueuedEdit, QueuedEditError)> {
+        let edits = std::mem::take(&mut *self.edits());
+        if edits.is_empty() {
+            return Vec::new();
+        }
+        topology.edit(move |editor| {
+            edits
+                .into_iter()
+                .filter_map(|edit| {
+                    let result = edit.apply(editor);
+                    result.err().map(|error| (edit, error))
+                })
+                .collect()
+        })
+    }
+
+    /// Access the inner list of queued edits
+    fn edits(&self) -> std::sync::MutexGuard<'_, Vec<QueuedEdit>> {
+        // Queued edits are only pushed and taken, so they remain consistent
+        // even if a thread panicked while holding the lock
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Topology edit that was queued in an [`EditQueue`]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum QueuedEdit {
+    /// Insert a [`Misc`] object, see [`TopologyEditor::insert_misc_object()`]
+    ///
+    /// [`Misc`]: crate::object::types::ObjectType::Misc
+    InsertMisc {
+        /// Parent of the new Misc object
+        parent: ObjectHandle,
+
+        /// Name of the new Misc object
+        name: String,
+
+        /// Textual info pairs to be attached to the new Misc object
+        infos: Vec<(String, String)>,
+    },
+
+    /// Insert a [`Group`] object, see
+    /// [`TopologyEditor::insert_group_object()`]
+    ///
+    /// Textual info pairs are attached to the resulting object, which may be
+    /// an existing object if the new group was merged into it.
+    ///
+    /// [`Group`]: crate::object::types::ObjectType::Group
+    InsertGroup {
+        /// Objects that the new group should cover
+        children: Vec<ObjectHandle>,
+
+        /// Kind of the new group, see
+        /// [`TopologyEditor::insert_group_object_with_kind()`]
+        kind: Option<GroupKind>,
+
+        /// Merging policy of the new group
+        merge: Option<GroupMerge>,
+
+        /// Textual info pairs to be attached to the resulting object
+        infos: Vec<(String, String)>,
+    },
+}
+//
+impl QueuedEdit {
+    /// Apply this edit to a topology
+    fn apply(&self, editor: &mut TopologyEditor<'_>) -> Result<(), QueuedEditError> {
+        /// Check that all handles can be resolved
+        fn check_handles<'a>(
+            editor: &mut TopologyEditor<'_>,
+            handles: impl IntoIterator<Item = &'a ObjectHandle>,
+        ) -> Result<(), QueuedEditError> {
+            let topology = editor.topology();
+            for &handle in handles {
+                if topology.object_by_handle(handle).is_none() {
+                    return Err(QueuedEditError::StaleHandle(handle));
+                }
+            }
+            Ok(())
+        }
+
+        /// Resolve a handle that was checked by `check_handles`
+        fn resolve(topology: &Topology, handle: ObjectHandle) -> &TopologyObject {
+            topology
+                .object_by_handle(handle)
+                .expect("Handle should have been checked")
+        }
+
+        /// Resolve handles that were checked by `check_handles`
+        fn resolve_all<'topology>(
+            topology: &'topology Topology,
+            handles: &[ObjectHandle],
+        ) -> Vec<&'topology TopologyObject> {
+            handles
+                .iter()
+                .map(|&handle| resolve(topology, handle))
+                .collect()
+        }
+
+        /// Check that the strings of an edit contain no NUL chars, so that
+        /// failing to convert them does not leave the edit half-applied
+        fn check_strings<'a>(
+            strings: impl IntoIterator<Item = &'a String>,
+        ) -> Result<(), QueuedEditError> {
+            if strings.into_iter().any(|s| s.contains('\0')) {
+                return Err(QueuedEditError::ContainsNul);
+            }
+            Ok(())
+        }
+
+        /// Attach textual info pairs to an object that was just inserted
+        fn add_infos(
+            obj: &mut TopologyObject,
+            infos: &[(String, String)],
+        ) -> Result<(), QueuedEditError> {
+            for (name, value) in infos {
+                obj.add_info(name, value).map_err(|e| match e {
+                    HybridError::Rust(_) => {
+                        unreachable!("Strings were checked for NUL chars")
+                    }
+                    HybridError::Hwloc(e) => QueuedEditError::PartiallyApplied(e),
+                })?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::InsertMisc {
+                parent,
+                name,
+                infos,
+            } => {
+                check_strings(
+                    std::iter::once(name)
+                        .chain(infos.iter().flat_map(|(name, value)| [name, value])),
+                )?;
+                check_handles(editor, [parent])?;
+                let misc = editor
+                    .insert_misc_object(name, |topology| resolve(topology, *parent))
+                    .map_err(|e| match e {
+                        HybridError::Rust(InsertMiscError::NameContainsNul) => {
+                            unreachable!("Strings were checked for NUL chars")
+                        }
+                        HybridError::Rust(InsertMiscError::ForeignParent(_)) => {
+                            unreachable!("Parent was resolved from the edited topology")
+                        }
+                        HybridError::Hwloc(e) => QueuedEditError::Hwloc(e),
+                    })?;
+                add_infos(misc, infos)
+            }
+            Self::InsertGroup {
+                children,
+                kind,
+                merge,
+                infos,
+            } => {
+                check_strings(infos.iter().flat_map(|(name, value)| [name, value]))?;
+                check_handles(editor, children)?;
+                let group = match kind {
+                    Some(kind) => editor.insert_group_object_with_kind(*kind, *merge, |topology| {
+                        resolve_all(topology, children)
+                    }),
+                    None => editor
+                        .insert_group_object(*merge, |topology| resolve_all(topology, children)),
+                }
+                .map_err(|e| match e {
+                    HybridError::Rust(_) => {
+                        unreachable!("Children were resolved from the edited topology")
+                    }
+                    HybridError::Hwloc(e) => QueuedEditError::Hwloc(e),
+                })?;
+                match group {
+                    InsertedGroup::New(obj) | InsertedGroup::Existing(obj) => add_infos(obj, infos),
+                }
+            }
+        }
+    }
+}
+
+/// Error returned when a [`QueuedEdit`] could not be applied
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum QueuedEditError {
+    /// An object handle does not designate any object of the edited topology
+    ///
+    /// This happens when an object was removed by an earlier edit. Handles
+    /// from an unrelated topology may also end up here, but they may just as
+    /// well designate unrelated objects of the edited topology, see
+    /// [`ObjectHandle`].
+    #[error("no object matches handle {0:?} in the edited topology")]
+    StaleHandle(ObjectHandle),
+
+    /// An object name or textual info contains NUL chars
+    #[error("topology edits can't contain strings with NUL chars")]
+    ContainsNul,
+
+    /// hwloc failed to apply the edit
+    ///
+    /// The topology was left unchanged by this edit.
+    #[error(transparent)]
+    Hwloc(#[from] RawHwlocError),
+
+    /// The object was inserted, but hwloc failed to attach textual info pairs
+    /// to it
+    ///
+    /// Unlike other errors, this means that the edit was partially applied:
+    /// the inserted object is in the topology, but lacks some of the
+    /// requested textual info.
+    #[error("object was inserted, but attaching textual info to it failed")]
+    PartiallyApplied(#[source] RawHwlocError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{attributes::ObjectAttributes, types::ObjectType};
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{ffi::CStr, fmt::Debug, hash::Hash};
+
+    assert_impl_all!(EditQueue: Debug, Default, Send, Sync);
+    assert_impl_all!(QueuedEdit: Clone, Debug, Eq, Hash, Send, Sync);
+    assert_impl_all!(QueuedEditError: Copy, Debug, Eq, Hash, Send, Sync);
+
+    #[test]
+    fn commit() {
+        let mut topology = Topology::test_instance().clone();
+        let queue = EditQueue::new();
+        assert!(queue.is_empty());
+        assert!(queue.commit(&mut topology).is_empty());
+
+        let root = topology.root_object().handle();
+        queue.insert_misc_object(
+            root,
+            "queued",
+            [("QueuedInfo".to_owned(), "value".to_owned())],
+        );
+        queue.insert_misc_object(root, "bad\0name", []);
+        queue.insert_misc_object(
+            root,
+            "bad info",
+            [("QueuedInfo".to_owned(), "bad\0value".to_owned())],
+        );
+        assert_eq!(queue.len(), 3);
+
+        let num_misc = topology.objects_with_type(ObjectType::Misc).count();
+        let failures = queue.commit(&mut topology);
+        assert!(queue.is_empty());
+        assert_eq!(failures.len(), 2);
+        for (_edit, error) in &failures {
+            assert_eq!(*error, QueuedEditError::ContainsNul);
+        }
+
+        // Edits with invalid strings should not be applied at all
+        assert_eq!(
+            topology.objects_with_type(ObjectType::Misc).count(),
+            num_misc + 1
+        );
+        let misc = topology
+            .objects_with_type(ObjectType::Misc)
+            .find(|obj| obj.name().is_some_and(|name| name.to_bytes() == b"queued"))
+            .unwrap();
+        assert_eq!(
+            misc.info("QueuedInfo").map(CStr::to_bytes),
+            Some(&b"value"[..])
+        );
+    }
+
+    #[test]
+    fn commit_group() {
+        let mut topology = Topology::test_instance().clone();
+        let pus = topology
+            .objects_with_type(ObjectType::PU)
+            .take(2)
+            .map(|pu| (pu.handle(), pu.cpuset().unwrap().clone_target()))
+            .collect::<Vec<_>>();
+        let queue = EditQueue::new();
+        queue.insert_group_object(
+            pus.iter().map(|(handle, _cpuset)| *handle),
+            None,
+            [("QueuedGroup".to_owned(), "good".to_owned())],
+        );
+        queue.insert_group_object(
+            pus.iter().map(|(handle, _cpuset)| *handle),
+            None,
+            [("QueuedGroup".to_owned(), "bad\0value".to_owned())],
+        );
+        assert_eq!(queue.len(), 2);
+
+        let failures = queue.commit(&mut topology);
+        assert!(queue.is_empty());
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].1, QueuedEditError::ContainsNul);
+
+        // The group, or the existing object that replaced it, should cover
+        // the requested children and carry the requested info
+        let group = topology
+            .objects()
+            .find(|obj| obj.info("QueuedGroup").is_some())
+            .unwrap();
+        assert_eq!(
+            group.info("QueuedGroup").map(CStr::to_bytes),
+            Some(&b"good"[..])
+        );
+        let group_cpuset = group.cpuset().unwrap();
+        for (_handle, cpuset) in &pus {
+            assert!(group_cpuset.includes(cpuset));
+        }
+    }
+
+    #[test]
+    fn commit_group_with_kind() {
+        let mut topology = Topology::test_instance().clone();
+        let children = topology
+            .objects_with_type(ObjectType::PU)
+            .take(2)
+            .map(TopologyObject::handle)
+            .collect::<Vec<_>>();
+        let queue = EditQueue::new();
+        queue.push(QueuedEdit::InsertGroup {
+            children,
+            kind: Some(GroupKind::new(7, 3)),
+            merge: Some(GroupMerge::Never),
+            infos: vec![("KindedGroup".to_owned(), "yes".to_owned())],
+        });
+        assert!(queue.commit(&mut topology).is_empty());
+
+        // Merging was prevented, so a new group must have been inserted, with
+        // the requested kind and subkind
+        let group = topology
+            .objects_with_type(ObjectType::Group)
+            .find(|obj| obj.info("KindedGroup").is_some())
+            .unwrap();
+        let Some(ObjectAttributes::Group(attributes)) = group.attributes() else {
+            panic!("Groups should have group attributes");
+        };
+        assert_eq!((attributes.kind(), attributes.subkind()), (7, 3));
+    }
+}
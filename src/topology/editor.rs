@@ -62,6 +62,7 @@ use libc::{EINVAL, ENOMEM};
 #[cfg(test)]
 use similar_asserts::assert_eq;
 use std::{
+    ffi::c_uint,
     fmt::{self, Write},
     panic::{AssertUnwindSafe, UnwindSafe},
     ptr::{self, NonNull},
@@ -129,6 +130,9 @@ impl Topology {
             std::process::abort()
         }
 
+        // Invalidate Rust-side caches
+        self.1.clear();
+
         // Check topology for correctness before exposing it
         if cfg!(debug_assertions) {
             // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
@@ -293,6 +297,43 @@ impl<'topology> TopologyEditor<'topology> {
         polymorphized(self, set.borrow(), flags)
     }
 
+    /// Restrict the topology to the given NUMA node set
+    ///
+    /// This is a memory-centric specialization of [`restrict()`] which makes
+    /// it explicit that the restriction is performed by [`NodeSet`]. It is
+    /// typically used to trim NUMA nodes that should not be used by the
+    /// application, e.g. CXL-attached or otherwise memory-only nodes.
+    ///
+    /// The hwloc `BYNODESET` flag is set internally, and
+    /// [`RestrictFlags::REMOVE_EMPTIED`] removes objects that lost access to
+    /// all memory, i.e. objects whose nodeset became empty.
+    ///
+    /// All other semantics, including the irreversibility of restriction, are
+    /// identical to those of [`restrict()`].
+    ///
+    /// # Errors
+    ///
+    /// Err([`ParameterError`]) will be returned if the input nodeset is
+    /// invalid, e.g. if it does not contain any NUMA node of the topology. The
+    /// topology is not modified in this case.
+    ///
+    /// # Aborts
+    ///
+    /// Failure to allocate internal data will lead to a process abort, because
+    /// the topology gets corrupted in this case and must not be touched again,
+    /// but we have no way to prevent this in a safe API.
+    ///
+    /// [`restrict()`]: Self::restrict()
+    #[doc(alias = "hwloc_topology_restrict")]
+    #[doc(alias = "HWLOC_RESTRICT_FLAG_BYNODESET")]
+    pub fn restrict_by_nodeset(
+        &mut self,
+        nodeset: &NodeSet,
+        flags: RestrictFlags,
+    ) -> Result<(), ParameterError<NodeSet>> {
+        self.restrict(nodeset, flags)
+    }
+
     /// Change the sets of allowed PUs and NUMA nodes in the topology
     ///
     /// This function only works if [`BuildFlags::INCLUDE_DISALLOWED`] was set
@@ -356,7 +397,12 @@ impl<'topology> TopologyEditor<'topology> {
     /// non-empty, or no Group object will be created.
     ///
     /// Use the `merge` option to control hwloc's propension to merge groups
-    /// with hierarchically-identical topology objects.
+    /// with hierarchically-identical topology objects. By default, a group
+    /// that covers the same objects as an existing object may be discarded,
+    /// in which case [`InsertedGroup::Existing`] is returned. Use
+    /// [`GroupMerge::Never`] if the group must be inserted no matter what,
+    /// and [`insert_group_object_with_kind()`] if you need finer control over
+    /// which of several identical groups is kept.
     ///
     /// After a successful insertion, [`TopologyObject::set_subtype()`] can be
     /// used to display something other than "Group" as the type name for this
@@ -374,6 +420,7 @@ impl<'topology> TopologyEditor<'topology> {
     ///     - The effective CPU set or NUMA node set ends up being empty.
     ///
     /// [`Group`]: ObjectType::Group
+    /// [`insert_group_object_with_kind()`]: Self::insert_group_object_with_kind()
     //
     // --- Implementation details ---
     //
@@ -396,6 +443,40 @@ impl<'topology> TopologyEditor<'topology> {
         group.insert().map_err(HybridError::Hwloc)
     }
 
+    /// Variant of [`insert_group_object()`] that sets the [`GroupKind`] of the
+    /// new group
+    ///
+    /// When several groups have the same locality, hwloc merges them by
+    /// keeping the one with the lowest [`GroupKind::kind()`], unless merging
+    /// was prevented with [`GroupMerge::Never`]. Groups of the same kind are
+    /// ordered in the topology tree by increasing [`GroupKind::subkind()`].
+    ///
+    /// [`GroupMerge::Always`] is implemented by giving the new group the
+    /// highest possible kind, so it overrides `kind.kind()` while keeping
+    /// `kind.subkind()`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert_group_object()`].
+    ///
+    /// [`insert_group_object()`]: Self::insert_group_object()
+    #[doc(alias = "hwloc_group_attr_s::kind")]
+    #[doc(alias = "hwloc_group_attr_s::subkind")]
+    pub fn insert_group_object_with_kind(
+        &mut self,
+        kind: GroupKind,
+        merge: Option<GroupMerge>,
+        find_children: impl FnOnce(&Topology) -> Vec<&TopologyObject>,
+    ) -> Result<InsertedGroup<'topology>, HybridError<ForeignObjectError>> {
+        let mut group = AllocatedGroup::new(self).map_err(HybridError::Hwloc)?;
+        group.add_children(find_children)?;
+        group.set_kind(kind);
+        if let Some(merge) = merge {
+            group.set_merge_policy(merge);
+        }
+        group.insert().map_err(HybridError::Hwloc)
+    }
+
     /// Add a [`Misc`] object as a leaf of the topology
     ///
     /// A new [`Misc`] object will be created and inserted into the topology as
@@ -489,7 +570,8 @@ impl<'topology> TopologyEditor<'topology> {
 }
 
 bitflags! {
-    /// Flags to be given to [`TopologyEditor::restrict()`]
+    /// Flags to be given to [`TopologyEditor::restrict()`] and
+    /// [`TopologyEditor::restrict_by_nodeset()`]
     #[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
     #[doc(alias = "hwloc_restrict_flags_e")]
     pub struct RestrictFlags: hwloc_restrict_flags_e {
@@ -660,6 +742,42 @@ impl From<bool> for GroupMerge {
     }
 }
 
+/// Kind and subkind of a newly inserted group
+///
+/// See [`TopologyEditor::insert_group_object_with_kind()`] for a description
+/// of how these are used by hwloc.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GroupKind {
+    /// Kind of group, lower kinds are preferred when merging
+    kind: c_uint,
+
+    /// Subkind of group, lower subkinds are placed higher in the tree
+    subkind: c_uint,
+}
+//
+impl GroupKind {
+    /// Set up a group kind and subkind
+    pub fn new(kind: c_uint, subkind: c_uint) -> Self {
+        Self { kind, subkind }
+    }
+
+    /// Kind of group
+    ///
+    /// When groups with the same locality are merged, the group with the
+    /// lowest kind is kept.
+    pub fn kind(&self) -> c_uint {
+        self.kind
+    }
+
+    /// Subkind of group
+    ///
+    /// Groups of the same kind are ordered in the topology tree by
+    /// increasing subkind.
+    pub fn subkind(&self) -> c_uint {
+        self.subkind
+    }
+}
+
 /// RAII guard for `Group` objects that have been allocated, but not inserted
 ///
 /// Ensures that these groups are auto-deleted if not inserted for any reason
@@ -761,6 +879,18 @@ impl<'editor, 'topology> AllocatedGroup<'editor, 'topology> {
         Ok(())
     }
 
+    /// Set the kind and subkind of this group
+    pub(self) fn set_kind(&mut self, kind: GroupKind) {
+        let group_attributes: &mut GroupAttributes =
+            // SAFETY: - We know this is a group object as a type invariant, so
+            //           accessing the group raw attribute is safe
+            //         - We trust hwloc to have initialized the group attributes
+            //           to a valid state
+            //         - We are not changing the raw attributes variant
+            unsafe { (&mut (*self.group.as_mut().as_inner().attr).group).as_newtype() };
+        group_attributes.set_kind(kind.kind, kind.subkind);
+    }
+
     /// Configure hwloc's group merging policy
     ///
     /// By default, hwloc may or may not merge identical groups covering the
@@ -902,3 +1032,36 @@ impl From<NulError> for InsertMiscError {
 //       that would be unsafe as it would expose &Topology with unevaluated lazy
 //       hwloc caches, and calling their methods could violates Rust's aliasing
 //       model via mutation through &Topology.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::types::ObjectType;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn restrict_by_nodeset() {
+        let mut topology = Topology::test_instance().clone();
+        let nodeset = topology
+            .objects_with_type(ObjectType::NUMANode)
+            .next()
+            .unwrap()
+            .nodeset()
+            .unwrap()
+            .clone_target();
+        topology
+            .edit(|editor| editor.restrict_by_nodeset(&nodeset, RestrictFlags::empty()))
+            .unwrap();
+        assert_eq!(*topology.nodeset(), nodeset);
+        assert_eq!(topology.objects_with_type(ObjectType::NUMANode).count(), 1);
+
+        // Empty node sets are rejected, and leave the topology unchanged
+        let empty = NodeSet::new();
+        assert_eq!(
+            topology.edit(|editor| editor.restrict_by_nodeset(&empty, RestrictFlags::empty())),
+            Err(ParameterError::from(empty))
+        );
+        assert_eq!(*topology.nodeset(), nodeset);
+    }
+}
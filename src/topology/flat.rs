@@ -0,0 +1,285 @@
+//! Flattened topology snapshots
+//!
+//! Walking a [`Topology`] means following pointers through the linked data
+//! structures allocated by hwloc, and some queries like CPU set operations
+//! cross the FFI boundary. This is fine for most purposes, but it can become a
+//! bottleneck in tight placement loops.
+//!
+//! [`FlatTopology`] addresses this use case by copying the essential
+//! information about every object of a topology into a few contiguous arrays,
+//! once, so that it can be iterated over quickly afterwards.
+
+use crate::{
+    object::{depth::Depth, handle::ObjectHandle, types::ObjectType, TopologyObject},
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::ops::Range;
+
+/// Number of bits in a word of [`FlatTopology::cpuset_words()`]
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// # Flattened snapshots
+impl Topology {
+    /// Contiguous snapshot of this topology's objects
+    ///
+    /// See [`FlatTopology`] for more information.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::types::ObjectType, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let flat = topology.flattened();
+    /// let num_cores = flat
+    ///     .objects()
+    ///     .iter()
+    ///     .filter(|obj| obj.object_type() == ObjectType::Core)
+    ///     .count();
+    /// assert_eq!(num_cores, topology.objects_with_type(ObjectType::Core).count());
+    /// ```
+    pub fn flattened(&self) -> FlatTopology {
+        let mut flat = FlatTopology {
+            objects: Vec::new(),
+            children: Vec::new(),
+            cpuset_words: Vec::new(),
+        };
+        flat.push_subtree(self.root_object(), None);
+        flat
+    }
+}
+
+/// Owned, contiguous snapshot of the objects of a [`Topology`]
+///
+/// Objects are stored in depth-first order, starting with the root object at
+/// index 0, and including memory, I/O and Misc objects. Objects refer to each
+/// other using their index within [`FlatTopology::objects()`], and their CPU
+/// sets are stored as slices of 64-bit words where bit `i % 64` of word
+/// `i / 64` is set if CPU `i` belongs to the set.
+///
+/// This snapshot does not borrow from the topology it was built from, and is
+/// not updated when that topology is modified.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlatTopology {
+    /// Object records, in depth-first order
+    objects: Vec<FlatObject>,
+
+    /// Storage for the children lists of all objects
+    children: Vec<usize>,
+
+    /// Storage for the CPU sets of all objects
+    cpuset_words: Vec<u64>,
+}
+//
+impl FlatTopology {
+    /// Object records, in depth-first order starting from the root object
+    pub fn objects(&self) -> &[FlatObject] {
+        &self.objects[..]
+    }
+
+    /// Index of the parent of the object at index `object`, if any
+    ///
+    /// # Panics
+    ///
+    /// If `object` is not a valid index into [`FlatTopology::objects()`].
+    pub fn parent(&self, object: usize) -> Option<usize> {
+        self.objects[object].parent
+    }
+
+    /// Indices of the children of the object at index `object`
+    ///
+    /// Normal children come first, followed by memory, I/O and Misc children.
+    ///
+    /// # Panics
+    ///
+    /// If `object` is not a valid index into [`FlatTopology::objects()`].
+    pub fn children(&self, object: usize) -> &[usize] {
+        &self.children[self.objects[object].children.clone()]
+    }
+
+    /// CPU set of the object at index `object`, as a slice of 64-bit words
+    ///
+    /// Trailing zero words are omitted, so objects without a CPU set and
+    /// objects with an empty CPU set get an empty slice.
+    ///
+    /// # Panics
+    ///
+    /// If `object` is not a valid index into [`FlatTopology::objects()`].
+    pub fn cpuset_words(&self, object: usize) -> &[u64] {
+        &self.cpuset_words[self.objects[object].cpuset_words.clone()]
+    }
+
+    /// Truth that CPU `cpu` belongs to the CPU set of the object at index
+    /// `object`
+    ///
+    /// # Panics
+    ///
+    /// If `object` is not a valid index into [`FlatTopology::objects()`].
+    pub fn has_cpu(&self, object: usize, cpu: usize) -> bool {
+        self.cpuset_words(object)
+            .get(cpu / WORD_BITS)
+            .is_some_and(|word| word & (1 << (cpu % WORD_BITS)) != 0)
+    }
+
+    /// Append a topology object and its descendants to this snapshot
+    fn push_subtree(&mut self, object: &TopologyObject, parent: Option<usize>) -> usize {
+        // Record the object's CPU set. Object CPU sets are finite, so the
+        // last_set() bound is only missing when the set is empty.
+        let words_start = self.cpuset_words.len();
+        if let Some(cpuset) = object.cpuset() {
+            if let Some(last) = cpuset.last_set() {
+                let num_words = usize::from(last) / WORD_BITS + 1;
+                self.cpuset_words.resize(words_start + num_words, 0);
+                for cpu in cpuset.iter_set().take_while(|&cpu| cpu <= last) {
+                    let cpu = usize::from(cpu);
+                    self.cpuset_words[words_start + cpu / WORD_BITS] |= 1 << (cpu % WORD_BITS);
+                }
+            }
+        }
+
+        // Record the object itself
+        let index = self.objects.len();
+        self.objects.push(FlatObject {
+            object_type: object.object_type(),
+            handle: object.handle(),
+            os_index: object.os_index(),
+            parent,
+            children: 0..0,
+            cpuset_words: words_start..self.cpuset_words.len(),
+        });
+
+        // Record its descendants, then its children list
+        let children = object
+            .all_children()
+            .map(|child| self.push_subtree(child, Some(index)))
+            .collect::<Vec<_>>();
+        let children_start = self.children.len();
+        self.children.extend(children);
+        self.objects[index].children = children_start..self.children.len();
+        index
+    }
+}
+
+/// Record of one object within a [`FlatTopology`]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlatObject {
+    /// Type of the object
+    object_type: ObjectType,
+
+    /// Handle to the original object
+    handle: ObjectHandle,
+
+    /// OS index of the object, if any
+    os_index: Option<usize>,
+
+    /// Index of the parent object, if any
+    parent: Option<usize>,
+
+    /// Range of [`FlatTopology::children`] listing this object's children
+    children: Range<usize>,
+
+    /// Range of [`FlatTopology::cpuset_words`] holding this object's CPU set
+    cpuset_words: Range<usize>,
+}
+//
+impl FlatObject {
+    /// Type of the object
+    ///
+    /// See [`TopologyObject::object_type()`].
+    pub fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    /// Depth of the object
+    ///
+    /// See [`TopologyObject::depth()`].
+    pub fn depth(&self) -> Depth {
+        self.handle.depth()
+    }
+
+    /// Logical index of the object at its depth
+    ///
+    /// See [`TopologyObject::logical_index()`].
+    pub fn logical_index(&self) -> usize {
+        self.handle.logical_index()
+    }
+
+    /// OS index of the object, if any
+    ///
+    /// See [`TopologyObject::os_index()`].
+    pub fn os_index(&self) -> Option<usize> {
+        self.os_index
+    }
+
+    /// Handle to the original object
+    ///
+    /// This can be used to go back to the full [`TopologyObject`] using
+    /// [`Topology::object_by_handle()`].
+    pub fn handle(&self) -> ObjectHandle {
+        self.handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(FlatObject:
+        Clone, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(FlatTopology:
+        Clone, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    #[test]
+    fn flattened() {
+        let topology = Topology::test_instance();
+        let flat = topology.flattened();
+        assert_eq!(flat.objects().len(), topology.objects().count());
+        for (index, record) in flat.objects().iter().enumerate() {
+            let object = topology.object_by_handle(record.handle()).unwrap();
+            assert_eq!(record.object_type(), object.object_type());
+            assert_eq!(record.depth(), object.depth());
+            assert_eq!(record.logical_index(), object.logical_index());
+            assert_eq!(record.os_index(), object.os_index());
+
+            // Check parent/child links
+            assert_eq!(
+                flat.parent(index)
+                    .map(|parent| flat.objects()[parent].handle()),
+                object.parent().map(TopologyObject::handle)
+            );
+            let children = flat
+                .children(index)
+                .iter()
+                .map(|&child| flat.objects()[child].handle())
+                .collect::<Vec<_>>();
+            let expected = object
+                .all_children()
+                .map(TopologyObject::handle)
+                .collect::<Vec<_>>();
+            assert_eq!(children, expected);
+
+            // Check CPU sets
+            let words = flat.cpuset_words(index);
+            assert!(words.last().map_or(true, |&word| word != 0));
+            if let Some(cpuset) = object.cpuset() {
+                for cpu in cpuset.iter_set().take(1024) {
+                    assert!(flat.has_cpu(index, usize::from(cpu)));
+                }
+                let weight = words.iter().map(|word| word.count_ones()).sum::<u32>();
+                assert_eq!(Some(weight as usize), cpuset.weight());
+            } else {
+                assert!(words.is_empty());
+            }
+        }
+    }
+}
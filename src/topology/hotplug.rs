@@ -0,0 +1,424 @@
+//! Hotplug change detection
+//!
+//! A [`Topology`] is a snapshot of the hardware resources that were available
+//! when it was built. Long-running programs, like system daemons, may need to
+//! notice when CPUs are taken offline or memory is hot-added afterwards.
+//!
+//! This module provides [`Topology::is_stale()`], which re-runs topology
+//! discovery and compares the result with an existing topology, and
+//! [`Topology::reload()`], which additionally replaces the existing topology
+//! with the rediscovered one when they differ. On Linux, a [`HotplugWatcher`]
+//! can also be used to get notified of CPU, memory and NUMA node hotplug
+//! events as they happen, and thus know when these checks are worth running.
+
+use crate::{
+    errors::HybridError,
+    object::types::ObjectType,
+    topology::{builder::TopologyBuilder, Topology},
+};
+use hwlocality_sys::hwloc_obj_type_t;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+#[cfg(target_os = "linux")]
+use std::{
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+};
+use thiserror::Error;
+
+/// # Hotplug change detection
+impl Topology {
+    /// Re-run topology discovery with the same configuration as this topology
+    ///
+    /// The new topology is built from the operating system, using the same
+    /// [build flags](Topology::build_flags()) and
+    /// [type filters](Topology::type_filter()) as this topology.
+    ///
+    /// Note that topologies which were loaded from XML or from a synthetic
+    /// description with [`BuildFlags::ASSUME_THIS_SYSTEM`] will generally
+    /// differ from the rediscovered topology, since the latter will describe
+    /// the actual system.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotThisSystemError`] if this topology does not describe the system
+    ///   on which the program is running, see [`Topology::is_this_system()`].
+    ///
+    /// [`BuildFlags::ASSUME_THIS_SYSTEM`]: crate::topology::builder::BuildFlags::ASSUME_THIS_SYSTEM
+    pub fn rediscover(&self) -> Result<Self, HybridError<NotThisSystemError>> {
+        if !self.is_this_system() {
+            return Err(NotThisSystemError.into());
+        }
+        let mut builder = TopologyBuilder::new()
+            .with_flags(self.build_flags())
+            .expect("Flags of an existing topology should be valid");
+        for ty in
+            (0..=hwloc_obj_type_t::from(u8::MAX)).filter_map(|raw| ObjectType::try_from(raw).ok())
+        {
+            let filter = self.type_filter(ty).map_err(HybridError::Hwloc)?;
+            builder = builder
+                .with_type_filter(ty, filter)
+                .expect("Filters of an existing topology should be valid");
+        }
+        builder.build().map_err(HybridError::Hwloc)
+    }
+
+    /// Truth that the hardware resources available on this system do not
+    /// match this topology anymore
+    ///
+    /// This re-runs topology discovery using [`Topology::rediscover()`], and
+    /// checks if the result differs from this topology, either
+    /// [structurally](Topology::structurally_equal()) or in the set of CPUs
+    /// and NUMA nodes that the process is allowed to use.
+    ///
+    /// Since topology discovery is relatively expensive, you should not call
+    /// this too often. On Linux, a [`HotplugWatcher`] can tell you when a
+    /// check is warranted.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotThisSystemError`] if this topology does not describe the system
+    ///   on which the program is running, see [`Topology::is_this_system()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::Topology;
+    /// let topology = Topology::new()?;
+    /// if topology.is_stale()? {
+    ///     println!("The hardware changed since the topology was built");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    pub fn is_stale(&self) -> Result<bool, HybridError<NotThisSystemError>> {
+        let current = self.rediscover()?;
+        Ok(self.differs_from(&current))
+    }
+
+    /// Replace this topology with a rediscovered one if it is stale
+    ///
+    /// This is equivalent to calling [`Topology::rediscover()`] and replacing
+    /// this topology with the result if [`Topology::is_stale()`] would have
+    /// returned `true`. The return value tells whether the topology was
+    /// replaced.
+    ///
+    /// # Errors
+    ///
+    /// - [`NotThisSystemError`] if this topology does not describe the system
+    ///   on which the program is running, see [`Topology::is_this_system()`].
+    pub fn reload(&mut self) -> Result<bool, HybridError<NotThisSystemError>> {
+        let current = self.rediscover()?;
+        let stale = self.differs_from(&current);
+        if stale {
+            *self = current;
+        }
+        Ok(stale)
+    }
+
+    /// Truth that a rediscovered topology differs from this one
+    fn differs_from(&self, current: &Self) -> bool {
+        !self.structurally_equal(current)
+            || self.allowed_cpuset() != current.allowed_cpuset()
+            || self.allowed_nodeset() != current.allowed_nodeset()
+    }
+}
+
+/// Error returned when attempting to rediscover a topology that does not
+/// describe the system on which the program is running
+#[derive(Copy, Clone, Debug, Default, Eq, Error, Hash, PartialEq)]
+#[error("topology does not describe this system, so it cannot be rediscovered")]
+pub struct NotThisSystemError;
+
+/// Kernel uevent multicast group of `NETLINK_KOBJECT_UEVENT` sockets
+#[cfg(target_os = "linux")]
+const UEVENT_GROUP: u32 = 1;
+
+/// Delay after which the watcher thread checks if it should stop
+#[cfg(target_os = "linux")]
+const STOP_POLL_MS: libc::c_int = 100;
+
+/// Background watcher of Linux CPU, memory and NUMA node hotplug events
+///
+/// The watcher listens to the kernel device events that udev also relies on,
+/// and forwards those that concern CPUs, memory blocks and NUMA nodes to a
+/// user-provided callback, from a background thread. A typical reaction is to
+/// call [`Topology::reload()`] from the callback, or to notify another thread
+/// that should do so.
+///
+/// The watcher thread is stopped when the `HotplugWatcher` is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use hwlocality::topology::hotplug::HotplugWatcher;
+/// let _watcher = HotplugWatcher::spawn(|event| {
+///     println!("{:?} {:?} at {}", event.subsystem, event.action, event.devpath);
+/// })?;
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+pub struct HotplugWatcher {
+    /// Flag telling the watcher thread to stop
+    stop: Arc<AtomicBool>,
+
+    /// Watcher thread
+    thread: Option<JoinHandle<()>>,
+}
+//
+#[cfg(target_os = "linux")]
+impl HotplugWatcher {
+    /// Start watching hotplug events
+    ///
+    /// `on_event` will be called from a background thread for every hotplug
+    /// event, until the returned watcher is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error encountered while setting up the kernel event socket.
+    pub fn spawn(mut on_event: impl FnMut(HotplugEvent) + Send + 'static) -> io::Result<Self> {
+        let socket = uevent_socket()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name("hwlocality-hotplug".to_owned())
+            .spawn(move || {
+                let mut buffer = vec![0u8; 8192];
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let Some(len) = recv_uevent(&socket, &mut buffer) else {
+                        continue;
+                    };
+                    if let Some(event) = HotplugEvent::parse(&buffer[..len]) {
+                        on_event(event);
+                    }
+                }
+            })?;
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+//
+#[cfg(target_os = "linux")]
+impl Drop for HotplugWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            // A panic in the user callback has already been reported
+            thread.join().ok();
+        }
+    }
+}
+
+/// Open a netlink socket that receives kernel uevents
+#[cfg(target_os = "linux")]
+fn uevent_socket() -> io::Result<OwnedFd> {
+    // SAFETY: socket() has no safety preconditions
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_NETLINK,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            libc::NETLINK_KOBJECT_UEVENT,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: fd is a freshly created socket that nothing else owns
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // SAFETY: sockaddr_nl is a plain C struct for which all-zeroes is valid
+    let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    address.nl_family = libc::sa_family_t::try_from(libc::AF_NETLINK)
+        .expect("AF_NETLINK should fit in sa_family_t");
+    address.nl_groups = UEVENT_GROUP;
+    // SAFETY: - socket is a valid netlink socket
+    //         - address is a valid sockaddr_nl whose size is passed along
+    let result = unsafe {
+        libc::bind(
+            socket.as_raw_fd(),
+            std::ptr::addr_of!(address).cast(),
+            libc::socklen_t::try_from(std::mem::size_of::<libc::sockaddr_nl>())
+                .expect("sockaddr_nl size should fit in socklen_t"),
+        )
+    };
+    if result < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(socket)
+}
+
+/// Wait for a kernel uevent for a little while, and receive it into `buffer`
+///
+/// Returns the size of the uevent if one was received before the timeout.
+#[cfg(target_os = "linux")]
+fn recv_uevent(socket: &OwnedFd, buffer: &mut [u8]) -> Option<usize> {
+    let mut pollfd = libc::pollfd {
+        fd: socket.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // SAFETY: pollfd is a valid pollfd array of length 1
+    if unsafe { libc::poll(&mut pollfd, 1, STOP_POLL_MS) } <= 0 {
+        return None;
+    }
+    // SAFETY: - socket is a valid socket
+    //         - buffer is valid for writes of buffer.len() bytes
+    let len = unsafe {
+        libc::recv(
+            socket.as_raw_fd(),
+            buffer.as_mut_ptr().cast(),
+            buffer.len(),
+            libc::MSG_DONTWAIT,
+        )
+    };
+    usize::try_from(len).ok()
+}
+
+/// Hotplug event reported by a [`HotplugWatcher`]
+#[cfg(target_os = "linux")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct HotplugEvent {
+    /// Kind of device that the event is about
+    pub subsystem: HotplugSubsystem,
+
+    /// What happened to the device
+    pub action: HotplugAction,
+
+    /// Path of the device within sysfs, e.g. `/devices/system/cpu/cpu3`
+    pub devpath: String,
+}
+//
+#[cfg(target_os = "linux")]
+impl HotplugEvent {
+    /// Decode a kernel uevent, if it is a hotplug event of interest
+    ///
+    /// Kernel uevents start with an `action@devpath` header, followed by
+    /// NUL-separated `KEY=value` pairs.
+    fn parse(uevent: &[u8]) -> Option<Self> {
+        let uevent = std::str::from_utf8(uevent).ok()?;
+        let mut fields = uevent.split('\0');
+        fields.next()?.split_once('@')?;
+        let (mut subsystem, mut action, mut devpath) = (None, None, None);
+        for (key, value) in fields.filter_map(|field| field.split_once('=')) {
+            match key {
+                "SUBSYSTEM" => {
+                    subsystem = Some(match value {
+                        "cpu" => HotplugSubsystem::Cpu,
+                        "memory" => HotplugSubsystem::Memory,
+                        "node" => HotplugSubsystem::Node,
+                        _ => return None,
+                    });
+                }
+                "ACTION" => {
+                    action = Some(match value {
+                        "add" => HotplugAction::Add,
+                        "remove" => HotplugAction::Remove,
+                        "online" => HotplugAction::Online,
+                        "offline" => HotplugAction::Offline,
+                        "change" => HotplugAction::Change,
+                        _ => return None,
+                    });
+                }
+                "DEVPATH" => devpath = Some(value.to_owned()),
+                _ => {}
+            }
+        }
+        Some(Self {
+            subsystem: subsystem?,
+            action: action?,
+            devpath: devpath?,
+        })
+    }
+}
+
+/// Kind of device that a [`HotplugEvent`] is about
+#[cfg(target_os = "linux")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HotplugSubsystem {
+    /// Logical CPU (PU)
+    Cpu,
+
+    /// Memory block
+    Memory,
+
+    /// NUMA node
+    Node,
+}
+
+/// What happened to the device that a [`HotplugEvent`] is about
+#[cfg(target_os = "linux")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum HotplugAction {
+    /// Device was added to the system
+    Add,
+
+    /// Device was removed from the system
+    Remove,
+
+    /// Device was brought online
+    Online,
+
+    /// Device was taken offline
+    Offline,
+
+    /// Device state changed in another way
+    Change,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{error::Error, fmt::Debug, hash::Hash, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(NotThisSystemError:
+        Copy, Default, Error, Hash, Sized, Sync, Unpin, UnwindSafe
+    );
+    #[cfg(target_os = "linux")]
+    assert_impl_all!(HotplugEvent:
+        Clone, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+    #[cfg(target_os = "linux")]
+    assert_impl_all!(HotplugWatcher: Debug, Send, Sized, Sync, Unpin);
+
+    #[test]
+    fn fresh_topology_is_not_stale() {
+        let topology = Topology::test_instance();
+        let rediscovered = topology.rediscover().unwrap();
+        assert!(topology.structurally_equal(&rediscovered));
+        assert!(!topology.is_stale().unwrap());
+        let mut clone = topology.clone();
+        assert!(!clone.reload().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn parse_uevent() {
+        let event = b"offline@/devices/system/cpu/cpu3\0ACTION=offline\0DEVPATH=/devices/system/cpu/cpu3\0SUBSYSTEM=cpu\0SEQNUM=4242\0";
+        assert_eq!(
+            HotplugEvent::parse(event),
+            Some(HotplugEvent {
+                subsystem: HotplugSubsystem::Cpu,
+                action: HotplugAction::Offline,
+                devpath: "/devices/system/cpu/cpu3".to_owned(),
+            })
+        );
+        let unrelated = b"add@/devices/virtual/net/lo\0ACTION=add\0DEVPATH=/devices/virtual/net/lo\0SUBSYSTEM=net\0";
+        assert_eq!(HotplugEvent::parse(unrelated), None);
+        assert_eq!(HotplugEvent::parse(b"garbage"), None);
+    }
+}
@@ -7,10 +7,18 @@
 //! almost any other feature of the library is accessed.
 
 pub mod builder;
+pub mod compare;
+#[cfg(feature = "hwloc-2_3_0")]
+pub mod edit_queue;
 #[cfg(feature = "hwloc-2_3_0")]
 pub mod editor;
 pub mod export;
+pub mod flat;
+pub mod hotplug;
+pub mod placement;
+pub mod read;
 pub mod support;
+pub mod synthetic;
 
 use self::{
     builder::{BuildFlags, TopologyBuilder, TypeFilter},
@@ -24,24 +32,34 @@ use crate::{
     errors::{self, ForeignObjectError, RawHwlocError},
     ffi::transparent::AsNewtype,
     memory::nodeset::NodeSet,
-    object::{depth::NormalDepth, types::ObjectType, TopologyObject},
+    object::{depth::NormalDepth, hierarchy::DepthCache, types::ObjectType, TopologyObject},
+};
+#[cfg(feature = "hwloc-2_4_0")]
+use crate::{
+    cpu::kind::{CpuEfficiency, NoData},
+    info::TextualInfo,
 };
 use bitflags::bitflags;
 use errno::Errno;
 use hwlocality_sys::{
-    hwloc_bitmap_s, hwloc_distrib_flags_e, hwloc_topology, hwloc_type_filter_e,
-    HWLOC_DISTRIB_FLAG_REVERSE,
+    hwloc_bitmap_s, hwloc_distrib_flags_e, hwloc_topology, hwloc_topology_support,
+    hwloc_type_filter_e, HWLOC_DISTRIB_FLAG_REVERSE,
 };
 use libc::EINVAL;
+#[cfg(any(test, feature = "proptest"))]
+use proptest::prelude::*;
 #[allow(unused)]
 #[cfg(test)]
 use similar_asserts::assert_eq;
+#[cfg(any(test, feature = "proptest"))]
+use std::ops::RangeInclusive;
 use std::{
+    collections::HashMap,
     convert::TryInto,
     fmt::{self, Pointer},
     ops::Deref,
     ptr::{self, NonNull},
-    sync::OnceLock,
+    sync::{Arc, OnceLock},
 };
 use thiserror::Error;
 
@@ -51,6 +69,10 @@ use thiserror::Error;
 /// structure of a system. It can be used to query the system topology and to
 /// bind threads and processes to hardware CPU cores and NUMA nodes.
 ///
+/// `Topology` is [`Send`] and [`Sync`], and all of its `&self` methods can be
+/// called concurrently from multiple threads. See [`SharedTopology`] for more
+/// information about sharing a topology between threads.
+///
 /// Since there are **many** things you can do with a `Topology`, the API is
 /// broken down into sections roughly following the structure of the upstream
 /// hwloc documentation:
@@ -106,7 +128,65 @@ use thiserror::Error;
 #[derive(Debug)]
 #[doc(alias = "hwloc_topology")]
 #[doc(alias = "hwloc_topology_t")]
-pub struct Topology(NonNull<hwloc_topology>);
+pub struct Topology(
+    NonNull<hwloc_topology>,
+    DepthCache,
+    OnceLock<NonNull<hwloc_topology_support>>,
+);
+
+/// [`Topology`] that is shared between multiple threads
+///
+/// Once built, a topology is mostly used for read-only queries, which do not
+/// need to be synchronized. Therefore, the simplest way to share a topology
+/// between threads is to wrap it into an [`Arc`], without any [`Mutex`] or
+/// [`RwLock`].
+///
+/// The few `Topology` methods that modify the topology, like
+/// [`Topology::edit()`], take `&mut self` and can therefore not be called
+/// while the topology is shared. If you need to modify a shared topology, you
+/// can use [`Arc::make_mut()`], which will clone the topology if other
+/// threads still hold a reference to it.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{object::types::ObjectType, topology::SharedTopology, Topology};
+/// # use std::sync::Arc;
+/// let topology: SharedTopology = Arc::new(Topology::new()?);
+/// let handles = [ObjectType::Core, ObjectType::PU].map(|ty| {
+///     let topology = Arc::clone(&topology);
+///     std::thread::spawn(move || topology.objects_with_type(ty).count())
+/// });
+/// for handle in handles {
+///     println!("Found {} objects", handle.join().unwrap());
+/// }
+/// # Ok::<(), eyre::Report>(())
+/// ```
+///
+/// Modifying a topology while other threads can read it is a compile-time
+/// error, whether it is shared via an `Arc` or via plain references:
+///
+/// ```compile_fail,E0502
+/// # use hwlocality::Topology;
+/// let mut topology = Topology::new()?;
+/// std::thread::scope(|scope| {
+///     // This thread borrows the topology for reading...
+///     scope.spawn(|| println!("{} objects", topology.objects().count()));
+///
+///     // ...so it cannot be modified until that thread is done
+///     let _stale = topology.reload();
+/// });
+/// # Ok::<(), eyre::Report>(())
+/// ```
+///
+/// Unsafe code that modifies the underlying hwloc topology through raw
+/// pointers while it is shared breaks this guarantee. In debug builds, some
+/// `Topology` queries check their cached results against hwloc's current
+/// answer and panic if such a modification is detected.
+///
+/// [`Mutex`]: std::sync::Mutex
+/// [`RwLock`]: std::sync::RwLock
+pub type SharedTopology = Arc<Topology>;
 
 /// # Topology building
 //
@@ -301,12 +381,16 @@ impl Topology {
     /// ```
     #[doc(alias = "hwloc_topology_get_support")]
     pub fn feature_support(&self) -> &FeatureSupport {
-        // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
-        //         - hwloc ops are trusted not to modify *const parameters
-        let ptr = errors::call_hwloc_ptr("hwloc_topology_get_support", || unsafe {
-            hwlocality_sys::hwloc_topology_get_support(self.as_ptr())
-        })
-        .expect("Unexpected hwloc error");
+        // Support information does not change over the lifetime of a topology,
+        // so it only needs to be queried once
+        let ptr = self.2.get_or_init(|| {
+            // SAFETY: - Topology is trusted to contain a valid ptr (type invariant)
+            //         - hwloc ops are trusted not to modify *const parameters
+            errors::call_hwloc_ptr("hwloc_topology_get_support", || unsafe {
+                hwlocality_sys::hwloc_topology_get_support(self.as_ptr())
+            })
+            .expect("Unexpected hwloc error")
+        });
         // SAFETY: - If hwloc succeeded, the output is assumed to be valid and
         //           point to a valid target devoid of mutable aliases
         //         - Output reference will be bound the the lifetime of &self by
@@ -427,6 +511,13 @@ impl Topology {
     /// By setting `flags` to [`DistributeFlags::REVERSE`], you can ask for them
     /// to be provided in reverse order instead (from last child to first child).
     ///
+    /// When the children of an object with a [symmetric
+    /// subtree](TopologyObject::is_symmetric_subtree()) receive the same
+    /// number of items, the distribution of items within the first child is
+    /// only computed once, then replicated to the other children. This does
+    /// not affect the output, but makes distribution much faster on large
+    /// homogeneous topologies.
+    ///
     /// # Errors
     ///
     /// - [`EmptyRoots`] if there are no CPUs to distribute work to (the
@@ -438,7 +529,6 @@ impl Topology {
     /// [`EmptyRoots`]: DistributeError::EmptyRoots
     /// [`ForeignRoot`]: DistributeError::ForeignRoot
     /// [`OverlappingRoots`]: DistributeError::OverlappingRoots
-    #[allow(clippy::missing_docs_in_private_items)]
     #[doc(alias = "hwloc_distrib")]
     pub fn distribute_items(
         &self,
@@ -446,6 +536,123 @@ impl Topology {
         num_items: usize,
         max_depth: NormalDepth,
         flags: DistributeFlags,
+    ) -> Result<Vec<CpuSet>, DistributeError> {
+        self.distribute_weighted_items(roots, num_items, max_depth, flags, &cpu_count, true)
+    }
+
+    /// Distribute `num_items` work items over the topology under `roots`,
+    /// weighting CPUs according to their kind
+    ///
+    /// This works like [`distribute_items()`], but instead of considering all
+    /// CPUs to be equally able to process work items, the `kind_weight`
+    /// callback is used to give each CPU a weight according to its
+    /// [CPU kind](Topology::cpu_kinds()). Work items are then distributed in
+    /// proportion to these weights, so that on a hybrid CPU, giving
+    /// performance cores a weight of 2 and efficiency cores a weight of 1
+    /// results in performance cores receiving about twice as many work items
+    /// as efficiency cores. CPUs of a kind that is given a weight of 0 do not
+    /// receive any work item.
+    ///
+    /// The `kind_weight` callback receives the index of a CPU kind within the
+    /// output of [`cpu_kinds()`], along with its efficiency and textual
+    /// information. If no information about CPU kinds is available, all CPUs
+    /// are given a weight of 1 and this function behaves like
+    /// [`distribute_items()`]. CPUs that do not belong to any known CPU kind
+    /// are given a weight of 0.
+    ///
+    /// Each output cpuset is accompanied by its total weight, i.e. the sum of
+    /// the weights of the CPUs that it contains, which can be used as a hint
+    /// of how much work the corresponding work item can handle. Work items
+    /// which share the same cpuset share this processing capacity.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if there are no CPUs with a nonzero weight to
+    ///   distribute work to.
+    /// - [`ForeignRoot`] if some of the specified roots do not belong to this
+    ///   topology.
+    /// - [`OverlappingRoots`] if some of the roots have overlapping CPU sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{object::depth::NormalDepth, topology::DistributeFlags, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let num_kinds = topology.num_cpu_kinds().map_or(1, usize::from);
+    /// let items = topology.distribute_items_by_cpu_kind(
+    ///     &[topology.root_object()],
+    ///     8,
+    ///     // Give the most efficient CPU kind twice the weight of other kinds
+    ///     |kind_index, _efficiency, _infos| if kind_index == num_kinds - 1 { 2 } else { 1 },
+    ///     NormalDepth::MAX,
+    ///     DistributeFlags::empty(),
+    /// )?;
+    /// for (cpuset, weight) in items {
+    ///     println!("Work item on {cpuset} with weight {weight}");
+    /// }
+    /// # Ok::<(), eyre::Report>(())
+    /// ```
+    ///
+    /// [`cpu_kinds()`]: Topology::cpu_kinds()
+    /// [`distribute_items()`]: Topology::distribute_items()
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    /// [`ForeignRoot`]: DistributeError::ForeignRoot
+    /// [`OverlappingRoots`]: DistributeError::OverlappingRoots
+    #[cfg(feature = "hwloc-2_4_0")]
+    pub fn distribute_items_by_cpu_kind(
+        &self,
+        roots: &[&TopologyObject],
+        num_items: usize,
+        mut kind_weight: impl FnMut(usize, Option<CpuEfficiency>, &[TextualInfo]) -> usize,
+        max_depth: NormalDepth,
+        flags: DistributeFlags,
+    ) -> Result<Vec<(CpuSet, usize)>, DistributeError> {
+        // Determine the weight of each CPU kind
+        let kind_weights = match self.cpu_kinds() {
+            Ok(kinds) => kinds
+                .enumerate()
+                .map(|(kind_index, (cpuset, efficiency, infos))| {
+                    (cpuset, kind_weight(kind_index, efficiency, infos))
+                })
+                .collect::<Vec<_>>(),
+            Err(NoData) => vec![(self.cpuset().clone_target(), 1)],
+        };
+
+        // Distribute work items according to the weight of each CPU
+        let cpu_weight = |set: &CpuSet| {
+            kind_weights
+                .iter()
+                .map(|(kind_set, weight)| cpu_count(&(set & kind_set)) * weight)
+                .sum()
+        };
+        let result =
+            self.distribute_weighted_items(roots, num_items, max_depth, flags, &cpu_weight, false)?;
+        Ok(result
+            .into_iter()
+            .map(|cpuset| {
+                let weight = cpu_weight(&cpuset);
+                (cpuset, weight)
+            })
+            .collect())
+    }
+
+    /// Implementation of [`Topology::distribute_items()`] where each CPU set is
+    /// given a weight by the `cpu_weight` callback
+    ///
+    /// If `replicate_symmetric` is set, `cpu_weight` must give the same weight
+    /// to all objects with identically shaped subtrees (which is true of
+    /// [`cpu_count()`]). The distribution of items across siblings within a
+    /// [symmetric subtree](TopologyObject::is_symmetric_subtree()) is then
+    /// computed once for the first sibling, and replicated for the others.
+    #[allow(clippy::missing_docs_in_private_items, clippy::too_many_lines)]
+    fn distribute_weighted_items(
+        &self,
+        roots: &[&TopologyObject],
+        num_items: usize,
+        max_depth: NormalDepth,
+        flags: DistributeFlags,
+        cpu_weight: &dyn Fn(&CpuSet) -> usize,
+        replicate_symmetric: bool,
     ) -> Result<Vec<CpuSet>, DistributeError> {
         // Make sure all roots belong to this topology
         for root in roots.iter().copied() {
@@ -461,12 +668,65 @@ impl Topology {
 
         /// Inner recursive distribution algorithm
         fn recurse<'a>(
-            roots_and_cpusets: impl DoubleEndedIterator<Item = ObjSetWeightDepth<'a>> + Clone,
+            roots: impl DoubleEndedIterator<Item = &'a TopologyObject> + Clone,
             num_items: usize,
             max_depth: NormalDepth,
             flags: DistributeFlags,
+            cpu_weight: &dyn Fn(&CpuSet) -> usize,
+            replicate_symmetric: bool,
             result: &mut Vec<CpuSet>,
         ) {
+            // Ignore roots whose CPUs have no weight
+            let roots_and_cpusets = roots.filter_map(|root| decode_normal_obj(root, cpu_weight));
+
+            // Fast path for the children of symmetric subtrees: if each child
+            // gets the same number of items, distribute items within the
+            // first child, then replicate the result to the other children
+            if replicate_symmetric && are_symmetric_siblings(roots_and_cpusets.clone()) {
+                let num_roots = roots_and_cpusets.clone().count();
+                if num_roots > 1 && num_items % num_roots == 0 {
+                    let mut roots = roots_and_cpusets
+                        .clone()
+                        .map(|(root, _, _, _)| root)
+                        .collect::<Vec<_>>();
+                    if flags.contains(DistributeFlags::REVERSE) {
+                        roots.reverse();
+                    }
+                    let first_len = result.len();
+                    recurse(
+                        std::iter::once(roots[0]),
+                        num_items / num_roots,
+                        max_depth,
+                        flags,
+                        cpu_weight,
+                        replicate_symmetric,
+                        result,
+                    );
+                    let first_pus = normal_leaf_indices(roots[0]);
+                    let first_pu_positions = first_pus
+                        .iter()
+                        .enumerate()
+                        .map(|(position, &pu)| (pu, position))
+                        .collect::<HashMap<_, _>>();
+                    for root in &roots[1..] {
+                        let pus = normal_leaf_indices(root);
+                        debug_assert_eq!(
+                            pus.len(),
+                            first_pus.len(),
+                            "Symmetric subtrees should have the same number of PUs"
+                        );
+                        for item in first_len..first_len + num_items / num_roots {
+                            let mut cpuset = CpuSet::new();
+                            for pu in &result[item] {
+                                cpuset.set(pus[first_pu_positions[&usize::from(pu)]]);
+                            }
+                            result.push(cpuset);
+                        }
+                    }
+                    return;
+                }
+            }
+
             // Debug mode checks
             debug_assert_ne!(
                 roots_and_cpusets.clone().count(),
@@ -479,7 +739,7 @@ impl Topology {
             );
             let initial_len = result.len();
 
-            // Total number of cpus covered by the active roots
+            // Total weight of the cpus covered by the active roots
             let total_weight: usize = roots_and_cpusets
                 .clone()
                 .map(|(_, _, weight, _)| weight)
@@ -501,10 +761,12 @@ impl Topology {
                 // run out of items to distribute, or hit the depth limit
                 if root.normal_arity() > 0 && my_items > 1 && depth < max_depth {
                     recurse(
-                        root.normal_children().filter_map(decode_normal_obj),
+                        root.normal_children(),
                         my_items,
                         max_depth,
                         flags,
+                        cpu_weight,
+                        replicate_symmetric,
                         result,
                     );
                 } else if my_items > 0 {
@@ -550,7 +812,7 @@ impl Topology {
             let mut root_then_ancestors = std::iter::once(root)
                 .chain(root.ancestors())
                 .skip_while(|candidate| !candidate.object_type().is_normal());
-            root_then_ancestors.find_map(decode_normal_obj)
+            root_then_ancestors.find_map(|candidate| decode_normal_obj(candidate, cpu_weight))
         });
         if decoded_roots.clone().count() == 0 {
             return Err(DistributeError::EmptyRoots);
@@ -561,7 +823,15 @@ impl Topology {
 
         // Run the recursion, collect results
         let mut result = Vec::with_capacity(num_items);
-        recurse(decoded_roots, num_items, max_depth, flags, &mut result);
+        recurse(
+            decoded_roots.map(|(root, _, _, _)| root),
+            num_items,
+            max_depth,
+            flags,
+            cpu_weight,
+            replicate_symmetric,
+            &mut result,
+        );
         debug_assert_eq!(
             result.len(),
             num_items,
@@ -641,18 +911,68 @@ fn weight_to_items(given_weight: usize, total_weight: usize, num_items: usize) -
         .expect("Cannot happen if computation is correct")
 }
 
+/// Default CPU weighting of [`Topology::distribute_items()`], where each CPU
+/// has a weight of 1
+fn cpu_count(set: &CpuSet) -> usize {
+    set.weight()
+        .expect("Topology objects should not have infinite cpusets")
+}
+
+/// Truth that a set of distribution roots are children of the same object, and
+/// that this object has a symmetric subtree
+///
+/// This means that all roots have identically shaped subtrees.
+fn are_symmetric_siblings<'a>(
+    mut roots_and_cpusets: impl Iterator<Item = ObjSetWeightDepth<'a>>,
+) -> bool {
+    let Some((first, _, _, _)) = roots_and_cpusets.next() else {
+        return false;
+    };
+    let Some(parent) = first
+        .parent()
+        .filter(|parent| parent.is_symmetric_subtree())
+    else {
+        return false;
+    };
+    roots_and_cpusets.all(|(root, _, _, _)| {
+        root.parent()
+            .is_some_and(|other_parent| ptr::eq(other_parent, parent))
+    })
+}
+
+/// OS indices of the leaves of the normal subtree rooted at `obj` (normally
+/// PUs), in depth-first order
+///
+/// This is used to map CPUs between identically shaped subtrees during
+/// [`Topology::distribute_items()`].
+fn normal_leaf_indices(obj: &TopologyObject) -> Vec<usize> {
+    /// Recursive implementation
+    fn recurse(obj: &TopologyObject, indices: &mut Vec<usize>) {
+        if obj.normal_arity() == 0 {
+            indices.push(obj.os_index().expect("PUs should have an OS index"));
+        }
+        for child in obj.normal_children() {
+            recurse(child, indices);
+        }
+    }
+    let mut indices = Vec::new();
+    recurse(obj, &mut indices);
+    indices
+}
+
 /// Part of the implementation of [`Topology::distribute_items()`] that extracts
 /// information from a [`TopologyObject`] that is known to be normal, and
-/// returns this information if the object has a non-empty cpuset
-fn decode_normal_obj(obj: &TopologyObject) -> Option<ObjSetWeightDepth<'_>> {
+/// returns this information if the object's cpuset has a nonzero weight
+fn decode_normal_obj<'a>(
+    obj: &'a TopologyObject,
+    cpu_weight: &dyn Fn(&CpuSet) -> usize,
+) -> Option<ObjSetWeightDepth<'a>> {
     debug_assert!(
         obj.object_type().is_normal(),
         "This function only works on normal objects"
     );
     let cpuset = obj.cpuset().expect("Normal objects should have cpusets");
-    let weight = cpuset
-        .weight()
-        .expect("Topology objects should not have infinite cpusets");
+    let weight = cpu_weight(&cpuset);
     let depth = obj.depth().expect_normal();
     (weight > 0).then_some((obj, cpuset, weight, depth))
 }
@@ -885,11 +1205,26 @@ impl Topology {
 
 // # General-purpose internal utilities
 impl Topology {
+    /// Take ownership of a fully built hwloc topology
+    ///
+    /// # Safety
+    ///
+    /// `topology` must point to a valid fully built, non-aliased topology,
+    /// whose ownership is transferred to the resulting `Topology`.
+    pub(crate) unsafe fn from_non_null(topology: NonNull<hwloc_topology>) -> Self {
+        Self(topology, DepthCache::new(), OnceLock::new())
+    }
+
     /// Contained hwloc topology pointer (for interaction with hwloc)
     pub(crate) fn as_ptr(&self) -> *const hwloc_topology {
         self.0.as_ptr()
     }
 
+    /// Cache of type-to-depth query results
+    pub(crate) fn depth_cache(&self) -> &DepthCache {
+        &self.1
+    }
+
     /// Contained mutable hwloc topology pointer (for interaction with hwloc)
     ///
     /// Be warned that as a result of hwloc employing lazy caching techniques,
@@ -933,10 +1268,67 @@ impl Clone for Topology {
         })
         .expect("Duplicating a topology should not fail");
 
-        Self(NonNull::new(clone).expect("Got null pointer from hwloc_topology_dup"))
+        let clone = NonNull::new(clone).expect("Got null pointer from hwloc_topology_dup");
+        // SAFETY: hwloc_topology_dup returns a fully built topology that we own
+        unsafe { Self::from_non_null(clone) }
+    }
+}
+
+/// Generates topologies from random synthetic descriptions
+///
+/// See [`synthetic_descriptions()`] for the range of topologies that can be
+/// generated. This can be used to check that code which makes decisions based
+/// on the topology behaves correctly across a wide range of machine shapes.
+#[cfg(any(test, feature = "proptest"))]
+impl Arbitrary for Topology {
+    type Parameters = ();
+    type Strategy = prop::strategy::Map<SyntheticDescriptions, fn(String) -> Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        synthetic_descriptions().prop_map(|description| {
+            Self::builder()
+                .from_synthetic(&description)
+                .expect("Generated synthetic descriptions should be valid")
+                .build()
+                .expect("Generated synthetic topologies should be buildable")
+        })
     }
 }
 
+/// Generate random valid synthetic topology descriptions
+///
+/// Descriptions are suitable for [`TopologyBuilder::from_synthetic()`]. They
+/// may contain `Package`, `NUMANode`, `L3Cache`, `L2Cache` and `L1Cache`
+/// levels, and always contain `Core` and `PU` levels. Level arities are kept
+/// small enough that generated topologies never have more than 1024 PUs.
+#[cfg(any(test, feature = "proptest"))]
+pub fn synthetic_descriptions() -> SyntheticDescriptions {
+    /// Name of each level, and range of arities where 0 means "no level"
+    const LEVELS: [(&str, RangeInclusive<usize>); 7] = [
+        ("Package", 0..=4),
+        ("NUMANode", 0..=2),
+        ("L3Cache", 0..=2),
+        ("L2Cache", 0..=2),
+        ("L1Cache", 0..=2),
+        ("Core", 1..=4),
+        ("PU", 1..=4),
+    ];
+    LEVELS.map(|(_name, arities)| arities).prop_map(|arities| {
+        LEVELS
+            .iter()
+            .zip(arities)
+            .filter(|&(_level, arity)| arity > 0)
+            .map(|((name, _arities), arity)| format!("{name}:{arity}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// Strategy emitted by [`synthetic_descriptions()`]
+#[cfg(any(test, feature = "proptest"))]
+pub type SyntheticDescriptions =
+    prop::strategy::Map<[RangeInclusive<usize>; 7], fn([usize; 7]) -> String>;
+
 impl Drop for Topology {
     #[doc(alias = "hwloc_topology_destroy")]
     fn drop(&mut self) {
@@ -952,10 +1344,17 @@ impl Pointer for Topology {
     }
 }
 
-// SAFETY: No shared mutability
+// SAFETY: - The hwloc topology is owned, and hwloc topologies are not tied to
+//           the thread that created them
+//         - The cached support pointer targets memory owned by the topology
 unsafe impl Send for Topology {}
 
-// SAFETY: No shared mutability
+// SAFETY: - hwloc topologies may be queried from several threads at once, and
+//           they are only modified through &mut Topology
+//         - The depth cache only uses atomics for shared mutability
+//         - The support pointer is only written once through a OnceLock, and
+//           targets memory that hwloc does not modify after the topology is
+//           built
 unsafe impl Sync for Topology {}
 
 #[allow(clippy::too_many_lines)]
@@ -964,7 +1363,6 @@ mod tests {
     use super::*;
     use crate::{ffi::PositiveInt, topology::builder::tests::DataSource};
     use bitflags::Flags;
-    use proptest::prelude::*;
     #[allow(unused)]
     use similar_asserts::assert_eq;
     use static_assertions::{assert_impl_all, assert_not_impl_any};
@@ -1033,6 +1431,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn shared_topology() {
+        let mut topology: SharedTopology = Arc::new(Topology::test_instance().clone());
+        let expected = topology.objects().count();
+        let shared = Arc::clone(&topology);
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| assert_eq!(shared.objects().count(), expected));
+            }
+        });
+
+        // Mutable access to a shared topology goes through a copy
+        let copy = Arc::make_mut(&mut topology);
+        assert_ne!(format!("{copy:p}"), format!("{:p}", *shared));
+        assert!(copy.structurally_equal(&shared));
+    }
+
     /// Bias the `max_depth` input to `distribute_items` tests so that
     /// interesting depth values below the maximum possible depth are sampled
     /// often enough
@@ -1041,6 +1456,16 @@ mod tests {
             .prop_map(|us| NormalDepth::try_from(us).unwrap())
     }
 
+    proptest! {
+        #[test]
+        fn arbitrary_topology(topology: Topology) {
+            let num_pus = topology.objects_with_type(ObjectType::PU).count();
+            prop_assert!((1..=1024).contains(&num_pus));
+            prop_assert!(topology.objects_with_type(ObjectType::NUMANode).count() >= 1);
+            prop_assert!(!topology.is_this_system());
+        }
+    }
+
     proptest! {
         // Check that absence of roots to distribute too is reported correctly
         #[test]
@@ -1274,6 +1699,73 @@ mod tests {
         }
     }
 
+    proptest! {
+        /// Check that replicating distributions across symmetric subtrees does
+        /// not affect the output of [`Topology::distribute_items()`]
+        #[test]
+        fn distribute_symmetric(
+            topology: Topology,
+            num_items: NonZeroU8,
+            flags: DistributeFlags,
+        ) {
+            let num_items = usize::from(num_items.get());
+            let packages = topology
+                .objects_with_type(ObjectType::Package)
+                .collect::<Vec<_>>();
+            for roots in [&[topology.root_object()][..], &packages[..]] {
+                if roots.is_empty() {
+                    continue;
+                }
+                let distribute = |replicate_symmetric| {
+                    topology.distribute_weighted_items(
+                        roots,
+                        num_items,
+                        NormalDepth::MAX,
+                        flags,
+                        &cpu_count,
+                        replicate_symmetric,
+                    )
+                };
+                prop_assert_eq!(distribute(true), distribute(false));
+            }
+        }
+    }
+
+    #[cfg(feature = "hwloc-2_4_0")]
+    proptest! {
+        /// Check that uniform CPU kind weights reproduce the output of
+        /// [`Topology::distribute_items()`]
+        #[test]
+        fn distribute_by_uniform_cpu_kind(
+            disjoint_roots in disjoint_roots(),
+            max_depth in max_depth(),
+            num_items: NonZeroU8,
+            flags: DistributeFlags,
+        ) {
+            let topology = Topology::test_instance();
+            let num_items = usize::from(num_items.get());
+            let expected = topology
+                .distribute_items(&disjoint_roots, num_items, max_depth, flags)
+                .unwrap()
+                .into_iter()
+                .map(|set| {
+                    let weight = set.weight().unwrap();
+                    (set, weight)
+                })
+                .collect::<Vec<_>>();
+            let actual = topology
+                .distribute_items_by_cpu_kind(
+                    &disjoint_roots,
+                    num_items,
+                    |_kind_index, _efficiency, _infos| 1,
+                    max_depth,
+                    flags,
+                )
+                .unwrap();
+            prop_assert_eq!(actual, expected);
+        }
+    }
+
     /// To the random input provided by `disjoint_roots`, add an extra root that
     /// overlaps with the existing ones
     fn overlapping_roots() -> impl Strategy<Value = Vec<&'static TopologyObject>> {
@@ -0,0 +1,378 @@
+//! Placement plans
+//!
+//! A [`PlacementPlan`] records which CPUs and NUMA nodes each role of an
+//! application (worker thread, I/O thread, process of a job...) should run
+//! on. It is typically computed once from a [`Topology`], for example using
+//! [`PlacementPlan::distribute()`], then saved in textual form so that it can
+//! be applied again by another process or after a restart.
+
+use crate::{
+    bitmap::{Bitmap, BitmapIndex, OwnedSpecializedBitmap},
+    cpu::{
+        binding::{CpuBindingError, CpuBindingFlags},
+        cpuset::CpuSet,
+        pinning::AffinityPolicy,
+    },
+    errors::BindingError,
+    memory::{
+        binding::{MemoryBindingError, MemoryBindingFlags, MemoryBindingPolicy},
+        nodeset::NodeSet,
+    },
+    topology::{DistributeError, DistributeFlags, Topology},
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// Assignment of application roles to CPUs and NUMA nodes
+///
+/// # Textual form
+///
+/// Placement plans can be saved by converting them to a string using their
+/// [`Display`] implementation, and loaded back using their [`FromStr`]
+/// implementation. The textual form has one line per placement, made of the
+/// role name, the CPU set and the NUMA node set separated by tabs, with sets
+/// using the same list syntax as their [`Display`] implementation.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{topology::placement::PlacementPlan, Topology};
+/// # let topology = Topology::test_instance();
+/// let plan = PlacementPlan::distribute(topology, ["server", "logger"])?;
+///
+/// // Save the plan, then load it back
+/// let saved = plan.to_string();
+/// let loaded = saved.parse::<PlacementPlan>()?;
+/// assert_eq!(loaded, plan);
+///
+/// // Apply the plan to a thread
+/// std::thread::spawn(move || {
+///     let topology = Topology::test_instance();
+///     if let Err(e) = loaded.apply(topology, "logger") {
+///         eprintln!("Failed to bind logger thread: {e}");
+///     }
+/// })
+/// .join()
+/// .unwrap();
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlacementPlan {
+    /// Placement of each role
+    placements: Vec<Placement>,
+}
+//
+impl PlacementPlan {
+    /// Empty placement plan
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Distribute roles over a topology
+    ///
+    /// CPUs are assigned using [`Topology::distribute_items()`] over the
+    /// entire topology, and each role gets the NUMA nodes that are local to
+    /// its CPUs.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn distribute(
+        topology: &Topology,
+        roles: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self, DistributeError> {
+        let roles = roles.into_iter().map(Into::into).collect::<Vec<_>>();
+        let cpusets = topology.distribute_items(
+            &[topology.root_object()],
+            roles.len(),
+            topology.depth() - 1,
+            DistributeFlags::default(),
+        )?;
+        let placements = roles
+            .into_iter()
+            .zip(cpusets)
+            .map(|(role, cpuset)| {
+                let nodeset = NodeSet::from_cpuset(topology, &cpuset);
+                Placement {
+                    role,
+                    cpuset,
+                    nodeset,
+                }
+            })
+            .collect();
+        Ok(Self { placements })
+    }
+
+    /// Distribute roles over a topology according to an [`AffinityPolicy`]
+    ///
+    /// The i-th role gets the CPUs that [`Topology::affinity_assignments()`]
+    /// computes for the i-th thread, and the NUMA nodes that are local to
+    /// these CPUs.
+    ///
+    /// # Errors
+    ///
+    /// - [`EmptyRoots`] if the topology contains no accessible CPU, or if no
+    ///   object of the kind targeted by `policy` contains accessible CPUs
+    ///
+    /// [`EmptyRoots`]: DistributeError::EmptyRoots
+    pub fn distribute_with_policy(
+        topology: &Topology,
+        roles: impl IntoIterator<Item = impl Into<String>>,
+        policy: AffinityPolicy,
+    ) -> Result<Self, DistributeError> {
+        let roles = roles.into_iter().map(Into::into).collect::<Vec<_>>();
+        let cpusets = topology.affinity_assignments(roles.len(), policy)?;
+        let placements = roles
+            .into_iter()
+            .zip(cpusets)
+            .map(|(role, cpuset)| {
+                let nodeset = NodeSet::from_cpuset(topology, &cpuset);
+                Placement {
+                    role,
+                    cpuset,
+                    nodeset,
+                }
+            })
+            .collect();
+        Ok(Self { placements })
+    }
+
+    /// Add a placement to this plan
+    ///
+    /// If a placement already exists for this role, it is replaced.
+    pub fn insert(&mut self, placement: Placement) {
+        if let Some(existing) = self
+            .placements
+            .iter_mut()
+            .find(|existing| existing.role == placement.role)
+        {
+            *existing = placement;
+        } else {
+            self.placements.push(placement);
+        }
+    }
+
+    /// Placements of this plan, in insertion order
+    pub fn placements(&self) -> &[Placement] {
+        &self.placements[..]
+    }
+
+    /// Placement of a certain role, if any
+    pub fn placement(&self, role: &str) -> Option<&Placement> {
+        self.placements
+            .iter()
+            .find(|placement| placement.role == role)
+    }
+
+    /// Bind the current thread according to the placement of `role`
+    ///
+    /// The current thread is bound to the CPUs of the placement, and its
+    /// memory allocations are bound to the NUMA nodes of the placement.
+    ///
+    /// # Errors
+    ///
+    /// - [`UnknownRole`] if this plan has no placement for `role`
+    /// - [`CpuBinding`] if the thread could not be bound to the placement's
+    ///   CPUs
+    /// - [`MemoryBinding`] if the thread's memory allocations could not be
+    ///   bound to the placement's NUMA nodes
+    ///
+    /// [`CpuBinding`]: ApplyPlacementError::CpuBinding
+    /// [`MemoryBinding`]: ApplyPlacementError::MemoryBinding
+    /// [`UnknownRole`]: ApplyPlacementError::UnknownRole
+    pub fn apply(&self, topology: &Topology, role: &str) -> Result<(), ApplyPlacementError> {
+        let placement = self
+            .placement(role)
+            .ok_or_else(|| ApplyPlacementError::UnknownRole(role.to_owned()))?;
+        topology.bind_cpu(&placement.cpuset, CpuBindingFlags::THREAD)?;
+        if !placement.nodeset.is_empty() {
+            topology.bind_memory(
+                &placement.nodeset,
+                MemoryBindingPolicy::Bind,
+                MemoryBindingFlags::THREAD,
+            )?;
+        }
+        Ok(())
+    }
+}
+//
+impl Display for PlacementPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for placement in &self.placements {
+            writeln!(
+                f,
+                "{}\t{}\t{}",
+                placement.role, placement.cpuset, placement.nodeset
+            )?;
+        }
+        Ok(())
+    }
+}
+//
+impl FromStr for PlacementPlan {
+    type Err = ParsePlacementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut plan = Self::new();
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let error = || ParsePlacementError(line.to_owned());
+            let mut fields = line.split('\t');
+            let (Some(role), Some(cpuset), Some(nodeset), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(error());
+            };
+            plan.insert(Placement {
+                role: role.to_owned(),
+                cpuset: parse_list(cpuset).ok_or_else(error)?,
+                nodeset: parse_list(nodeset).ok_or_else(error)?,
+            });
+        }
+        Ok(plan)
+    }
+}
+
+/// Placement of one role of a [`PlacementPlan`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Placement {
+    /// Name of the role
+    ///
+    /// When saving a plan in textual form, this name must not contain tabs or
+    /// newlines.
+    pub role: String,
+
+    /// CPUs that the role should run on
+    pub cpuset: CpuSet,
+
+    /// NUMA nodes that the role should allocate memory from
+    pub nodeset: NodeSet,
+}
+
+/// Error while applying a [`PlacementPlan`]
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+pub enum ApplyPlacementError {
+    /// The plan has no placement for this role
+    #[error("no placement for role {0:?}")]
+    UnknownRole(String),
+
+    /// Failed to bind the current thread to the placement's CPUs
+    #[error(transparent)]
+    CpuBinding(#[from] BindingError<CpuBindingError>),
+
+    /// Failed to bind the current thread's memory to the placement's NUMA nodes
+    #[error(transparent)]
+    MemoryBinding(#[from] BindingError<MemoryBindingError<NodeSet>>),
+}
+
+/// Error while parsing the textual form of a [`PlacementPlan`]
+#[derive(Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[error("invalid placement plan line {0:?}")]
+pub struct ParsePlacementError(String);
+
+/// Parse a bitmap in list syntax (e.g. `0-3,5,8-`)
+fn parse_list<Set: OwnedSpecializedBitmap>(list: &str) -> Option<Set> {
+    let mut bitmap = Bitmap::new();
+    for part in list.split(',').filter(|part| !part.is_empty()) {
+        if let Some((start, end)) = part.split_once('-') {
+            let start = BitmapIndex::from_str(start).ok()?;
+            if end.is_empty() {
+                bitmap.set_range(start..);
+            } else {
+                bitmap.set_range(start..=BitmapIndex::from_str(end).ok()?);
+            }
+        } else {
+            bitmap.set(BitmapIndex::from_str(part).ok()?);
+        }
+    }
+    Some(bitmap.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+
+    #[test]
+    fn list_roundtrip() {
+        for list in ["", "0", "0-3", "1,3-5,8-"] {
+            let cpuset = parse_list::<CpuSet>(list).unwrap();
+            assert_eq!(cpuset.to_string(), list);
+        }
+        assert_eq!(parse_list::<CpuSet>("a"), None);
+        assert_eq!(parse_list::<CpuSet>("1-b"), None);
+    }
+
+    #[test]
+    fn distribute() {
+        let topology = Topology::test_instance();
+        let plan = PlacementPlan::distribute(topology, ["a", "b", "c"]).unwrap();
+        assert_eq!(plan.placements().len(), 3);
+        for (placement, role) in plan.placements().iter().zip(["a", "b", "c"]) {
+            assert_eq!(placement.role, role);
+            assert!(topology.cpuset().includes(&placement.cpuset));
+            assert_eq!(
+                placement.nodeset,
+                NodeSet::from_cpuset(topology, &placement.cpuset)
+            );
+        }
+    }
+
+    #[test]
+    fn distribute_with_policy() {
+        let topology = Topology::test_instance();
+        for policy in [
+            AffinityPolicy::Compact,
+            AffinityPolicy::PerCore,
+            AffinityPolicy::PackWithinL3,
+            AffinityPolicy::PerNUMANode,
+        ] {
+            let plan = PlacementPlan::distribute_with_policy(topology, ["a", "b"], policy).unwrap();
+            let expected = topology.affinity_assignments(2, policy).unwrap();
+            assert_eq!(plan.placements().len(), 2);
+            for (placement, cpuset) in plan.placements().iter().zip(expected) {
+                assert_eq!(placement.cpuset, cpuset);
+                assert!(topology.allowed_cpuset().includes(&placement.cpuset));
+            }
+        }
+    }
+
+    #[test]
+    fn text_roundtrip() {
+        let topology = Topology::test_instance();
+        let mut plan = PlacementPlan::distribute(topology, ["a", "b"]).unwrap();
+        plan.insert(Placement {
+            role: "empty".to_owned(),
+            cpuset: CpuSet::new(),
+            nodeset: NodeSet::new(),
+        });
+        assert_eq!(plan.to_string().parse::<PlacementPlan>(), Ok(plan));
+        assert_eq!(
+            "a\t0".parse::<PlacementPlan>().unwrap_err(),
+            ParsePlacementError("a\t0".to_owned())
+        );
+    }
+
+    #[test]
+    fn insert_replaces() {
+        let mut plan = PlacementPlan::new();
+        let placement = |cpu: usize| Placement {
+            role: "worker".to_owned(),
+            cpuset: CpuSet::from_range(cpu..=cpu),
+            nodeset: NodeSet::new(),
+        };
+        plan.insert(placement(0));
+        plan.insert(placement(1));
+        assert_eq!(plan.placements(), &[placement(1)]);
+        assert_eq!(plan.placement("worker"), Some(&placement(1)));
+        assert_eq!(plan.placement("other"), None);
+    }
+}
@@ -0,0 +1,504 @@
+//! Read-only topology queries as traits
+//!
+//! Higher-level libraries that make decisions based on a [`Topology`], such as
+//! thread pools or allocators, are easier to unit-test if these decisions can
+//! be checked against machines other than the one the tests run on.
+//!
+//! This module abstracts the most commonly used read-only topology queries
+//! (object iteration, depth queries and topology-wide CPU and NUMA node sets)
+//! into the [`TopologyRead`] and [`ObjectRead`] traits. Code that is generic
+//! over these traits can be used with a real [`Topology`] in production, and
+//! with a [`MockTopology`] in tests.
+//!
+//! Building a `MockTopology` does not involve any hwloc topology discovery, so
+//! tests can describe machines other than the one they run on. However, the
+//! [`CpuSet`]s and [`NodeSet`]s that these traits expose are hwloc bitmaps, so
+//! code that uses a `MockTopology` still needs the hwloc library to be
+//! available, be it linked at build time or loaded at runtime with the
+//! `dlopen` feature. Mock topologies are thus not a way to run tests on
+//! machines where hwloc is not installed.
+//!
+//! # Examples
+//!
+//! ```
+//! # use hwlocality::{
+//! #     object::types::ObjectType,
+//! #     topology::read::{MockTopology, TopologyRead},
+//! #     Topology,
+//! # };
+//! /// Number of cores, which is what we want to test
+//! fn num_cores(topology: &impl TopologyRead) -> usize {
+//!     topology.objects_with_type(ObjectType::Core).count()
+//! }
+//!
+//! // Production code runs on the real topology...
+//! # let topology = Topology::test_instance();
+//! println!("This machine has {} cores", num_cores(topology));
+//!
+//! // ...while tests can use a fake machine
+//! let mock = MockTopology::symmetric(&[
+//!     (ObjectType::Package, 2),
+//!     (ObjectType::Core, 8),
+//!     (ObjectType::PU, 2),
+//! ]);
+//! assert_eq!(num_cores(&mock), 16);
+//! ```
+
+use crate::{
+    bitmap::BitmapRef,
+    cpu::cpuset::CpuSet,
+    memory::nodeset::NodeSet,
+    object::{
+        depth::{Depth, NormalDepth, TypeToDepthError},
+        types::ObjectType,
+        TopologyObject,
+    },
+    topology::Topology,
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+
+/// Read-only queries on a topology
+///
+/// See the [module-level documentation](self) for more information.
+pub trait TopologyRead {
+    /// Type of the objects of this topology
+    type Object: ObjectRead + ?Sized;
+
+    /// Number of normal depths, see [`Topology::depth()`]
+    fn depth(&self) -> NormalDepth;
+
+    /// Depth of objects of a certain type, see [`Topology::depth_for_type()`]
+    ///
+    /// # Errors
+    ///
+    /// - [`TypeToDepthError::Nonexistent`] if no object of this type exists
+    /// - [`TypeToDepthError::Multiple`] if objects of this type exist at
+    ///   multiple depths
+    fn depth_for_type(&self, object_type: ObjectType) -> Result<Depth, TypeToDepthError>;
+
+    /// Type of the objects at a certain depth, see [`Topology::type_at_depth()`]
+    fn type_at_depth(&self, depth: Depth) -> Option<ObjectType>;
+
+    /// Objects at a certain depth, see [`Topology::objects_at_depth()`]
+    fn objects_at_depth(&self, depth: Depth) -> Box<dyn Iterator<Item = &Self::Object> + '_>;
+
+    /// Objects of a certain type, see [`Topology::objects_with_type()`]
+    fn objects_with_type(
+        &self,
+        object_type: ObjectType,
+    ) -> Box<dyn Iterator<Item = &Self::Object> + '_> {
+        let depths = NormalDepth::iter_range(NormalDepth::MIN, self.depth())
+            .map(Depth::from)
+            .chain(Depth::VIRTUAL_DEPTHS.iter().copied())
+            .filter(move |&depth| self.type_at_depth(depth) == Some(object_type));
+        Box::new(depths.flat_map(|depth| self.objects_at_depth(depth)))
+    }
+
+    /// Root object, see [`Topology::root_object()`]
+    fn root_object(&self) -> &Self::Object {
+        self.objects_at_depth(Depth::from(NormalDepth::MIN))
+            .next()
+            .expect("Root object should exist")
+    }
+
+    /// CPUs that are covered by this topology, see [`Topology::cpuset()`]
+    fn cpuset(&self) -> BitmapRef<'_, CpuSet>;
+
+    /// CPUs that the process is allowed to use, see
+    /// [`Topology::allowed_cpuset()`]
+    fn allowed_cpuset(&self) -> BitmapRef<'_, CpuSet>;
+
+    /// NUMA nodes that are covered by this topology, see
+    /// [`Topology::nodeset()`]
+    fn nodeset(&self) -> BitmapRef<'_, NodeSet>;
+
+    /// NUMA nodes that the process is allowed to use, see
+    /// [`Topology::allowed_nodeset()`]
+    fn allowed_nodeset(&self) -> BitmapRef<'_, NodeSet>;
+}
+
+/// Read-only queries on a topology object
+///
+/// See the [module-level documentation](self) for more information.
+pub trait ObjectRead {
+    /// Type of the object, see [`TopologyObject::object_type()`]
+    fn object_type(&self) -> ObjectType;
+
+    /// Depth of the object, see [`TopologyObject::depth()`]
+    fn depth(&self) -> Depth;
+
+    /// Logical index of the object, see [`TopologyObject::logical_index()`]
+    fn logical_index(&self) -> usize;
+
+    /// OS index of the object, see [`TopologyObject::os_index()`]
+    fn os_index(&self) -> Option<usize>;
+
+    /// CPUs covered by the object, see [`TopologyObject::cpuset()`]
+    fn cpuset(&self) -> Option<BitmapRef<'_, CpuSet>>;
+
+    /// NUMA nodes covered by the object, see [`TopologyObject::nodeset()`]
+    fn nodeset(&self) -> Option<BitmapRef<'_, NodeSet>>;
+}
+
+impl TopologyRead for Topology {
+    type Object = TopologyObject;
+
+    fn depth(&self) -> NormalDepth {
+        Self::depth(self)
+    }
+
+    fn depth_for_type(&self, object_type: ObjectType) -> Result<Depth, TypeToDepthError> {
+        Self::depth_for_type(self, object_type)
+    }
+
+    fn type_at_depth(&self, depth: Depth) -> Option<ObjectType> {
+        Self::type_at_depth(self, depth)
+    }
+
+    fn objects_at_depth(&self, depth: Depth) -> Box<dyn Iterator<Item = &TopologyObject> + '_> {
+        Box::new(Self::objects_at_depth(self, depth))
+    }
+
+    fn objects_with_type(
+        &self,
+        object_type: ObjectType,
+    ) -> Box<dyn Iterator<Item = &TopologyObject> + '_> {
+        Box::new(Self::objects_with_type(self, object_type))
+    }
+
+    fn root_object(&self) -> &TopologyObject {
+        Self::root_object(self)
+    }
+
+    fn cpuset(&self) -> BitmapRef<'_, CpuSet> {
+        Self::cpuset(self)
+    }
+
+    fn allowed_cpuset(&self) -> BitmapRef<'_, CpuSet> {
+        Self::allowed_cpuset(self)
+    }
+
+    fn nodeset(&self) -> BitmapRef<'_, NodeSet> {
+        Self::nodeset(self)
+    }
+
+    fn allowed_nodeset(&self) -> BitmapRef<'_, NodeSet> {
+        Self::allowed_nodeset(self)
+    }
+}
+
+impl ObjectRead for TopologyObject {
+    fn object_type(&self) -> ObjectType {
+        Self::object_type(self)
+    }
+
+    fn depth(&self) -> Depth {
+        Self::depth(self)
+    }
+
+    fn logical_index(&self) -> usize {
+        Self::logical_index(self)
+    }
+
+    fn os_index(&self) -> Option<usize> {
+        Self::os_index(self)
+    }
+
+    fn cpuset(&self) -> Option<BitmapRef<'_, CpuSet>> {
+        Self::cpuset(self)
+    }
+
+    fn nodeset(&self) -> Option<BitmapRef<'_, NodeSet>> {
+        Self::nodeset(self)
+    }
+}
+
+/// Fake topology for testing code that is generic over [`TopologyRead`]
+///
+/// Mock topologies are symmetric trees of normal objects rooted at a
+/// [`Machine`](ObjectType::Machine) object, with NUMA nodes attached to the
+/// first level below the root. All objects of the mock topology are allowed,
+/// and their OS indices are equal to their logical indices.
+///
+/// Mock topologies are not discovered by hwloc, but their CPU and NUMA node
+/// sets are hwloc bitmaps, so the hwloc library must still be available. See
+/// the [module-level documentation](self) for more information.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MockTopology {
+    /// Objects at each normal depth, starting from the root
+    levels: Vec<Vec<MockObject>>,
+
+    /// NUMA nodes
+    numa_nodes: Vec<MockObject>,
+}
+//
+impl MockTopology {
+    /// Build a symmetric mock topology
+    ///
+    /// `levels` lists the normal object levels below the root
+    /// [`Machine`](ObjectType::Machine) object from top to bottom, along with
+    /// the number of children that each object of the level above has. One
+    /// NUMA node is attached to each object of the first level, or to the root
+    /// if `levels` is empty.
+    ///
+    /// # Panics
+    ///
+    /// - If `levels` does not end with a [`PU`](ObjectType::PU) level
+    /// - If a level has a zero arity or a non-normal object type
+    pub fn symmetric(levels: &[(ObjectType, usize)]) -> Self {
+        assert!(
+            levels.last().map(|&(ty, _arity)| ty) == Some(ObjectType::PU),
+            "Mock topologies must have PUs at the bottom"
+        );
+        for &(ty, arity) in levels {
+            assert!(ty.is_normal(), "Mock topology levels must be normal");
+            assert!(arity > 0, "Mock topology levels must not be empty");
+        }
+
+        // Compute the number of objects at each depth
+        let mut widths = vec![1];
+        for &(_ty, arity) in levels {
+            widths.push(widths.last().expect("At least the root exists") * arity);
+        }
+        let num_pus = *widths.last().expect("At least the root exists");
+        let numa_depth = usize::from(!levels.is_empty());
+        let num_numa_nodes = widths[numa_depth];
+
+        // Generate objects
+        let types = std::iter::once(ObjectType::Machine).chain(levels.iter().map(|&(ty, _)| ty));
+        let levels = types
+            .zip(widths)
+            .enumerate()
+            .map(|(depth, (object_type, width))| {
+                let pus_per_object = num_pus / width;
+                let depth = NormalDepth::try_from(depth).expect("Depth should be in range");
+                (0..width)
+                    .map(|index| {
+                        // Objects above NUMA nodes cover several of them,
+                        // objects below NUMA nodes are covered by one of them
+                        let nodes = if width <= num_numa_nodes {
+                            let nodes_per_object = num_numa_nodes / width;
+                            index * nodes_per_object..(index + 1) * nodes_per_object
+                        } else {
+                            let node = index / (width / num_numa_nodes);
+                            node..node + 1
+                        };
+                        MockObject {
+                            object_type,
+                            depth: Depth::from(depth),
+                            logical_index: index,
+                            cpuset: CpuSet::from_range(
+                                index * pus_per_object..(index + 1) * pus_per_object,
+                            ),
+                            nodeset: NodeSet::from_range(nodes),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let numa_nodes = levels[numa_depth]
+            .iter()
+            .map(|parent| MockObject {
+                object_type: ObjectType::NUMANode,
+                depth: Depth::NUMANode,
+                logical_index: parent.logical_index,
+                cpuset: parent.cpuset.clone(),
+                nodeset: NodeSet::from_range(parent.logical_index..=parent.logical_index),
+            })
+            .collect();
+        Self { levels, numa_nodes }
+    }
+}
+//
+impl TopologyRead for MockTopology {
+    type Object = MockObject;
+
+    fn depth(&self) -> NormalDepth {
+        NormalDepth::try_from(self.levels.len()).expect("Depth should be in range")
+    }
+
+    fn depth_for_type(&self, object_type: ObjectType) -> Result<Depth, TypeToDepthError> {
+        if object_type == ObjectType::NUMANode {
+            return Ok(Depth::NUMANode);
+        }
+        let mut depths = self
+            .levels
+            .iter()
+            .filter(|level| level[0].object_type == object_type)
+            .map(|level| level[0].depth);
+        match (depths.next(), depths.next()) {
+            (Some(depth), None) => Ok(depth),
+            (Some(_), Some(_)) => Err(TypeToDepthError::Multiple),
+            (None, _) => Err(TypeToDepthError::Nonexistent),
+        }
+    }
+
+    fn type_at_depth(&self, depth: Depth) -> Option<ObjectType> {
+        self.level(depth).map(|level| level[0].object_type)
+    }
+
+    fn objects_at_depth(&self, depth: Depth) -> Box<dyn Iterator<Item = &MockObject> + '_> {
+        Box::new(self.level(depth).unwrap_or_default().iter())
+    }
+
+    fn cpuset(&self) -> BitmapRef<'_, CpuSet> {
+        BitmapRef::from(&self.levels[0][0].cpuset)
+    }
+
+    fn allowed_cpuset(&self) -> BitmapRef<'_, CpuSet> {
+        self.cpuset()
+    }
+
+    fn nodeset(&self) -> BitmapRef<'_, NodeSet> {
+        BitmapRef::from(&self.levels[0][0].nodeset)
+    }
+
+    fn allowed_nodeset(&self) -> BitmapRef<'_, NodeSet> {
+        self.nodeset()
+    }
+}
+//
+impl MockTopology {
+    /// Objects at a certain depth, if this depth exists
+    fn level(&self, depth: Depth) -> Option<&[MockObject]> {
+        match depth {
+            Depth::Normal(depth) => self.levels.get(usize::from(depth)).map(Vec::as_slice),
+            Depth::NUMANode => Some(&self.numa_nodes[..]),
+            Depth::Bridge | Depth::PCIDevice | Depth::OSDevice | Depth::Misc => None,
+            #[cfg(feature = "hwloc-2_1_0")]
+            Depth::MemCache => None,
+        }
+    }
+}
+
+/// Object of a [`MockTopology`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MockObject {
+    /// Type of the object
+    object_type: ObjectType,
+
+    /// Depth of the object
+    depth: Depth,
+
+    /// Logical index of the object, which is also its OS index
+    logical_index: usize,
+
+    /// CPUs covered by the object
+    cpuset: CpuSet,
+
+    /// NUMA nodes covered by the object
+    nodeset: NodeSet,
+}
+//
+impl ObjectRead for MockObject {
+    fn object_type(&self) -> ObjectType {
+        self.object_type
+    }
+
+    fn depth(&self) -> Depth {
+        self.depth
+    }
+
+    fn logical_index(&self) -> usize {
+        self.logical_index
+    }
+
+    fn os_index(&self) -> Option<usize> {
+        Some(self.logical_index)
+    }
+
+    fn cpuset(&self) -> Option<BitmapRef<'_, CpuSet>> {
+        Some(BitmapRef::from(&self.cpuset))
+    }
+
+    fn nodeset(&self) -> Option<BitmapRef<'_, NodeSet>> {
+        Some(BitmapRef::from(&self.nodeset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, panic::UnwindSafe};
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(MockObject:
+        Clone, Debug, ObjectRead, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+    assert_impl_all!(MockTopology:
+        Clone, Debug, Send, Sized, Sync, TopologyRead, Unpin, UnwindSafe
+    );
+    assert_impl_all!(Topology: TopologyRead);
+    assert_impl_all!(TopologyObject: ObjectRead);
+
+    /// Check the consistency of a topology through the [`TopologyRead`] trait
+    fn check_consistency(topology: &impl TopologyRead) {
+        let root = topology.root_object();
+        assert_eq!(root.object_type(), ObjectType::Machine);
+        assert_eq!(root.cpuset().unwrap(), topology.cpuset());
+        assert_eq!(root.nodeset().unwrap(), topology.nodeset());
+        for depth in NormalDepth::iter_range(NormalDepth::MIN, topology.depth()) {
+            let depth = Depth::from(depth);
+            let ty = topology.type_at_depth(depth).unwrap();
+            for (index, object) in topology.objects_at_depth(depth).enumerate() {
+                assert_eq!(object.object_type(), ty);
+                assert_eq!(object.depth(), depth);
+                assert_eq!(object.logical_index(), index);
+                assert!(topology.cpuset().includes(object.cpuset().unwrap()));
+            }
+        }
+        let num_pus = topology.objects_with_type(ObjectType::PU).count();
+        assert_eq!(Some(num_pus), topology.cpuset().weight());
+    }
+
+    #[test]
+    fn topology() {
+        check_consistency(Topology::test_instance());
+    }
+
+    #[test]
+    fn mock() {
+        let mock = MockTopology::symmetric(&[
+            (ObjectType::Package, 2),
+            (ObjectType::L3Cache, 2),
+            (ObjectType::Core, 3),
+            (ObjectType::PU, 2),
+        ]);
+        check_consistency(&mock);
+        assert_eq!(usize::from(mock.depth()), 5);
+        assert_eq!(mock.cpuset(), CpuSet::from_range(0..24));
+        assert_eq!(mock.nodeset(), NodeSet::from_range(0..2));
+        assert_eq!(
+            mock.depth_for_type(ObjectType::Core),
+            Ok(Depth::from(NormalDepth::try_from(3).unwrap()))
+        );
+        assert_eq!(
+            mock.depth_for_type(ObjectType::Group),
+            Err(TypeToDepthError::Nonexistent)
+        );
+        let numa_nodes = mock
+            .objects_with_type(ObjectType::NUMANode)
+            .collect::<Vec<_>>();
+        assert_eq!(numa_nodes.len(), 2);
+        for (node, package) in numa_nodes
+            .into_iter()
+            .zip(mock.objects_with_type(ObjectType::Package))
+        {
+            assert_eq!(node.cpuset(), package.cpuset());
+            assert_eq!(node.nodeset(), package.nodeset());
+        }
+        for core in mock.objects_with_type(ObjectType::Core) {
+            let expected_node = core.logical_index() / 6;
+            assert_eq!(
+                core.nodeset().unwrap(),
+                NodeSet::from_range(expected_node..=expected_node)
+            );
+        }
+    }
+}
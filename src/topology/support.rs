@@ -10,9 +10,12 @@
 
 #[cfg(doc)]
 use super::{builder::BuildFlags, Topology};
-use crate::ffi::{
-    self,
-    transparent::{AsNewtype, TransparentNewtype},
+use crate::{
+    ffi::{
+        self,
+        transparent::{AsNewtype, TransparentNewtype},
+    },
+    memory::binding::MemoryBindingPolicy,
 };
 #[cfg(feature = "hwloc-2_3_0")]
 use hwlocality_sys::hwloc_topology_misc_support;
@@ -497,13 +500,53 @@ impl MemoryBindingSupport {
     pub fn migrate_flag(&self) -> bool {
         support_flag(self.0.migrate_membind)
     }
+
+    /// Weighted interleave policy is supported
+    #[cfg(feature = "hwloc-2_11_0")]
+    #[doc(alias = "hwloc_topology_membind_support::weighted_interleave_membind")]
+    pub fn weighted_interleave_policy(&self) -> bool {
+        support_flag(self.0.weighted_interleave_membind)
+    }
+
+    /// A certain memory binding policy is supported
+    ///
+    /// This dispatches to the policy-specific support flag, which lets you
+    /// select a policy that is known to work before trying to bind memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use hwlocality::{memory::binding::MemoryBindingPolicy, Topology};
+    /// # let topology = Topology::test_instance();
+    /// let policy = topology
+    ///     .feature_support()
+    ///     .memory_binding()
+    ///     .map_or(MemoryBindingPolicy::Bind, |support| {
+    ///         if support.policy(MemoryBindingPolicy::Interleave) {
+    ///             MemoryBindingPolicy::Interleave
+    ///         } else {
+    ///             MemoryBindingPolicy::Bind
+    ///         }
+    ///     });
+    /// println!("Will bind memory using the {policy:?} policy");
+    /// ```
+    pub fn policy(&self, policy: MemoryBindingPolicy) -> bool {
+        match policy {
+            MemoryBindingPolicy::FirstTouch => self.first_touch_policy(),
+            MemoryBindingPolicy::Bind => self.bind_policy(),
+            MemoryBindingPolicy::Interleave => self.interleave_policy(),
+            MemoryBindingPolicy::NextTouch => self.next_touch_policy(),
+            #[cfg(feature = "hwloc-2_11_0")]
+            MemoryBindingPolicy::WeightedInterleave => self.weighted_interleave_policy(),
+        }
+    }
 }
 //
 #[cfg(any(test, feature = "proptest"))]
 impl Arbitrary for MemoryBindingSupport {
     type Parameters = ();
     type Strategy =
-        prop::strategy::Map<[crate::strategies::HwlocBool; 15], fn([c_uchar; 15]) -> Self>;
+        prop::strategy::Map<[crate::strategies::HwlocBool; 16], fn([c_uchar; 16]) -> Self>;
 
     #[allow(unused)]
     fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
@@ -511,7 +554,7 @@ impl Arbitrary for MemoryBindingSupport {
         [
             b.clone(), b.clone(), b.clone(), b.clone(), b.clone(), b.clone(),
             b.clone(), b.clone(), b.clone(), b.clone(), b.clone(), b.clone(),
-            b.clone(), b.clone(), b
+            b.clone(), b.clone(), b.clone(), b
         ].prop_map(
             |([
                 set_thisproc_membind,
@@ -529,6 +572,7 @@ impl Arbitrary for MemoryBindingSupport {
                 nexttouch_membind,
                 migrate_membind,
                 get_area_memlocation,
+                weighted_interleave_membind,
             ])| {
                 Self(hwloc_topology_membind_support {
                     set_thisproc_membind,
@@ -546,6 +590,8 @@ impl Arbitrary for MemoryBindingSupport {
                     nexttouch_membind,
                     migrate_membind,
                     get_area_memlocation,
+                    #[cfg(feature = "hwloc-2_11_0")]
+                    weighted_interleave_membind,
                 })
             },
         )
@@ -554,7 +600,8 @@ impl Arbitrary for MemoryBindingSupport {
 //
 impl Debug for MemoryBindingSupport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("MemoryBindingSupport")
+        let mut debug = f.debug_struct("MemoryBindingSupport");
+        debug
             .field("set_current_process", &self.set_current_process())
             .field("get_current_process", &self.get_current_process())
             .field("set_process", &self.set_process())
@@ -569,8 +616,13 @@ impl Debug for MemoryBindingSupport {
             .field("bind_policy", &self.bind_policy())
             .field("interleave_policy", &self.interleave_policy())
             .field("next_touch_policy", &self.next_touch_policy())
-            .field("migrate_flag", &self.migrate_flag())
-            .finish()
+            .field("migrate_flag", &self.migrate_flag());
+        #[cfg(feature = "hwloc-2_11_0")]
+        debug.field(
+            "weighted_interleave_policy",
+            &self.weighted_interleave_policy(),
+        );
+        debug.finish()
     }
 }
 //
@@ -718,6 +770,21 @@ mod tests {
         )>()
     }
 
+    proptest! {
+        #[test]
+        fn membind_policy(membind: MemoryBindingSupport, policy: MemoryBindingPolicy) {
+            let flag_repr = match policy {
+                MemoryBindingPolicy::FirstTouch => membind.0.firsttouch_membind,
+                MemoryBindingPolicy::Bind => membind.0.bind_membind,
+                MemoryBindingPolicy::Interleave => membind.0.interleave_membind,
+                MemoryBindingPolicy::NextTouch => membind.0.nexttouch_membind,
+                #[cfg(feature = "hwloc-2_11_0")]
+                MemoryBindingPolicy::WeightedInterleave => membind.0.weighted_interleave_membind,
+            };
+            check_flag(flag_repr, || membind.policy(policy))?;
+        }
+    }
+
     proptest! {
         #[test]
         fn random(components in support_components()) {
@@ -780,6 +847,8 @@ mod tests {
                 nexttouch_membind: 0,
                 migrate_membind: 1,
                 get_area_memlocation: 1,
+                #[cfg(feature = "hwloc-2_11_0")]
+                weighted_interleave_membind: 0,
             },
         )
     }
@@ -828,6 +897,8 @@ mod tests {
                 nexttouch_membind: 0,
                 migrate_membind: 0,
                 get_area_memlocation: 0,
+                #[cfg(feature = "hwloc-2_11_0")]
+                weighted_interleave_membind: 0,
             },
         )
     }
@@ -876,6 +947,8 @@ mod tests {
                 nexttouch_membind: 0,
                 migrate_membind: 0,
                 get_area_memlocation: 1,
+                #[cfg(feature = "hwloc-2_11_0")]
+                weighted_interleave_membind: 0,
             },
         )
     }
@@ -972,7 +1045,17 @@ mod tests {
         );
 
         assert_eq!(support.cpu_binding().unwrap().0, cpubind);
-        assert_eq!(support.memory_binding().unwrap().0, membind);
+        let membind_raw = support.memory_binding().unwrap().0;
+        assert_eq!(
+            membind_raw,
+            hwloc_topology_membind_support {
+                #[cfg(all(feature = "hwloc-2_11_0", target_os = "linux"))]
+                // Support for weighted interleave depends on the Linux kernel
+                // version, so can't test its value in CI...
+                weighted_interleave_membind: membind_raw.weighted_interleave_membind,
+                ..membind
+            }
+        );
 
         #[cfg(feature = "hwloc-2_3_0")]
         assert_eq!(
@@ -1055,6 +1138,8 @@ mod tests {
                 interleave_membind -> interleave_policy,
                 nexttouch_membind -> next_touch_policy,
                 migrate_membind -> migrate_flag,
+                #[cfg(feature = "hwloc-2_11_0")]
+                weighted_interleave_membind -> weighted_interleave_policy,
             },
             #[cfg(feature = "hwloc-2_3_0")]
             misc {
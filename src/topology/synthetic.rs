@@ -0,0 +1,489 @@
+//! Building synthetic topologies from Rust code
+//!
+//! hwloc can build topologies from [synthetic textual
+//! descriptions](https://hwloc.readthedocs.io/en/v2.9/synthetic.html) such as
+//! `Package:2 L3Cache:1 Core:8 PU:2`, using
+//! [`TopologyBuilder::from_synthetic()`]. These are a convenient way to build
+//! test topologies, but their syntax is only checked at runtime by hwloc, and
+//! level attributes like memory sizes or OS indexes quickly make them hard to
+//! read.
+//!
+//! [`SyntheticBuilder`] provides a type-checked alternative: each level of the
+//! topology is added by a builder method call, attributes are attached to the
+//! level that was added last, and the result is compiled down to a synthetic
+//! description, a [`Topology`] or an XML export.
+
+use crate::{
+    errors::HybridError,
+    object::types::ObjectType,
+    topology::{
+        builder::{StringInputError, TopologyBuilder},
+        export::xml::XMLExportFlags,
+        Topology,
+    },
+};
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::fmt::Write;
+use thiserror::Error;
+
+/// Type-checked builder of synthetic topology descriptions
+///
+/// Levels are listed from the top of the topology to the bottom, each with
+/// the number of children that every object of the previous level has
+/// (its arity). Attribute methods like [`memory()`](Self::memory) and
+/// [`indexes()`](Self::indexes) apply to the level that was added last.
+///
+/// # Examples
+///
+/// ```rust
+/// # use hwlocality::topology::synthetic::SyntheticBuilder;
+/// let builder = SyntheticBuilder::new()
+///     .packages(2)
+///     .numa_nodes(1)
+///     .memory(16 << 30)
+///     .l3(1)
+///     .memory(32 << 20)
+///     .cores(8)
+///     .pus(2);
+/// assert_eq!(
+///     builder.to_synthetic()?,
+///     "Package:2 NUMANode:1(memory=17179869184) L3Cache:1(size=33554432) Core:8 PU:2"
+/// );
+///
+/// let topology = builder.build()?;
+/// assert_eq!(topology.cpuset().weight(), Some(32));
+/// # Ok::<(), eyre::Report>(())
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyntheticBuilder {
+    /// Levels of the topology, from top to bottom
+    levels: Vec<SyntheticLevel>,
+
+    /// Truth that an attribute was set before any level was added
+    orphan_attribute: bool,
+}
+//
+impl SyntheticBuilder {
+    /// Start building a synthetic topology with no level
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a level of `arity` objects of type `object_type` below each object
+    /// of the previous level
+    ///
+    /// Valid level types are the normal object types other than
+    /// [`ObjectType::Machine`], and [`ObjectType::NUMANode`]. Invalid types
+    /// and zero arities are reported when the description is compiled.
+    pub fn level(mut self, object_type: ObjectType, arity: usize) -> Self {
+        self.levels.push(SyntheticLevel {
+            object_type,
+            arity,
+            memory: None,
+            indexes: None,
+        });
+        self
+    }
+
+    /// Add a level of [`Package`](ObjectType::Package)s
+    pub fn packages(self, arity: usize) -> Self {
+        self.level(ObjectType::Package, arity)
+    }
+
+    /// Add a level of [`Die`](ObjectType::Die)s
+    #[cfg(feature = "hwloc-2_1_0")]
+    pub fn dies(self, arity: usize) -> Self {
+        self.level(ObjectType::Die, arity)
+    }
+
+    /// Add a level of [`NUMANode`](ObjectType::NUMANode)s
+    ///
+    /// NUMA nodes become memory children of the objects of the previous level,
+    /// and the next level is attached to these same objects.
+    pub fn numa_nodes(self, arity: usize) -> Self {
+        self.level(ObjectType::NUMANode, arity)
+    }
+
+    /// Add a level of [`Group`](ObjectType::Group)s
+    pub fn groups(self, arity: usize) -> Self {
+        self.level(ObjectType::Group, arity)
+    }
+
+    /// Add a level of [`L3Cache`](ObjectType::L3Cache)s
+    pub fn l3(self, arity: usize) -> Self {
+        self.level(ObjectType::L3Cache, arity)
+    }
+
+    /// Add a level of [`L2Cache`](ObjectType::L2Cache)s
+    pub fn l2(self, arity: usize) -> Self {
+        self.level(ObjectType::L2Cache, arity)
+    }
+
+    /// Add a level of [`L1Cache`](ObjectType::L1Cache)s
+    pub fn l1(self, arity: usize) -> Self {
+        self.level(ObjectType::L1Cache, arity)
+    }
+
+    /// Add a level of [`Core`](ObjectType::Core)s
+    pub fn cores(self, arity: usize) -> Self {
+        self.level(ObjectType::Core, arity)
+    }
+
+    /// Add a level of [`PU`](ObjectType::PU)s
+    ///
+    /// This should be the last level of the topology.
+    pub fn pus(self, arity: usize) -> Self {
+        self.level(ObjectType::PU, arity)
+    }
+
+    /// Set the memory size of each object of the last level, in bytes
+    ///
+    /// This is the local memory of NUMA nodes, and the size of CPU caches.
+    /// Other levels have no memory size, which is reported when the
+    /// description is compiled.
+    #[doc(alias = "size")]
+    pub fn memory(mut self, bytes: u64) -> Self {
+        match self.levels.last_mut() {
+            Some(level) => level.memory = Some(bytes),
+            None => self.orphan_attribute = true,
+        }
+        self
+    }
+
+    /// Set the OS indexes of the objects of the last level
+    ///
+    /// There must be one index per object of the last level across the whole
+    /// topology, listed in logical order. By default, hwloc numbers objects
+    /// of each level in logical order starting from 0.
+    pub fn indexes(mut self, indexes: impl IntoIterator<Item = usize>) -> Self {
+        match self.levels.last_mut() {
+            Some(level) => level.indexes = Some(indexes.into_iter().collect()),
+            None => self.orphan_attribute = true,
+        }
+        self
+    }
+
+    /// Compile this builder into a synthetic description
+    ///
+    /// The output can be passed to [`TopologyBuilder::from_synthetic()`].
+    ///
+    /// # Errors
+    ///
+    /// - [`NoLevels`] if no level was added
+    /// - [`OrphanAttribute`] if an attribute was set before adding any level
+    /// - [`BadLevelType`] if a level has an object type that cannot be used
+    ///   in synthetic topologies
+    /// - [`ZeroArity`] if a level has an arity of zero
+    /// - [`TooManyObjects`] if a level has more than `usize::MAX` objects
+    /// - [`NoMemorySize`] if a memory size was set on a level that does not
+    ///   have one
+    /// - [`WrongIndexCount`] if a level does not have one OS index per object
+    ///
+    /// [`BadLevelType`]: SyntheticError::BadLevelType
+    /// [`NoLevels`]: SyntheticError::NoLevels
+    /// [`NoMemorySize`]: SyntheticError::NoMemorySize
+    /// [`OrphanAttribute`]: SyntheticError::OrphanAttribute
+    /// [`TooManyObjects`]: SyntheticError::TooManyObjects
+    /// [`WrongIndexCount`]: SyntheticError::WrongIndexCount
+    /// [`ZeroArity`]: SyntheticError::ZeroArity
+    pub fn to_synthetic(&self) -> Result<String, SyntheticError> {
+        if self.levels.is_empty() {
+            return Err(SyntheticError::NoLevels);
+        }
+        if self.orphan_attribute {
+            return Err(SyntheticError::OrphanAttribute);
+        }
+        let mut description = String::new();
+        let mut num_objects = 1usize;
+        for level in &self.levels {
+            let object_type = level.object_type;
+            let valid_type = (object_type.is_normal() && object_type != ObjectType::Machine)
+                || object_type == ObjectType::NUMANode;
+            if !valid_type {
+                return Err(SyntheticError::BadLevelType(object_type));
+            }
+            if level.arity == 0 {
+                return Err(SyntheticError::ZeroArity(object_type));
+            }
+            let level_objects = num_objects
+                .checked_mul(level.arity)
+                .ok_or(SyntheticError::TooManyObjects(object_type))?;
+            // NUMA nodes are memory children, so the next level is attached
+            // to the objects of the previous level, not to the NUMA nodes
+            if object_type != ObjectType::NUMANode {
+                num_objects = level_objects;
+            }
+
+            if !description.is_empty() {
+                description.push(' ');
+            }
+            write!(description, "{object_type}:{}", level.arity)
+                .expect("Writing to a String can't fail");
+
+            let mut attributes = Vec::new();
+            if let Some(bytes) = level.memory {
+                let key = if object_type == ObjectType::NUMANode {
+                    "memory"
+                } else if object_type.is_cpu_cache() {
+                    "size"
+                } else {
+                    return Err(SyntheticError::NoMemorySize(object_type));
+                };
+                attributes.push(format!("{key}={bytes}"));
+            }
+            if let Some(indexes) = &level.indexes {
+                if indexes.len() != level_objects {
+                    return Err(SyntheticError::WrongIndexCount {
+                        object_type,
+                        expected: level_objects,
+                        actual: indexes.len(),
+                    });
+                }
+                let indexes = indexes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+                attributes.push(format!("indexes={indexes}"));
+            }
+            if !attributes.is_empty() {
+                write!(description, "({})", attributes.join(" "))
+                    .expect("Writing to a String can't fail");
+            }
+        }
+        Ok(description)
+    }
+
+    /// Build a topology from this synthetic description
+    ///
+    /// CPU and memory binding operations will not do anything on the
+    /// resulting topology.
+    ///
+    /// # Errors
+    ///
+    /// - Any error from [`to_synthetic()`](Self::to_synthetic)
+    /// - [`Invalid`] if hwloc rejected the synthetic description
+    /// - Hwloc errors from [`TopologyBuilder::build()`]
+    ///
+    /// [`Invalid`]: SyntheticError::Invalid
+    pub fn build(&self) -> Result<Topology, HybridError<SyntheticError>> {
+        let description = self.to_synthetic()?;
+        let builder = TopologyBuilder::new()
+            .from_synthetic(&description)
+            .map_err(|e| match e {
+                StringInputError::ContainsNul => {
+                    unreachable!("Synthetic descriptions don't contain NUL chars")
+                }
+                StringInputError::Invalid => SyntheticError::Invalid,
+            })?;
+        builder.build().map_err(HybridError::Hwloc)
+    }
+
+    /// Build a topology from this synthetic description, then export it to
+    /// XML
+    ///
+    /// # Errors
+    ///
+    /// - Any error from [`build()`](Self::build)
+    /// - Hwloc errors from [`Topology::export_xml()`]
+    pub fn to_xml(&self) -> Result<String, HybridError<SyntheticError>> {
+        let topology = self.build()?;
+        let xml = topology
+            .export_xml(XMLExportFlags::empty())
+            .map_err(HybridError::Hwloc)?;
+        Ok(xml.as_str().to_owned())
+    }
+}
+
+/// Level of a [`SyntheticBuilder`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct SyntheticLevel {
+    /// Type of objects at this level
+    object_type: ObjectType,
+
+    /// Number of objects below each object of the previous level
+    arity: usize,
+
+    /// Memory size of each object, in bytes
+    memory: Option<u64>,
+
+    /// OS indexes of the objects of this level, in logical order
+    indexes: Option<Vec<usize>>,
+}
+
+/// Error returned when a [`SyntheticBuilder`] cannot be compiled
+#[allow(variant_size_differences)]
+#[derive(Copy, Clone, Debug, Eq, Error, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum SyntheticError {
+    /// No level was added to the builder
+    #[error("synthetic topologies must have at least one level")]
+    NoLevels,
+
+    /// An attribute was set before any level was added
+    #[error("synthetic topology attributes must follow a level")]
+    OrphanAttribute,
+
+    /// This object type cannot be used as a synthetic topology level
+    #[error("{0} can't be used as a synthetic topology level")]
+    BadLevelType(ObjectType),
+
+    /// A level has no object below the objects of the previous level
+    #[error("synthetic topology level {0} can't have an arity of zero")]
+    ZeroArity(ObjectType),
+
+    /// A level has more objects than can be counted
+    #[error("synthetic topology level {0} has too many objects")]
+    TooManyObjects(ObjectType),
+
+    /// A memory size was set on a level that doesn't have one
+    #[error("synthetic topology level {0} has no memory size")]
+    NoMemorySize(ObjectType),
+
+    /// The number of OS indexes does not match the number of objects
+    #[error("synthetic topology level {object_type} has {expected} objects, but {actual} indexes were specified")]
+    WrongIndexCount {
+        /// Type of objects at this level
+        object_type: ObjectType,
+
+        /// Number of objects at this level
+        expected: usize,
+
+        /// Number of OS indexes that were specified
+        actual: usize,
+    },
+
+    /// hwloc rejected the synthetic description
+    #[error("hwloc rejected the synthetic topology description")]
+    Invalid,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{fmt::Debug, hash::Hash};
+
+    assert_impl_all!(SyntheticBuilder: Clone, Debug, Default, Eq, Send, Sync);
+    assert_impl_all!(SyntheticError: Copy, Debug, Eq, Hash, Send, Sync);
+
+    #[test]
+    fn description() {
+        let builder = SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(1)
+            .memory(1 << 30)
+            .cores(2)
+            .indexes([3, 2, 1, 0])
+            .pus(1);
+        assert_eq!(
+            builder.to_synthetic().unwrap(),
+            "Package:2 NUMANode:1(memory=1073741824) Core:2(indexes=3,2,1,0) PU:1"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let check = |builder: SyntheticBuilder, error: SyntheticError| {
+            assert_eq!(builder.to_synthetic(), Err(error));
+        };
+        check(SyntheticBuilder::new(), SyntheticError::NoLevels);
+        check(
+            SyntheticBuilder::new().memory(42).pus(1),
+            SyntheticError::OrphanAttribute,
+        );
+        check(
+            SyntheticBuilder::new().level(ObjectType::Machine, 1),
+            SyntheticError::BadLevelType(ObjectType::Machine),
+        );
+        check(
+            SyntheticBuilder::new().cores(0),
+            SyntheticError::ZeroArity(ObjectType::Core),
+        );
+        check(
+            SyntheticBuilder::new().cores(usize::MAX).pus(2),
+            SyntheticError::TooManyObjects(ObjectType::PU),
+        );
+        check(
+            SyntheticBuilder::new().cores(2).memory(42),
+            SyntheticError::NoMemorySize(ObjectType::Core),
+        );
+        check(
+            SyntheticBuilder::new().cores(2).pus(2).indexes([0, 1]),
+            SyntheticError::WrongIndexCount {
+                object_type: ObjectType::PU,
+                expected: 4,
+                actual: 2,
+            },
+        );
+    }
+
+    #[test]
+    fn build() {
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .l3(1)
+            .memory(8 << 20)
+            .cores(3)
+            .pus(2)
+            .build()
+            .unwrap();
+        let count = |ty| topology.objects_with_type(ty).count();
+        assert_eq!(count(ObjectType::Package), 2);
+        assert_eq!(count(ObjectType::L3Cache), 2);
+        assert_eq!(count(ObjectType::Core), 6);
+        assert_eq!(count(ObjectType::PU), 12);
+        assert!(SyntheticBuilder::new()
+            .pus(2)
+            .to_xml()
+            .unwrap()
+            .contains("type=\"PU\""));
+    }
+
+    #[test]
+    fn build_numa_indexes() {
+        // The core level is attached to packages, not to NUMA nodes, so
+        // there are as many cores as with no NUMA level
+        let topology = SyntheticBuilder::new()
+            .packages(2)
+            .numa_nodes(2)
+            .indexes([3, 2, 1, 0])
+            .cores(2)
+            .indexes([7, 6, 5, 4])
+            .pus(1)
+            .build()
+            .unwrap();
+        let os_indices = |ty| {
+            topology
+                .objects_with_type(ty)
+                .map(|obj| obj.os_index().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(os_indices(ObjectType::NUMANode), [3, 2, 1, 0]);
+        assert_eq!(os_indices(ObjectType::Core), [7, 6, 5, 4]);
+        assert_eq!(topology.objects_with_type(ObjectType::PU).count(), 4);
+        for package in topology.objects_with_type(ObjectType::Package) {
+            assert_eq!(package.memory_arity(), 2);
+            assert_eq!(package.normal_arity(), 2);
+        }
+
+        // Index counts are checked against the right number of objects
+        assert_eq!(
+            SyntheticBuilder::new()
+                .packages(2)
+                .numa_nodes(2)
+                .cores(2)
+                .indexes([0, 1, 2, 3, 4, 5, 6, 7])
+                .to_synthetic(),
+            Err(SyntheticError::WrongIndexCount {
+                object_type: ObjectType::Core,
+                expected: 4,
+                actual: 8,
+            })
+        );
+    }
+}
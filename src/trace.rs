@@ -0,0 +1,136 @@
+//! Observation of hwloc calls
+//!
+//! When the `trace-calls` feature is enabled, every call to an hwloc entry
+//! point that goes through hwlocality's error handling is reported to a
+//! process-wide observer, which can be installed using [`set_call_observer()`].
+//!
+//! This lets you diagnose production issues, such as failing CPU or memory
+//! binding calls, by forwarding these reports to your logging infrastructure
+//! of choice instead of reaching out for `strace` or a debugger.
+//
+// --- Implementation details ---
+//
+// The observer is a plain function pointer rather than a boxed closure, so
+// that observing a call only requires taking a read lock and does not involve
+// any dynamic allocation.
+
+use errno::Errno;
+#[allow(unused)]
+#[cfg(test)]
+use similar_asserts::assert_eq;
+use std::{sync::RwLock, time::Duration};
+
+/// Report of a completed call to an hwloc entry point
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct HwlocCall {
+    /// Hwloc entry point that was called
+    pub api: &'static str,
+
+    /// Truth that the entry point reported success
+    pub succeeded: bool,
+
+    /// Observed errno value, if the call failed and errno was set
+    pub errno: Option<Errno>,
+
+    /// Time spent in the call
+    pub duration: Duration,
+}
+
+/// Callback that is notified of every hwloc call
+pub type CallObserver = fn(&HwlocCall);
+
+/// Currently installed call observer
+static OBSERVER: RwLock<Option<CallObserver>> = RwLock::new(None);
+
+/// Install a process-wide observer of hwloc calls
+///
+/// The observer will be called on the thread that performed the hwloc call,
+/// right after the call completed, which means it should be quick and must not
+/// call into hwlocality itself. Any previously installed observer is replaced
+/// and returned.
+///
+/// # Examples
+///
+/// ```
+/// # use hwlocality::{trace::{self, HwlocCall}, Topology};
+/// fn log_failures(call: &HwlocCall) {
+///     if !call.succeeded {
+///         eprintln!("{} failed with errno {:?}", call.api, call.errno);
+///     }
+/// }
+/// trace::set_call_observer(log_failures);
+/// # let topology = Topology::test_instance();
+/// let _ = topology.cpu_binding(hwlocality::cpu::binding::CpuBindingFlags::THREAD);
+/// # trace::clear_call_observer();
+/// # Ok::<(), eyre::Report>(())
+/// ```
+pub fn set_call_observer(observer: CallObserver) -> Option<CallObserver> {
+    OBSERVER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .replace(observer)
+}
+
+/// Remove the process-wide observer of hwloc calls, if any, and return it
+pub fn clear_call_observer() -> Option<CallObserver> {
+    OBSERVER
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .take()
+}
+
+/// Report a completed hwloc call to the installed observer, if any
+pub(crate) fn notify(call: &HwlocCall) {
+    let observer = *OBSERVER
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(observer) = observer {
+        observer(call);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::{builder::StringInputError, Topology};
+    #[allow(unused)]
+    use similar_asserts::assert_eq;
+    use static_assertions::assert_impl_all;
+    use std::{
+        fmt::Debug,
+        hash::Hash,
+        panic::UnwindSafe,
+        sync::{Mutex, PoisonError},
+    };
+
+    // Check that public types in this module keep implementing all expected
+    // traits, in the interest of detecting future semver-breaking changes
+    assert_impl_all!(HwlocCall:
+        Copy, Debug, Hash, Send, Sized, Sync, Unpin, UnwindSafe
+    );
+
+    #[test]
+    fn observer_lifecycle() {
+        /// Calls that were reported to the observer
+        static CALLS: Mutex<Vec<HwlocCall>> = Mutex::new(Vec::new());
+        fn observer(call: &HwlocCall) {
+            CALLS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(*call);
+        }
+        let previous = set_call_observer(observer);
+
+        // Make an hwloc call fail, then uninstall the observer
+        let result = Topology::builder().from_synthetic("ZaLgO");
+        assert!(matches!(result, Err(StringInputError::Invalid)));
+        let removed = previous.map_or_else(clear_call_observer, set_call_observer);
+        assert!(removed.is_some());
+
+        // The failing call should have been reported
+        let calls = CALLS.lock().unwrap_or_else(PoisonError::into_inner);
+        assert!(calls
+            .iter()
+            .any(|call| call.api == "hwloc_topology_set_synthetic" && !call.succeeded));
+    }
+}